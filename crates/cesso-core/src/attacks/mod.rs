@@ -5,7 +5,9 @@ mod magic_data;
 mod tables;
 
 use crate::bitboard::Bitboard;
+use crate::board::Board;
 use crate::color::Color;
+use crate::piece_kind::PieceKind;
 use crate::square::Square;
 
 use self::magic::{bishop_attacks_lookup, rook_attacks_lookup};
@@ -29,6 +31,15 @@ pub fn pawn_attacks(color: Color, sq: Square) -> Bitboard {
     PAWN_ATTACKS[color.index()][sq.index()]
 }
 
+/// Return every square attacked by a pawn of `color` on `board`.
+///
+/// Shifts the whole pawn bitboard at once instead of looking up each pawn;
+/// see [`Bitboard::pawn_attacks_set`].
+#[inline]
+pub fn pawn_attacks_all(board: &Board, color: Color) -> Bitboard {
+    (board.pieces(PieceKind::Pawn) & board.side(color)).pawn_attacks_set(color)
+}
+
 /// Return rook attacks from `sq` given `occupied` squares.
 #[inline]
 pub fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
@@ -96,6 +107,22 @@ mod tests {
         assert_eq!(king_attacks(Square::E4).count(), 8);
     }
 
+    #[test]
+    fn pawn_attacks_all_matches_per_pawn_lookup() {
+        for fen in [
+            crate::fen::STARTING_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "4k3/p6p/1p4p1/8/P6P/1P4P1/8/4K3 w - - 0 1",
+        ] {
+            let board: Board = fen.parse().unwrap();
+            for color in Color::ALL {
+                let pawns = board.pieces(PieceKind::Pawn) & board.side(color);
+                let expected = pawns.fold(Bitboard::EMPTY, |acc, sq| acc | pawn_attacks(color, sq));
+                assert_eq!(pawn_attacks_all(&board, color), expected, "{fen} {color:?}");
+            }
+        }
+    }
+
     #[test]
     fn pawn_no_wrap_file_a() {
         // White pawn on A4 should attack B5 only (not wrap to H-file).
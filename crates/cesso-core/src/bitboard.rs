@@ -3,6 +3,7 @@
 use std::fmt;
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul, Not, Shl, Shr};
 
+use crate::color::Color;
 use crate::file::File;
 use crate::rank::Rank;
 use crate::square::Square;
@@ -50,6 +51,11 @@ impl Bitboard {
         Self::FILE_E, Self::FILE_F, Self::FILE_G, Self::FILE_H,
     ];
 
+    /// Light squares (h1, a8, ...).
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55AA_55AA_55AA_55AA);
+    /// Dark squares (a1, h8, ...).
+    pub const DARK_SQUARES: Bitboard = Bitboard(!Self::LIGHT_SQUARES.0);
+
     /// Create a bitboard from a raw `u64`.
     #[inline]
     pub const fn new(bits: u64) -> Bitboard {
@@ -125,6 +131,33 @@ impl Bitboard {
         }
     }
 
+    /// Return the most significant set bit as a square, or `None` if empty.
+    #[inline]
+    pub const fn msb(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(Square::from_index_unchecked(63 - self.0.leading_zeros() as u8))
+        }
+    }
+
+    /// Return the highest set square (rank first, then file), or `None` if empty.
+    ///
+    /// Alias for [`msb`](Self::msb), for code that walks the board from rank 8 down.
+    #[inline]
+    pub const fn highest_square(self) -> Option<Square> {
+        self.msb()
+    }
+
+    /// Pop the most significant set bit, returning the square and the remaining bitboard.
+    #[inline]
+    pub const fn pop_msb(self) -> Option<(Square, Bitboard)> {
+        match self.msb() {
+            Some(sq) => Some((sq, Bitboard(self.0 ^ (1u64 << sq.index())))),
+            None => None,
+        }
+    }
+
     /// Return the rank mask for the given rank.
     #[inline]
     pub const fn rank_mask(rank: Rank) -> Bitboard {
@@ -136,6 +169,77 @@ impl Bitboard {
     pub const fn file_mask(file: File) -> Bitboard {
         Self::FILES[file.index()]
     }
+
+    // --- Directional shifts ---
+    //
+    // Each moves every square one step; squares stepped off the board are
+    // dropped rather than wrapping to the opposite edge.
+
+    /// Shift every square one rank up (towards rank 8).
+    #[inline]
+    pub const fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    /// Shift every square one rank down (towards rank 1).
+    #[inline]
+    pub const fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    /// Shift every square one file right (towards the h-file).
+    #[inline]
+    pub const fn east(self) -> Bitboard {
+        Bitboard((self.0 << 1) & !Self::FILE_A.0)
+    }
+
+    /// Shift every square one file left (towards the a-file).
+    #[inline]
+    pub const fn west(self) -> Bitboard {
+        Bitboard((self.0 >> 1) & !Self::FILE_H.0)
+    }
+
+    /// Shift every square one step up and right.
+    #[inline]
+    pub const fn north_east(self) -> Bitboard {
+        Bitboard((self.0 << 9) & !Self::FILE_A.0)
+    }
+
+    /// Shift every square one step up and left.
+    #[inline]
+    pub const fn north_west(self) -> Bitboard {
+        Bitboard((self.0 << 7) & !Self::FILE_H.0)
+    }
+
+    /// Shift every square one step down and right.
+    #[inline]
+    pub const fn south_east(self) -> Bitboard {
+        Bitboard((self.0 >> 7) & !Self::FILE_A.0)
+    }
+
+    /// Shift every square one step down and left.
+    #[inline]
+    pub const fn south_west(self) -> Bitboard {
+        Bitboard((self.0 >> 9) & !Self::FILE_H.0)
+    }
+
+    /// Shift every square one rank towards `color`'s promotion rank (a pawn push).
+    #[inline]
+    pub const fn forward(self, color: Color) -> Bitboard {
+        match color {
+            Color::White => self.north(),
+            Color::Black => self.south(),
+        }
+    }
+
+    /// Return every square attacked by a `color` pawn on any square of `self`.
+    #[inline]
+    pub const fn pawn_attacks_set(self, color: Color) -> Bitboard {
+        match color {
+            Color::White => Bitboard(self.north_east().0 | self.north_west().0),
+            Color::Black => Bitboard(self.south_east().0 | self.south_west().0),
+        }
+    }
 }
 
 // --- Operator impls ---
@@ -266,6 +370,8 @@ impl fmt::Debug for Bitboard {
 #[cfg(test)]
 mod tests {
     use super::Bitboard;
+    use crate::attacks::pawn_attacks;
+    use crate::color::Color;
     use crate::file::File;
     use crate::rank::Rank;
     use crate::square::Square;
@@ -355,6 +461,29 @@ mod tests {
         assert!(rest2.is_empty());
     }
 
+    #[test]
+    fn msb() {
+        assert_eq!(Bitboard::EMPTY.msb(), None);
+        let bb = Bitboard::EMPTY.with(Square::C3).with(Square::F6).with(Square::B6);
+        assert_eq!(bb.msb(), Some(Square::F6));
+        assert_eq!(bb.highest_square(), bb.msb());
+        assert_ne!(bb.msb(), bb.lsb());
+        assert_eq!(Square::H8.bitboard().msb(), Some(Square::H8));
+        assert_eq!(Square::A1.bitboard().msb(), Square::A1.bitboard().lsb());
+    }
+
+    #[test]
+    fn pop_msb() {
+        let bb = Bitboard::EMPTY.with(Square::A1).with(Square::H8);
+        let (sq, rest) = bb.pop_msb().unwrap();
+        assert_eq!(sq, Square::H8);
+        assert_eq!(rest, Square::A1.bitboard());
+        let (sq2, rest2) = rest.pop_msb().unwrap();
+        assert_eq!(sq2, Square::A1);
+        assert!(rest2.is_empty());
+        assert_eq!(rest2.pop_msb(), None);
+    }
+
     #[test]
     fn iterator_order_and_count() {
         let bb = Bitboard::EMPTY
@@ -393,6 +522,38 @@ mod tests {
         assert_eq!(back, Bitboard::RANK_1);
     }
 
+    #[test]
+    fn directional_shifts_drop_edge_squares() {
+        assert_eq!(Bitboard::FILE_A.west(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::FILE_H.east(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::RANK_8.north(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::RANK_1.south(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::FILE_A.north_west(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::FILE_H.south_east(), Bitboard::EMPTY);
+
+        assert_eq!(Bitboard::FILE_A.east(), Bitboard::FILE_B);
+        assert_eq!(Bitboard::RANK_1.north(), Bitboard::RANK_2);
+        assert_eq!(Square::E4.bitboard().north_east(), Square::F5.bitboard());
+        assert_eq!(Square::E4.bitboard().south_west(), Square::D3.bitboard());
+        assert_eq!(Square::E2.bitboard().forward(Color::White), Square::E3.bitboard());
+        assert_eq!(Square::E7.bitboard().forward(Color::Black), Square::E6.bitboard());
+    }
+
+    #[test]
+    fn pawn_attacks_set_matches_per_square_attacks() {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        for _ in 0..200 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let pawns = Bitboard::new(state);
+            for color in [Color::White, Color::Black] {
+                let expected = pawns.fold(Bitboard::EMPTY, |acc, sq| acc | pawn_attacks(color, sq));
+                assert_eq!(pawns.pawn_attacks_set(color), expected, "{pawns:?} {color:?}");
+            }
+        }
+    }
+
     #[test]
     fn default_is_empty() {
         assert_eq!(Bitboard::default(), Bitboard::EMPTY);
@@ -410,4 +571,14 @@ mod tests {
         bb ^= Bitboard::EMPTY.with(Square::A1);
         assert_eq!(bb.count(), 1);
     }
+
+    #[test]
+    fn light_and_dark_squares() {
+        assert_eq!(Bitboard::LIGHT_SQUARES.count(), 32);
+        assert_eq!(Bitboard::LIGHT_SQUARES | Bitboard::DARK_SQUARES, Bitboard::FULL);
+        for sq in Square::all() {
+            assert_eq!(Bitboard::LIGHT_SQUARES.contains(sq), sq.is_light_square(), "{sq}");
+            assert_eq!(Bitboard::DARK_SQUARES.contains(sq), sq.is_dark_square(), "{sq}");
+        }
+    }
 }
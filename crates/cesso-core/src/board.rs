@@ -2,15 +2,46 @@
 
 use std::fmt;
 
+use crate::attacks::pawn_attacks;
 use crate::bitboard::Bitboard;
-use crate::castle_rights::CastleRights;
+use crate::castle_rights::{CastleRights, CastleSide};
 use crate::color::Color;
 use crate::error::BoardError;
+use crate::file::File;
 use crate::piece::Piece;
 use crate::piece_kind::PieceKind;
+use crate::rank::Rank;
 use crate::square::Square;
 use crate::zobrist;
 
+/// How certain a draw by material is; see [`Board::material_draw_class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaterialDraw {
+    /// No sequence of legal moves can end in mate, so the game is drawn by
+    /// rule: KvK, KBvK, KNvK, and KBvKB with bishops on the same colour.
+    Dead,
+    /// Mate is possible only if the defender helps: KNNvK, and KBvKB with
+    /// bishops on opposite colours.
+    Unforceable,
+    /// One minor piece each (KNvKN, KBvKN): either side can blunder into
+    /// mate, but neither can force it in practice.
+    Practical,
+}
+
+/// Bits per piece count in [`Board::material_key`].
+const MATERIAL_COUNT_BITS: u32 = 5;
+
+/// Mask of one piece count in [`Board::material_key`].
+const MATERIAL_COUNT_MASK: u64 = (1 << MATERIAL_COUNT_BITS) - 1;
+
+/// Bit offset of `color`'s `kind` count in [`Board::material_key`].
+const fn material_shift(color: Color, kind: PieceKind) -> u32 {
+    (color.index() * PieceKind::COUNT + kind.index()) as u32 * MATERIAL_COUNT_BITS
+}
+
+/// Mailbox byte of an empty square; any other byte is a [`Piece::raw`].
+const EMPTY_SQUARE: u8 = 0xFF;
+
 /// Complete chess position state.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Board {
@@ -24,7 +55,7 @@ pub struct Board {
     side_to_move: Color,
     /// Current castling rights.
     castling: CastleRights,
-    /// En passant target square, if any.
+    /// En passant target square, kept only while an enemy pawn attacks it.
     en_passant: Option<Square>,
     /// Halfmove clock for the fifty-move rule.
     halfmove_clock: u16,
@@ -40,6 +71,12 @@ pub struct Board {
     major_hash: u64,
     /// Zobrist hash of knights and bishops (both colors).
     minor_hash: u64,
+    /// Per-color count of each piece kind, packed as in [`Board::material_key`].
+    material_key: u64,
+    /// Piece on each square as [`Piece::raw`], or [`EMPTY_SQUARE`]. Mirrors
+    /// the bitboards so square lookups are a single read; packed to a byte
+    /// so copy-make pays for 64 bytes rather than 128.
+    mailbox: [u8; Square::COUNT],
 }
 
 impl Board {
@@ -98,13 +135,10 @@ impl Board {
             non_pawn_hash: [0; 2],
             major_hash: 0,
             minor_hash: 0,
+            material_key: 0,
+            mailbox: [EMPTY_SQUARE; Square::COUNT],
         };
-        board.hash = zobrist::hash_from_scratch(&board);
-        let (ph, nph, majh, minh) = zobrist::partial_hashes_from_scratch(&board);
-        board.set_pawn_hash(ph);
-        board.set_non_pawn_hash(nph);
-        board.set_major_hash(majh);
-        board.set_minor_hash(minh);
+        board.recompute_hashes();
         board
     }
 
@@ -125,7 +159,7 @@ impl Board {
         major_hash: u64,
         minor_hash: u64,
     ) -> Board {
-        Board {
+        let mut board = Board {
             pieces,
             sides,
             occupied,
@@ -139,21 +173,24 @@ impl Board {
             non_pawn_hash,
             major_hash,
             minor_hash,
-        }
+            material_key: 0,
+            mailbox: [EMPTY_SQUARE; Square::COUNT],
+        };
+        board.material_key = board.material_key_from_scratch();
+        board.mailbox = board.mailbox_from_scratch();
+        board
     }
 
     /// Return the piece kind on the given square, if any.
+    #[inline]
     pub fn piece_on(&self, sq: Square) -> Option<PieceKind> {
-        PieceKind::ALL
-            .into_iter()
-            .find(|&kind| self.pieces[kind.index()].contains(sq))
+        self.colored_piece_on(sq).map(Piece::kind)
     }
 
     /// Return the color of the piece on the given square, if any.
+    #[inline]
     pub fn color_on(&self, sq: Square) -> Option<Color> {
-        Color::ALL
-            .into_iter()
-            .find(|&color| self.sides[color.index()].contains(sq))
+        self.colored_piece_on(sq).map(Piece::color)
     }
 
     /// Return the bitboard for the given piece kind (both colors).
@@ -205,6 +242,10 @@ impl Board {
     }
 
     /// Return the en passant target square, if any.
+    ///
+    /// The square is recorded only when a pawn of the side to move attacks
+    /// it, so a double push nobody can capture leaves no trace in the
+    /// position or its hash. The capture may still be illegal because of a pin.
     #[inline]
     pub fn en_passant(&self) -> Option<Square> {
         self.en_passant
@@ -275,6 +316,9 @@ impl Board {
     pub(crate) fn xor_minor_hash(&mut self, key: u64) { self.minor_hash ^= key; }
 
     /// Toggle a piece into/out of the board arrays via XOR.
+    ///
+    /// The mailbox follows the piece's bitboard: the square holds the piece
+    /// after a toggle that sets the bit and is empty after one that clears it.
     #[inline]
     #[allow(dead_code)]
     pub(crate) fn toggle_piece(&mut self, sq: Square, kind: PieceKind, color: Color) {
@@ -282,13 +326,20 @@ impl Board {
         self.pieces[kind.index()] = self.pieces[kind.index()] ^ mask;
         self.sides[color.index()] = self.sides[color.index()] ^ mask;
         self.occupied = self.sides[Color::White.index()] | self.sides[Color::Black.index()];
+        self.mailbox[sq.index()] = if self.pieces[kind.index()].contains(sq) {
+            Piece::new(kind, color).raw()
+        } else {
+            EMPTY_SQUARE
+        };
     }
 
     /// Return the colored piece on the given square, if any.
+    #[inline]
     pub fn colored_piece_on(&self, sq: Square) -> Option<Piece> {
-        let kind = self.piece_on(sq)?;
-        let color = self.color_on(sq)?;
-        Some(Piece::new(kind, color))
+        match self.mailbox[sq.index()] {
+            EMPTY_SQUARE => None,
+            raw => Some(Piece::from_raw(raw)),
+        }
     }
 
     /// Toggle a packed piece into/out of the board arrays via XOR.
@@ -304,6 +355,12 @@ impl Board {
         self.en_passant = sq;
     }
 
+    /// Whether a pawn of `by` attacks the en passant square `ep`.
+    #[inline]
+    pub(crate) fn pawn_attacks_en_passant(&self, ep: Square, by: Color) -> bool {
+        (pawn_attacks(by.flip(), ep) & self.pieces(PieceKind::Pawn) & self.side(by)).is_nonempty()
+    }
+
     /// Set the castling rights.
     #[inline]
     pub(crate) fn set_castling(&mut self, rights: CastleRights) {
@@ -328,6 +385,230 @@ impl Board {
         self.fullmove_number = number;
     }
 
+    /// Whether the position is dead by material: KvK, KBvK, KNvK, or KBvKB
+    /// with both bishops on the same colour, the cases GUIs adjudicate as
+    /// drawn under the FIDE dead-position rule.
+    ///
+    /// KNNvK is not included; it cannot be forced, but a mate still exists.
+    pub fn is_insufficient_material(&self) -> bool {
+        self.material_draw_class() == Some(MaterialDraw::Dead)
+    }
+
+    /// Whether the material left is a draw in practice: any of the
+    /// [`material_draw_class`](Self::material_draw_class) cases.
+    pub fn is_material_draw(&self) -> bool {
+        self.material_draw_class().is_some()
+    }
+
+    /// Classify a material-only draw, or `None` if either side still has
+    /// winning material (any pawn, rook, or queen, or two minors against a
+    /// bare king other than two knights).
+    pub fn material_draw_class(&self) -> Option<MaterialDraw> {
+        match self.minor_counts()? {
+            ([0 | 1, 0] | [0, 1], _) => Some(MaterialDraw::Dead),
+            ([1, 1], [0, 0]) => {
+                let bishops = self.pieces(PieceKind::Bishop);
+                let same_colour = (bishops & Bitboard::LIGHT_SQUARES).is_empty()
+                    || (bishops & Bitboard::DARK_SQUARES).is_empty();
+                Some(if same_colour { MaterialDraw::Dead } else { MaterialDraw::Unforceable })
+            }
+            ([2, 0], [2, _]) | ([0, 2], [_, 2]) => Some(MaterialDraw::Unforceable),
+            ([1, 1], _) => Some(MaterialDraw::Practical),
+            _ => None,
+        }
+    }
+
+    /// Minor pieces per side as `([white, black], [white knights, black knights])`,
+    /// or `None` if any pawn, rook, or queen is left.
+    fn minor_counts(&self) -> Option<([u32; 2], [u32; 2])> {
+        let heavy = self.pieces(PieceKind::Pawn) | self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen);
+        if heavy.is_nonempty() {
+            return None;
+        }
+        let count = |kind: PieceKind, color: Color| (self.pieces(kind) & self.side(color)).count();
+        let knights = [count(PieceKind::Knight, Color::White), count(PieceKind::Knight, Color::Black)];
+        let bishops = [count(PieceKind::Bishop, Color::White), count(PieceKind::Bishop, Color::Black)];
+        Some(([knights[0] + bishops[0], knights[1] + bishops[1]], knights))
+    }
+
+    /// Return the colour-mirrored position: every piece changes colour and
+    /// moves to the same file on the opposite rank, the side to move flips,
+    /// and each side inherits the other's castling rights.
+    ///
+    /// The result is the same game seen from the other side, so a symmetric
+    /// evaluation scores both boards identically from the side to move.
+    pub fn flip_color(&self) -> Board {
+        let mirror = |bb: Bitboard| Bitboard::new(bb.inner().swap_bytes());
+        let mirror_sq = |sq: Square| Square::new(Rank::ALL[7 - sq.rank().index()], sq.file());
+
+        let mut castling = CastleRights::NONE;
+        for color in Color::ALL {
+            for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+                if self.castling.has(color, side) {
+                    castling = castling.with_rook(color.flip(), side, self.castling.rook_file(color, side));
+                }
+            }
+        }
+
+        let mut board = Board {
+            pieces: self.pieces.map(mirror),
+            sides: [mirror(self.sides[Color::Black.index()]), mirror(self.sides[Color::White.index()])],
+            occupied: mirror(self.occupied),
+            side_to_move: self.side_to_move.flip(),
+            castling,
+            en_passant: self.en_passant.map(mirror_sq),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: 0,
+            pawn_hash: 0,
+            non_pawn_hash: [0; 2],
+            major_hash: 0,
+            minor_hash: 0,
+            material_key: 0,
+            mailbox: [EMPTY_SQUARE; Square::COUNT],
+        };
+        board.recompute_hashes();
+        board
+    }
+
+    /// Return the position reflected across the d/e file boundary: every
+    /// piece moves to the mirrored file on the same rank, as does the en
+    /// passant square.
+    ///
+    /// Castling rights are kept in the Chess960 sense: a right with the rook
+    /// on file `f` becomes a right on the other side with the rook on the
+    /// mirrored file, since the king still stands between its rooks. Castling
+    /// itself is not symmetric (the king always lands on the c or g file), so
+    /// only positions without castling rights mirror exactly.
+    pub fn mirror_horizontal(&self) -> Board {
+        let mirror = |bb: Bitboard| Bitboard::new(bb.inner().reverse_bits().swap_bytes());
+        let mirror_sq = |sq: Square| Square::new(sq.rank(), File::ALL[7 - sq.file().index()]);
+
+        let mut castling = CastleRights::NONE;
+        for color in Color::ALL {
+            for (side, other) in [(CastleSide::KingSide, CastleSide::QueenSide), (CastleSide::QueenSide, CastleSide::KingSide)] {
+                if self.castling.has(color, side) {
+                    let rook_file = File::ALL[7 - self.castling.rook_file(color, side).index()];
+                    castling = castling.with_rook(color, other, rook_file);
+                }
+            }
+        }
+
+        let mut board = Board {
+            pieces: self.pieces.map(mirror),
+            sides: self.sides.map(mirror),
+            occupied: mirror(self.occupied),
+            castling,
+            en_passant: self.en_passant.map(mirror_sq),
+            ..*self
+        };
+        board.recompute_hashes();
+        board
+    }
+
+    /// Recompute the full and partial Zobrist hashes, and the material key,
+    /// from scratch.
+    pub(crate) fn recompute_hashes(&mut self) {
+        self.hash = zobrist::hash_from_scratch(self);
+        let (ph, nph, majh, minh) = zobrist::partial_hashes_from_scratch(self);
+        self.pawn_hash = ph;
+        self.non_pawn_hash = nph;
+        self.major_hash = majh;
+        self.minor_hash = minh;
+        self.material_key = self.material_key_from_scratch();
+        self.mailbox = self.mailbox_from_scratch();
+    }
+
+    /// Return the material signature: the number of pieces of each kind and
+    /// color, five bits per count, White's six kinds in [`PieceKind`] order
+    /// followed by Black's.
+    ///
+    /// Maintained incrementally by [`Board::make_move`], so positions with
+    /// the same material have the same key. Counts above 31 (impossible from
+    /// the starting position) would overflow into the neighbouring field.
+    #[inline]
+    pub fn material_key(&self) -> u64 {
+        self.material_key
+    }
+
+    /// Return the number of `color` pieces of `kind`, from the material key.
+    #[inline]
+    pub fn piece_count(&self, color: Color, kind: PieceKind) -> u32 {
+        ((self.material_key >> material_shift(color, kind)) & MATERIAL_COUNT_MASK) as u32
+    }
+
+    /// Return the number of pieces on the board, kings included, from the
+    /// material key.
+    #[inline]
+    pub fn piece_total(&self) -> u32 {
+        let mut key = self.material_key;
+        let mut total = 0;
+        while key != 0 {
+            total += (key & MATERIAL_COUNT_MASK) as u32;
+            key >>= MATERIAL_COUNT_BITS;
+        }
+        total
+    }
+
+    /// Return the non-pawn material phase of both sides: knights and
+    /// bishops count 1, rooks 2 and queens 4, for 24 in the starting
+    /// position. Promotions can push it higher.
+    #[inline]
+    pub fn phase(&self) -> i32 {
+        Color::ALL
+            .into_iter()
+            .map(|color| {
+                self.piece_count(color, PieceKind::Knight)
+                    + self.piece_count(color, PieceKind::Bishop)
+                    + self.piece_count(color, PieceKind::Rook) * 2
+                    + self.piece_count(color, PieceKind::Queen) * 4
+            })
+            .sum::<u32>() as i32
+    }
+
+    /// Count one more `color` piece of `kind` in the material key.
+    #[inline]
+    pub(crate) fn add_material(&mut self, color: Color, kind: PieceKind) {
+        self.material_key += 1 << material_shift(color, kind);
+    }
+
+    /// Count one fewer `color` piece of `kind` in the material key.
+    #[inline]
+    pub(crate) fn remove_material(&mut self, color: Color, kind: PieceKind) {
+        self.material_key -= 1 << material_shift(color, kind);
+    }
+
+    /// Set the material key, as saved before a move.
+    #[inline]
+    pub(crate) fn set_material_key(&mut self, key: u64) {
+        self.material_key = key;
+    }
+
+    /// Build the material key by counting the piece bitboards.
+    pub(crate) fn material_key_from_scratch(&self) -> u64 {
+        let mut key = 0;
+        for color in Color::ALL {
+            for kind in PieceKind::ALL {
+                let count = (self.pieces(kind) & self.side(color)).count() as u64;
+                key |= count.min(MATERIAL_COUNT_MASK) << material_shift(color, kind);
+            }
+        }
+        key
+    }
+
+    /// Build the mailbox by scanning the piece bitboards.
+    fn mailbox_from_scratch(&self) -> [u8; Square::COUNT] {
+        let mut mailbox = [EMPTY_SQUARE; Square::COUNT];
+        for color in Color::ALL {
+            for kind in PieceKind::ALL {
+                for sq in self.pieces(kind) & self.side(color) {
+                    mailbox[sq.index()] = Piece::new(kind, color).raw();
+                }
+            }
+        }
+        mailbox
+    }
+
     /// Validate the structural integrity of the board.
     pub fn validate(&self) -> Result<(), BoardError> {
         // Check exactly one king per side
@@ -371,6 +652,100 @@ impl Board {
             return Err(BoardError::InconsistentOccupied);
         }
 
+        // Check the mailbox agrees with the bitboards
+        let expected_mailbox = self.mailbox_from_scratch();
+        if let Some(square) = Square::all().find(|sq| self.mailbox[sq.index()] != expected_mailbox[sq.index()]) {
+            return Err(BoardError::InconsistentMailbox { square });
+        }
+
+        Ok(())
+    }
+
+    /// Validate the board as a position reachable in a real game.
+    ///
+    /// Runs [`validate`](Self::validate) first, then checks that:
+    /// - the side not to move is not in check;
+    /// - neither side has more than 8 pawns, nor more knights, bishops,
+    ///   rooks and queens than its missing pawns could have promoted to;
+    /// - the en passant square, if any, is empty and sits behind a pawn of
+    ///   the side that just moved, with the pawn's start square empty;
+    /// - every castling right has its king on the back rank and a rook of
+    ///   the same color on the right's file, on the matching side of the king.
+    pub fn validate_legality(&self) -> Result<(), BoardError> {
+        self.validate()?;
+
+        let color_name = |color: Color| match color {
+            Color::White => "white",
+            Color::Black => "black",
+        };
+
+        let them = self.side_to_move.flip();
+        let their_king = self.king_square(them);
+        if (self.attackers_to(their_king, self.occupied) & self.side(self.side_to_move)).is_nonempty() {
+            return Err(BoardError::OpponentInCheck { color: color_name(them) });
+        }
+
+        for color in Color::ALL {
+            let count = |kind: PieceKind| (self.pieces(kind) & self.side(color)).count();
+            let pawns = count(PieceKind::Pawn);
+            if pawns > 8 {
+                return Err(BoardError::TooManyPawns { color: color_name(color), count: pawns });
+            }
+            let promoted = count(PieceKind::Knight).saturating_sub(2)
+                + count(PieceKind::Bishop).saturating_sub(2)
+                + count(PieceKind::Rook).saturating_sub(2)
+                + count(PieceKind::Queen).saturating_sub(1);
+            if promoted > 8 - pawns {
+                return Err(BoardError::TooManyPieces {
+                    color: color_name(color),
+                    promoted,
+                    missing: 8 - pawns,
+                });
+            }
+        }
+
+        if let Some(ep) = self.en_passant {
+            // The pawn moved from `start` over `ep` to `pawn`
+            let (ep_rank, pawn, start) = match them {
+                Color::White => (Rank::Rank3, ep.index() + 8, ep.index().wrapping_sub(8)),
+                Color::Black => (Rank::Rank6, ep.index().wrapping_sub(8), ep.index() + 8),
+            };
+            let square = |idx: usize| Square::from_index(idx as u8);
+            let pushed = square(pawn).is_some_and(|sq| {
+                self.piece_on(sq) == Some(PieceKind::Pawn) && self.color_on(sq) == Some(them)
+            });
+            let start_empty = square(start).is_some_and(|sq| !self.is_occupied(sq));
+            if ep.rank() != ep_rank || self.is_occupied(ep) || !pushed || !start_empty {
+                return Err(BoardError::InvalidEnPassant { square: ep });
+            }
+        }
+
+        for color in Color::ALL {
+            let back_rank = match color {
+                Color::White => Rank::Rank1,
+                Color::Black => Rank::Rank8,
+            };
+            let king = self.king_square(color);
+            for (side, side_name) in [(CastleSide::KingSide, "kingside"), (CastleSide::QueenSide, "queenside")] {
+                if !self.castling.has(color, side) {
+                    continue;
+                }
+                let invalid = |missing| BoardError::InvalidCastling { color: color_name(color), side: side_name, missing };
+                if king.rank() != back_rank {
+                    return Err(invalid("king"));
+                }
+                let rook_file = self.castling.rook_file(color, side);
+                let rook = Square::new(back_rank, rook_file);
+                let on_side = match side {
+                    CastleSide::KingSide => rook_file.index() > king.file().index(),
+                    CastleSide::QueenSide => rook_file.index() < king.file().index(),
+                };
+                if !on_side || self.piece_on(rook) != Some(PieceKind::Rook) || self.color_on(rook) != Some(color) {
+                    return Err(invalid("rook"));
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -396,11 +771,7 @@ impl fmt::Display for PrettyBoard<'_> {
             write!(f, "{}  ", rank_idx + 1)?;
             for file_idx in 0u8..8 {
                 let sq = Square::from_index(rank_idx * 8 + file_idx).unwrap();
-                let c = match (board.piece_on(sq), board.color_on(sq)) {
-                    (Some(kind), Some(Color::White)) => kind.fen_char().to_ascii_uppercase(),
-                    (Some(kind), Some(Color::Black)) => kind.fen_char(),
-                    _ => '.',
-                };
+                let c = board.colored_piece_on(sq).map_or('.', Piece::fen_char);
                 if file_idx < 7 {
                     write!(f, "{c} ")?;
                 } else {
@@ -415,8 +786,12 @@ impl fmt::Display for PrettyBoard<'_> {
 
 #[cfg(test)]
 mod tests {
-    use super::Board;
+    use super::{Board, MaterialDraw};
+    use crate::builder::BoardBuilder;
+    use crate::chess_move::Move;
     use crate::color::Color;
+    use crate::error::BoardError;
+    use crate::movegen::generate_legal_moves;
     use crate::piece::Piece;
     use crate::piece_kind::PieceKind;
     use crate::square::Square;
@@ -427,6 +802,29 @@ mod tests {
         board.validate().unwrap();
     }
 
+    #[test]
+    fn validate_legality_checks_castling_pieces() {
+        use crate::castle_rights::CastleRights;
+        use crate::error::BoardError;
+
+        Board::starting_position().validate_legality().unwrap();
+        let no_rook = kings().castling(CastleRights::WHITE_KING).build().unwrap();
+        assert_eq!(
+            no_rook.validate_legality(),
+            Err(BoardError::InvalidCastling { color: "white", side: "kingside", missing: "rook" })
+        );
+        let wrong_side = kings().piece(Square::A1, Piece::WHITE_ROOK).castling(CastleRights::WHITE_KING).build().unwrap();
+        assert!(matches!(wrong_side.validate_legality(), Err(BoardError::InvalidCastling { missing: "rook", .. })));
+        let moved_king = BoardBuilder::default()
+            .piece(Square::E2, Piece::WHITE_KING)
+            .piece(Square::H1, Piece::WHITE_ROOK)
+            .piece(Square::E8, Piece::BLACK_KING)
+            .castling(CastleRights::WHITE_KING)
+            .build()
+            .unwrap();
+        assert!(matches!(moved_king.validate_legality(), Err(BoardError::InvalidCastling { missing: "king", .. })));
+    }
+
     #[test]
     fn starting_position_piece_on() {
         let board = Board::starting_position();
@@ -473,6 +871,34 @@ mod tests {
         assert_eq!(board.color_on(Square::E4), Some(Color::White));
     }
 
+    #[test]
+    fn mailbox_follows_make_and_unmake() {
+        // Captures, promotions, en passant and castling on both wings.
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+            let start = board(fen);
+            for mv in generate_legal_moves(&start).as_slice() {
+                let child = start.make_move(*mv);
+                assert_eq!(child.validate(), Ok(()), "{fen} {mv}");
+
+                let mut undone = start;
+                let undo = undone.make_move_in_place(*mv);
+                undone.unmake_move(*mv, undo);
+                assert_eq!(undone, start, "{fen} {mv}");
+            }
+        }
+    }
+
+    #[test]
+    fn validate_catches_stale_mailbox() {
+        let mut board = Board::starting_position();
+        board.mailbox[Square::E4.index()] = Piece::WHITE_QUEEN.raw();
+        assert_eq!(board.validate(), Err(BoardError::InconsistentMailbox { square: Square::E4 }));
+    }
+
     #[test]
     fn pretty_print() {
         let board = Board::starting_position();
@@ -490,4 +916,156 @@ mod tests {
         assert_eq!(board.colored_piece_on(Square::D1), Some(Piece::WHITE_QUEEN));
         assert_eq!(board.colored_piece_on(Square::E4), None);
     }
+
+    fn board(fen: &str) -> Board {
+        fen.parse().unwrap()
+    }
+
+    /// Kings on e1 and e8, White to move.
+    fn kings() -> BoardBuilder {
+        BoardBuilder::default()
+            .piece(Square::E1, Piece::WHITE_KING)
+            .piece(Square::E8, Piece::BLACK_KING)
+    }
+
+    #[test]
+    fn flip_color_mirrors_the_position() {
+        let board: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K1R1 b Qkq - 0 1".parse().unwrap();
+        let flipped = board.flip_color();
+        assert_eq!(
+            flipped.to_string(),
+            "r3k1r1/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R w KQq - 0 1"
+        );
+        assert_eq!(flipped.hash(), crate::zobrist::hash_from_scratch(&flipped));
+        assert_eq!(flipped.flip_color(), board);
+    }
+
+    #[test]
+    fn material_key_tracks_captures_and_promotions() {
+        let start = Board::starting_position();
+        assert_eq!(start.piece_count(Color::White, PieceKind::Pawn), 8);
+        assert_eq!(start.piece_count(Color::Black, PieceKind::Knight), 2);
+        assert_eq!(start.piece_count(Color::Black, PieceKind::King), 1);
+        assert_eq!(start.phase(), 24);
+        assert_eq!(start.piece_total(), 32);
+        assert_eq!(start.material_key(), start.flip_color().material_key());
+
+        // Promoting with a capture: the pawn becomes a queen and a rook goes
+        let before = board("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        let after = before.make_move(Move::from_uci_legal("a7b8q", &before).unwrap());
+        assert_eq!(after.piece_count(Color::White, PieceKind::Pawn), 0);
+        assert_eq!(after.piece_count(Color::White, PieceKind::Queen), 1);
+        assert_eq!(after.piece_count(Color::Black, PieceKind::Rook), 0);
+        assert_eq!((before.phase(), after.phase()), (2, 4));
+        assert_eq!((before.piece_total(), after.piece_total()), (4, 3));
+        assert_eq!(after.material_key(), board("1Q2k3/8/8/8/8/8/8/4K3 b - - 0 1").material_key());
+
+        // En passant removes the captured pawn
+        let ep = board("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        let after = ep.make_move(Move::from_uci_legal("e5d6", &ep).unwrap());
+        assert_eq!(after.piece_count(Color::Black, PieceKind::Pawn), 0);
+        assert_eq!(after.piece_count(Color::White, PieceKind::Pawn), 1);
+    }
+
+    #[test]
+    fn mirror_horizontal_reflects_files() {
+        let start = board("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K1R1 b Qkq - 0 1");
+        let mirrored = start.mirror_horizontal();
+        assert_eq!(mirrored.to_string(), "r2k3r/1bpqpp1p/1pnp2nb/3NP3/3P2p1/p1Q2N2/PPPBBPPP/1R1K3R b Kkq - 0 1");
+        assert_eq!(mirrored.hash(), crate::zobrist::hash_from_scratch(&mirrored));
+        assert_eq!(mirrored.mirror_horizontal(), start);
+
+        let en_passant = board("4k3/8/8/8/2Pp4/8/8/4K3 b - c3 0 1");
+        assert_eq!(en_passant.mirror_horizontal().en_passant(), Some(Square::F3));
+    }
+
+    #[test]
+    fn insufficient_material_cases() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",   // KvK
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", // KBvK
+            "4k3/8/8/8/8/8/8/1N2K3 b - - 0 1", // KNvK
+            "4k3/8/8/8/8/8/8/4Kb2 w - - 0 1",  // KvKB
+            "3bk3/8/8/8/8/8/8/2B1K3 w - - 0 1", // KBvKB, both on dark squares
+        ] {
+            let board = board(fen);
+            assert!(board.is_insufficient_material(), "{fen}");
+            assert!(board.is_material_draw(), "{fen}");
+            assert_eq!(board.material_draw_class(), Some(MaterialDraw::Dead), "{fen}");
+        }
+    }
+
+    #[test]
+    fn unforceable_mates_are_not_dead() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1", // KNNvK
+            "1nn1k3/8/8/8/8/8/8/4K3 w - - 0 1", // KvKNN
+            "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1", // KBvKB, opposite colours
+        ] {
+            let board = board(fen);
+            assert!(!board.is_insufficient_material(), "{fen}");
+            assert!(board.is_material_draw(), "{fen}");
+            assert_eq!(board.material_draw_class(), Some(MaterialDraw::Unforceable), "{fen}");
+        }
+    }
+
+    #[test]
+    fn sufficient_material_cases() {
+        for fen in [
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",  // KPvK
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",   // KRvK
+            "4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1", // KBBvK
+            "4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1", // KBNvK
+            "4k2r/8/8/8/8/8/8/2B1K3 w - - 0 1", // KBvKR
+            "4k3/8/8/8/8/8/8/1NN1KN2 w - - 0 1", // KNNNvK
+            "1b2k3/8/8/8/8/8/8/1NB1K3 w - - 0 1", // KBNvKB
+        ] {
+            let board = board(fen);
+            assert!(!board.is_insufficient_material(), "{fen}");
+            assert!(!board.is_material_draw(), "{fen}");
+            assert_eq!(board.material_draw_class(), None, "{fen}");
+        }
+        assert!(!Board::starting_position().is_material_draw());
+    }
+
+    #[test]
+    fn single_minor_each_is_only_a_practical_draw() {
+        for (sq, minor) in [(Square::B1, Piece::WHITE_KNIGHT), (Square::C1, Piece::WHITE_BISHOP)] {
+            let board = kings()
+                .piece(Square::B8, Piece::BLACK_KNIGHT)
+                .piece(sq, minor)
+                .build()
+                .unwrap();
+            assert!(!board.is_insufficient_material(), "{board}");
+            assert!(board.is_material_draw(), "{board}");
+            assert_eq!(board.material_draw_class(), Some(MaterialDraw::Practical), "{board}");
+        }
+    }
+
+    #[test]
+    fn insufficient_material_after_exchanges() {
+        // Rxe7+ Kxe7 leaves bare kings
+        let start = BoardBuilder::default()
+            .piece(Square::G1, Piece::WHITE_KING)
+            .piece(Square::E1, Piece::WHITE_ROOK)
+            .piece(Square::E7, Piece::BLACK_ROOK)
+            .piece(Square::E8, Piece::BLACK_KING)
+            .build()
+            .unwrap();
+        assert!(!start.is_material_draw());
+        let after_rxe7 = start.make_move(Move::from_uci("e1e7", &start).unwrap());
+        assert!(!after_rxe7.is_insufficient_material());
+        let after_kxe7 = after_rxe7.make_move(Move::from_uci("e8e7", &after_rxe7).unwrap());
+        assert!(after_kxe7.is_insufficient_material());
+
+        // Kxd2 turns KBvKN into KBvK
+        let start = kings()
+            .piece(Square::D1, Piece::WHITE_BISHOP)
+            .piece(Square::D2, Piece::BLACK_KNIGHT)
+            .build()
+            .unwrap();
+        assert!(!start.is_insufficient_material());
+        let after_kxd2 = start.make_move(Move::from_uci("e1d2", &start).unwrap());
+        assert!(after_kxd2.is_insufficient_material());
+    }
 }
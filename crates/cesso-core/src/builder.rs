@@ -0,0 +1,221 @@
+//! Programmatic board construction.
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::castle_rights::CastleRights;
+use crate::color::Color;
+use crate::error::BoardError;
+use crate::piece::Piece;
+use crate::piece_kind::PieceKind;
+use crate::square::Square;
+
+/// Builder for arbitrary positions without going through FEN.
+///
+/// Starts from an empty board with White to move, no castling rights, no en
+/// passant square and the move counters at `0` and `1`.
+///
+/// # Examples
+///
+/// ```
+/// use cesso_core::{BoardBuilder, Color, Piece, Square};
+///
+/// let board = BoardBuilder::default()
+///     .piece(Square::E1, Piece::WHITE_KING)
+///     .piece(Square::A1, Piece::WHITE_ROOK)
+///     .piece(Square::E8, Piece::BLACK_KING)
+///     .side_to_move(Color::Black)
+///     .build()
+///     .unwrap();
+/// assert_eq!(board.to_string(), "4k3/8/8/8/8/8/8/R3K3 b - - 0 1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoardBuilder {
+    squares: [Option<Piece>; Square::COUNT],
+    side_to_move: Color,
+    castling: CastleRights,
+    en_passant: Option<Square>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    /// First square that was given two pieces, reported by `build`.
+    doubly_placed: Option<Square>,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        Self {
+            squares: [None; Square::COUNT],
+            side_to_move: Color::White,
+            castling: CastleRights::NONE,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            doubly_placed: None,
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Place `piece` on `sq`. Placing a second piece on the same square makes
+    /// [`build`](Self::build) fail.
+    pub fn piece(mut self, sq: Square, piece: Piece) -> Self {
+        if self.squares[sq.index()].is_some() {
+            self.doubly_placed.get_or_insert(sq);
+        }
+        self.squares[sq.index()] = Some(piece);
+        self
+    }
+
+    /// Side to move (default White).
+    pub fn side_to_move(mut self, color: Color) -> Self {
+        self.side_to_move = color;
+        self
+    }
+
+    /// Castling rights (default none). They are taken as given, so each
+    /// right needs its king and rook in place.
+    pub fn castling(mut self, rights: CastleRights) -> Self {
+        self.castling = rights;
+        self
+    }
+
+    /// En passant target square (default none). As after a move, the square
+    /// is dropped unless a pawn of the side to move can capture onto it.
+    pub fn en_passant(mut self, sq: Option<Square>) -> Self {
+        self.en_passant = sq;
+        self
+    }
+
+    /// Halfmove clock for the fifty-move rule (default 0).
+    pub fn halfmove_clock(mut self, clock: u16) -> Self {
+        self.halfmove_clock = clock;
+        self
+    }
+
+    /// Fullmove number (default 1).
+    pub fn fullmove_number(mut self, number: u16) -> Self {
+        self.fullmove_number = number;
+        self
+    }
+
+    /// Validate the position and build the board with its hashes computed.
+    pub fn build(self) -> Result<Board, BoardError> {
+        if let Some(square) = self.doubly_placed {
+            return Err(BoardError::SquareOccupied { square });
+        }
+
+        let mut pieces = [Bitboard::EMPTY; PieceKind::COUNT];
+        let mut sides = [Bitboard::EMPTY; Color::COUNT];
+        for sq in Square::all() {
+            if let Some(piece) = self.squares[sq.index()] {
+                pieces[piece.kind().index()] |= sq.bitboard();
+                sides[piece.color().index()] |= sq.bitboard();
+            }
+        }
+        let occupied = sides[Color::White.index()] | sides[Color::Black.index()];
+
+        let mut board = Board::from_raw(
+            pieces,
+            sides,
+            occupied,
+            self.side_to_move,
+            self.castling,
+            self.en_passant,
+            self.halfmove_clock,
+            self.fullmove_number,
+            0,      // placeholder hash
+            0,      // placeholder pawn_hash
+            [0; 2], // placeholder non_pawn_hash
+            0,      // placeholder major_hash
+            0,      // placeholder minor_hash
+        );
+        board.validate()?;
+
+        if let Some(ep) = board.en_passant()
+            && !board.pawn_attacks_en_passant(ep, self.side_to_move)
+        {
+            board.set_en_passant(None);
+        }
+
+        board.recompute_hashes();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::STARTING_FEN;
+    use crate::zobrist;
+
+    #[test]
+    fn builds_the_starting_position() {
+        let back_rank = [
+            PieceKind::Rook,
+            PieceKind::Knight,
+            PieceKind::Bishop,
+            PieceKind::Queen,
+            PieceKind::King,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            PieceKind::Rook,
+        ];
+        let mut builder = BoardBuilder::default().castling(CastleRights::ALL);
+        for (file, kind) in back_rank.into_iter().enumerate() {
+            builder = builder
+                .piece(Square::from_index(file as u8).unwrap(), Piece::new(kind, Color::White))
+                .piece(Square::from_index(8 + file as u8).unwrap(), Piece::WHITE_PAWN)
+                .piece(Square::from_index(48 + file as u8).unwrap(), Piece::BLACK_PAWN)
+                .piece(Square::from_index(56 + file as u8).unwrap(), Piece::new(kind, Color::Black));
+        }
+        let board = builder.build().unwrap();
+        assert_eq!(board.to_string(), STARTING_FEN);
+        assert_eq!(board, Board::starting_position());
+    }
+
+    #[test]
+    fn rejects_two_pieces_on_one_square() {
+        let result = BoardBuilder::default()
+            .piece(Square::E1, Piece::WHITE_KING)
+            .piece(Square::E8, Piece::BLACK_KING)
+            .piece(Square::D4, Piece::WHITE_KNIGHT)
+            .piece(Square::D4, Piece::BLACK_BISHOP)
+            .build();
+        assert_eq!(result, Err(BoardError::SquareOccupied { square: Square::D4 }));
+    }
+
+    #[test]
+    fn rejects_a_missing_king() {
+        let result = BoardBuilder::default().piece(Square::E1, Piece::WHITE_KING).build();
+        assert_eq!(result, Err(BoardError::InvalidKingCount { color: "black", count: 0 }));
+    }
+
+    #[test]
+    fn matches_the_fen_parser() {
+        let board = BoardBuilder::default()
+            .piece(Square::E1, Piece::WHITE_KING)
+            .piece(Square::E5, Piece::WHITE_PAWN)
+            .piece(Square::D5, Piece::BLACK_PAWN)
+            .piece(Square::E8, Piece::BLACK_KING)
+            .en_passant(Some(Square::D6))
+            .halfmove_clock(0)
+            .fullmove_number(3)
+            .build()
+            .unwrap();
+        assert_eq!(board, "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 3".parse().unwrap());
+    }
+
+    #[test]
+    fn drops_an_uncapturable_en_passant_square() {
+        let board = BoardBuilder::default()
+            .piece(Square::E1, Piece::WHITE_KING)
+            .piece(Square::D5, Piece::BLACK_PAWN)
+            .piece(Square::E8, Piece::BLACK_KING)
+            .en_passant(Some(Square::D6))
+            .halfmove_clock(42)
+            .build()
+            .unwrap();
+        assert_eq!(board.en_passant(), None);
+        assert_eq!(board.halfmove_clock(), 42);
+        assert_eq!(board.hash(), zobrist::hash_from_scratch(&board));
+    }
+}
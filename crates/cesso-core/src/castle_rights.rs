@@ -1,10 +1,11 @@
-//! Castling rights stored as a 4-bit field within a `u8`.
+//! Castling rights stored as a 4-bit field plus the rook start file of each right.
 
 use std::fmt;
 use std::ops::{BitAnd, BitOr, Not};
 
 use crate::color::Color;
 use crate::error::FenError;
+use crate::file::File;
 
 /// Which side of the board to castle toward.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -13,70 +14,116 @@ pub enum CastleSide {
     QueenSide,
 }
 
-/// Castling rights encoded as a 4-bit field: bit 0 = WK, 1 = WQ, 2 = BK, 3 = BQ.
+/// Bits 0-3 of the packed field: the four rights.
+const FLAGS: u16 = 0b1111;
+/// Bit offset of the first 3-bit rook file; one file per right, in flag order.
+const FILE_SHIFT: u16 = 4;
+/// Rook files of the classical setup: h, a, h, a.
+const CLASSICAL_FILES: u16 = (7 << FILE_SHIFT) | (7 << (FILE_SHIFT + 6));
+
+/// Castling rights encoded as a 4-bit field (bit 0 = WK, 1 = WQ, 2 = BK,
+/// 3 = BQ) followed by the start file of each right's rook, 3 bits apiece.
+///
+/// The rook files default to the corners; they differ only in Chess960 or
+/// Shredder-FEN positions. Set operations (`insert`, `remove`, `&`, `|`,
+/// `!`) act on the four rights and keep `self`'s rook files.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub struct CastleRights(u8);
+pub struct CastleRights(u16);
 
 impl CastleRights {
     /// No castling rights.
-    pub const NONE: CastleRights = CastleRights(0);
+    pub const NONE: CastleRights = CastleRights(CLASSICAL_FILES);
     /// All castling rights.
-    pub const ALL: CastleRights = CastleRights(0b1111);
+    pub const ALL: CastleRights = CastleRights(CLASSICAL_FILES | 0b1111);
 
     /// White king-side castling.
-    pub const WHITE_KING: CastleRights = CastleRights(0b0001);
+    pub const WHITE_KING: CastleRights = CastleRights(CLASSICAL_FILES | 0b0001);
     /// White queen-side castling.
-    pub const WHITE_QUEEN: CastleRights = CastleRights(0b0010);
+    pub const WHITE_QUEEN: CastleRights = CastleRights(CLASSICAL_FILES | 0b0010);
     /// Black king-side castling.
-    pub const BLACK_KING: CastleRights = CastleRights(0b0100);
+    pub const BLACK_KING: CastleRights = CastleRights(CLASSICAL_FILES | 0b0100);
     /// Black queen-side castling.
-    pub const BLACK_QUEEN: CastleRights = CastleRights(0b1000);
+    pub const BLACK_QUEEN: CastleRights = CastleRights(CLASSICAL_FILES | 0b1000);
 
     /// Both white castling rights.
-    pub const WHITE_BOTH: CastleRights = CastleRights(0b0011);
+    pub const WHITE_BOTH: CastleRights = CastleRights(CLASSICAL_FILES | 0b0011);
     /// Both black castling rights.
-    pub const BLACK_BOTH: CastleRights = CastleRights(0b1100);
+    pub const BLACK_BOTH: CastleRights = CastleRights(CLASSICAL_FILES | 0b1100);
 
-    /// Create castling rights from a raw `u8`, masking to the lower 4 bits.
+    /// Create classical castling rights from a raw `u8`, masking to the lower 4 bits.
     #[inline]
     pub const fn new(bits: u8) -> CastleRights {
-        CastleRights(bits & 0b1111)
+        CastleRights(CLASSICAL_FILES | (bits as u16 & FLAGS))
     }
 
-    /// Return the raw bits.
+    /// Return the raw 4-bit rights, without the rook files.
     #[inline]
     pub const fn bits(self) -> u8 {
-        self.0
+        (self.0 & FLAGS) as u8
     }
 
     /// Return `true` if no castling rights remain.
     #[inline]
     pub const fn is_empty(self) -> bool {
-        self.0 == 0
+        self.0 & FLAGS == 0
     }
 
-    /// Return `true` if all bits in `other` are set in `self`.
+    /// Return `true` if all rights in `other` are set in `self`.
     #[inline]
     pub const fn contains(self, other: CastleRights) -> bool {
-        (self.0 & other.0) == other.0
+        (self.0 & other.0 & FLAGS) == other.0 & FLAGS
     }
 
-    /// Return new rights with all bits from `other` added.
+    /// Return new rights with all rights from `other` added.
     #[inline]
     pub const fn insert(self, other: CastleRights) -> CastleRights {
-        CastleRights(self.0 | other.0)
+        CastleRights(self.0 | (other.0 & FLAGS))
     }
 
-    /// Return new rights with all bits from `other` removed.
+    /// Return new rights with all rights from `other` removed.
     #[inline]
     pub const fn remove(self, other: CastleRights) -> CastleRights {
-        CastleRights(self.0 & !other.0)
+        CastleRights(self.0 & !(other.0 & FLAGS))
+    }
+
+    /// Return the start file of the rook for a color and side.
+    ///
+    /// Meaningful only while the right is held; otherwise it is the file the
+    /// right last referred to (the corner by default).
+    #[inline]
+    pub const fn rook_file(self, color: Color, side: CastleSide) -> File {
+        let shift = FILE_SHIFT + 3 * Self::flag(color, side).0.trailing_zeros() as u16;
+        File::ALL[((self.0 >> shift) & 0b111) as usize]
+    }
+
+    /// Return new rights with the right for `color` and `side` set, castling
+    /// with the rook that starts on `rook_file`.
+    #[inline]
+    pub const fn with_rook(self, color: Color, side: CastleSide, rook_file: File) -> CastleRights {
+        let flag = Self::flag(color, side).0 & FLAGS;
+        let shift = FILE_SHIFT + 3 * flag.trailing_zeros() as u16;
+        let cleared = self.0 & !(0b111 << shift);
+        CastleRights(cleared | flag | ((rook_file.index() as u16) << shift))
+    }
+
+    /// Return `true` if every right held castles with a corner rook.
+    #[inline]
+    pub const fn is_classical(self) -> bool {
+        let files = self.0 ^ CLASSICAL_FILES;
+        let mut i = 0;
+        while i < 4 {
+            if self.0 & (1 << i) != 0 && (files >> (FILE_SHIFT + 3 * i)) & 0b111 != 0 {
+                return false;
+            }
+            i += 1;
+        }
+        true
     }
 
     /// Check whether a specific color and side can castle.
     #[inline]
     pub const fn has(self, color: Color, side: CastleSide) -> bool {
-        let bit = Self::flag(color, side).0;
+        let bit = Self::flag(color, side).0 & FLAGS;
         (self.0 & bit) != 0
     }
 
@@ -89,6 +136,14 @@ impl CastleRights {
         }
     }
 
+    /// Every color and side, in FEN order (K, Q, k, q).
+    const ORDER: [(Color, CastleSide); 4] = [
+        (Color::White, CastleSide::KingSide),
+        (Color::White, CastleSide::QueenSide),
+        (Color::Black, CastleSide::KingSide),
+        (Color::Black, CastleSide::QueenSide),
+    ];
+
     /// Return the single-bit flag for a color and side.
     #[inline]
     const fn flag(color: Color, side: CastleSide) -> CastleRights {
@@ -101,6 +156,10 @@ impl CastleRights {
     }
 
     /// Parse castling rights from the FEN castling field (e.g. "KQkq", "Kq", "-").
+    ///
+    /// Only the classical letters are accepted here, since file letters
+    /// ("HAha") need the king's square to say which side they castle
+    /// towards; the board's FEN parser handles both.
     pub fn from_fen(s: &str) -> Result<CastleRights, FenError> {
         if s == "-" {
             return Ok(CastleRights::NONE);
@@ -121,10 +180,27 @@ impl CastleRights {
     }
 
     /// Serialize castling rights to the FEN castling field.
+    ///
+    /// Classical rights print as "KQkq". If any right castles with a rook
+    /// off the corner, every right prints as its rook's file instead
+    /// (Shredder-FEN, e.g. "GBgb").
     pub fn to_fen(self) -> String {
         if self.is_empty() {
             return "-".to_string();
         }
+        if !self.is_classical() {
+            return Self::ORDER
+                .iter()
+                .filter(|&&(color, side)| self.has(color, side))
+                .map(|&(color, side)| {
+                    let letter = self.rook_file(color, side).to_char();
+                    match color {
+                        Color::White => letter.to_ascii_uppercase(),
+                        Color::Black => letter,
+                    }
+                })
+                .collect();
+        }
 
         let mut s = String::with_capacity(4);
         if self.contains(Self::WHITE_KING) {
@@ -147,7 +223,7 @@ impl BitAnd for CastleRights {
     type Output = CastleRights;
     #[inline]
     fn bitand(self, rhs: CastleRights) -> CastleRights {
-        CastleRights(self.0 & rhs.0)
+        CastleRights(self.0 & (rhs.0 | !FLAGS))
     }
 }
 
@@ -155,7 +231,7 @@ impl BitOr for CastleRights {
     type Output = CastleRights;
     #[inline]
     fn bitor(self, rhs: CastleRights) -> CastleRights {
-        CastleRights(self.0 | rhs.0)
+        self.insert(rhs)
     }
 }
 
@@ -163,7 +239,7 @@ impl Not for CastleRights {
     type Output = CastleRights;
     #[inline]
     fn not(self) -> CastleRights {
-        CastleRights(!self.0 & 0b1111)
+        CastleRights(self.0 ^ FLAGS)
     }
 }
 
@@ -183,6 +259,7 @@ impl fmt::Debug for CastleRights {
 mod tests {
     use super::{CastleRights, CastleSide};
     use crate::color::Color;
+    use crate::file::File;
 
     #[test]
     fn insert_remove_roundtrip() {
@@ -271,4 +348,36 @@ mod tests {
         let rights = CastleRights::new(0xFF);
         assert_eq!(rights.bits(), 0b1111);
     }
+
+    #[test]
+    fn rook_files_default_to_corners() {
+        let rights = CastleRights::ALL;
+        assert_eq!(rights.rook_file(Color::White, CastleSide::KingSide), File::FileH);
+        assert_eq!(rights.rook_file(Color::White, CastleSide::QueenSide), File::FileA);
+        assert_eq!(rights.rook_file(Color::Black, CastleSide::KingSide), File::FileH);
+        assert_eq!(rights.rook_file(Color::Black, CastleSide::QueenSide), File::FileA);
+        assert!(rights.is_classical());
+        assert_eq!(
+            CastleRights::NONE.with_rook(Color::White, CastleSide::KingSide, File::FileH),
+            CastleRights::WHITE_KING
+        );
+    }
+
+    #[test]
+    fn non_corner_rooks_print_as_shredder_fen() {
+        let rights = CastleRights::NONE
+            .with_rook(Color::White, CastleSide::KingSide, File::FileG)
+            .with_rook(Color::White, CastleSide::QueenSide, File::FileA)
+            .with_rook(Color::Black, CastleSide::QueenSide, File::FileB);
+        assert!(!rights.is_classical());
+        assert_eq!(rights.rook_file(Color::White, CastleSide::KingSide), File::FileG);
+        assert_eq!(rights.rook_file(Color::Black, CastleSide::QueenSide), File::FileB);
+        assert_eq!(rights.to_fen(), "GAb");
+        assert_eq!(rights.bits(), 0b1011);
+
+        // Set operations leave the rook files alone.
+        let white = rights & CastleRights::WHITE_BOTH;
+        assert_eq!(white.to_fen(), "GA");
+        assert_eq!(white.remove(CastleRights::WHITE_KING).to_fen(), "Q");
+    }
 }
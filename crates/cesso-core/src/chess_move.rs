@@ -3,7 +3,13 @@
 use std::fmt;
 
 use crate::board::Board;
+use crate::castle_rights::CastleSide;
+use crate::color::Color;
+use crate::error::SanError;
+use crate::file::File;
+use crate::movegen::generate_legal_moves;
 use crate::piece_kind::PieceKind;
+use crate::rank::Rank;
 use crate::square::Square;
 
 // Private bit-field constants.
@@ -86,7 +92,11 @@ impl PromotionPiece {
 /// bits 12-13: promotion piece    (Knight=0, Bishop=1, Rook=2, Queen=3)
 /// bits 14-15: move kind          (Normal=0, Promotion=1, EnPassant=2, Castling=3)
 /// ```
+///
+/// With the `serde` feature a move serializes as this raw `u16`, which,
+/// unlike its UCI string, can be read back without the position.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Move(u16);
 
 impl Move {
@@ -184,6 +194,25 @@ impl Move {
         (self.0 & KIND_MASK) >> KIND_SHIFT == MoveKind::Normal as u16
     }
 
+    /// Return `true` if this move captures on `board`: an enemy piece on the
+    /// destination (promotions included) or en passant.
+    ///
+    /// Castling never captures, even though its destination holds the rook.
+    #[inline]
+    pub fn is_capture(self, board: &Board) -> bool {
+        self.captured_piece(board).is_some()
+    }
+
+    /// Return the kind of piece this move captures on `board`, `Pawn` for en passant.
+    #[inline]
+    pub fn captured_piece(self, board: &Board) -> Option<PieceKind> {
+        match self.kind() {
+            MoveKind::EnPassant => Some(PieceKind::Pawn),
+            MoveKind::Castling => None,
+            MoveKind::Normal | MoveKind::Promotion => board.piece_on(self.dest()),
+        }
+    }
+
     /// Return the raw u16 bit representation.
     #[inline]
     pub const fn raw(self) -> u16 {
@@ -216,15 +245,28 @@ impl Move {
     /// The board is needed to disambiguate castling and en-passant moves from
     /// normal moves, since UCI notation does not encode move kind explicitly.
     ///
-    /// Returns `None` if the string is malformed.
+    /// Returns `None` if the string is malformed, the source square holds no
+    /// piece of the side to move, a promotion is not a pawn stepping from its
+    /// seventh rank to its eighth, or a castling move has no matching right.
+    /// Other moves are not checked against the rules, so "e2e5" still parses;
+    /// moves from outside the engine should go through
+    /// [`from_uci_legal`](Move::from_uci_legal).
     pub fn from_uci(s: &str, board: &Board) -> Option<Move> {
         let len = s.len();
-        if len < 4 || len > 5 {
+        if !(4..=5).contains(&len) {
             return None;
         }
 
         let src = Square::from_algebraic(&s[0..2])?;
         let dst = Square::from_algebraic(&s[2..4])?;
+        let us = board.side_to_move();
+        if board.color_on(src) != Some(us) {
+            return None;
+        }
+        let (back_rank, seventh, eighth) = match us {
+            Color::White => (Rank::Rank1, Rank::Rank7, Rank::Rank8),
+            Color::Black => (Rank::Rank8, Rank::Rank2, Rank::Rank1),
+        };
 
         // If length 5, it is a promotion.
         if len == 5 {
@@ -235,14 +277,27 @@ impl Move {
                 b'n' => PromotionPiece::Knight,
                 _ => return None,
             };
+            if board.piece_on(src) != Some(PieceKind::Pawn) || src.rank() != seventh || dst.rank() != eighth {
+                return None;
+            }
             return Some(Move::new_promotion(src, dst, promo));
         }
 
-        // Castling: king moving exactly 2 files.
+        // Castling: the king from its classical home two files sideways, or
+        // (Chess960 notation) onto its own castling rook.
         if board.piece_on(src) == Some(PieceKind::King) {
-            let file_diff = (src.file().index() as i8 - dst.file().index() as i8).unsigned_abs();
-            if file_diff == 2 {
-                return Some(Move::new_castle(src, dst));
+            let castling = board.castling();
+            for (side, king_dst_file) in [(CastleSide::KingSide, File::FileG), (CastleSide::QueenSide, File::FileC)] {
+                if castling.has(us, side) && dst == Square::new(back_rank, castling.rook_file(us, side)) {
+                    return Some(Move::new_castle(src, Square::new(back_rank, king_dst_file)));
+                }
+            }
+            let home = Square::new(back_rank, File::FileE);
+            if src == home && dst.rank() == back_rank && matches!(dst.file(), File::FileG | File::FileC) {
+                let side = if dst.file() == File::FileG { CastleSide::KingSide } else { CastleSide::QueenSide };
+                // A king cannot otherwise move two files, so without the
+                // right there is no such move
+                return castling.has(us, side).then(|| Move::new_castle(src, dst));
             }
         }
 
@@ -257,6 +312,175 @@ impl Move {
         // Normal move (quiet or capture).
         Some(Move::new(src, dst))
     }
+
+    /// Parse a UCI move string and return it only if it is legal in `board`.
+    ///
+    /// Use this for moves from outside the engine (GUI commands, books,
+    /// files): a move that parses but is not legal would corrupt the board
+    /// when played.
+    pub fn from_uci_legal(s: &str, board: &Board) -> Option<Move> {
+        Move::from_uci(s, board).filter(|&mv| board.is_legal(mv))
+    }
+
+    /// Return the Standard Algebraic Notation for this move (e.g. "Nbxd7+", "O-O", "e8=Q#").
+    ///
+    /// `self` must be legal in `board`. When several pieces of the same kind
+    /// can reach the destination, the source file, rank, or both are added,
+    /// in that order of preference, as the PGN standard requires; pinned
+    /// pieces that cannot legally move there do not count.
+    pub fn to_san(self, board: &Board) -> String {
+        let src = self.source();
+        let dst = self.dest();
+        let mut san = String::new();
+
+        if self.is_castle() {
+            san.push_str(if dst.file() == File::FileG { "O-O" } else { "O-O-O" });
+        } else {
+            let piece = board.piece_on(src).unwrap_or(PieceKind::Pawn);
+            let is_capture = self.is_en_passant() || board.is_occupied(dst);
+
+            if piece == PieceKind::Pawn {
+                if is_capture {
+                    san.push_str(&src.file().to_string());
+                }
+            } else {
+                san.push(piece.fen_char().to_ascii_uppercase());
+                let rivals: Vec<Square> = generate_legal_moves(board)
+                    .as_slice()
+                    .iter()
+                    .filter(|mv| mv.dest() == dst && mv.source() != src)
+                    .filter(|mv| board.piece_on(mv.source()) == Some(piece))
+                    .map(|mv| mv.source())
+                    .collect();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|from| from.file() != src.file()) {
+                        san.push_str(&src.file().to_string());
+                    } else if rivals.iter().all(|from| from.rank() != src.rank()) {
+                        san.push_str(&src.rank().to_string());
+                    } else {
+                        san.push_str(&src.to_string());
+                    }
+                }
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&dst.to_string());
+            if self.is_promotion() {
+                san.push('=');
+                san.push(self.promotion_piece().uci_char().to_ascii_uppercase());
+            }
+        }
+
+        let child = board.make_move(self);
+        let them = child.side_to_move();
+        if child.is_square_attacked(child.king_square(them), !them) {
+            san.push(if child.has_legal_moves() { '+' } else { '#' });
+        }
+        san
+    }
+
+    /// Parse a move in Standard Algebraic Notation (e.g. "Nbd7", "exd5", "O-O-O+", "e8=N").
+    ///
+    /// Check, mate and annotation suffixes ("+", "#", "!", "?") are ignored,
+    /// castling may be written with letters or zeros, and a promotion piece
+    /// may follow the square with or without "=". The string must pick out
+    /// exactly one legal move.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanError::Malformed`] if the string cannot be a SAN move,
+    /// [`SanError::NoSuchMove`] if no legal move matches, and
+    /// [`SanError::Ambiguous`] if more than one does.
+    pub fn from_san(s: &str, board: &Board) -> Result<Move, SanError> {
+        let malformed = || SanError::Malformed { san: s.to_string() };
+        let body = s.trim().trim_end_matches(['+', '#', '!', '?']);
+        let body = body.strip_suffix("e.p.").unwrap_or(body).trim_end();
+        let legal = generate_legal_moves(board);
+
+        let castle_file = match body {
+            "O-O" | "0-0" => Some(File::FileG),
+            "O-O-O" | "0-0-0" => Some(File::FileC),
+            _ => None,
+        };
+        let candidates: Vec<Move> = if let Some(file) = castle_file {
+            legal
+                .as_slice()
+                .iter()
+                .copied()
+                .filter(|mv| mv.is_castle() && mv.dest().file() == file)
+                .collect()
+        } else {
+            let (piece, rest) = match body.chars().next() {
+                Some(c @ ('N' | 'B' | 'R' | 'Q' | 'K')) => {
+                    (PieceKind::from_fen_char(c).ok_or_else(malformed)?, &body[1..])
+                }
+                Some('a'..='h') => (PieceKind::Pawn, body),
+                _ => return Err(malformed()),
+            };
+
+            // Promotion suffix: "=Q" or a bare trailing piece letter.
+            let (rest, promotion) = match rest.char_indices().last() {
+                Some((i, c @ ('N' | 'B' | 'R' | 'Q'))) if piece == PieceKind::Pawn => {
+                    let promo = PromotionPiece::ALL
+                        .into_iter()
+                        .find(|promo| promo.uci_char() == c.to_ascii_lowercase())
+                        .ok_or_else(malformed)?;
+                    (rest[..i].strip_suffix('=').unwrap_or(&rest[..i]), Some(promo))
+                }
+                _ => (rest, None),
+            };
+
+            if rest.len() < 2 || !rest.is_char_boundary(rest.len() - 2) {
+                return Err(malformed());
+            }
+            let (hint, dest) = rest.split_at(rest.len() - 2);
+            let dst = Square::from_algebraic(dest).ok_or_else(malformed)?;
+            let hint = hint.strip_suffix('x').unwrap_or(hint);
+            let (mut file_hint, mut rank_hint) = (None, None);
+            for c in hint.chars() {
+                match c {
+                    'a'..='h' if file_hint.is_none() && rank_hint.is_none() => {
+                        file_hint = File::from_char(c);
+                    }
+                    '1'..='8' if rank_hint.is_none() => rank_hint = Rank::from_char(c),
+                    _ => return Err(malformed()),
+                }
+            }
+
+            legal
+                .as_slice()
+                .iter()
+                .copied()
+                .filter(|mv| !mv.is_castle() && mv.dest() == dst)
+                .filter(|mv| board.piece_on(mv.source()) == Some(piece))
+                .filter(|mv| file_hint.is_none_or(|file| mv.source().file() == file))
+                .filter(|mv| rank_hint.is_none_or(|rank| mv.source().rank() == rank))
+                .filter(|mv| match promotion {
+                    Some(promo) => mv.is_promotion() && mv.promotion_piece() == promo,
+                    None => !mv.is_promotion(),
+                })
+                .collect()
+        };
+
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(SanError::NoSuchMove { san: s.to_string() }),
+            _ => Err(SanError::Ambiguous {
+                san: s.to_string(),
+                candidates: candidates.len(),
+            }),
+        }
+    }
+}
+
+impl Board {
+    /// Return the Standard Algebraic Notation for `mv` played from this
+    /// position; see [`Move::to_san`].
+    pub fn to_san(&self, mv: Move) -> String {
+        mv.to_san(self)
+    }
 }
 
 impl fmt::Display for Move {
@@ -283,9 +507,30 @@ mod tests {
 
     use super::{Move, MoveKind, PromotionPiece};
     use crate::board::Board;
+    use crate::error::SanError;
     use crate::piece_kind::PieceKind;
     use crate::square::Square;
 
+    #[test]
+    fn captured_piece_by_move_kind() {
+        let captured = |fen: &str, uci: &str| {
+            let board: Board = fen.parse().unwrap();
+            let mv = Move::from_uci(uci, &board).unwrap();
+            assert_eq!(mv.is_capture(&board), mv.captured_piece(&board).is_some());
+            mv.captured_piece(&board)
+        };
+        // Normal capture and quiet move
+        assert_eq!(captured("4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1", "e4d5"), Some(PieceKind::Knight));
+        assert_eq!(captured("4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1", "e4e5"), None);
+        // En passant captures a pawn on an empty square
+        assert_eq!(captured("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), Some(PieceKind::Pawn));
+        // Capturing promotion
+        assert_eq!(captured("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q"), Some(PieceKind::Rook));
+        // Castling, including Chess960 castling onto the rook's square
+        assert_eq!(captured("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1g1"), None);
+        assert_eq!(captured("4k3/8/8/8/8/8/8/1RK5 w Q - 0 1", "c1b1"), None);
+    }
+
     #[test]
     fn size_of_move() {
         assert_eq!(std::mem::size_of::<Move>(), 2);
@@ -545,4 +790,207 @@ mod tests {
             .unwrap();
         assert!(Move::from_uci("e7e8x", &board).is_none());
     }
+
+    #[test]
+    fn from_uci_rejects_impossible_moves() {
+        let board = Board::starting_position();
+        // Empty source and the opponent's piece
+        assert_eq!(Move::from_uci("e4e5", &board), None);
+        assert_eq!(Move::from_uci("e7e5", &board), None);
+        // Promotions off the seventh rank
+        assert_eq!(Move::from_uci("e2e4q", &board), None);
+        let pawn: Board = "4k3/8/4P3/8/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(Move::from_uci("e6e7q", &pawn), None);
+        // Phantom castling: no rights left
+        let no_rights: Board = "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1".parse().unwrap();
+        assert_eq!(Move::from_uci("e1g1", &no_rights), None);
+        assert_eq!(Move::from_uci("e1c1", &no_rights), None);
+        assert_eq!(Move::from_uci("e1h1", &no_rights).map(|mv| mv.kind()), Some(MoveKind::Normal));
+        // Only the black rights are left
+        let black_rights: Board = "r3k2r/8/8/8/8/8/8/R3K2R b kq - 0 1".parse().unwrap();
+        assert_eq!(Move::from_uci("e8g8", &black_rights).map(|mv| mv.kind()), Some(MoveKind::Castling));
+        assert_eq!(Move::from_uci("e1g1", &black_rights), None);
+    }
+
+    #[test]
+    fn from_uci_legal_checks_the_rules() {
+        let board = Board::starting_position();
+        assert!(Move::from_uci("e2e5", &board).is_some());
+        assert_eq!(Move::from_uci_legal("e2e5", &board), None);
+        assert_eq!(Move::from_uci_legal("g1g3", &board), None);
+        assert_eq!(Move::from_uci_legal("e2e4", &board), Move::from_uci("e2e4", &board));
+        // Castling through the f1 bishop
+        let blocked: Board = "r3k2r/8/8/8/8/8/8/R3KB1R w KQkq - 0 1".parse().unwrap();
+        assert_eq!(Move::from_uci_legal("e1g1", &blocked), None);
+        assert!(Move::from_uci_legal("e1c1", &blocked).is_some());
+    }
+
+    // --- SAN ---
+
+    fn san(fen: &str, uci: &str) -> String {
+        let board: Board = fen.parse().unwrap();
+        Move::from_uci(uci, &board).unwrap().to_san(&board)
+    }
+
+    #[test]
+    fn san_basic_moves() {
+        let start = crate::fen::STARTING_FEN;
+        assert_eq!(san(start, "e2e4"), "e4");
+        assert_eq!(san(start, "g1f3"), "Nf3");
+        // 1.e4 d5: exd5
+        assert_eq!(san("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", "e4d5"), "exd5");
+        assert_eq!(san("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+    }
+
+    #[test]
+    fn san_castling_and_promotion() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(san(fen, "e1g1"), "O-O");
+        assert_eq!(san(fen, "e1c1"), "O-O-O");
+        assert_eq!(san("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"), "a8=Q+");
+        assert_eq!(san("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8n"), "axb8=N");
+    }
+
+    #[test]
+    fn san_check_and_mate() {
+        // Back-rank mate and a plain check.
+        assert_eq!(san("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1a8"), "Ra8#");
+        assert_eq!(san("6k1/6pp/8/8/8/8/8/R5K1 w - - 0 1", "a1a8"), "Ra8+");
+        // Scholar's mate.
+        assert_eq!(
+            san("r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", "h5f7"),
+            "Qxf7#"
+        );
+    }
+
+    #[test]
+    fn san_disambiguation() {
+        // Knights on b1 and f1 (same rank): the file tells them apart.
+        assert_eq!(san("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1", "b1d2"), "Nbd2");
+        // Knights on d1 and d5 (same file): the rank tells them apart.
+        assert_eq!(san("4k3/8/8/3N4/8/8/8/3NK3 w - - 0 1", "d1c3"), "N1c3");
+        // Rooks on a1 and h1 (same rank) both reach d1.
+        assert_eq!(san("4k3/8/8/8/8/8/4K3/R6R w - - 0 1", "a1d1"), "Rad1");
+        // Queens on a1, a3 and c1 all reach b2: neither file nor rank alone is unique.
+        assert_eq!(san("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "a1b2"), "Qa1b2");
+    }
+
+    #[test]
+    fn san_pinned_rival_is_not_ambiguous() {
+        // Knights on c3 and g1 both see e2, but the c3 knight is pinned by the
+        // bishop on a5 against the king on e1.
+        assert_eq!(san("4k3/8/8/b7/8/2N5/8/4K1N1 w - - 0 1", "g1e2"), "Ne2");
+        assert_eq!(san("4k3/8/8/8/8/2N5/8/4K1N1 w - - 0 1", "g1e2"), "Nge2");
+        // Knights on b1 and e2, with e2 pinned by the rook on e7.
+        assert_eq!(san("k7/4r3/8/8/8/8/4N3/1N2K3 w - - 0 1", "b1c3"), "Nc3");
+        assert_eq!(san("k7/8/8/8/8/8/4N3/1N2K3 w - - 0 1", "b1c3"), "Nbc3");
+        // Rooks on d1 and d5 share a file, but the rook on a5 pins d5 to the king.
+        assert_eq!(san("4k3/8/8/r2R3K/8/8/8/3R4 w - - 0 1", "d1d3"), "Rd3");
+        assert_eq!(san("4k3/8/8/3R3K/8/8/8/3R4 w - - 0 1", "d1d3"), "R1d3");
+        // Parsing accepts the short form, since only one rook can legally go there
+        assert_eq!(from_san("4k3/8/8/r2R3K/8/8/8/3R4 w - - 0 1", "Rd3"), Ok("d1d3".to_string()));
+        // Three queens after promotions: only the full square is unique.
+        assert_eq!(san("1k6/8/8/8/4Q2Q/8/K7/7Q w - - 0 1", "h4e1"), "Qh4e1");
+        assert_eq!(san("1k6/8/8/8/7Q/8/K7/7Q w - - 0 1", "h4e1"), "Q4e1");
+        assert_eq!(san("1k6/8/8/8/4Q2Q/8/K7/8 w - - 0 1", "h4e1"), "Qhe1");
+    }
+
+    fn from_san(fen: &str, san: &str) -> Result<String, SanError> {
+        let board: Board = fen.parse().unwrap();
+        Move::from_san(san, &board).map(Move::to_uci)
+    }
+
+    #[test]
+    fn from_san_round_trips_to_san() {
+        let fens = [
+            crate::fen::STARTING_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1",
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+        ];
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            for &mv in crate::movegen::generate_legal_moves(&board).as_slice() {
+                let san = mv.to_san(&board);
+                assert_eq!(Move::from_san(&san, &board), Ok(mv), "{fen}: {san}");
+            }
+        }
+    }
+
+    #[test]
+    fn board_to_san_covers_every_piece_kind() {
+        let cases = [
+            ("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", "e2e4", "e4"),
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5", "exd5"),
+            ("4k3/8/8/8/8/8/8/4K1N1 w - - 0 1", "g1f3", "Nf3"),
+            ("4k3/8/8/8/8/8/8/4KB2 w - - 0 1", "f1b5", "Bb5+"),
+            ("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "a1d1", "Rd1"),
+            ("r3k3/8/8/8/8/8/8/R2K3R w - - 0 1", "a1a8", "Rxa8+"),
+            ("4k3/8/8/8/8/8/4K3/R6R w - - 0 1", "h1d1", "Rhd1"),
+            ("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", "d1d7", "Qd7+"),
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", "e1f2", "Kf2"),
+            ("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1g1", "O-O"),
+            ("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1c1", "O-O-O"),
+            ("8/4P3/8/8/8/8/8/k3K3 w - - 0 1", "e7e8q", "e8=Q"),
+            ("7k/8/6K1/8/8/8/8/R7 w - - 0 1", "a1a8", "Ra8#"),
+        ];
+        for (fen, uci, expected) in cases {
+            let board: Board = fen.parse().unwrap();
+            let mv = Move::from_uci_legal(uci, &board).unwrap();
+            assert_eq!(board.to_san(mv), expected, "{fen}");
+            assert_eq!(Move::from_san(expected, &board), Ok(mv), "{fen}");
+        }
+    }
+
+    #[test]
+    fn from_san_accepts_common_spellings() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(from_san(fen, "0-0").unwrap(), "e1g1");
+        assert_eq!(from_san(fen, "O-O-O").unwrap(), "e1c1");
+        assert_eq!(from_san(fen, "0-0-0+").unwrap(), "e1c1");
+        let start = crate::fen::STARTING_FEN;
+        assert_eq!(from_san(start, "e4!?").unwrap(), "e2e4");
+        assert_eq!(from_san(start, "Ng1f3").unwrap(), "g1f3");
+        assert_eq!(from_san("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8N").unwrap(), "a7a8n");
+        assert_eq!(from_san("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "exd6 e.p.").unwrap(), "e5d6");
+    }
+
+    #[test]
+    fn from_san_handles_every_disambiguation() {
+        // Queens on d4, h4 and d1: d2 is reached from d4 and d1, d8 from d4 and h4
+        let fen = "4k3/8/8/8/3Q3Q/8/8/3QK3 w - - 0 1";
+        assert_eq!(from_san(fen, "Q4d2").unwrap(), "d4d2");
+        assert_eq!(from_san(fen, "Qd4d2").unwrap(), "d4d2");
+        assert_eq!(from_san(fen, "Qhd8+").unwrap(), "h4d8");
+        assert_eq!(from_san(fen, "Q1d2").unwrap(), "d1d2");
+        assert!(matches!(from_san(fen, "Qd2"), Err(SanError::Ambiguous { .. })));
+        // Over-specified spellings of unambiguous moves are accepted too,
+        // but a disambiguator does not make an illegal move legal
+        assert_eq!(from_san("4k3/8/8/8/8/3Q4/8/4K3 w - - 0 1", "Qd3d1").unwrap(), "d3d1");
+        assert_eq!(from_san("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1", "Nbd2").unwrap(), "b1d2");
+        let start = crate::fen::STARTING_FEN;
+        assert_eq!(from_san(start, "Nbd2"), Err(SanError::NoSuchMove { san: "Nbd2".to_string() }));
+    }
+
+    #[test]
+    fn from_san_reports_what_went_wrong() {
+        let fen = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+        assert_eq!(
+            from_san(fen, "Nd2"),
+            Err(SanError::Ambiguous { san: "Nd2".to_string(), candidates: 2 })
+        );
+        assert_eq!(from_san(fen, "Nfd2").unwrap(), "f1d2");
+        assert_eq!(from_san(fen, "Nd4"), Err(SanError::NoSuchMove { san: "Nd4".to_string() }));
+        // Castling without the rights is not a move here.
+        assert!(matches!(from_san(fen, "O-O"), Err(SanError::NoSuchMove { .. })));
+        // A promotion must name its piece.
+        assert!(matches!(
+            from_san("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8"),
+            Err(SanError::NoSuchMove { .. })
+        ));
+        for bad in ["", "Zf3", "N", "Nz9", "e9", "Nb1b1d2", "O-O-O-O"] {
+            assert!(matches!(from_san(fen, bad), Err(SanError::Malformed { .. })), "{bad:?}");
+        }
+    }
 }
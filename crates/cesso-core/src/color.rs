@@ -5,6 +5,7 @@ use std::ops::Not;
 
 /// A chess piece color: White or Black.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
 #[repr(u8)]
 pub enum Color {
     White = 0,
@@ -32,6 +33,16 @@ impl Color {
             Color::Black => Color::White,
         }
     }
+
+    /// Parse a FEN side-to-move letter: 'w' or 'b'.
+    #[inline]
+    pub const fn from_char(c: char) -> Option<Color> {
+        match c {
+            'w' => Some(Color::White),
+            'b' => Some(Color::Black),
+            _ => None,
+        }
+    }
 }
 
 impl Not for Color {
@@ -75,6 +86,15 @@ mod tests {
         assert_eq!(!Color::Black, Color::White);
     }
 
+    #[test]
+    fn from_char_roundtrip() {
+        for color in Color::ALL {
+            assert_eq!(Color::from_char(color.to_string().chars().next().unwrap()), Some(color));
+        }
+        assert_eq!(Color::from_char('W'), None);
+        assert_eq!(Color::from_char('x'), None);
+    }
+
     #[test]
     fn display() {
         assert_eq!(format!("{}", Color::White), "w");
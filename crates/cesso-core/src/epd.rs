@@ -0,0 +1,252 @@
+//! EPD (Extended Position Description) record parsing for [`Board`].
+
+use std::collections::HashMap;
+
+use crate::board::Board;
+use crate::chess_move::Move;
+use crate::error::{EpdError, SanError};
+
+impl Board {
+    /// Parse an EPD record into a board and its operations.
+    ///
+    /// The first four fields are the FEN piece placement, side to move,
+    /// castling rights, and en passant square. The rest of the line is a
+    /// list of `opcode operand...;` operations, returned as a map from opcode
+    /// to its operands. Double-quoted operands (as used by `id` and `c0`) keep
+    /// their spaces and semicolons and are returned without the quotes. SAN
+    /// operands such as `bm` and `am` are left as text; resolve them with
+    /// [`Move::from_san`](crate::Move::from_san).
+    ///
+    /// The move counters come from the `hmvc` and `fmvn` operations when
+    /// present and default to `0 1` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EpdError`] if the position fields are missing or invalid,
+    /// an opcode is malformed, or a quoted operand is not closed.
+    pub fn from_epd(epd: &str) -> Result<(Board, HashMap<String, Vec<String>>), EpdError> {
+        let mut rest = epd.trim_start();
+        let mut fields = Vec::with_capacity(4);
+        while fields.len() < 4 && !rest.is_empty() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+        if fields.len() < 4 {
+            return Err(EpdError::MissingFields { found: fields.len() });
+        }
+
+        let operations = parse_operations(rest)?;
+        let counter = |opcode: &str, default: &'static str| {
+            operations
+                .get(opcode)
+                .and_then(|operands| operands.first())
+                .map_or(default, String::as_str)
+        };
+        let fen = format!(
+            "{} {} {}",
+            fields.join(" "),
+            counter("hmvc", "0"),
+            counter("fmvn", "1")
+        );
+        let board = fen.parse()?;
+        Ok((board, operations))
+    }
+}
+
+/// A parsed EPD record: a position and its operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdRecord {
+    /// The position, with move counters from `hmvc`/`fmvn` or `0 1`.
+    pub board: Board,
+    /// The operands of each opcode, with quotes removed.
+    pub operations: HashMap<String, Vec<String>>,
+}
+
+impl EpdRecord {
+    /// The `bm` (best move) operands resolved to moves, empty if absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SanError`] if an operand is not a legal move.
+    pub fn best_moves(&self) -> Result<Vec<Move>, SanError> {
+        self.moves("bm")
+    }
+
+    /// The `am` (avoid move) operands resolved to moves, empty if absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SanError`] if an operand is not a legal move.
+    pub fn avoid_moves(&self) -> Result<Vec<Move>, SanError> {
+        self.moves("am")
+    }
+
+    /// The first `id` operand, if present.
+    pub fn id(&self) -> Option<&str> {
+        self.operations.get("id").and_then(|operands| operands.first()).map(String::as_str)
+    }
+
+    fn moves(&self, opcode: &str) -> Result<Vec<Move>, SanError> {
+        self.operations
+            .get(opcode)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|san| Move::from_san(san, &self.board))
+            .collect()
+    }
+}
+
+/// Parse one line of an EPD file into an [`EpdRecord`].
+///
+/// See [`Board::from_epd`] for the accepted syntax.
+///
+/// # Errors
+///
+/// Returns an [`EpdError`] if the position fields are missing or invalid,
+/// an opcode is malformed, or a quoted operand is not closed.
+pub fn parse_epd_line(line: &str) -> Result<EpdRecord, EpdError> {
+    let (board, operations) = Board::from_epd(line)?;
+    Ok(EpdRecord { board, operations })
+}
+
+/// Split the operation section of an EPD record into opcodes and operands.
+fn parse_operations(mut rest: &str) -> Result<HashMap<String, Vec<String>>, EpdError> {
+    let mut operations = HashMap::new();
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ';');
+        if rest.is_empty() {
+            return Ok(operations);
+        }
+
+        let end = rest.find(|c: char| c.is_whitespace() || c == ';').unwrap_or(rest.len());
+        let opcode = &rest[..end];
+        let valid = opcode.starts_with(|c: char| c.is_ascii_alphabetic())
+            && opcode.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            let found = rest.split(';').next().unwrap_or(rest).trim_end();
+            return Err(EpdError::InvalidOpcode { found: found.to_string() });
+        }
+        rest = &rest[end..];
+
+        let mut operands = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(';') {
+                rest = after;
+                break;
+            }
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let Some(close) = quoted.find('"') else {
+                    return Err(EpdError::UnterminatedString { opcode: opcode.to_string() });
+                };
+                operands.push(quoted[..close].to_string());
+                rest = &quoted[close + 1..];
+            } else {
+                let end = rest.find(|c: char| c.is_whitespace() || c == ';').unwrap_or(rest.len());
+                operands.push(rest[..end].to_string());
+                rest = &rest[end..];
+            }
+        }
+        operations.insert(opcode.to_string(), operands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::chess_move::Move;
+    use crate::error::{EpdError, FenError};
+    use super::parse_epd_line;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_wac_record() {
+        let epd = r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#;
+        let (board, ops) = Board::from_epd(epd).unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1"
+        );
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops["bm"], strings(&["Qg6"]));
+        assert_eq!(ops["id"], strings(&["WAC.001"]));
+
+        let best = Move::from_san(&ops["bm"][0], &board).unwrap();
+        assert_eq!(best.to_uci(), "g3g6");
+    }
+
+    #[test]
+    fn parses_multiple_operands_and_opcodes() {
+        let epd = concat!(
+            "r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - ",
+            "bm Nxc6 Qd2;am Bxc5 ; dm 3; c0 \"quoted; with spaces\"; hmvc 4; fmvn 7;"
+        );
+        let (board, ops) = Board::from_epd(epd).unwrap();
+        assert_eq!(ops["bm"], strings(&["Nxc6", "Qd2"]));
+        assert_eq!(ops["am"], strings(&["Bxc5"]));
+        assert_eq!(ops["dm"], strings(&["3"]));
+        assert_eq!(ops["c0"], strings(&["quoted; with spaces"]));
+        assert_eq!(board.halfmove_clock(), 4);
+        assert_eq!(board.fullmove_number(), 7);
+    }
+
+    #[test]
+    fn operations_are_optional() {
+        let (board, ops) = Board::from_epd("8/8/8/8/8/8/8/K6k b - -").unwrap();
+        assert!(ops.is_empty());
+        assert_eq!(board.to_fen(), "8/8/8/8/8/8/8/K6k b - - 0 1");
+
+        // An operation without operands, and no trailing semicolon.
+        let (_, ops) = Board::from_epd("8/8/8/8/8/8/8/K6k b - - noop").unwrap();
+        assert_eq!(ops["noop"], Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            Board::from_epd("8/8/8/8/8/8/8/K6k b"),
+            Err(EpdError::MissingFields { found: 2 })
+        );
+        assert!(matches!(
+            Board::from_epd("8/8/8/8/8/8/8/K6k x - - id \"x\";"),
+            Err(EpdError::InvalidPosition { source: FenError::InvalidColor { .. } })
+        ));
+        assert!(matches!(
+            Board::from_epd("8/8/8/8/8/8/8/K6k b - - hmvc x;"),
+            Err(EpdError::InvalidPosition { source: FenError::InvalidMoveCounter { .. } })
+        ));
+        assert_eq!(
+            Board::from_epd("8/8/8/8/8/8/8/K6k b - - 1bm Qg6;"),
+            Err(EpdError::InvalidOpcode { found: "1bm Qg6".to_string() })
+        );
+        assert_eq!(
+            Board::from_epd("8/8/8/8/8/8/8/K6k b - - id \"WAC.001;"),
+            Err(EpdError::UnterminatedString { opcode: "id".to_string() })
+        );
+    }
+
+    #[test]
+    fn record_accessors_resolve_moves() {
+        let record = parse_epd_line(concat!(
+            "r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - ",
+            "bm Nxc6 Qd2; am Nf5; id \"test; one\";"
+        ))
+        .unwrap();
+        let uci = |moves: Vec<Move>| moves.iter().map(|mv| mv.to_uci()).collect::<Vec<_>>();
+        assert_eq!(uci(record.best_moves().unwrap()), ["d4c6", "d1d2"]);
+        assert_eq!(uci(record.avoid_moves().unwrap()), ["d4f5"]);
+        assert_eq!(record.id(), Some("test; one"));
+
+        let bare = parse_epd_line("8/8/8/8/8/8/8/K6k b - - bm Kb1;").unwrap();
+        assert!(bare.avoid_moves().unwrap().is_empty());
+        assert_eq!(bare.id(), None);
+        assert!(bare.best_moves().is_err());
+    }
+}
@@ -1,7 +1,11 @@
-//! Error types for FEN parsing and board validation.
+//! Error types for FEN, EPD, SAN and PGN parsing, square/file/rank parsing,
+//! and board validation.
 
 use std::fmt;
 
+use crate::color::Color;
+use crate::square::Square;
+
 /// Errors that occur when parsing a FEN string.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FenError {
@@ -37,7 +41,15 @@ pub enum FenError {
         /// The invalid character.
         character: char,
     },
-    /// The en passant field is not "-" or a valid algebraic square.
+    /// The castling rights name a king or rook that is not on its home square.
+    InvalidCastlingRights {
+        /// The castling field as written.
+        rights: String,
+        /// Which piece is missing, e.g. "no white rook on h1".
+        board_state: String,
+    },
+    /// The en passant field is not "-" or a square a pawn could just have
+    /// skipped over with a double push.
     InvalidEnPassant {
         /// The invalid en passant string.
         found: String,
@@ -80,6 +92,9 @@ impl fmt::Display for FenError {
             FenError::InvalidCastlingChar { character } => {
                 write!(f, "invalid castling character: '{character}'")
             }
+            FenError::InvalidCastlingRights { rights, board_state } => {
+                write!(f, "castling rights \"{rights}\" do not match the board: {board_state}")
+            }
             FenError::InvalidEnPassant { found } => {
                 write!(f, "invalid en passant square: \"{found}\"")
             }
@@ -128,9 +143,185 @@ pub enum BoardError {
     /// The occupied bitboard does not equal the union of both sides.
     #[error("occupied bitboard is inconsistent with side bitboards")]
     InconsistentOccupied,
+    /// The mailbox disagrees with the bitboards.
+    #[error("mailbox is inconsistent with bitboards on {square}")]
+    InconsistentMailbox {
+        /// The first square where the two disagree.
+        square: Square,
+    },
     /// The two side bitboards overlap.
     #[error("white and black side bitboards overlap")]
     InconsistentSides,
+    /// Two pieces were placed on the same square.
+    #[error("square {square} given more than one piece")]
+    SquareOccupied {
+        /// The square placed twice.
+        square: Square,
+    },
+    /// The side that just moved left its king in check.
+    #[error("{color} is not to move but its king is in check")]
+    OpponentInCheck {
+        /// The side not to move.
+        color: &'static str,
+    },
+    /// A side has more than eight pawns.
+    #[error("expected at most 8 pawns for {color}, found {count}")]
+    TooManyPawns {
+        /// Which side has too many pawns.
+        color: &'static str,
+        /// Number of pawns found.
+        count: u32,
+    },
+    /// A side has more pieces than its missing pawns could have promoted to.
+    #[error("{color} has {promoted} promoted pieces but only {missing} missing pawns")]
+    TooManyPieces {
+        /// Which side has too many pieces.
+        color: &'static str,
+        /// Pieces beyond the starting set of each kind.
+        promoted: u32,
+        /// Pawns missing from the starting eight.
+        missing: u32,
+    },
+    /// The en passant square does not sit behind a pawn that just
+    /// double-pushed.
+    #[error("en passant square {square} does not follow a double pawn push")]
+    InvalidEnPassant {
+        /// The en passant square.
+        square: Square,
+    },
+    /// A castling right has no king or rook on its starting square.
+    #[error("{color} {side} castling right has no {missing} on its starting square")]
+    InvalidCastling {
+        /// Which side holds the right.
+        color: &'static str,
+        /// "kingside" or "queenside".
+        side: &'static str,
+        /// "king" or "rook".
+        missing: &'static str,
+    },
+}
+
+/// Errors from parsing a move in Standard Algebraic Notation.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SanError {
+    /// The string is not shaped like a SAN move.
+    #[error("malformed SAN move: \"{san}\"")]
+    Malformed {
+        /// The string as given.
+        san: String,
+    },
+    /// No legal move in the position matches.
+    #[error("no legal move matches \"{san}\"")]
+    NoSuchMove {
+        /// The string as given.
+        san: String,
+    },
+    /// Several legal moves match and the string does not say which.
+    #[error("\"{san}\" is ambiguous: {candidates} legal moves match")]
+    Ambiguous {
+        /// The string as given.
+        san: String,
+        /// Number of matching legal moves.
+        candidates: usize,
+    },
+}
+
+/// Errors from parsing an EPD record.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EpdError {
+    /// The record has fewer than the four position fields.
+    #[error("expected 4 EPD position fields, found {found}")]
+    MissingFields {
+        /// Number of fields found.
+        found: usize,
+    },
+    /// The position fields do not describe a valid position.
+    #[error("invalid EPD position: {source}")]
+    InvalidPosition {
+        /// The underlying FEN error.
+        #[from]
+        source: FenError,
+    },
+    /// An operation does not start with a valid opcode.
+    #[error("invalid EPD opcode: \"{found}\"")]
+    InvalidOpcode {
+        /// The offending operation text.
+        found: String,
+    },
+    /// A quoted operand is missing its closing quote.
+    #[error("unterminated string operand for opcode \"{opcode}\"")]
+    UnterminatedString {
+        /// The opcode whose operand is unterminated.
+        opcode: String,
+    },
+}
+
+/// Errors from parsing a PGN game.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PgnError {
+    /// A tag pair is not of the form `[Name "value"]`.
+    #[error("malformed PGN tag: {tag}")]
+    MalformedTag {
+        /// The tag text as written.
+        tag: String,
+    },
+    /// A `{` comment is never closed.
+    #[error("unterminated comment in movetext")]
+    UnterminatedComment,
+    /// The parentheses of the recursive variations do not balance.
+    #[error("unbalanced variation parentheses in movetext")]
+    UnbalancedVariation,
+    /// The `FEN` tag does not hold a valid position.
+    #[error("invalid FEN tag: {source}")]
+    InvalidFen {
+        /// The underlying FEN error.
+        #[from]
+        source: FenError,
+    },
+    /// A move in the movetext is not legal in the game position.
+    #[error("move {move_number}{} {san}: {source}", if *color == Color::White { "." } else { "..." })]
+    IllegalMove {
+        /// Fullmove number of the move.
+        move_number: u16,
+        /// Side that played it.
+        color: Color,
+        /// The move as written.
+        san: String,
+        /// Why it could not be resolved.
+        source: SanError,
+    },
+}
+
+/// A string that is not a square name such as `e4`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid square: \"{input}\"")]
+pub struct ParseSquareError {
+    /// The string as given.
+    pub input: String,
+}
+
+/// A string that is not a file letter `a` to `h`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid file: \"{input}\"")]
+pub struct ParseFileError {
+    /// The string as given.
+    pub input: String,
+}
+
+/// A string that is not a rank digit `1` to `8`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid rank: \"{input}\"")]
+pub struct ParseRankError {
+    /// The string as given.
+    pub input: String,
+}
+
+/// A square index outside `0..64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("square index {index} out of range 0..64")]
+pub struct SquareIndexError {
+    /// The index as given.
+    pub index: u8,
 }
 
 #[cfg(test)]
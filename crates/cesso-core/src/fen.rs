@@ -3,12 +3,15 @@
 use std::str::FromStr;
 use std::fmt;
 
+use crate::attacks::pawn_attacks;
 use crate::bitboard::Bitboard;
 use crate::board::Board;
-use crate::castle_rights::CastleRights;
+use crate::castle_rights::{CastleRights, CastleSide};
+use crate::chess_move::Move;
 use crate::color::Color;
 use crate::error::FenError;
 use crate::file::File;
+use crate::piece::Piece;
 use crate::piece_kind::PieceKind;
 use crate::rank::Rank;
 use crate::square::Square;
@@ -50,14 +53,10 @@ impl FromStr for Board {
                     }
                     file_index += digit as u8;
                 } else {
-                    let kind = PieceKind::from_fen_char(c).ok_or(FenError::InvalidPieceChar {
+                    let piece = Piece::from_fen_char(c).ok_or(FenError::InvalidPieceChar {
                         character: c,
                     })?;
-                    let color = if c.is_ascii_uppercase() {
-                        Color::White
-                    } else {
-                        Color::Black
-                    };
+                    let (kind, color) = (piece.kind(), piece.color());
 
                     if file_index >= 8 {
                         return Err(FenError::BadRankLength {
@@ -87,19 +86,16 @@ impl FromStr for Board {
         let occupied = sides[Color::White.index()] | sides[Color::Black.index()];
 
         // Parse active color
-        let side_to_move = match fields[1] {
-            "w" => Color::White,
-            "b" => Color::Black,
-            other => {
+        let mut color_chars = fields[1].chars();
+        let side_to_move = match (color_chars.next().and_then(Color::from_char), color_chars.next()) {
+            (Some(color), None) => color,
+            _ => {
                 return Err(FenError::InvalidColor {
-                    found: other.to_string(),
+                    found: fields[1].to_string(),
                 })
             }
         };
 
-        // Parse castling rights
-        let castling = CastleRights::from_fen(fields[2])?;
-
         // Parse en passant
         let en_passant = if fields[3] == "-" {
             None
@@ -131,7 +127,7 @@ impl FromStr for Board {
             sides,
             occupied,
             side_to_move,
-            castling,
+            CastleRights::NONE, // resolved against the board below
             en_passant,
             halfmove_clock,
             fullmove_number,
@@ -142,6 +138,20 @@ impl FromStr for Board {
             0,      // placeholder minor_hash
         );
 
+        // Castling letters name rooks relative to the king, so they are
+        // resolved once the pieces are in place.
+        board.set_castling(parse_castling(&board, fields[2])?);
+
+        board.validate()?;
+        validate_en_passant(&board, fields[3])?;
+
+        // A recorded square no pawn can capture onto is dropped, as make_move does
+        if let Some(ep) = board.en_passant()
+            && !board.pawn_attacks_en_passant(ep, side_to_move)
+        {
+            board.set_en_passant(None);
+        }
+
         // Compute and set the real Zobrist hash
         let hash = crate::zobrist::hash_from_scratch(&board);
         board.set_hash(hash);
@@ -153,11 +163,175 @@ impl FromStr for Board {
         board.set_major_hash(majh);
         board.set_minor_hash(minh);
 
-        board.validate()?;
         Ok(board)
     }
 }
 
+/// Parse the castling field against the board.
+///
+/// Accepts classical "KQkq", Shredder-FEN file letters ("HAha") and a mix of
+/// both (X-FEN). A file letter names the rook directly and castles toward
+/// whichever side of the king it stands on; "K"/"Q" name the outermost rook
+/// on that side. Every right needs its king on the back rank and a rook of
+/// the same color on the named file.
+fn parse_castling(board: &Board, field: &str) -> Result<CastleRights, FenError> {
+    if field == "-" {
+        return Ok(CastleRights::NONE);
+    }
+
+    let mut rights = CastleRights::NONE;
+    for c in field.chars() {
+        let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+        let back_rank = match color {
+            Color::White => Rank::Rank1,
+            Color::Black => Rank::Rank8,
+        };
+        // Shredder-FEN letters name the rook file outright
+        let (side, named_file) = match c.to_ascii_lowercase() {
+            'k' => (CastleSide::KingSide, None),
+            'q' => (CastleSide::QueenSide, None),
+            letter => {
+                let Some(file) = File::from_char(letter) else {
+                    return Err(FenError::InvalidCastlingChar { character: c });
+                };
+                let king = back_rank_king(board, color, back_rank, field)?;
+                let side = if file.index() > king.file().index() {
+                    CastleSide::KingSide
+                } else {
+                    CastleSide::QueenSide
+                };
+                (side, Some(file))
+            }
+        };
+
+        let mismatch = |board_state: String| FenError::InvalidCastlingRights {
+            rights: field.to_string(),
+            board_state,
+        };
+        let rook_file = match named_file {
+            Some(file) => file,
+            None => {
+                let king = back_rank_king(board, color, back_rank, field)?;
+                outermost_rook(board, color, king, side).unwrap_or(match side {
+                    CastleSide::KingSide => File::FileH,
+                    CastleSide::QueenSide => File::FileA,
+                })
+            }
+        };
+        let rook_sq = Square::new(back_rank, rook_file);
+        if board.piece_on(rook_sq) != Some(PieceKind::Rook) || board.color_on(rook_sq) != Some(color) {
+            return Err(mismatch(format!("no {} rook on {rook_sq}", color_name(color))));
+        }
+        if rights.has(color, side) && rights.rook_file(color, side) != rook_file {
+            let side_name = match side {
+                CastleSide::KingSide => "king",
+                CastleSide::QueenSide => "queen",
+            };
+            return Err(mismatch(format!("two {} {side_name}side rooks named", color_name(color))));
+        }
+        rights = rights.with_rook(color, side, rook_file);
+    }
+    Ok(rights)
+}
+
+/// Return the square of `color`'s king, which must be on its back rank
+/// between two files for castling to be possible.
+fn back_rank_king(board: &Board, color: Color, back_rank: Rank, field: &str) -> Result<Square, FenError> {
+    let kings = board.pieces(PieceKind::King) & board.side(color);
+    kings
+        .lsb()
+        .filter(|king| king.rank() == back_rank && !matches!(king.file(), File::FileA | File::FileH))
+        .ok_or_else(|| FenError::InvalidCastlingRights {
+            rights: field.to_string(),
+            board_state: format!("no {} king on rank {back_rank}", color_name(color)),
+        })
+}
+
+/// Return the file of `color`'s rook furthest from the king on `side`, if any.
+fn outermost_rook(board: &Board, color: Color, king: Square, side: CastleSide) -> Option<File> {
+    let rooks = board.pieces(PieceKind::Rook) & board.side(color);
+    let on_side = |sq: &Square| {
+        sq.rank() == king.rank()
+            && match side {
+                CastleSide::KingSide => sq.file().index() > king.file().index(),
+                CastleSide::QueenSide => sq.file().index() < king.file().index(),
+            }
+    };
+    let mut candidates = rooks.filter(on_side);
+    match side {
+        CastleSide::KingSide => candidates.last(),
+        CastleSide::QueenSide => candidates.next(),
+    }
+    .map(Square::file)
+}
+
+/// Check that the en passant square sits behind a pawn that could just have
+/// double-pushed: on the third or sixth rank for the side that moved, empty,
+/// with that side's pawn directly in front of it.
+///
+/// A capturing pawn is not required, so FENs that record the square after
+/// every double push are accepted.
+fn validate_en_passant(board: &Board, field: &str) -> Result<(), FenError> {
+    let Some(ep) = board.en_passant() else {
+        return Ok(());
+    };
+    let mover = board.side_to_move().flip();
+    let (ep_rank, pawn_idx) = match mover {
+        Color::White => (Rank::Rank3, ep.index() + 8),
+        Color::Black => (Rank::Rank6, ep.index().wrapping_sub(8)),
+    };
+    let pawn_sq = Square::from_index(pawn_idx as u8);
+    let pushed_pawn = pawn_sq.is_some_and(|sq| {
+        board.piece_on(sq) == Some(PieceKind::Pawn) && board.color_on(sq) == Some(mover)
+    });
+    if ep.rank() != ep_rank || board.is_occupied(ep) || !pushed_pawn {
+        return Err(FenError::InvalidEnPassant {
+            found: field.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+impl Board {
+    /// Parse a FEN string and reject positions that could not arise in a
+    /// game, as checked by [`validate_legality`](Board::validate_legality).
+    ///
+    /// The plain [`FromStr`] parser only checks the board's structure, so
+    /// it also accepts test positions such as the side not to move being in
+    /// check.
+    pub fn from_fen_strict(fen: &str) -> Result<Board, FenError> {
+        let board: Board = fen.parse()?;
+        board.validate_legality()?;
+        Ok(board)
+    }
+
+    /// Serialize the position to a FEN string.
+    ///
+    /// The en passant square is written only when the side to move has a
+    /// legal en passant capture onto it, so positions that differ only in an
+    /// unusable en passant square produce the same FEN. Same as the
+    /// [`Display`](fmt::Display) output.
+    pub fn to_fen(&self) -> String {
+        self.to_string()
+    }
+
+    /// The en passant square, if the side to move can legally capture onto it.
+    fn capturable_en_passant(&self) -> Option<Square> {
+        let us = self.side_to_move();
+        let our_pawns = self.pieces(PieceKind::Pawn) & self.side(us);
+        self.en_passant().filter(|&ep| {
+            (pawn_attacks(us.flip(), ep) & our_pawns).any(|src| self.is_legal(Move::new_en_passant(src, ep)))
+        })
+    }
+}
+
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Piece placement
@@ -169,19 +343,15 @@ impl fmt::Display for Board {
                 let file = File::from_index(file_idx).unwrap();
                 let sq = Square::new(rank, file);
 
-                match (self.piece_on(sq), self.color_on(sq)) {
-                    (Some(kind), Some(color)) => {
+                match self.colored_piece_on(sq) {
+                    Some(piece) => {
                         if empty_count > 0 {
                             write!(f, "{empty_count}")?;
                             empty_count = 0;
                         }
-                        let c = match color {
-                            Color::White => kind.fen_char().to_ascii_uppercase(),
-                            Color::Black => kind.fen_char(),
-                        };
-                        write!(f, "{c}")?;
+                        write!(f, "{}", piece.fen_char())?;
                     }
-                    _ => {
+                    None => {
                         empty_count += 1;
                     }
                 }
@@ -203,7 +373,7 @@ impl fmt::Display for Board {
         write!(f, " {}", self.castling())?;
 
         // En passant
-        match self.en_passant() {
+        match self.capturable_en_passant() {
             Some(sq) => write!(f, " {sq}")?,
             None => write!(f, " -")?,
         }
@@ -217,6 +387,10 @@ impl fmt::Display for Board {
 mod tests {
     use super::STARTING_FEN;
     use crate::board::Board;
+    use crate::castle_rights::CastleSide;
+    use crate::color::Color;
+    use crate::error::FenError;
+    use crate::file::File;
 
     fn roundtrip(fen: &str) {
         let board: Board = fen.parse().unwrap();
@@ -227,6 +401,13 @@ mod tests {
         assert_eq!(board, board2);
     }
 
+    /// Parse `fen` and check that `to_fen` gives back `normalized`.
+    fn normalizes_to(fen: &str, normalized: &str) {
+        let board: Board = fen.parse().unwrap();
+        assert_eq!(board.to_fen(), normalized, "from {fen}");
+        assert_eq!(normalized.parse::<Board>().unwrap().to_fen(), normalized);
+    }
+
     #[test]
     fn roundtrip_starting() {
         roundtrip(STARTING_FEN);
@@ -234,7 +415,11 @@ mod tests {
 
     #[test]
     fn roundtrip_sicilian() {
-        roundtrip("rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2");
+        // No white pawn attacks c6, so the en passant square is dropped.
+        normalizes_to(
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2",
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2",
+        );
     }
 
     #[test]
@@ -251,7 +436,31 @@ mod tests {
 
     #[test]
     fn roundtrip_black_to_move() {
-        roundtrip("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        normalizes_to(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+        );
+    }
+
+    #[test]
+    fn to_fen_keeps_capturable_en_passant() {
+        // White e5 pawn next to the d-pawn that just double-pushed.
+        roundtrip("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        // Black pawns on both sides of the pushed pawn.
+        roundtrip("4k3/8/8/8/2pPp3/8/8/4K3 b - d3 0 1");
+        // The b5 pawn attacks c6, but capturing would expose the king on a5.
+        normalizes_to("4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1", "4k3/8/8/KPp4r/8/8/8/8 w - - 0 1");
+        // Only an a-file pawn can capture onto b6; the h-file does not wrap.
+        roundtrip("4k3/8/8/Pp6/8/8/8/4K3 w - b6 0 1");
+        normalizes_to("4k3/8/8/1p5P/8/8/8/4K3 w - b6 0 1", "4k3/8/8/1p5P/8/8/8/4K3 w - - 0 1");
+    }
+
+    #[test]
+    fn to_fen_castling_fields() {
+        roundtrip("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        roundtrip("r3k2r/8/8/8/8/8/8/R3K2R b Kq - 3 20");
+        roundtrip("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1");
+        normalizes_to("r3k2r/8/8/8/8/8/8/R3K2R w qkQK - 0 1", "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
     }
 
     #[test]
@@ -308,4 +517,119 @@ mod tests {
             "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - abc 1".parse::<Board>();
         assert!(result.is_err());
     }
+
+    fn castling_error(fen: &str) -> Option<String> {
+        match fen.parse::<Board>() {
+            Err(FenError::InvalidCastlingRights { board_state, .. }) => Some(board_state),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn error_castling_rights_without_pieces() {
+        assert_eq!(castling_error("4k3/8/8/8/8/8/8/4K3 w K - 0 1").as_deref(), Some("no white rook on h1"));
+        assert_eq!(castling_error("4k3/8/8/8/8/8/3K4/R7 w Q - 0 1").as_deref(), Some("no white king on rank 1"));
+        assert_eq!(castling_error("4k3/8/8/8/8/8/8/4K3 w k - 0 1").as_deref(), Some("no black rook on h8"));
+        assert_eq!(castling_error("4k2r/8/8/8/8/8/8/4K3 w q - 0 1").as_deref(), Some("no black rook on a8"));
+        assert_eq!(castling_error("r3k3/8/8/8/8/8/8/4K3 w q - 0 1"), None);
+
+        let err = "4k3/8/8/8/8/8/8/4K3 w Kq - 0 1".parse::<Board>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "castling rights \"Kq\" do not match the board: no white rook on h1"
+        );
+    }
+
+    #[test]
+    fn shredder_and_x_fen_castling() {
+        // File letters on a classical setup print as classical letters.
+        normalizes_to(
+            "r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+        );
+        normalizes_to("r3k2r/8/8/8/8/8/8/R3K2R w Ha - 0 1", "r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1");
+        roundtrip("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1");
+
+        // Chess960: the queenside rooks start on f1/f8, so the field prints
+        // Shredder-style however it was written.
+        let shredder = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        roundtrip(shredder);
+        normalizes_to("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9", shredder);
+        normalizes_to("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KFkq - 2 9", shredder);
+
+        // "K" names the outermost rook; a letter can pick the inner one.
+        let board: Board = "4k3/8/8/8/8/8/8/R3KRR1 w G - 0 1".parse().unwrap();
+        assert_eq!(board.castling().rook_file(Color::White, CastleSide::KingSide), File::FileG);
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/R3KRR1 w G - 0 1");
+        let board: Board = "4k3/8/8/8/8/8/8/R3KRR1 w KA - 0 1".parse().unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/R3KRR1 w GA - 0 1");
+    }
+
+    #[test]
+    fn error_castling_letters_without_rooks() {
+        // A file letter pointing at a file with no rook, or at the king itself.
+        assert_eq!(castling_error("r3k2r/8/8/8/8/8/8/R3K2R w G - 0 1").as_deref(), Some("no white rook on g1"));
+        assert_eq!(castling_error("r3k2r/8/8/8/8/8/8/R3K2R w e - 0 1").as_deref(), Some("no black rook on e8"));
+        // The rook must be the mover's own.
+        assert_eq!(castling_error("r3k2r/8/8/8/8/8/8/R3K2R w HAhb - 0 1").as_deref(), Some("no black rook on b8"));
+        // Letters need a king on the back rank to tell the sides apart.
+        assert_eq!(castling_error("r6r/4k3/8/8/8/8/8/R3K2R w Ah - 0 1").as_deref(), Some("no black king on rank 8"));
+        // Two different rooks for the same right.
+        assert_eq!(
+            castling_error("4k3/8/8/8/8/8/8/R3KRR1 w FG - 0 1").as_deref(),
+            Some("two white kingside rooks named")
+        );
+        assert!(matches!(
+            "r3k2r/8/8/8/8/8/8/R3K2R w Ki - 0 1".parse::<Board>(),
+            Err(FenError::InvalidCastlingChar { character: 'i' })
+        ));
+    }
+
+    #[test]
+    fn error_en_passant_inconsistent_with_board() {
+        for fen in [
+            // Wrong rank for the side to move
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d3 0 1",
+            // No pawn in front of the square
+            "4k3/8/8/4P3/8/8/8/4K3 w - d6 0 1",
+            // Square occupied
+            "4k3/8/3n4/3pP3/8/8/8/4K3 w - d6 0 1",
+            // Own pawn in front of the square
+            "4k3/8/8/3PP3/8/8/8/4K3 w - d6 0 1",
+        ] {
+            assert_eq!(
+                fen.parse::<Board>(),
+                Err(FenError::InvalidEnPassant { found: fen.split(' ').nth(3).unwrap().to_string() }),
+                "{fen}"
+            );
+        }
+        assert!("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1".parse::<Board>().is_ok());
+    }
+
+    #[test]
+    fn strict_parsing_rejects_unreachable_positions() {
+        use crate::error::BoardError;
+        use crate::square::Square;
+
+        let strict = |fen: &str| match Board::from_fen_strict(fen) {
+            Ok(_) => None,
+            Err(FenError::InvalidBoard { source }) => Some(source),
+            Err(err) => panic!("{fen}: {err}"),
+        };
+        assert_eq!(strict(STARTING_FEN), None);
+        assert_eq!(strict("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"), None);
+
+        for (fen, expected) in [
+            ("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1", BoardError::OpponentInCheck { color: "black" }),
+            ("4k3/8/8/8/P7/8/PPPPPPPP/4K3 w - - 0 1", BoardError::TooManyPawns { color: "white", count: 9 }),
+            (
+                "4k3/8/8/8/8/8/PPPPPPPP/3QKQ2 w - - 0 1",
+                BoardError::TooManyPieces { color: "white", promoted: 1, missing: 0 },
+            ),
+            ("4k3/3n4/8/3pP3/8/8/8/4K3 w - d6 0 1", BoardError::InvalidEnPassant { square: Square::D6 }),
+        ] {
+            assert!(fen.parse::<Board>().is_ok(), "{fen}");
+            assert_eq!(strict(fen), Some(expected), "{fen}");
+        }
+    }
 }
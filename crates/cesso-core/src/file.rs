@@ -1,6 +1,9 @@
 //! Chess board files (columns a–h).
 
 use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseFileError;
 
 /// A file (column) on the chess board, from FileA to FileH.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -53,12 +56,40 @@ impl File {
             _ => None,
         }
     }
+
+    /// Parse a lowercase file letter ('a' to 'h').
+    #[inline]
+    pub const fn from_char(c: char) -> Option<File> {
+        match c {
+            'a'..='h' => File::from_index(c as u8 - b'a'),
+            _ => None,
+        }
+    }
+
+    /// Return the lowercase file letter ('a' to 'h').
+    #[inline]
+    pub const fn to_char(self) -> char {
+        (b'a' + self as u8) as char
+    }
 }
 
 impl fmt::Display for File {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = (b'a' + self.index() as u8) as char;
-        write!(f, "{c}")
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for File {
+    type Err = ParseFileError;
+
+    /// Parse a file letter, either case (`"e"` or `"E"`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => File::from_char(c.to_ascii_lowercase()),
+            _ => None,
+        }
+        .ok_or_else(|| ParseFileError { input: s.to_string() })
     }
 }
 
@@ -85,6 +116,17 @@ mod tests {
         assert_eq!(File::from_index(255), None);
     }
 
+    #[test]
+    fn char_roundtrip() {
+        for file in File::ALL {
+            assert_eq!(File::from_char(file.to_char()), Some(file));
+        }
+        assert_eq!(File::FileE.to_char(), 'e');
+        assert_eq!(File::from_char('i'), None);
+        assert_eq!(File::from_char('A'), None);
+        assert_eq!(File::from_char('1'), None);
+    }
+
     #[test]
     fn display() {
         assert_eq!(format!("{}", File::FileA), "a");
@@ -102,4 +144,15 @@ mod tests {
         assert_eq!(File::COUNT, 8);
         assert_eq!(File::ALL.len(), File::COUNT);
     }
+
+    #[test]
+    fn from_str_roundtrip() {
+        for file in File::ALL {
+            assert_eq!(file.to_string().parse::<File>(), Ok(file));
+            assert_eq!(file.to_string().to_uppercase().parse::<File>(), Ok(file));
+        }
+        for bad in ["", "i", "ab", "1", " e"] {
+            assert_eq!(bad.parse::<File>().unwrap_err().input, bad);
+        }
+    }
 }
@@ -0,0 +1,231 @@
+//! A board together with the game history needed for draw rules.
+
+use crate::board::{Board, MaterialDraw};
+use crate::chess_move::Move;
+use crate::color::Color;
+
+/// How a game ended, or may be ended by a claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The side to move is checkmated.
+    Checkmate {
+        /// The side that delivered mate.
+        winner: Color,
+    },
+    /// The side to move has no legal move and is not in check.
+    Stalemate,
+    /// The current position has occurred three times.
+    ThreefoldRepetition,
+    /// 100 halfmoves without a capture or pawn move.
+    FiftyMoveRule,
+    /// Neither side has the material to mate by any sequence of moves.
+    InsufficientMaterial,
+}
+
+impl Outcome {
+    /// Return `true` for every outcome except checkmate.
+    #[inline]
+    pub const fn is_draw(self) -> bool {
+        !matches!(self, Outcome::Checkmate { .. })
+    }
+}
+
+/// A board plus the Zobrist hashes of every earlier position in the game.
+///
+/// The history does not include the current position, matching what the
+/// search expects for repetition detection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    board: Board,
+    history: Vec<u64>,
+}
+
+impl Game {
+    /// Start a game from `board` with no earlier positions.
+    pub fn new(board: Board) -> Game {
+        Game { board, history: Vec::new() }
+    }
+
+    /// Resume a game at `board`, reached through the positions hashed in
+    /// `history` (oldest first, not including `board` itself).
+    pub fn with_history(board: Board, history: Vec<u64>) -> Game {
+        Game { board, history }
+    }
+
+    /// Return the current position.
+    #[inline]
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Return the hashes of every earlier position, oldest first.
+    #[inline]
+    pub fn history(&self) -> &[u64] {
+        &self.history
+    }
+
+    /// Play `mv`, which must be legal in the current position.
+    pub fn push(&mut self, mv: Move) {
+        self.history.push(self.board.hash());
+        self.board = self.board.make_move(mv);
+    }
+
+    /// Return how many times the current position has occurred, itself
+    /// included.
+    pub fn repetition_count(&self) -> usize {
+        1 + self.board.repetitions(&self.history)
+    }
+
+    /// Return `true` if the current position has occurred at least three times.
+    pub fn is_threefold(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// Return `true` if a draw can be claimed under the fifty-move rule.
+    ///
+    /// That takes 100 halfmoves without a capture or pawn move, unless the
+    /// move that reached 100 delivered checkmate.
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.board.halfmove_clock() >= 100 && !self.board.is_checkmate()
+    }
+
+    /// Return the result of the game if it is over, or drawn by a rule a
+    /// player may claim.
+    ///
+    /// Mate and stalemate take precedence, then the fifty-move rule,
+    /// threefold repetition and finally dead material.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if !self.board.has_legal_moves() {
+            return Some(if self.board.is_check() {
+                Outcome::Checkmate { winner: self.board.side_to_move().flip() }
+            } else {
+                Outcome::Stalemate
+            });
+        }
+        if self.board.halfmove_clock() >= 100 {
+            return Some(Outcome::FiftyMoveRule);
+        }
+        if self.is_threefold() {
+            return Some(Outcome::ThreefoldRepetition);
+        }
+        if self.board.material_draw_class() == Some(MaterialDraw::Dead) {
+            return Some(Outcome::InsufficientMaterial);
+        }
+        None
+    }
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new(Board::starting_position())
+    }
+}
+
+impl Board {
+    /// Return how many positions in `history` equal this one.
+    ///
+    /// Only the last [`halfmove_clock`](Board::halfmove_clock) entries are
+    /// scanned: anything older precedes a capture or pawn move and cannot
+    /// recur. `history` is oldest first and excludes this position, as kept
+    /// by [`Game`].
+    pub fn repetitions(&self, history: &[u64]) -> usize {
+        let hash = self.hash();
+        let lookback = (self.halfmove_clock() as usize).min(history.len());
+        history[history.len() - lookback..].iter().filter(|&&h| h == hash).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Game, Outcome};
+    use crate::board::Board;
+    use crate::chess_move::Move;
+    use crate::color::Color;
+
+    fn game(fen: &str) -> Game {
+        Game::new(fen.parse().unwrap())
+    }
+
+    fn push_uci(game: &mut Game, moves: &[&str]) {
+        for uci in moves {
+            let mv = Move::from_uci_legal(uci, game.board()).unwrap();
+            game.push(mv);
+        }
+    }
+
+    const KNIGHT_SHUFFLE: [&str; 4] = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+    #[test]
+    fn threefold_after_two_knight_shuffles() {
+        let mut game = Game::default();
+        assert_eq!(game.repetition_count(), 1);
+
+        push_uci(&mut game, &KNIGHT_SHUFFLE);
+        assert_eq!(game.repetition_count(), 2);
+        assert!(!game.is_threefold());
+        assert_eq!(game.outcome(), None);
+
+        push_uci(&mut game, &KNIGHT_SHUFFLE);
+        assert_eq!(game.repetition_count(), 3);
+        assert!(game.is_threefold());
+        assert_eq!(game.outcome(), Some(Outcome::ThreefoldRepetition));
+    }
+
+    #[test]
+    fn capture_resets_the_clock_on_a_repeated_position() {
+        // The capture resets the clock to 0; the position it reaches is the
+        // first occurrence, exactly at the edge of the lookback window when
+        // it recurs four plies later, and must still count.
+        let mut game = game("4k3/7p/8/3p4/8/2N5/7P/4K3 w - - 7 30");
+        push_uci(&mut game, &["c3d5"]);
+        let after_capture = *game.board();
+        assert_eq!(after_capture.halfmove_clock(), 0);
+        assert_eq!(game.repetition_count(), 1);
+
+        let cycle = ["e8d7", "d5c3", "d7e8", "c3d5"];
+        push_uci(&mut game, &cycle);
+        assert_eq!(game.board().hash(), after_capture.hash());
+        assert_eq!(game.repetition_count(), 2);
+        push_uci(&mut game, &cycle);
+        assert!(game.is_threefold());
+
+        // History from before the capture is outside the window
+        let stale = vec![after_capture.hash(); 4];
+        assert_eq!(Game::with_history(after_capture, stale).repetition_count(), 1);
+    }
+
+    #[test]
+    fn fifty_move_rule_needs_100_halfmoves() {
+        let mut game = game("4k3/8/8/8/8/8/8/R3K3 w - - 98 80");
+        push_uci(&mut game, &["a1a2"]);
+        assert!(!game.is_fifty_move_draw());
+        push_uci(&mut game, &["e8d8"]);
+        assert!(game.is_fifty_move_draw());
+        assert_eq!(game.outcome(), Some(Outcome::FiftyMoveRule));
+    }
+
+    #[test]
+    fn mate_on_the_hundredth_halfmove_wins() {
+        let mut game = game("k7/8/1K6/8/8/8/8/7R w - - 99 80");
+        push_uci(&mut game, &["h1h8"]);
+        assert_eq!(game.board().halfmove_clock(), 100);
+        assert!(!game.is_fifty_move_draw());
+        assert_eq!(game.outcome(), Some(Outcome::Checkmate { winner: Color::White }));
+    }
+
+    #[test]
+    fn outcome_reports_stalemate_and_dead_material() {
+        assert_eq!(game("k7/8/1Q6/8/8/8/8/7K b - - 0 1").outcome(), Some(Outcome::Stalemate));
+        assert_eq!(game("k7/8/8/8/8/8/8/6BK w - - 0 1").outcome(), Some(Outcome::InsufficientMaterial));
+        assert!(Outcome::Stalemate.is_draw());
+        assert!(!Outcome::Checkmate { winner: Color::Black }.is_draw());
+    }
+
+    #[test]
+    fn repetitions_ignores_history_before_the_clock() {
+        let board: Board = "4k3/8/8/8/8/8/8/R3K3 w - - 2 1".parse().unwrap();
+        let history = [board.hash(), board.hash(), 0, board.hash()];
+        assert_eq!(board.repetitions(&history), 1);
+        assert_eq!(board.repetitions(&[]), 0);
+    }
+}
@@ -3,36 +3,52 @@
 mod attacks;
 mod bitboard;
 mod board;
+mod builder;
 mod castle_rights;
 mod chess_move;
 mod color;
+mod epd;
 mod error;
 mod fen;
+mod game;
 mod file;
 mod make_move;
 mod movegen;
 mod perft;
+pub mod pgn;
 mod piece;
 mod piece_kind;
 mod rank;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod square;
 mod zobrist;
 
 pub use bitboard::Bitboard;
-pub use board::{Board, PrettyBoard};
+pub use board::{Board, MaterialDraw, PrettyBoard};
+pub use builder::BoardBuilder;
+pub use make_move::Undo;
 pub use castle_rights::{CastleRights, CastleSide};
 pub use chess_move::{Move, MoveKind, PromotionPiece};
 pub use color::Color;
-pub use error::{BoardError, FenError};
+pub use error::{
+    BoardError, EpdError, FenError, ParseFileError, ParseRankError, ParseSquareError, PgnError, SanError, SquareIndexError,
+};
+pub use epd::{parse_epd_line, EpdRecord};
 pub use fen::STARTING_FEN;
+pub use game::{Game, Outcome};
 pub use file::File;
 pub use piece::Piece;
 pub use piece_kind::PieceKind;
 pub use rank::Rank;
 pub use attacks::{
-    between, bishop_attacks, king_attacks, knight_attacks, line, pawn_attacks, queen_attacks,
+    between, bishop_attacks, king_attacks, knight_attacks, line, pawn_attacks, pawn_attacks_all, queen_attacks,
     rook_attacks,
 };
-pub use movegen::{generate_legal_moves, MoveList};
-pub use perft::{divide, perft};
+pub use movegen::{generate_captures, generate_legal_moves, generate_quiets, legal_moves_for, MoveList};
+pub use perft::{
+    divide, divide_hashed, divide_parallel, print_divide, perft, perft_cached, perft_hashed, perft_parallel, perft_slow,
+    PerftTable,
+};
 pub use square::Square;
+pub use zobrist::polyglot_hash;
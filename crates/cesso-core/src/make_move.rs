@@ -1,13 +1,15 @@
-//! Move execution via copy-make.
+//! Move execution via copy-make, plus an in-place make/unmake pair.
 
 use crate::attacks::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks};
 use crate::bitboard::Bitboard;
 use crate::board::Board;
-use crate::castle_rights::CastleRights;
+use crate::castle_rights::{CastleRights, CastleSide};
 use crate::chess_move::{Move, MoveKind};
 use crate::color::Color;
+use crate::file::File;
 use crate::piece::Piece;
 use crate::piece_kind::PieceKind;
+use crate::rank::Rank;
 use crate::square::Square;
 use crate::zobrist;
 
@@ -25,24 +27,95 @@ fn toggle_partial_hashes(b: &mut Board, kind: PieceKind, color: Color, sq: Squar
     }
 }
 
-/// Maps each square index to the castling rights that must be removed when
-/// that square is the source or destination of any move.
-const CASTLE_RIGHTS_REVOKE: [CastleRights; 64] = {
-    let mut table = [CastleRights::NONE; 64];
-    // E1 (index 4): White king moves — remove both white rights.
-    table[Square::E1.index()] = CastleRights::WHITE_BOTH;
-    // A1 (index 0): White queenside rook.
-    table[Square::A1.index()] = CastleRights::WHITE_QUEEN;
-    // H1 (index 7): White kingside rook.
-    table[Square::H1.index()] = CastleRights::WHITE_KING;
-    // E8 (index 60): Black king moves — remove both black rights.
-    table[Square::E8.index()] = CastleRights::BLACK_BOTH;
-    // A8 (index 56): Black queenside rook.
-    table[Square::A8.index()] = CastleRights::BLACK_QUEEN;
-    // H8 (index 63): Black kingside rook.
-    table[Square::H8.index()] = CastleRights::BLACK_KING;
-    table
-};
+/// Each castling right with the color and side it belongs to.
+const RIGHTS: [(CastleRights, Color, CastleSide); 4] = [
+    (CastleRights::WHITE_KING, Color::White, CastleSide::KingSide),
+    (CastleRights::WHITE_QUEEN, Color::White, CastleSide::QueenSide),
+    (CastleRights::BLACK_KING, Color::Black, CastleSide::KingSide),
+    (CastleRights::BLACK_QUEEN, Color::Black, CastleSide::QueenSide),
+];
+
+/// The back rank a color castles on.
+const fn back_rank(color: Color) -> Rank {
+    match color {
+        Color::White => Rank::Rank1,
+        Color::Black => Rank::Rank8,
+    }
+}
+
+/// Castling rights lost by a move from `src` to `dst`: all of the mover's
+/// rights if it is the king, and any right whose rook square is touched.
+fn revoked_castling(rights: CastleRights, moving_piece: PieceKind, us: Color, src: Square, dst: Square) -> CastleRights {
+    let mut revoked = CastleRights::NONE;
+    if rights.is_empty() {
+        return revoked;
+    }
+    for (right, color, side) in RIGHTS {
+        let rook_sq = Square::new(back_rank(color), rights.rook_file(color, side));
+        let king_move = moving_piece == PieceKind::King && color == us;
+        if king_move || rook_sq == src || rook_sq == dst {
+            revoked = revoked.insert(right);
+        }
+    }
+    revoked
+}
+
+/// Rook source and destination squares for `color` castling with its king
+/// landing on `king_dst` (the g- or c-file of its back rank).
+///
+/// The rook starts on the file recorded in `castling`, which is the corner
+/// in classical chess and anywhere on the back rank in Chess960; it always
+/// lands on the f- or d-file.
+pub(crate) fn castle_rook_squares(castling: CastleRights, color: Color, king_dst: Square) -> Option<(Square, Square)> {
+    let rank = back_rank(color);
+    if king_dst.rank() != rank {
+        return None;
+    }
+    let (side, rook_dst_file) = match king_dst.file() {
+        File::FileG => (CastleSide::KingSide, File::FileF),
+        File::FileC => (CastleSide::QueenSide, File::FileD),
+        _ => return None,
+    };
+    Some((Square::new(rank, castling.rook_file(color, side)), Square::new(rank, rook_dst_file)))
+}
+
+/// Square of the pawn captured en passant by `us` landing on `ep_dst`.
+fn en_passant_victim(ep_dst: Square, us: Color) -> Option<Square> {
+    let idx = if us == Color::White {
+        ep_dst.index().checked_sub(8)? // captured pawn is south of the EP square
+    } else {
+        ep_dst.index() + 8 // captured pawn is north of the EP square
+    };
+    Square::from_index(idx as u8)
+}
+
+/// State that [`Board::make_move_in_place`] overwrites and [`Board::unmake_move`] needs back.
+///
+/// Only the irreversible parts of the position are kept; piece placement is
+/// restored by replaying the move backwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Undo {
+    /// Piece that moved, or `None` if the move was rejected and nothing changed.
+    moved: Option<PieceKind>,
+    /// Piece removed by the move, if any.
+    captured: Option<PieceKind>,
+    castling: CastleRights,
+    en_passant: Option<Square>,
+    halfmove_clock: u16,
+    hash: u64,
+    pawn_hash: u64,
+    non_pawn_hash: [u64; 2],
+    major_hash: u64,
+    minor_hash: u64,
+    material_key: u64,
+}
+
+impl Undo {
+    /// Piece captured by the move, if any.
+    pub fn captured(&self) -> Option<PieceKind> {
+        self.captured
+    }
+}
 
 impl Board {
     /// Return `true` if `sq` is attacked by any piece of `by_color`.
@@ -63,39 +136,33 @@ impl Board {
         by_color: Color,
         occupied: Bitboard,
     ) -> bool {
-        let them = self.side(by_color);
-
-        // Knight attacks: non-sliding, occupancy-independent.
-        if (knight_attacks(sq) & them & self.pieces(PieceKind::Knight)).is_nonempty() {
-            return true;
-        }
+        (self.attackers_to(sq, occupied) & self.side(by_color)).is_nonempty()
+    }
 
-        // King attacks: non-sliding, occupancy-independent.
-        if (king_attacks(sq) & them & self.pieces(PieceKind::King)).is_nonempty() {
-            return true;
-        }
+    /// Return every piece of either color that attacks `sq`.
+    ///
+    /// Sliding attacks are traced through `occupied` rather than the board's
+    /// own occupancy, so removing pieces from it reveals X-ray attackers behind
+    /// them. Pieces missing from `occupied` are still reported if they attack
+    /// `sq`; mask with `occupied` to drop them, and with [`Board::side`] to keep
+    /// one color.
+    ///
+    /// Uses reverse-attack lookup: attack patterns are cast from the target
+    /// square and intersected with the attacker's pieces of each type.
+    pub fn attackers_to(&self, sq: Square, occupied: Bitboard) -> Bitboard {
+        let knights = knight_attacks(sq) & self.pieces(PieceKind::Knight);
+        let kings = king_attacks(sq) & self.pieces(PieceKind::King);
 
         // Pawn attacks: a white pawn on X attacks Y iff pawn_attacks(Black, Y) contains X.
-        // So to find pawns of `by_color` that attack `sq`, we cast pawn_attacks from `sq`
-        // using the *opposite* color.
-        let opp_color = by_color.flip();
-        if (pawn_attacks(opp_color, sq) & them & self.pieces(PieceKind::Pawn)).is_nonempty() {
-            return true;
-        }
+        let pawns = self.pieces(PieceKind::Pawn);
+        let white_pawns = pawn_attacks(Color::Black, sq) & pawns & self.side(Color::White);
+        let black_pawns = pawn_attacks(Color::White, sq) & pawns & self.side(Color::Black);
 
-        // Rook / Queen (orthogonal sliders).
-        let rook_queen = (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen)) & them;
-        if (rook_attacks(sq, occupied) & rook_queen).is_nonempty() {
-            return true;
-        }
+        let queens = self.pieces(PieceKind::Queen);
+        let rook_queen = rook_attacks(sq, occupied) & (self.pieces(PieceKind::Rook) | queens);
+        let bishop_queen = bishop_attacks(sq, occupied) & (self.pieces(PieceKind::Bishop) | queens);
 
-        // Bishop / Queen (diagonal sliders).
-        let bishop_queen = (self.pieces(PieceKind::Bishop) | self.pieces(PieceKind::Queen)) & them;
-        if (bishop_attacks(sq, occupied) & bishop_queen).is_nonempty() {
-            return true;
-        }
-
-        false
+        knights | kings | white_pawns | black_pawns | rook_queen | bishop_queen
     }
 
     /// Apply a move and return the resulting board. Copy-make: `self` is not modified.
@@ -105,17 +172,70 @@ impl Board {
     /// If the source square is empty (invalid move), the board is returned unchanged.
     pub fn make_move(&self, mv: Move) -> Board {
         let mut b = *self;
+        b.make_move_in_place(mv);
+        b
+    }
+
+    /// Apply `moves` in order and return the resulting board.
+    pub fn apply_moves(&self, moves: &[Move]) -> Board {
+        moves.iter().fold(*self, |board, &mv| board.make_move(mv))
+    }
+
+    /// Apply `moves` in order, returning the resulting board and the Zobrist
+    /// hash of each position a move was played from.
+    ///
+    /// The hashes are the game history expected by the search's repetition
+    /// detection: every earlier position, not including the final one.
+    pub fn apply_moves_with_history(&self, moves: &[Move]) -> (Board, Vec<u64>) {
+        let mut board = *self;
+        let mut history = Vec::with_capacity(moves.len());
+        for &mv in moves {
+            history.push(board.hash());
+            board = board.make_move(mv);
+        }
+        (board, history)
+    }
+
+    /// Apply a move to `self` and return the state needed to take it back.
+    ///
+    /// Performs exactly the same incremental updates as [`Board::make_move`];
+    /// pass the returned [`Undo`] to [`Board::unmake_move`] to restore the
+    /// position. If the source square is empty (invalid move), the board is
+    /// left unchanged and unmaking is a no-op.
+    pub fn make_move_in_place(&mut self, mv: Move) -> Undo {
+        let b = self;
         let us = b.side_to_move();
         let them = us.flip();
         let src = mv.source();
         let dst = mv.dest();
 
+        let mut undo = Undo {
+            moved: None,
+            captured: None,
+            castling: b.castling(),
+            en_passant: b.en_passant(),
+            halfmove_clock: b.halfmove_clock(),
+            hash: b.hash(),
+            pawn_hash: b.pawn_hash(),
+            non_pawn_hash: [b.non_pawn_hash(Color::White), b.non_pawn_hash(Color::Black)],
+            major_hash: b.major_hash(),
+            minor_hash: b.minor_hash(),
+            material_key: b.material_key(),
+        };
+
         // The piece on the source square must exist for a valid move.
         let moving_piece = match b.piece_on(src) {
             Some(kind) => kind,
-            None => return b,
+            None => return undo,
         };
 
+        // A castling move must land on the g- or c-file of the mover's back rank.
+        let rook_squares = castle_rook_squares(b.castling(), us, dst);
+        if mv.is_castle() && rook_squares.is_none() {
+            return undo;
+        }
+        undo.moved = Some(moving_piece);
+
         // XOR out old en passant file from hash (before clearing).
         if let Some(old_ep) = b.en_passant() {
             b.set_hash(b.hash() ^ zobrist::EN_PASSANT_FILE[old_ep.file().index()]);
@@ -137,7 +257,9 @@ impl Board {
                 if is_capture && let Some(captured_kind) = b.piece_on(dst) {
                     b.toggle_piece(dst, captured_kind, them);
                     b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[Piece::new(captured_kind, them).index()][dst.index()]);
-                    toggle_partial_hashes(&mut b, captured_kind, them, dst);
+                    toggle_partial_hashes(b, captured_kind, them, dst);
+                    b.remove_material(them, captured_kind);
+                    undo.captured = Some(captured_kind);
                 }
 
                 // Move our piece: XOR it off src and onto dst.
@@ -146,10 +268,11 @@ impl Board {
                 let piece_idx = Piece::new(moving_piece, us).index();
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[piece_idx][src.index()]);
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[piece_idx][dst.index()]);
-                toggle_partial_hashes(&mut b, moving_piece, us, src);
-                toggle_partial_hashes(&mut b, moving_piece, us, dst);
+                toggle_partial_hashes(b, moving_piece, us, src);
+                toggle_partial_hashes(b, moving_piece, us, dst);
 
-                // Record en passant target square after a double pawn push.
+                // Record the en passant target square after a double pawn
+                // push, but only if an enemy pawn could capture onto it.
                 if moving_piece == PieceKind::Pawn {
                     let rank_diff = dst.index().abs_diff(src.index());
                     if rank_diff == 16 {
@@ -158,7 +281,8 @@ impl Board {
                         } else {
                             src.index() - 8
                         };
-                        b.set_en_passant(Square::from_index(ep_idx as u8));
+                        let ep_sq = Square::from_index(ep_idx as u8).filter(|&ep| b.pawn_attacks_en_passant(ep, them));
+                        b.set_en_passant(ep_sq);
                     }
                 }
             }
@@ -168,19 +292,23 @@ impl Board {
                 if is_capture && let Some(captured_kind) = b.piece_on(dst) {
                     b.toggle_piece(dst, captured_kind, them);
                     b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[Piece::new(captured_kind, them).index()][dst.index()]);
-                    toggle_partial_hashes(&mut b, captured_kind, them, dst);
+                    toggle_partial_hashes(b, captured_kind, them, dst);
+                    b.remove_material(them, captured_kind);
+                    undo.captured = Some(captured_kind);
                 }
 
                 // Remove the promoting pawn from src.
                 b.toggle_piece(src, PieceKind::Pawn, us);
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[Piece::new(PieceKind::Pawn, us).index()][src.index()]);
-                toggle_partial_hashes(&mut b, PieceKind::Pawn, us, src);
+                toggle_partial_hashes(b, PieceKind::Pawn, us, src);
 
                 // Place the promoted piece on dst.
                 let promo_kind = mv.promotion_piece().to_piece_kind();
                 b.toggle_piece(dst, promo_kind, us);
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[Piece::new(promo_kind, us).index()][dst.index()]);
-                toggle_partial_hashes(&mut b, promo_kind, us, dst);
+                toggle_partial_hashes(b, promo_kind, us, dst);
+                b.remove_material(us, PieceKind::Pawn);
+                b.add_material(us, promo_kind);
             }
 
             MoveKind::EnPassant => {
@@ -190,56 +318,46 @@ impl Board {
                 let pawn_idx = Piece::new(PieceKind::Pawn, us).index();
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[pawn_idx][src.index()]);
                 b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[pawn_idx][dst.index()]);
-                toggle_partial_hashes(&mut b, PieceKind::Pawn, us, src);
-                toggle_partial_hashes(&mut b, PieceKind::Pawn, us, dst);
+                toggle_partial_hashes(b, PieceKind::Pawn, us, src);
+                toggle_partial_hashes(b, PieceKind::Pawn, us, dst);
 
                 // Remove the captured pawn, which stands on the same rank as
                 // `src` and the same file as `dst` — one rank behind `dst`.
-                let captured_idx = if us == Color::White {
-                    dst.index() - 8 // captured pawn is south of the EP square
-                } else {
-                    dst.index() + 8 // captured pawn is north of the EP square
-                };
-                if let Some(captured_sq) = Square::from_index(captured_idx as u8) {
+                if let Some(captured_sq) = en_passant_victim(dst, us) {
                     b.toggle_piece(captured_sq, PieceKind::Pawn, them);
                     b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[Piece::new(PieceKind::Pawn, them).index()][captured_sq.index()]);
-                    toggle_partial_hashes(&mut b, PieceKind::Pawn, them, captured_sq);
+                    toggle_partial_hashes(b, PieceKind::Pawn, them, captured_sq);
+                    b.remove_material(them, PieceKind::Pawn);
+                    undo.captured = Some(PieceKind::Pawn);
                 }
             }
 
             MoveKind::Castling => {
-                // Move the king.
-                b.toggle_piece(src, PieceKind::King, us);
-                b.toggle_piece(dst, PieceKind::King, us);
-                let king_idx = Piece::new(PieceKind::King, us).index();
-                b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[king_idx][src.index()]);
-                b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[king_idx][dst.index()]);
-                toggle_partial_hashes(&mut b, PieceKind::King, us, src);
-                toggle_partial_hashes(&mut b, PieceKind::King, us, dst);
-
-                // Move the rook to its post-castling square.
-                let (rook_src, rook_dst) = match dst.index() {
-                    6 => (Square::H1, Square::F1),   // White kingside:  G1
-                    2 => (Square::A1, Square::D1),   // White queenside: C1
-                    62 => (Square::H8, Square::F8),  // Black kingside:  G8
-                    58 => (Square::A8, Square::D8),  // Black queenside: C8
-                    _ => return b,                   // should never occur for a valid move
-                };
-                b.toggle_piece(rook_src, PieceKind::Rook, us);
-                b.toggle_piece(rook_dst, PieceKind::Rook, us);
-                let rook_idx = Piece::new(PieceKind::Rook, us).index();
-                b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[rook_idx][rook_src.index()]);
-                b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[rook_idx][rook_dst.index()]);
-                toggle_partial_hashes(&mut b, PieceKind::Rook, us, rook_src);
-                toggle_partial_hashes(&mut b, PieceKind::Rook, us, rook_dst);
+                // In Chess960 the king and rook may land on each other's
+                // start squares, so lift both pieces before placing either.
+                if let Some((rook_src, rook_dst)) = rook_squares {
+                    let king_idx = Piece::new(PieceKind::King, us).index();
+                    let rook_idx = Piece::new(PieceKind::Rook, us).index();
+                    b.toggle_piece(src, PieceKind::King, us);
+                    b.toggle_piece(rook_src, PieceKind::Rook, us);
+                    b.toggle_piece(dst, PieceKind::King, us);
+                    b.toggle_piece(rook_dst, PieceKind::Rook, us);
+                    b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[king_idx][src.index()]);
+                    b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[king_idx][dst.index()]);
+                    b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[rook_idx][rook_src.index()]);
+                    b.set_hash(b.hash() ^ zobrist::PIECE_SQUARE[rook_idx][rook_dst.index()]);
+                    toggle_partial_hashes(b, PieceKind::King, us, src);
+                    toggle_partial_hashes(b, PieceKind::King, us, dst);
+                    toggle_partial_hashes(b, PieceKind::Rook, us, rook_src);
+                    toggle_partial_hashes(b, PieceKind::Rook, us, rook_dst);
+                }
             }
         }
 
-        // Revoke castling rights affected by any piece touching a corner or king square.
+        // Revoke castling rights affected by a king move or a piece touching a castling rook.
         let new_castling = b
             .castling()
-            .remove(CASTLE_RIGHTS_REVOKE[src.index()])
-            .remove(CASTLE_RIGHTS_REVOKE[dst.index()]);
+            .remove(revoked_castling(b.castling(), moving_piece, us, src, dst));
         b.set_castling(new_castling);
 
         // XOR in new castling rights.
@@ -268,14 +386,92 @@ impl Board {
             b.set_fullmove_number(b.fullmove_number() + 1);
         }
 
-        b
+        debug_assert_eq!(
+            b.material_key(),
+            b.material_key_from_scratch(),
+            "material key out of sync after {mv}"
+        );
+        undo
+    }
+
+    /// Take back `mv`, which must be the last move applied with
+    /// [`Board::make_move_in_place`] and `undo` the value it returned.
+    ///
+    /// Pieces are moved back directly; everything else, hashes included, is
+    /// restored from `undo` rather than recomputed.
+    pub fn unmake_move(&mut self, mv: Move, undo: Undo) {
+        let Some(moving_piece) = undo.moved else {
+            return;
+        };
+        let them = self.side_to_move();
+        let us = them.flip();
+        let src = mv.source();
+        let dst = mv.dest();
+
+        match mv.kind() {
+            MoveKind::Normal => {
+                self.toggle_piece(dst, moving_piece, us);
+                self.toggle_piece(src, moving_piece, us);
+                if let Some(captured_kind) = undo.captured {
+                    self.toggle_piece(dst, captured_kind, them);
+                }
+            }
+
+            MoveKind::Promotion => {
+                self.toggle_piece(dst, mv.promotion_piece().to_piece_kind(), us);
+                self.toggle_piece(src, PieceKind::Pawn, us);
+                if let Some(captured_kind) = undo.captured {
+                    self.toggle_piece(dst, captured_kind, them);
+                }
+            }
+
+            MoveKind::EnPassant => {
+                self.toggle_piece(dst, PieceKind::Pawn, us);
+                self.toggle_piece(src, PieceKind::Pawn, us);
+                if undo.captured.is_some()
+                    && let Some(captured_sq) = en_passant_victim(dst, us)
+                {
+                    self.toggle_piece(captured_sq, PieceKind::Pawn, them);
+                }
+            }
+
+            MoveKind::Castling => {
+                if let Some((rook_src, rook_dst)) = castle_rook_squares(undo.castling, us, dst) {
+                    self.toggle_piece(dst, PieceKind::King, us);
+                    self.toggle_piece(rook_dst, PieceKind::Rook, us);
+                    self.toggle_piece(src, PieceKind::King, us);
+                    self.toggle_piece(rook_src, PieceKind::Rook, us);
+                }
+            }
+        }
+
+        if us == Color::Black {
+            self.set_fullmove_number(self.fullmove_number() - 1);
+        }
+        self.set_side_to_move(us);
+        self.set_castling(undo.castling);
+        self.set_en_passant(undo.en_passant);
+        self.set_halfmove_clock(undo.halfmove_clock);
+        self.set_hash(undo.hash);
+        self.set_pawn_hash(undo.pawn_hash);
+        self.set_non_pawn_hash(undo.non_pawn_hash);
+        self.set_major_hash(undo.major_hash);
+        self.set_minor_hash(undo.minor_hash);
+        self.set_material_key(undo.material_key);
     }
 
     /// Apply a null move (pass) — flips the side to move without moving any piece.
     ///
-    /// Used in null move pruning. The null-move board clears en passant,
-    /// toggles the side to move, and increments the halfmove clock.
-    /// Castling rights and pieces are unchanged.
+    /// Used in null move pruning. The returned board:
+    ///
+    /// - has the other side to move, with the side-to-move key XORed into the hash;
+    /// - has no en passant square, with the old file's key XORed out of the hash;
+    /// - has its halfmove clock incremented (the fullmove number is untouched);
+    /// - keeps castling rights and piece placement, so the partial hashes are unchanged.
+    ///
+    /// The incremental hash always equals a from-scratch hash of the result,
+    /// and two null moves in a row restore the original hash unless the first
+    /// one cleared an en passant square.
     pub fn make_null_move(&self) -> Board {
         let mut b = *self;
 
@@ -298,10 +494,12 @@ impl Board {
 
 #[cfg(test)]
 mod tests {
+    use crate::bitboard::Bitboard;
     use crate::board::Board;
-    use crate::castle_rights::CastleRights;
+    use crate::castle_rights::{CastleRights, CastleSide};
     use crate::chess_move::{Move, PromotionPiece};
     use crate::color::Color;
+    use crate::movegen::generate_legal_moves;
     use crate::piece_kind::PieceKind;
     use crate::square::Square;
 
@@ -318,7 +516,8 @@ mod tests {
         assert_eq!(after.piece_on(Square::E4), Some(PieceKind::Pawn));
         assert_eq!(after.color_on(Square::E4), Some(Color::White));
         assert_eq!(after.piece_on(Square::E2), None);
-        assert_eq!(after.en_passant(), Some(Square::E3));
+        // No black pawn can take on e3, so no en passant square is recorded
+        assert_eq!(after.en_passant(), None);
         assert_eq!(after.side_to_move(), Color::Black);
     }
 
@@ -443,6 +642,27 @@ mod tests {
         assert_eq!(b2.fullmove_number(), 2); // incremented after Black
     }
 
+    #[test]
+    fn apply_moves_with_history_matches_manual_chaining() {
+        let moves = [
+            Move::new(Square::G1, Square::F3),
+            Move::new(Square::G8, Square::F6),
+            Move::new(Square::F3, Square::G1),
+            Move::new(Square::F6, Square::G8),
+        ];
+        let b0 = starting();
+        let b1 = b0.make_move(moves[0]);
+        let b2 = b1.make_move(moves[1]);
+        let b3 = b2.make_move(moves[2]);
+        let b4 = b3.make_move(moves[3]);
+
+        let (board, history) = b0.apply_moves_with_history(&moves);
+        assert_eq!(board, b4);
+        assert_eq!(history, [b0.hash(), b1.hash(), b2.hash(), b3.hash()]);
+        assert_eq!(b0.apply_moves(&moves), b4);
+        assert_eq!(b0.apply_moves_with_history(&[]), (b0, Vec::new()));
+    }
+
     #[test]
     fn is_square_attacked_starting() {
         let board = starting();
@@ -462,6 +682,41 @@ mod tests {
         assert!(board.is_square_attacked(Square::F6, Color::Black));
     }
 
+    #[test]
+    fn attackers_to_finds_stacked_attackers() {
+        // Queen on d1 behind rook on d2 hit d5; the black knight on f6 and
+        // pawn on e6 both defend it.
+        let board: Board = "4k3/8/4pn2/3p4/8/8/3R4/3QK3 w - - 0 1".parse().unwrap();
+        let occupied = board.occupied();
+        let attackers = board.attackers_to(Square::D5, occupied);
+        // The queen is blocked by the rook until the rook leaves.
+        assert_eq!(attackers & board.side(Color::White), Square::D2.bitboard());
+        let without_rook = occupied.without(Square::D2);
+        let xray = board.attackers_to(Square::D5, without_rook) & without_rook;
+        assert_eq!(xray & board.side(Color::White), Square::D1.bitboard());
+        assert_eq!(
+            attackers & board.side(Color::Black),
+            Square::F6.bitboard() | Square::E6.bitboard()
+        );
+    }
+
+    #[test]
+    fn attackers_to_agrees_with_is_square_attacked() {
+        let board: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse()
+            .unwrap();
+        for sq in Bitboard::FULL {
+            let attackers = board.attackers_to(sq, board.occupied());
+            for color in Color::ALL {
+                assert_eq!(
+                    (attackers & board.side(color)).is_nonempty(),
+                    board.is_square_attacked(sq, color),
+                    "{sq:?} {color:?}"
+                );
+            }
+        }
+    }
+
     // --- Incremental Zobrist hash tests ---
 
     #[test]
@@ -483,10 +738,48 @@ mod tests {
 
     #[test]
     fn incremental_hash_double_pawn_push() {
-        let board = starting();
+        // The black pawn on d4 can capture on e3
+        let board: Board = "4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1".parse().unwrap();
         let after = board.make_move(Move::new(Square::E2, Square::E4));
         assert_eq!(after.hash(), crate::zobrist::hash_from_scratch(&after));
-        assert!(after.en_passant().is_some());
+        assert_eq!(after.en_passant(), Some(Square::E3));
+    }
+
+    #[test]
+    fn uncapturable_en_passant_does_not_split_transpositions() {
+        // 1.e4 e6 2.d4 Nf6 3.Nf3 d5 ends in a double push no white pawn can
+        // capture; 1.e3 e6 2.e4 Nf6 3.d4 d6 4.Nf3 d5 reaches the same
+        // position with single steps. The two must hash alike.
+        let play = |moves: &[&str]| {
+            let mut board = starting();
+            for uci in moves {
+                board = board.make_move(Move::from_uci(uci, &board).unwrap());
+            }
+            board
+        };
+        let double_push = play(&["e2e4", "e7e6", "d2d4", "g8f6", "g1f3", "d7d5"]);
+        let single_steps = play(&["e2e3", "e7e6", "e3e4", "g8f6", "d2d4", "d7d6", "g1f3", "d6d5"]);
+        assert_eq!(double_push.en_passant(), None);
+        assert_eq!(double_push.to_fen().split(' ').next(), single_steps.to_fen().split(' ').next());
+        assert_eq!(double_push.hash(), single_steps.hash());
+
+        // With a white pawn on e5, ...d7-d5 can be taken en passant and
+        // ...d6-d5 cannot. Both reach the same placement with White to move,
+        // so the hashes must differ by exactly the en passant key.
+        let after = |fen: &str, uci: &str| {
+            let board: Board = fen.parse().unwrap();
+            board.make_move(Move::from_uci(uci, &board).unwrap())
+        };
+        let capturable = after("rnbqkb1r/pppp1ppp/4pn2/4P3/8/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3", "d7d5");
+        let no_capture = after("rnbqkb1r/ppp2ppp/3ppn2/4P3/8/5N2/PPPP1PPP/RNBQKB1R b KQkq - 0 4", "d6d5");
+        assert_eq!(capturable.to_fen().split(' ').next(), no_capture.to_fen().split(' ').next());
+        assert_eq!(capturable.side_to_move(), no_capture.side_to_move());
+        assert_eq!(capturable.castling(), no_capture.castling());
+        assert_eq!(capturable.en_passant(), Some(Square::D6));
+        assert_eq!(no_capture.en_passant(), None);
+        assert_eq!(capturable.hash(), crate::zobrist::hash_from_scratch(&capturable));
+        assert_eq!(no_capture.hash(), crate::zobrist::hash_from_scratch(&no_capture));
+        assert_eq!(capturable.hash() ^ no_capture.hash(), crate::zobrist::EN_PASSANT_FILE[3]);
     }
 
     #[test]
@@ -611,8 +904,8 @@ mod tests {
 
     #[test]
     fn null_move_clears_en_passant() {
-        let board = starting()
-            .make_move(Move::new(Square::E2, Square::E4));
+        let board: Board = "4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1".parse().unwrap();
+        let board = board.make_move(Move::new(Square::E2, Square::E4));
         assert!(board.en_passant().is_some());
         let null = board.make_null_move();
         assert!(null.en_passant().is_none());
@@ -652,6 +945,36 @@ mod tests {
         assert_eq!(null.hash(), crate::zobrist::hash_from_scratch(&null));
     }
 
+    #[test]
+    fn double_null_move_restores_hash() {
+        let board: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse()
+            .unwrap();
+        let back = board.make_null_move().make_null_move();
+        assert_eq!(back.hash(), board.hash());
+        assert_eq!(back.halfmove_clock(), board.halfmove_clock() + 2);
+        assert_eq!(back.side_to_move(), board.side_to_move());
+    }
+
+    #[test]
+    fn double_null_move_drops_only_en_passant_from_hash() {
+        // 1.e4 d5 2.e5 f5 — EP square f6, capturable by the e5 pawn.
+        let board = starting()
+            .make_move(Move::new(Square::E2, Square::E4))
+            .make_move(Move::new(Square::D7, Square::D5))
+            .make_move(Move::new(Square::E4, Square::E5))
+            .make_move(Move::new(Square::F7, Square::F5));
+        assert_eq!(board.en_passant(), Some(Square::F6));
+
+        let null = board.make_null_move();
+        assert_eq!(null.hash(), crate::zobrist::hash_from_scratch(&null));
+        let back = null.make_null_move();
+        assert_eq!(
+            back.hash() ^ crate::zobrist::EN_PASSANT_FILE[Square::F6.file().index()],
+            board.hash()
+        );
+    }
+
     #[test]
     fn null_move_increments_halfmove() {
         let board = starting();
@@ -704,6 +1027,46 @@ mod tests {
         assert_eq!(after.minor_hash(), minh);
     }
 
+    #[test]
+    fn chess960_castling_swaps_king_and_rook() {
+        // King f1, rook g1: castling kingside puts each on the other's square.
+        let mut board: Board = "4k3/8/8/8/8/8/8/5KR1 w K - 0 1".parse().unwrap();
+        let before = board;
+        let mv = Move::new_castle(Square::F1, Square::G1);
+        assert!(generate_legal_moves(&board).as_slice().contains(&mv));
+        assert_eq!(Move::from_uci("f1g1", &board), Some(mv));
+        assert_eq!(mv.to_san(&board), "O-O");
+
+        let after = board.make_move(mv);
+        assert_eq!(after.piece_on(Square::G1), Some(PieceKind::King));
+        assert_eq!(after.piece_on(Square::F1), Some(PieceKind::Rook));
+        assert_eq!(after.occupied().count(), 3);
+        assert!(after.castling().is_empty());
+        assert_eq!(after.hash(), crate::zobrist::hash_from_scratch(&after));
+
+        let undo = board.make_move_in_place(mv);
+        assert_eq!(board, after);
+        board.unmake_move(mv, undo);
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn chess960_castling_in_place_and_moving_rook_revokes() {
+        // King b1 with its queenside rook on a1: O-O-O leaves the king on c1.
+        let board: Board = "1k6/8/8/8/8/8/8/RK3R2 w FA - 0 1".parse().unwrap();
+        let after = board.make_move(Move::new_castle(Square::B1, Square::C1));
+        assert_eq!(after.piece_on(Square::C1), Some(PieceKind::King));
+        assert_eq!(after.piece_on(Square::D1), Some(PieceKind::Rook));
+        assert_eq!(after.piece_on(Square::A1), None);
+        assert!(after.castling().is_empty());
+
+        // Moving the f1 rook drops only the kingside right.
+        let after = board.make_move(Move::new(Square::F1, Square::F5));
+        assert!(!after.castling().has(Color::White, CastleSide::KingSide));
+        assert!(after.castling().has(Color::White, CastleSide::QueenSide));
+        assert_eq!(after.to_fen(), "1k6/8/8/5R2/8/8/8/RK6 b Q - 1 1");
+    }
+
     #[test]
     fn partial_hashes_match_scratch_after_castling() {
         let board: Board = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1".parse().unwrap();
@@ -772,4 +1135,73 @@ mod tests {
         assert_eq!(board.major_hash(), null.major_hash());
         assert_eq!(board.minor_hash(), null.minor_hash());
     }
+
+    // --- In-place make/unmake tests ---
+
+    #[test]
+    fn make_move_in_place_matches_copy_make() {
+        let board: Board = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1"
+            .parse()
+            .unwrap();
+        for mv in crate::movegen::generate_legal_moves(&board).as_slice() {
+            let mut in_place = board;
+            let undo = in_place.make_move_in_place(*mv);
+            assert_eq!(in_place, board.make_move(*mv), "boards differ after {mv}");
+            in_place.unmake_move(*mv, undo);
+            assert_eq!(in_place, board, "board not restored after {mv}");
+        }
+    }
+
+    #[test]
+    fn unmake_records_captured_piece() {
+        // 1.e4 d5 2.exd5
+        let mut board = starting()
+            .make_move(Move::new(Square::E2, Square::E4))
+            .make_move(Move::new(Square::D7, Square::D5));
+        let undo = board.make_move_in_place(Move::new(Square::E4, Square::D5));
+        assert_eq!(undo.captured(), Some(PieceKind::Pawn));
+    }
+
+    #[test]
+    fn random_make_unmake_round_trip() {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..200 {
+            let mut board = starting();
+            let mut played = Vec::new();
+            let mut history = vec![board];
+            for _ in 0..60 {
+                let moves = crate::movegen::generate_legal_moves(&board);
+                if moves.is_empty() {
+                    break;
+                }
+                let mv = moves.as_slice()[(next() % moves.len() as u64) as usize];
+                played.push((mv, board.make_move_in_place(mv)));
+                assert_eq!(board.hash(), crate::zobrist::hash_from_scratch(&board), "hash mismatch after {mv}");
+                history.push(board);
+            }
+            history.pop();
+            while let Some((mv, undo)) = played.pop() {
+                board.unmake_move(mv, undo);
+                let expected = history.pop().unwrap();
+                assert_eq!(board, expected, "board not restored after unmaking {mv}");
+            }
+        }
+    }
+
+    #[test]
+    fn unmake_after_rejected_move_is_noop() {
+        let mut board = starting();
+        let mv = Move::new(Square::E4, Square::E5);
+        let undo = board.make_move_in_place(mv);
+        assert_eq!(board, starting());
+        board.unmake_move(mv, undo);
+        assert_eq!(board, starting());
+    }
 }
@@ -0,0 +1,148 @@
+//! Check detection for a move before it is played.
+
+use crate::attacks::{bishop_attacks, knight_attacks, pawn_attacks, rook_attacks};
+use crate::board::Board;
+use crate::chess_move::{Move, MoveKind};
+use crate::color::Color;
+use crate::make_move::castle_rook_squares;
+use crate::piece_kind::PieceKind;
+use crate::square::Square;
+
+impl Board {
+    /// Return `true` if playing `mv` puts the opponent in check.
+    ///
+    /// `mv` must be legal in this position. The answer comes from the attack
+    /// tables against the occupancy after the move, so it covers direct
+    /// checks, discovered checks (two rays for en passant), promotions, and
+    /// the rook's check after castling without building the child board.
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let us = self.side_to_move();
+        let them = us.flip();
+        let their_king = self.king_square(them);
+        let src = mv.source();
+        let dst = mv.dest();
+        let Some(piece) = self.piece_on(src) else {
+            return false;
+        };
+
+        let ours = |kind: PieceKind| self.pieces(kind) & self.side(us);
+        let mut occupied = (self.occupied() ^ src.bitboard()) | dst.bitboard();
+        let mut diagonal = (ours(PieceKind::Bishop) | ours(PieceKind::Queen)) & !src.bitboard();
+        let mut orthogonal = (ours(PieceKind::Rook) | ours(PieceKind::Queen)) & !src.bitboard();
+        let mut knights = ours(PieceKind::Knight) & !src.bitboard();
+        let mut pawns = ours(PieceKind::Pawn) & !src.bitboard();
+
+        let landed = match mv.kind() {
+            MoveKind::Promotion => mv.promotion_piece().to_piece_kind(),
+            MoveKind::EnPassant => {
+                let captured = Square::from_index_unchecked(if us == Color::White {
+                    dst.index() as u8 - 8
+                } else {
+                    dst.index() as u8 + 8
+                });
+                occupied ^= captured.bitboard();
+                PieceKind::Pawn
+            }
+            MoveKind::Castling => {
+                let Some((rook_src, rook_dst)) = castle_rook_squares(self.castling(), us, dst) else {
+                    return false;
+                };
+                // The king may land on the rook's start square in Chess960.
+                occupied = (self.occupied() & !src.bitboard() & !rook_src.bitboard())
+                    | dst.bitboard()
+                    | rook_dst.bitboard();
+                orthogonal = (orthogonal & !rook_src.bitboard()) | rook_dst.bitboard();
+                PieceKind::King
+            }
+            MoveKind::Normal => piece,
+        };
+
+        match landed {
+            PieceKind::Pawn => pawns |= dst.bitboard(),
+            PieceKind::Knight => knights |= dst.bitboard(),
+            PieceKind::Bishop => diagonal |= dst.bitboard(),
+            PieceKind::Rook => orthogonal |= dst.bitboard(),
+            PieceKind::Queen => {
+                diagonal |= dst.bitboard();
+                orthogonal |= dst.bitboard();
+            }
+            // A king never gives check itself, only by uncovering a slider.
+            PieceKind::King => {}
+        }
+
+        let checkers = (pawn_attacks(them, their_king) & pawns)
+            | (knight_attacks(their_king) & knights)
+            | (bishop_attacks(their_king, occupied) & diagonal)
+            | (rook_attacks(their_king, occupied) & orthogonal);
+        checkers.is_nonempty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::chess_move::{Move, PromotionPiece};
+    use crate::movegen::generate_legal_moves;
+    use crate::square::Square;
+
+    /// Reference answer: play the move and look for attacks on the king.
+    fn gives_check_slow(board: &Board, mv: Move) -> bool {
+        let child = board.make_move(mv);
+        let them = child.side_to_move();
+        child.is_square_attacked(child.king_square(them), !them)
+    }
+
+    #[test]
+    fn special_moves_give_check() {
+        // Castling: the rook lands on f1 facing the king on f8.
+        let board: Board = "5k2/8/8/8/8/8/8/4K2R w K - 0 1".parse().unwrap();
+        assert!(board.gives_check(Move::new_castle(Square::E1, Square::G1)));
+
+        // En passant: the captured pawn uncovers the bishop on a2 ...
+        let board: Board = "6k1/8/8/3pP3/8/8/B7/4K3 w - d6 0 1".parse().unwrap();
+        assert!(board.gives_check(Move::new_en_passant(Square::E5, Square::D6)));
+        // ... and the capturing pawn uncovers the rook on e1.
+        let board: Board = "4k3/8/8/3pP3/8/8/8/K3R3 w - d6 0 1".parse().unwrap();
+        assert!(board.gives_check(Move::new_en_passant(Square::E5, Square::D6)));
+
+        // Promoting on d8: a knight checks the king on e6, a rook does not.
+        let board: Board = "8/3P4/4k3/8/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        let promote = |piece| Move::new_promotion(Square::D7, Square::D8, piece);
+        assert!(board.gives_check(promote(PromotionPiece::Knight)));
+        assert!(!board.gives_check(promote(PromotionPiece::Rook)));
+    }
+
+    #[test]
+    fn gives_check_matches_make_move_on_random_positions() {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let starts: [Board; 4] = [
+            Board::starting_position(),
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".parse().unwrap(),
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1".parse().unwrap(),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9".parse().unwrap(),
+        ];
+        let mut positions = 0;
+        for game in 0..60 {
+            let mut board = starts[game % starts.len()];
+            for _ in 0..80 {
+                let moves = generate_legal_moves(&board);
+                if moves.is_empty() {
+                    break;
+                }
+                for mv in moves.as_slice() {
+                    assert_eq!(board.gives_check(*mv), gives_check_slow(&board, *mv), "{board:?} {mv}");
+                }
+                positions += 1;
+                board = board.make_move(moves[(next() % moves.len() as u64) as usize]);
+            }
+        }
+        assert!(positions >= 2_000, "only {positions} positions visited");
+    }
+}
@@ -1,17 +1,21 @@
 //! King move and castling generation.
 
-use crate::attacks::king_attacks;
+use crate::attacks::{between, king_attacks};
+use crate::bitboard::Bitboard;
 use crate::board::Board;
-use crate::castle_rights::CastleRights;
+use crate::castle_rights::CastleSide;
 use crate::chess_move::Move;
 use crate::color::Color;
+use crate::file::File;
+use crate::make_move::castle_rook_squares;
+use crate::rank::Rank;
 use crate::square::Square;
 
-use super::MoveList;
+use super::MoveSink;
 use super::is_attacked;
 
-/// Generate legal king moves (normal moves + castling).
-pub(super) fn gen_king(board: &Board, king_sq: Square, list: &mut MoveList) {
+/// Generate legal non-castling king moves onto squares in `target_mask`.
+pub(super) fn gen_king<S: MoveSink>(board: &Board, king_sq: Square, target_mask: Bitboard, list: &mut S) {
     let us = board.side_to_move();
     let them = us.flip();
     let friendly = board.side(us);
@@ -19,73 +23,71 @@ pub(super) fn gen_king(board: &Board, king_sq: Square, list: &mut MoveList) {
     // checking destination safety (prevents the king from blocking its own retreat).
     let occupied_no_king = board.occupied() ^ king_sq.bitboard();
 
-    // Normal king moves
-    let mut targets = king_attacks(king_sq) & !friendly;
+    let mut targets = king_attacks(king_sq) & !friendly & target_mask;
     while let Some((dst, rest)) = targets.pop_lsb() {
         targets = rest;
         if !is_attacked(board, dst, them, occupied_no_king) {
             list.push(Move::new(king_sq, dst));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
+}
+
+/// Generate legal castling moves for the king on `king_sq`.
+///
+/// Handles classical and Chess960 setups alike: the rook comes from the file
+/// recorded in the castling rights, the king lands on the g- or c-file and
+/// the rook beside it. Every square either piece crosses or lands on must be
+/// empty apart from the two castling pieces, and no square the king crosses
+/// or lands on may be attacked.
+pub(super) fn gen_castling<S: MoveSink>(board: &Board, king_sq: Square, list: &mut S) {
+    let us = board.side_to_move();
+    let them = us.flip();
+    let occupied = board.occupied();
 
     // Castling — only when not currently in check
-    if is_attacked(board, king_sq, them, board.occupied()) {
+    if is_attacked(board, king_sq, them, occupied) {
         return;
     }
 
     let castling = board.castling();
-    let occupied = board.occupied();
+    let back_rank = match us {
+        Color::White => Rank::Rank1,
+        Color::Black => Rank::Rank8,
+    };
+    if king_sq.rank() != back_rank {
+        return;
+    }
 
-    match us {
-        Color::White => {
-            // Kingside: E1→G1, F1 and G1 must be empty and not attacked
-            if castling.contains(CastleRights::WHITE_KING) {
-                let path_clear =
-                    !occupied.contains(Square::F1) && !occupied.contains(Square::G1);
-                if path_clear
-                    && !is_attacked(board, Square::F1, them, occupied)
-                    && !is_attacked(board, Square::G1, them, occupied)
-                {
-                    list.push(Move::new_castle(Square::E1, Square::G1));
-                }
-            }
-            // Queenside: E1→C1, B1/C1/D1 must be empty, C1 and D1 not attacked
-            if castling.contains(CastleRights::WHITE_QUEEN) {
-                let path_clear = !occupied.contains(Square::B1)
-                    && !occupied.contains(Square::C1)
-                    && !occupied.contains(Square::D1);
-                if path_clear
-                    && !is_attacked(board, Square::C1, them, occupied)
-                    && !is_attacked(board, Square::D1, them, occupied)
-                {
-                    list.push(Move::new_castle(Square::E1, Square::C1));
-                }
-            }
+    for (side, king_dst_file) in [(CastleSide::KingSide, File::FileG), (CastleSide::QueenSide, File::FileC)] {
+        if !castling.has(us, side) {
+            continue;
         }
-        Color::Black => {
-            // Kingside: E8→G8, F8 and G8 must be empty and not attacked
-            if castling.contains(CastleRights::BLACK_KING) {
-                let path_clear =
-                    !occupied.contains(Square::F8) && !occupied.contains(Square::G8);
-                if path_clear
-                    && !is_attacked(board, Square::F8, them, occupied)
-                    && !is_attacked(board, Square::G8, them, occupied)
-                {
-                    list.push(Move::new_castle(Square::E8, Square::G8));
-                }
-            }
-            // Queenside: E8→C8, B8/C8/D8 must be empty, C8 and D8 not attacked
-            if castling.contains(CastleRights::BLACK_QUEEN) {
-                let path_clear = !occupied.contains(Square::B8)
-                    && !occupied.contains(Square::C8)
-                    && !occupied.contains(Square::D8);
-                if path_clear
-                    && !is_attacked(board, Square::C8, them, occupied)
-                    && !is_attacked(board, Square::D8, them, occupied)
-                {
-                    list.push(Move::new_castle(Square::E8, Square::C8));
-                }
-            }
+        let king_dst = Square::new(back_rank, king_dst_file);
+        let Some((rook_src, rook_dst)) = castle_rook_squares(castling, us, king_dst) else {
+            continue;
+        };
+
+        let movers = king_sq.bitboard() | rook_src.bitboard();
+        let king_path = between(king_sq, king_dst) | king_dst.bitboard();
+        let rook_path = between(rook_src, rook_dst) | rook_dst.bitboard();
+        if (occupied & (king_path | rook_path) & !movers).is_nonempty() {
+            continue;
+        }
+
+        // With the king and rook lifted, a slider "behind" the castling rook
+        // sees the squares it would leave exposed, including a king that
+        // castles without moving.
+        let occupied_without = occupied & !movers;
+        if (king_path | king_sq.bitboard()).any(|sq| is_attacked(board, sq, them, occupied_without)) {
+            continue;
+        }
+
+        list.push(Move::new_castle(king_sq, king_dst));
+        if list.is_satisfied() {
+            return;
         }
     }
 }
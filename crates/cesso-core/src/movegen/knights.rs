@@ -7,16 +7,16 @@ use crate::chess_move::Move;
 use crate::piece_kind::PieceKind;
 use crate::square::Square;
 
-use super::MoveList;
+use super::MoveSink;
 use super::check::CheckType;
 
 /// Generate legal knight moves.
-pub(super) fn gen_knights<T: CheckType>(
+pub(super) fn gen_knights<T: CheckType, S: MoveSink>(
     board: &Board,
     _king_sq: Square,
     pinned: Bitboard,
     check_mask: Bitboard,
-    list: &mut MoveList,
+    list: &mut S,
 ) {
     let us = board.side_to_move();
     let friendly = board.side(us);
@@ -32,6 +32,9 @@ pub(super) fn gen_knights<T: CheckType>(
         while let Some((dst, rest2)) = targets.pop_lsb() {
             targets = rest2;
             list.push(Move::new(src, dst));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
 }
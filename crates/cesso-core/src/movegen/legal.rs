@@ -0,0 +1,223 @@
+//! Legality test for a single move, without generating the full move list.
+
+use crate::attacks::{between, bishop_attacks, knight_attacks, line, pawn_attacks, queen_attacks, rook_attacks};
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::chess_move::{Move, MoveKind};
+use crate::color::Color;
+use crate::piece_kind::PieceKind;
+use crate::square::Square;
+
+use super::MoveSink;
+use super::king::{gen_castling, gen_king};
+use super::pins::compute_checkers_and_pinned;
+
+/// Sink that is satisfied once it sees one particular move.
+struct ContainsMove {
+    target: Move,
+    found: bool,
+}
+
+impl MoveSink for ContainsMove {
+    #[inline]
+    fn push(&mut self, mv: Move) {
+        self.found |= mv == self.target;
+    }
+
+    #[inline]
+    fn is_satisfied(&self) -> bool {
+        self.found
+    }
+}
+
+impl Board {
+    /// Return `true` if `mv` is legal in this position.
+    ///
+    /// Accepts exactly the moves [`generate_legal_moves`](super::generate_legal_moves)
+    /// would produce, bit for bit, so any 16-bit value — a TT move after a
+    /// hash collision, a move parsed from UCI — can be checked without
+    /// building and scanning the move list.
+    pub fn is_legal(&self, mv: Move) -> bool {
+        let us = self.side_to_move();
+        let src = mv.source();
+        let dst = mv.dest();
+
+        let friendly = self.side(us);
+        if !friendly.contains(src) {
+            return false;
+        }
+        let Some(piece) = self.piece_on(src) else {
+            return false;
+        };
+        // A Chess960 king may castle onto its own rook's square, or stay put.
+        if friendly.contains(dst) && mv.kind() != MoveKind::Castling {
+            return false;
+        }
+
+        // Every generated move has a canonical encoding: no stray promotion bits.
+        let canonical = match mv.kind() {
+            MoveKind::Normal => Move::new(src, dst),
+            MoveKind::Promotion => Move::new_promotion(src, dst, mv.promotion_piece()),
+            MoveKind::EnPassant => Move::new_en_passant(src, dst),
+            MoveKind::Castling => Move::new_castle(src, dst),
+        };
+        if mv != canonical {
+            return false;
+        }
+
+        let king_sq = self.king_square(us);
+        let (checkers, pinned) = compute_checkers_and_pinned(self);
+        // Empty only in double check, which no other piece can answer.
+        let check_mask = match checkers.count() {
+            0 => Bitboard::FULL,
+            1 => {
+                let Some(checker_sq) = checkers.lsb() else {
+                    return false;
+                };
+                between(king_sq, checker_sq) | checkers
+            }
+            _ => Bitboard::EMPTY,
+        };
+        if pinned.contains(src) && !line(king_sq, src).contains(dst) {
+            return false;
+        }
+
+        let enemy = self.side(us.flip());
+        let occupied = self.occupied();
+        match piece {
+            PieceKind::King => self.is_legal_king_move(mv, king_sq),
+            // Double check: only the king may move.
+            _ if check_mask.is_empty() => false,
+            PieceKind::Pawn => match mv.kind() {
+                MoveKind::EnPassant => self.is_legal_en_passant(src, dst, king_sq, checkers),
+                MoveKind::Castling => false,
+                kind => {
+                    let promo_rank = match us {
+                        Color::White => Bitboard::RANK_8,
+                        Color::Black => Bitboard::RANK_1,
+                    };
+                    if promo_rank.contains(dst) != (kind == MoveKind::Promotion) {
+                        return false;
+                    }
+                    check_mask.contains(dst) && self.is_pawn_move(src, dst, enemy, occupied)
+                }
+            },
+            _ if mv.kind() != MoveKind::Normal => false,
+            PieceKind::Knight => check_mask.contains(dst) && knight_attacks(src).contains(dst),
+            PieceKind::Bishop => check_mask.contains(dst) && bishop_attacks(src, occupied).contains(dst),
+            PieceKind::Rook => check_mask.contains(dst) && rook_attacks(src, occupied).contains(dst),
+            PieceKind::Queen => check_mask.contains(dst) && queen_attacks(src, occupied).contains(dst),
+        }
+    }
+
+    /// King moves and castling have their own safety rules; let the generator decide.
+    fn is_legal_king_move(&self, mv: Move, king_sq: Square) -> bool {
+        let mut sink = ContainsMove { target: mv, found: false };
+        match mv.kind() {
+            MoveKind::Normal => gen_king(self, king_sq, Bitboard::FULL, &mut sink),
+            MoveKind::Castling => gen_castling(self, king_sq, &mut sink),
+            MoveKind::Promotion | MoveKind::EnPassant => return false,
+        }
+        sink.found
+    }
+
+    /// Whether a pawn on `src` can push or capture onto `dst`, ignoring pins and checks.
+    fn is_pawn_move(&self, src: Square, dst: Square, enemy: Bitboard, occupied: Bitboard) -> bool {
+        let us = self.side_to_move();
+        if pawn_attacks(us, src).contains(dst) {
+            return enemy.contains(dst);
+        }
+        let (push_dir, double_rank): (i8, Bitboard) = match us {
+            Color::White => (8, Bitboard::RANK_4),
+            Color::Black => (-8, Bitboard::RANK_5),
+        };
+        let step = dst.index() as i8 - src.index() as i8;
+        if occupied.contains(dst) {
+            return false;
+        }
+        if step == push_dir {
+            return true;
+        }
+        let intermediate = Square::from_index_unchecked((src.index() as i8 + push_dir) as u8);
+        step == 2 * push_dir && double_rank.contains(dst) && !occupied.contains(intermediate)
+    }
+
+    /// Whether an en passant capture from `src` onto `ep_sq` is legal.
+    ///
+    /// Mirrors the en passant rules in the pawn generator, including the
+    /// horizontal discovered check when both pawns leave the king's rank.
+    fn is_legal_en_passant(&self, src: Square, ep_sq: Square, king_sq: Square, checkers: Bitboard) -> bool {
+        let us = self.side_to_move();
+        let them = us.flip();
+        if self.en_passant() != Some(ep_sq) || !pawn_attacks(them, ep_sq).contains(src) {
+            return false;
+        }
+
+        let captured_sq = Square::from_index_unchecked(if us == Color::White {
+            ep_sq.index() as u8 - 8
+        } else {
+            ep_sq.index() as u8 + 8
+        });
+
+        // In check: the capture must take the checker or block the check.
+        if let Some(checker_sq) = checkers.lsb() {
+            let check_mask = between(king_sq, checker_sq) | checkers;
+            if !check_mask.contains(ep_sq) && !check_mask.contains(captured_sq) {
+                return false;
+            }
+        }
+
+        let after_occ = (self.occupied() ^ src.bitboard() ^ captured_sq.bitboard()) | ep_sq.bitboard();
+        let their_rook_queen = (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen)) & self.side(them);
+        (rook_attacks(king_sq, after_occ) & their_rook_queen).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::chess_move::Move;
+    use crate::movegen::generate_legal_moves;
+    use crate::square::Square;
+
+    /// Positions covering castling (classical and Chess960), en passant
+    /// (including the pinned-rank case), promotions, pins, single and double check.
+    const CORPUS: [&str; 12] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1",
+        "4k3/8/8/8/2pP4/8/8/4K2B b - d3 0 1",
+        "4r1k1/8/8/8/8/5n2/8/4K3 w - - 0 1",
+        "4k3/8/8/1b6/8/8/4R3/R3K2R w KQ - 0 1",
+        "4k3/8/8/8/8/8/8/5KR1 w K - 0 1",
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ];
+
+    #[test]
+    fn is_legal_agrees_with_generator_on_every_encoding() {
+        for fen in CORPUS {
+            let board: Board = fen.parse().unwrap();
+            let legal = generate_legal_moves(&board);
+            let mut accepted = 0;
+            for raw in 0..=u16::MAX {
+                let mv = Move::from_raw(raw);
+                let expected = legal.as_slice().contains(&mv);
+                assert_eq!(board.is_legal(mv), expected, "{fen}: {mv:?}");
+                accepted += expected as usize;
+            }
+            assert_eq!(accepted, legal.len(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn is_legal_rejects_moves_from_the_wrong_side() {
+        let board = Board::starting_position();
+        assert!(board.is_legal(Move::new(Square::E2, Square::E4)));
+        assert!(!board.is_legal(Move::new(Square::E7, Square::E5)));
+        assert!(!board.is_legal(Move::NULL));
+    }
+}
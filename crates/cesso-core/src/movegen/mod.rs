@@ -1,28 +1,33 @@
 //! Legal move generation.
 
 mod check;
+mod gives_check;
 mod king;
 mod knights;
+mod legal;
 mod pawns;
 mod pins;
 mod sliders;
 
-use crate::attacks::{between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks};
+use crate::attacks::between;
 use crate::bitboard::Bitboard;
 use crate::board::Board;
 use crate::chess_move::Move;
 use crate::color::Color;
-use crate::piece_kind::PieceKind;
 use crate::square::Square;
 
 use self::check::{InCheck, NotInCheck};
-use self::king::gen_king;
+use self::king::{gen_castling, gen_king};
 use self::knights::gen_knights;
 use self::pawns::gen_pawns;
-use self::pins::compute_checkers_and_pinned;
+use self::pins::{checkers_and_pinned_for, compute_checkers_and_pinned};
 use self::sliders::gen_sliders;
 
 /// Stack-allocated buffer for generated moves. Capacity 256 covers the theoretical max of 218.
+///
+/// Filtering and sorting work in place on the buffer, so callers never need
+/// to copy the moves into a `Vec`.
+#[derive(Clone)]
 pub struct MoveList {
     moves: [Move; 256],
     len: u16,
@@ -62,6 +67,44 @@ impl MoveList {
     pub fn as_slice(&self) -> &[Move] {
         &self.moves[..self.len as usize]
     }
+
+    /// Return `true` if `mv` is in the list.
+    #[inline]
+    pub fn contains(&self, mv: Move) -> bool {
+        self.as_slice().contains(&mv)
+    }
+
+    /// Keep only the moves for which `keep` returns `true`, preserving their order.
+    pub fn retain(&mut self, mut keep: impl FnMut(Move) -> bool) {
+        let mut kept = 0;
+        for i in 0..self.len() {
+            let mv = self.moves[i];
+            if keep(mv) {
+                self.moves[kept] = mv;
+                kept += 1;
+            }
+        }
+        self.len = kept as u16;
+    }
+
+    /// Remove the move at `index` and return it, moving the last move into its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) -> Move {
+        assert!(index < self.len(), "swap_remove index {index} out of bounds (len {})", self.len);
+        let mv = self.moves[index];
+        self.len -= 1;
+        self.moves[index] = self.moves[self.len as usize];
+        mv
+    }
+
+    /// Sort the moves by `key`, keeping equal keys in their current order.
+    pub fn sort_by_key<K: Ord>(&mut self, key: impl FnMut(&Move) -> K) {
+        self.moves[..self.len as usize].sort_by_key(key);
+    }
 }
 
 impl Default for MoveList {
@@ -78,6 +121,22 @@ impl std::ops::Index<usize> for MoveList {
     }
 }
 
+impl Extend<Move> for MoveList {
+    fn extend<I: IntoIterator<Item = Move>>(&mut self, iter: I) {
+        for mv in iter {
+            self.push(mv);
+        }
+    }
+}
+
+impl FromIterator<Move> for MoveList {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        let mut list = MoveList::new();
+        list.extend(iter);
+        list
+    }
+}
+
 impl<'a> IntoIterator for &'a MoveList {
     type Item = &'a Move;
     type IntoIter = std::slice::Iter<'a, Move>;
@@ -86,34 +145,108 @@ impl<'a> IntoIterator for &'a MoveList {
     }
 }
 
-/// Check if `sq` is attacked by `by_color`, using `occupied` for sliding piece rays.
-fn is_attacked(board: &Board, sq: Square, by_color: Color, occupied: Bitboard) -> bool {
-    let them = board.side(by_color);
-    if (knight_attacks(sq) & them & board.pieces(PieceKind::Knight)).is_nonempty() {
-        return true;
+/// Destination for moves produced by the piece generators.
+///
+/// Generators push every legal move they find and return as soon as the sink
+/// reports itself satisfied, so a sink that only needs one move stops the
+/// search early.
+pub(crate) trait MoveSink {
+    /// Record a legal move.
+    fn push(&mut self, mv: Move);
+
+    /// Whether generation can stop now.
+    #[inline]
+    fn is_satisfied(&self) -> bool {
+        false
     }
-    if (king_attacks(sq) & them & board.pieces(PieceKind::King)).is_nonempty() {
-        return true;
+}
+
+impl MoveSink for MoveList {
+    #[inline]
+    fn push(&mut self, mv: Move) {
+        MoveList::push(self, mv);
     }
-    if (pawn_attacks(by_color.flip(), sq) & them & board.pieces(PieceKind::Pawn)).is_nonempty() {
-        return true;
+}
+
+/// Sink that is satisfied by the first legal move.
+#[derive(Default)]
+struct AnyMove {
+    found: bool,
+}
+
+impl MoveSink for AnyMove {
+    #[inline]
+    fn push(&mut self, _mv: Move) {
+        self.found = true;
     }
-    if (rook_attacks(sq, occupied) & them & (board.pieces(PieceKind::Rook) | board.pieces(PieceKind::Queen)))
-        .is_nonempty()
-    {
-        return true;
+
+    #[inline]
+    fn is_satisfied(&self) -> bool {
+        self.found
+    }
+}
+
+/// Check if `sq` is attacked by `by_color`, using `occupied` for sliding piece rays.
+fn is_attacked(board: &Board, sq: Square, by_color: Color, occupied: Bitboard) -> bool {
+    (board.attackers_to(sq, occupied) & board.side(by_color)).is_nonempty()
+}
+
+/// Destination squares a generation pass may produce.
+#[derive(Clone, Copy)]
+struct Targets {
+    /// Allowed destinations for knight, bishop, rook, queen and king moves.
+    pieces: Bitboard,
+    /// Allowed destinations for pawn pushes and captures.
+    pawns: Bitboard,
+    /// Whether to generate en passant captures.
+    en_passant: bool,
+    /// Whether to generate castling.
+    castling: bool,
+}
+
+impl Targets {
+    /// Every legal move.
+    const ALL: Targets = Targets {
+        pieces: Bitboard::FULL,
+        pawns: Bitboard::FULL,
+        en_passant: true,
+        castling: true,
+    };
+
+    /// Captures, en passant, and promotions (quiet or capturing).
+    fn captures(board: &Board) -> Targets {
+        let us = board.side_to_move();
+        let enemy = board.side(us.flip());
+        let promo_rank = match us {
+            Color::White => Bitboard::RANK_8,
+            Color::Black => Bitboard::RANK_1,
+        };
+        Targets {
+            pieces: enemy,
+            pawns: enemy | promo_rank,
+            en_passant: true,
+            castling: false,
+        }
     }
-    if (bishop_attacks(sq, occupied) & them & (board.pieces(PieceKind::Bishop) | board.pieces(PieceKind::Queen)))
-        .is_nonempty()
-    {
-        return true;
+
+    /// Non-capturing moves other than promotions, castling included.
+    fn quiets(board: &Board) -> Targets {
+        let empty = !board.occupied();
+        let promo_rank = match board.side_to_move() {
+            Color::White => Bitboard::RANK_8,
+            Color::Black => Bitboard::RANK_1,
+        };
+        Targets {
+            pieces: empty,
+            pawns: empty & !promo_rank,
+            en_passant: false,
+            castling: true,
+        }
     }
-    false
 }
 
-/// Generate all legal moves for the current position.
-pub fn generate_legal_moves(board: &Board) -> MoveList {
-    let mut list = MoveList::new();
+/// Run the generators for `targets` into `list`.
+fn generate_into<S: MoveSink>(board: &Board, targets: Targets, list: &mut S) {
     let us = board.side_to_move();
     let king_sq = board.king_square(us);
     let (checkers, pinned) = compute_checkers_and_pinned(board);
@@ -121,11 +254,13 @@ pub fn generate_legal_moves(board: &Board) -> MoveList {
     match checkers.count() {
         0 => {
             // Not in check: all piece moves are candidate-legal; check_mask = full board
-            let check_mask = Bitboard::FULL;
-            gen_pawns::<NotInCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_knights::<NotInCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_sliders::<NotInCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_king(board, king_sq, &mut list);
+            gen_pawns::<NotInCheck, _>(board, king_sq, pinned, targets.pawns, targets.en_passant, list);
+            gen_knights::<NotInCheck, _>(board, king_sq, pinned, targets.pieces, list);
+            gen_sliders::<NotInCheck, _>(board, king_sq, pinned, targets.pieces, list);
+            gen_king(board, king_sq, targets.pieces, list);
+            if targets.castling {
+                gen_castling(board, king_sq, list);
+            }
         }
         1 => {
             // Single check: non-king pieces must either capture the checker or
@@ -134,24 +269,139 @@ pub fn generate_legal_moves(board: &Board) -> MoveList {
             let checker_sq = checkers.lsb().expect("checkers has exactly 1 bit set");
             // check_mask = squares between king and checker (blocking) + checker itself
             let check_mask = between(king_sq, checker_sq) | checkers;
-            gen_pawns::<InCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_knights::<InCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_sliders::<InCheck>(board, king_sq, pinned, check_mask, &mut list);
-            gen_king(board, king_sq, &mut list);
+            gen_pawns::<InCheck, _>(board, king_sq, pinned, check_mask & targets.pawns, targets.en_passant, list);
+            gen_knights::<InCheck, _>(board, king_sq, pinned, check_mask & targets.pieces, list);
+            gen_sliders::<InCheck, _>(board, king_sq, pinned, check_mask & targets.pieces, list);
+            gen_king(board, king_sq, targets.pieces, list);
         }
         _ => {
             // Double (or more) check: only king moves can resolve it
-            gen_king(board, king_sq, &mut list);
+            gen_king(board, king_sq, targets.pieces, list);
         }
     }
+}
+
+/// Generate all legal moves for the current position.
+pub fn generate_legal_moves(board: &Board) -> MoveList {
+    let mut list = MoveList::new();
+    generate_into(board, Targets::ALL, &mut list);
+    list
+}
+
+/// Generate the legal moves of the piece on `sq`, e.g. to highlight its
+/// destinations in a GUI.
+///
+/// Empty when `sq` is empty or holds a piece of the side not to move.
+/// Castling belongs to the king's square. Costs as much as
+/// [`generate_legal_moves`], which it filters.
+pub fn legal_moves_for(board: &Board, sq: Square) -> MoveList {
+    let mut list = generate_legal_moves(board);
+    list.retain(|mv| mv.source() == sq);
+    list
+}
+
+/// Generate the legal captures, en passant captures, and promotions.
+///
+/// Quiet moves are never generated, which makes this cheaper than filtering
+/// [`generate_legal_moves`] when only tactical moves will be searched.
+///
+/// In check this stays capture-only: it yields the evasions that capture the
+/// checker (or promote). Callers that must see every evasion, as quiescence
+/// search does, generate the full legal move list instead.
+pub fn generate_captures(board: &Board) -> MoveList {
+    let mut list = MoveList::new();
+    generate_into(board, Targets::captures(board), &mut list);
+    list
+}
 
+/// Generate the legal quiet moves: everything [`generate_captures`] leaves out.
+///
+/// Castling counts as quiet; promotions and en passant never do. Together
+/// the two lists hold exactly the moves of [`generate_legal_moves`], with no
+/// move in both.
+pub fn generate_quiets(board: &Board) -> MoveList {
+    let mut list = MoveList::new();
+    generate_into(board, Targets::quiets(board), &mut list);
     list
 }
 
+impl Board {
+    /// Return `true` if the side to move has at least one legal move.
+    ///
+    /// Runs the same generators as [`generate_legal_moves`] but stops at the
+    /// first legal move, trying the king first since it usually has one.
+    pub fn has_legal_moves(&self) -> bool {
+        let mut sink = AnyMove::default();
+        let king_sq = self.king_square(self.side_to_move());
+        // Castling is skipped: whenever it is legal, so is the king's step
+        // towards the rook.
+        gen_king(self, king_sq, Bitboard::FULL, &mut sink);
+        if sink.found {
+            return true;
+        }
+
+        let (checkers, pinned) = compute_checkers_and_pinned(self);
+        match checkers.count() {
+            0 => {
+                let check_mask = Bitboard::FULL;
+                gen_knights::<NotInCheck, _>(self, king_sq, pinned, check_mask, &mut sink);
+                gen_sliders::<NotInCheck, _>(self, king_sq, pinned, check_mask, &mut sink);
+                gen_pawns::<NotInCheck, _>(self, king_sq, pinned, check_mask, true, &mut sink);
+            }
+            1 => {
+                let checker_sq = checkers.lsb().expect("checkers has exactly 1 bit set");
+                let check_mask = between(king_sq, checker_sq) | checkers;
+                gen_knights::<InCheck, _>(self, king_sq, pinned, check_mask, &mut sink);
+                gen_sliders::<InCheck, _>(self, king_sq, pinned, check_mask, &mut sink);
+                gen_pawns::<InCheck, _>(self, king_sq, pinned, check_mask, true, &mut sink);
+            }
+            // Double check: the king moves above were the only candidates.
+            _ => {}
+        }
+        sink.found
+    }
+
+    /// Return `true` if the game is over: the side to move is mated or stalemated.
+    pub fn is_terminal(&self) -> bool {
+        !self.has_legal_moves()
+    }
+
+    /// Return the enemy pieces giving check to the side to move.
+    pub fn checkers(&self) -> Bitboard {
+        compute_checkers_and_pinned(self).0
+    }
+
+    /// Return `color`'s pieces pinned to `color`'s own king.
+    ///
+    /// A piece is pinned when it is the only piece between its king and an
+    /// enemy slider on that line. The en passant case, where capturing would
+    /// lift two pawns off the king's rank at once, is not a pin.
+    pub fn pinned(&self, color: Color) -> Bitboard {
+        checkers_and_pinned_for(self, color).1
+    }
+
+    /// Return `true` if the side to move is in check.
+    pub fn is_check(&self) -> bool {
+        let us = self.side_to_move();
+        self.is_square_attacked(self.king_square(us), !us)
+    }
+
+    /// Return `true` if the side to move is in check with no legal move.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_check() && !self.has_legal_moves()
+    }
+
+    /// Return `true` if the side to move is not in check but has no legal move.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_check() && !self.has_legal_moves()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::board::Board;
+    use crate::chess_move::MoveKind;
     use crate::piece_kind::PieceKind;
     use crate::square::Square;
 
@@ -167,6 +417,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn legal_moves_for_one_piece() {
+        let board: Board = "7k/8/8/8/4R3/8/8/K7 w - - 0 1".parse().unwrap();
+        let mut dests: Vec<String> =
+            legal_moves_for(&board, Square::E4).as_slice().iter().map(|mv| mv.dest().to_string()).collect();
+        dests.sort();
+        assert_eq!(
+            dests,
+            ["a4", "b4", "c4", "d4", "e1", "e2", "e3", "e5", "e6", "e7", "e8", "f4", "g4", "h4"]
+        );
+        assert!(legal_moves_for(&board, Square::D5).is_empty());
+        assert!(legal_moves_for(&board, Square::H8).is_empty());
+
+        // The knight is pinned against its king
+        let pinned: Board = "4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1".parse().unwrap();
+        assert!(legal_moves_for(&pinned, Square::E2).is_empty());
+        assert_eq!(legal_moves_for(&pinned, Square::E1).len(), 4);
+    }
+
+    #[test]
+    fn move_list_filters_and_sorts_in_place() {
+        let board: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".parse().unwrap();
+        let legal = generate_legal_moves(&board);
+
+        // retain keeps the survivors in generation order
+        let mut captures = generate_legal_moves(&board);
+        captures.retain(|mv| !mv.is_castle() && board.is_occupied(mv.dest()));
+        let expected: Vec<Move> = legal
+            .as_slice()
+            .iter()
+            .copied()
+            .filter(|mv| !mv.is_castle() && board.is_occupied(mv.dest()))
+            .collect();
+        assert_eq!(captures.as_slice(), expected.as_slice());
+
+        // sort_by_key is stable: moves from one square keep their order
+        let mut by_source = generate_legal_moves(&board);
+        by_source.sort_by_key(|mv| mv.source().index());
+        for sq in Square::all() {
+            let sorted: Vec<Move> = by_source.as_slice().iter().copied().filter(|mv| mv.source() == sq).collect();
+            let original: Vec<Move> = legal.as_slice().iter().copied().filter(|mv| mv.source() == sq).collect();
+            assert_eq!(sorted, original);
+        }
+
+        let mut list: MoveList = legal.as_slice().iter().copied().collect();
+        let first = list[0];
+        let last = list[list.len() - 1];
+        assert_eq!(list.swap_remove(0), first);
+        assert_eq!(list.len(), legal.len() - 1);
+        assert_eq!(list[0], last);
+        assert!(!list.contains(first) && list.contains(last));
+    }
+
+    #[test]
+    fn move_list_holds_the_most_legal_moves_possible() {
+        // 218 legal moves, the known maximum
+        let board: Board = "R6R/3Q4/1Q4Q1/4Q3/2Q4Q/Q4Q2/pp1Q4/kBNN1KB1 w - - 0 1".parse().unwrap();
+        let moves = generate_legal_moves(&board);
+        assert_eq!(moves.len(), 218);
+
+        let mut full: MoveList = std::iter::repeat_n(moves[0], 256).collect();
+        assert_eq!(full.len(), 256);
+        full.retain(|_| false);
+        assert!(full.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_list_rejects_a_257th_move() {
+        let board = Board::starting_position();
+        let mv = generate_legal_moves(&board)[0];
+        let mut list: MoveList = std::iter::repeat_n(mv, 256).collect();
+        list.extend([mv]);
+    }
+
     #[test]
     fn pinned_knight_zero_moves() {
         // King on e1, knight on e2, rook on e8 — knight is pinned along the e-file
@@ -211,6 +536,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn chess960_castling_uncovered_by_own_rook() {
+        // King c1 with its queenside rook on b1: castling leaves the king on
+        // c1 and moves the rook to d1, so the black rook on a1 would give check.
+        let castles = |fen: &str| {
+            let board: Board = fen.parse().unwrap();
+            generate_legal_moves(&board).as_slice().iter().filter(|m| m.is_castle()).count()
+        };
+        assert_eq!(castles("4k3/8/8/8/8/8/8/1RK5 w Q - 0 1"), 1);
+        assert_eq!(castles("4k3/8/8/8/8/8/8/rRK5 w Q - 0 1"), 0);
+        // The rook's path must be clear too: d1 is taken by a knight.
+        assert_eq!(castles("4k3/8/8/8/8/8/8/1RKN4 w Q - 0 1"), 0);
+    }
+
     #[test]
     fn en_passant_legal() {
         // White pawn e5, black pawn d5 just moved, EP square d6
@@ -238,4 +577,157 @@ mod tests {
         let promo_moves: Vec<_> = moves.as_slice().iter().filter(|m| m.is_promotion()).collect();
         assert_eq!(promo_moves.len(), 4, "promotion should generate 4 moves (Q/R/B/N)");
     }
+
+    #[test]
+    fn has_legal_moves_detects_mate_and_stalemate() {
+        // Back-rank mate, stalemate, and a double check with one king escape.
+        let mate: Board = "3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1".parse().unwrap();
+        assert!(!mate.has_legal_moves());
+        assert!(mate.is_terminal());
+
+        let stalemate: Board = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".parse().unwrap();
+        assert!(!stalemate.has_legal_moves());
+
+        let double_check: Board = "4r1k1/8/8/8/8/5n2/8/4K3 w - - 0 1".parse().unwrap();
+        assert!(double_check.has_legal_moves());
+        assert!(!Board::starting_position().is_terminal());
+    }
+
+    #[test]
+    fn checkers_lists_every_checking_piece() {
+        assert_eq!(Board::starting_position().checkers(), Bitboard::EMPTY);
+        // Rook on e8 and knight on f3 both check the king on e1.
+        let board: Board = "4r1k1/8/8/8/8/5n2/8/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(board.checkers(), Square::E8.bitboard() | Square::F3.bitboard());
+    }
+
+    #[test]
+    fn pinned_diagonal_and_orthogonal() {
+        // White: knight d2 pinned by the bishop on b4, rook e3 pinned by the
+        // rook on e8. Black: bishop g7 pinned by the bishop on d4.
+        let board: Board = "4r2k/6b1/8/8/1b1B4/4R3/3N4/4K3 b - - 0 1".parse().unwrap();
+        assert_eq!(board.pinned(Color::White), Square::D2.bitboard() | Square::E3.bitboard());
+        assert_eq!(board.pinned(Color::Black), Square::G7.bitboard());
+        // Side to move does not matter.
+        let white_to_move: Board = "4r2k/6b1/8/8/1b1B4/4R3/3N4/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(white_to_move.pinned(Color::White), board.pinned(Color::White));
+    }
+
+    #[test]
+    fn pinned_excludes_enemy_blockers_and_en_passant_rank_pins() {
+        // The black pawn on d2 stands between the white king and the black
+        // bishop: it blocks, but it is not pinned to anything.
+        let board: Board = "4k3/8/8/8/8/8/3p4/2b1K3 w - - 0 1".parse().unwrap();
+        assert_eq!(board.pinned(Color::White), Bitboard::EMPTY);
+        assert_eq!(board.pinned(Color::Black), Bitboard::EMPTY);
+
+        // bxc6 e.p. would expose the king on a5, but with two pawns between
+        // king and rook neither is pinned. Move generation rejects the capture.
+        let board: Board = "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1".parse().unwrap();
+        assert_eq!(board.pinned(Color::White), Bitboard::EMPTY);
+        let moves = generate_legal_moves(&board);
+        assert!(moves.as_slice().iter().all(|mv| mv.kind() != MoveKind::EnPassant));
+    }
+
+    #[test]
+    fn check_mate_and_stalemate_predicates() {
+        let start = Board::starting_position();
+        assert!(!start.is_check() && !start.is_checkmate() && !start.is_stalemate());
+
+        let mate: Board = "3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1".parse().unwrap();
+        assert!(mate.is_check() && mate.is_checkmate() && !mate.is_stalemate());
+
+        let stalemate: Board = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".parse().unwrap();
+        assert!(!stalemate.is_check() && !stalemate.is_checkmate() && stalemate.is_stalemate());
+
+        // In check, but the king can step out of it.
+        let escapable: Board = "3R2k1/5pp1/8/8/8/8/8/6K1 b - - 0 1".parse().unwrap();
+        assert!(escapable.is_check() && !escapable.is_checkmate() && !escapable.is_stalemate());
+    }
+
+    #[test]
+    fn has_legal_moves_agrees_with_generator() {
+        fn walk(board: &Board, depth: usize) {
+            let moves = generate_legal_moves(board);
+            assert_eq!(board.has_legal_moves(), !moves.is_empty(), "{board:?}");
+            if depth == 0 {
+                return;
+            }
+            for mv in moves.as_slice() {
+                walk(&board.make_move(*mv), depth - 1);
+            }
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1",
+        ] {
+            walk(&fen.parse().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn captures_are_exactly_the_tactical_legal_moves() {
+        fn walk(board: &Board, depth: usize) {
+            let legal = generate_legal_moves(board);
+            let captures = generate_captures(board);
+            let tactical: Vec<Move> = legal
+                .as_slice()
+                .iter()
+                .copied()
+                .filter(|mv| mv.is_promotion() || mv.is_en_passant() || (!mv.is_castle() && board.is_occupied(mv.dest())))
+                .collect();
+            assert!(captures.as_slice().iter().all(|mv| legal.as_slice().contains(mv)), "{board:?}");
+            assert_eq!(captures.len(), tactical.len(), "{board:?}");
+            if depth == 0 {
+                return;
+            }
+            for mv in legal.as_slice() {
+                walk(&board.make_move(*mv), depth - 1);
+            }
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ] {
+            walk(&fen.parse().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn quiets_and_captures_partition_the_legal_moves() {
+        fn walk(board: &Board, depth: usize) {
+            let legal = generate_legal_moves(board);
+            let quiets = generate_quiets(board);
+            let captures = generate_captures(board);
+            assert_eq!(quiets.len() + captures.len(), legal.len(), "{board:?}");
+            for mv in quiets.as_slice() {
+                assert!(legal.as_slice().contains(mv), "{board:?} {mv:?}");
+                assert!(!captures.as_slice().contains(mv), "{board:?} {mv:?}");
+                assert!(!mv.is_promotion() && !mv.is_en_passant(), "{board:?} {mv:?}");
+            }
+            if depth == 0 {
+                return;
+            }
+            for mv in legal.as_slice() {
+                walk(&board.make_move(*mv), depth - 1);
+            }
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ] {
+            walk(&fen.parse().unwrap(), 2);
+        }
+    }
 }
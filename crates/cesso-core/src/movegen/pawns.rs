@@ -8,16 +8,20 @@ use crate::color::Color;
 use crate::piece_kind::PieceKind;
 use crate::square::Square;
 
-use super::MoveList;
+use super::MoveSink;
 use super::check::CheckType;
 
 /// Generate legal pawn moves.
-pub(super) fn gen_pawns<T: CheckType>(
+///
+/// Pushes and captures are limited to `check_mask`; en passant is generated
+/// only if `en_passant` is set.
+pub(super) fn gen_pawns<T: CheckType, S: MoveSink>(
     board: &Board,
     king_sq: Square,
     pinned: Bitboard,
     check_mask: Bitboard,
-    list: &mut MoveList,
+    en_passant: bool,
+    list: &mut S,
 ) {
     let us = board.side_to_move();
     let them = us.flip();
@@ -33,11 +37,7 @@ pub(super) fn gen_pawns<T: CheckType>(
     };
 
     // --- Single pushes ---
-    let single_push = if us == Color::White {
-        (our_pawns << 8) & empty
-    } else {
-        (our_pawns >> 8) & empty
-    };
+    let single_push = our_pawns.forward(us) & empty;
 
     // Non-promotion single pushes
     let mut quiet_singles = single_push & !promo_rank & check_mask;
@@ -46,6 +46,9 @@ pub(super) fn gen_pawns<T: CheckType>(
         let src = Square::from_index_unchecked((dst.index() as i8 - push_dir) as u8);
         if !pinned.contains(src) || line(king_sq, src).contains(dst) {
             list.push(Move::new(src, dst));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
 
@@ -57,21 +60,16 @@ pub(super) fn gen_pawns<T: CheckType>(
         if !pinned.contains(src) || line(king_sq, src).contains(dst) {
             for promo in PromotionPiece::ALL {
                 list.push(Move::new_promotion(src, dst, promo));
+                if list.is_satisfied() {
+                    return;
+                }
             }
         }
     }
 
     // --- Double pushes ---
-    let intermediate = if us == Color::White {
-        (our_pawns << 8) & empty
-    } else {
-        (our_pawns >> 8) & empty
-    };
-    let double_push = if us == Color::White {
-        (intermediate << 8) & empty & Bitboard::RANK_4 & check_mask
-    } else {
-        (intermediate >> 8) & empty & Bitboard::RANK_5 & check_mask
-    };
+    let double_rank = if us == Color::White { Bitboard::RANK_4 } else { Bitboard::RANK_5 };
+    let double_push = single_push.forward(us) & empty & double_rank & check_mask;
 
     let mut doubles = double_push;
     while let Some((dst, rest)) = doubles.pop_lsb() {
@@ -79,6 +77,9 @@ pub(super) fn gen_pawns<T: CheckType>(
         let src = Square::from_index_unchecked((dst.index() as i8 - push_dir * 2) as u8);
         if !pinned.contains(src) || line(king_sq, src).contains(dst) {
             list.push(Move::new(src, dst));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
 
@@ -96,15 +97,21 @@ pub(super) fn gen_pawns<T: CheckType>(
             if promo_rank.contains(dst) {
                 for promo in PromotionPiece::ALL {
                     list.push(Move::new_promotion(src, dst, promo));
+                    if list.is_satisfied() {
+                        return;
+                    }
                 }
             } else {
                 list.push(Move::new(src, dst));
+                if list.is_satisfied() {
+                    return;
+                }
             }
         }
     }
 
     // --- En passant ---
-    if let Some(ep_sq) = board.en_passant() {
+    if en_passant && let Some(ep_sq) = board.en_passant() {
         let mut ep_pawns = pawn_attacks(them, ep_sq) & our_pawns;
         while let Some((src, rest)) = ep_pawns.pop_lsb() {
             ep_pawns = rest;
@@ -142,6 +149,9 @@ pub(super) fn gen_pawns<T: CheckType>(
             }
 
             list.push(Move::new_en_passant(src, ep_sq));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
 }
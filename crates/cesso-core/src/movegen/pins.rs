@@ -3,15 +3,25 @@
 use crate::attacks::{between, bishop_attacks, knight_attacks, pawn_attacks, rook_attacks};
 use crate::bitboard::Bitboard;
 use crate::board::Board;
+use crate::color::Color;
 use crate::piece_kind::PieceKind;
 
-/// Compute the set of checking pieces and the set of pinned friendly pieces.
+/// Compute the set of checking pieces and the set of pinned friendly pieces
+/// for the side to move.
 ///
 /// Returns `(checkers, pinned)` where:
 /// - `checkers`: bitboard of enemy pieces giving check to our king
 /// - `pinned`: bitboard of our pieces that are pinned to our king
 pub(crate) fn compute_checkers_and_pinned(board: &Board) -> (Bitboard, Bitboard) {
-    let us = board.side_to_move();
+    checkers_and_pinned_for(board, board.side_to_move())
+}
+
+/// Compute [`compute_checkers_and_pinned`] for `us`, whether or not it is
+/// `us` to move.
+///
+/// Only single blockers are pinned: a pawn that may not capture en passant
+/// because the capture would clear two pieces off a rank is not included.
+pub(crate) fn checkers_and_pinned_for(board: &Board, us: Color) -> (Bitboard, Bitboard) {
     let them = us.flip();
     let king_sq = board.king_square(us);
     let our_pieces = board.side(us);
@@ -7,16 +7,16 @@ use crate::chess_move::Move;
 use crate::piece_kind::PieceKind;
 use crate::square::Square;
 
-use super::MoveList;
+use super::MoveSink;
 use super::check::CheckType;
 
 /// Generate legal slider moves (bishops, rooks, queens).
-pub(super) fn gen_sliders<T: CheckType>(
+pub(super) fn gen_sliders<T: CheckType, S: MoveSink>(
     board: &Board,
     king_sq: Square,
     pinned: Bitboard,
     check_mask: Bitboard,
-    list: &mut MoveList,
+    list: &mut S,
 ) {
     let us = board.side_to_move();
     let friendly = board.side(us);
@@ -38,12 +38,12 @@ pub(super) fn gen_sliders<T: CheckType>(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn gen_slider_type(
+fn gen_slider_type<S: MoveSink>(
     board: &Board,
     king_sq: Square,
     pinned: Bitboard,
     check_mask: Bitboard,
-    list: &mut MoveList,
+    list: &mut S,
     friendly: Bitboard,
     occupied: Bitboard,
     kind: PieceKind,
@@ -64,6 +64,9 @@ fn gen_slider_type(
         while let Some((dst, rest2)) = targets.pop_lsb() {
             targets = rest2;
             list.push(Move::new(src, dst));
+            if list.is_satisfied() {
+                return;
+            }
         }
     }
 }
@@ -1,6 +1,9 @@
 //! Perft (performance test) for move generation correctness verification.
 
+use std::fmt;
+
 use crate::board::Board;
+use crate::chess_move::Move;
 use crate::movegen::generate_legal_moves;
 
 /// Count the number of leaf nodes at the given depth.
@@ -26,24 +29,224 @@ pub fn perft(board: &Board, depth: usize) -> u64 {
     nodes
 }
 
+/// Count leaf nodes by making every move down to depth 0, without bulk counting.
+///
+/// Much slower than [`perft`]; kept to cross-check that counting moves at the
+/// horizon gives the same totals as playing them.
+pub fn perft_slow(board: &Board, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    generate_legal_moves(board)
+        .as_slice()
+        .iter()
+        .map(|mv| perft_slow(&board.make_move(*mv), depth - 1))
+        .sum()
+}
+
+/// Count leaf nodes like [`perft`], caching subtree counts in a hash table.
+///
+/// The table takes `hash_mb` megabytes and is keyed by Zobrist hash and
+/// remaining depth, so transpositions are counted once. A `hash_mb` of 0
+/// falls back to plain [`perft`]. A count that differs from [`perft`] points
+/// to a Zobrist collision or an incomplete hash.
+pub fn perft_hashed(board: &Board, depth: usize, hash_mb: usize) -> u64 {
+    if hash_mb == 0 {
+        return perft(board, depth);
+    }
+    perft_cached(board, depth, &mut PerftTable::new(hash_mb))
+}
+
+/// [`perft_hashed`] with a table owned by the caller.
+///
+/// Counts stay valid across calls, so a table reused for several depths or
+/// positions keeps paying off; [`PerftTable::clear`] empties it.
+pub fn perft_cached(board: &Board, depth: usize, table: &mut PerftTable) -> u64 {
+    if depth <= 1 {
+        return perft(board, depth);
+    }
+    let key = board.hash();
+    if let Some(nodes) = table.probe(key, depth as u8) {
+        return nodes;
+    }
+
+    let moves = generate_legal_moves(board);
+    let mut nodes = 0u64;
+    for mv in moves.as_slice() {
+        nodes += perft_cached(&board.make_move(*mv), depth - 1, table);
+    }
+    table.store(key, depth as u8, nodes);
+    nodes
+}
+
 /// Run perft with per-move breakdown (useful for debugging).
 ///
-/// Returns a vector of `(uci_move, node_count)` pairs sorted alphabetically.
-pub fn divide(board: &Board, depth: usize) -> Vec<(String, u64)> {
+/// Returns a vector of `(move, node_count)` pairs sorted by UCI notation.
+/// The node counts sum to `perft(board, depth)` for `depth >= 1`; at depth 0
+/// no move is played, so the breakdown is empty.
+pub fn divide(board: &Board, depth: usize) -> Vec<(Move, u64)> {
+    divide_hashed(board, depth, 0)
+}
+
+/// Write a [`divide`] breakdown as Stockfish prints `go perft`: one
+/// `move: nodes` line per root move, a blank line, then `Nodes searched: N`.
+///
+/// Every line, the last included, ends in a newline.
+pub fn print_divide(out: &mut impl fmt::Write, breakdown: &[(Move, u64)]) -> fmt::Result {
+    for (mv, nodes) in breakdown {
+        writeln!(out, "{}: {nodes}", mv.to_uci())?;
+    }
+    let total: u64 = breakdown.iter().map(|&(_, nodes)| nodes).sum();
+    writeln!(out)?;
+    writeln!(out, "Nodes searched: {total}")
+}
+
+/// [`divide`] with subtree counts cached as in [`perft_hashed`].
+///
+/// One table of `hash_mb` megabytes is shared by every root move; 0 disables it.
+pub fn divide_hashed(board: &Board, depth: usize, hash_mb: usize) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let mut table = (hash_mb > 0).then(|| PerftTable::new(hash_mb));
     let moves = generate_legal_moves(board);
-    let mut results: Vec<(String, u64)> = moves
+    let mut results: Vec<(Move, u64)> = moves
         .as_slice()
         .iter()
         .map(|mv| {
             let child = board.make_move(*mv);
-            let count = if depth <= 1 { 1 } else { perft(&child, depth - 1) };
-            (mv.to_uci(), count)
+            let count = match (&mut table, depth) {
+                (_, 1) => 1,
+                (Some(table), _) => perft_cached(&child, depth - 1, table),
+                (None, _) => perft(&child, depth - 1),
+            };
+            (*mv, count)
         })
         .collect();
-    results.sort_by(|a, b| a.0.cmp(&b.0));
+    results.sort_by_cached_key(|(mv, _)| mv.to_uci());
     results
 }
 
+/// Count leaf nodes like [`perft`], splitting the root moves across `threads` threads.
+///
+/// Each thread runs the sequential [`perft`] on whole root subtrees, so the
+/// count is identical to [`perft`]. See [`divide_parallel`] for the per-move
+/// breakdown.
+pub fn perft_parallel(board: &Board, depth: usize, threads: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    divide_parallel(board, depth, threads).iter().map(|&(_, nodes)| nodes).sum()
+}
+
+/// [`divide`] with the root moves split across `threads` threads.
+///
+/// Threads take the next unclaimed root move until none are left, so a few
+/// large subtrees do not leave the other threads idle. No more threads are
+/// spawned than there are root moves; 0 is treated as 1.
+///
+/// With the `rayon` feature the root moves are instead spread over a rayon
+/// pool of `threads` workers, sharing nothing but the read-only board.
+pub fn divide_parallel(board: &Board, depth: usize, threads: usize) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let moves = generate_legal_moves(board);
+    let count = |mv: Move| if depth <= 1 { 1 } else { perft(&board.make_move(mv), depth - 1) };
+    let mut results = split_root(moves.as_slice(), threads, count);
+    results.sort_by_cached_key(|(mv, _)| mv.to_uci());
+    results
+}
+
+/// Count each root move's subtree on a rayon pool of `threads` workers.
+///
+/// Falls back to [`split_root_scoped`] if the pool cannot be started.
+#[cfg(feature = "rayon")]
+fn split_root(moves: &[Move], threads: usize, count: impl Fn(Move) -> u64 + Sync) -> Vec<(Move, u64)> {
+    use rayon::prelude::*;
+
+    match rayon::ThreadPoolBuilder::new().num_threads(threads.clamp(1, moves.len().max(1))).build() {
+        Ok(pool) => pool.install(|| moves.par_iter().map(|&mv| (mv, count(mv))).collect()),
+        Err(error) => {
+            tracing::warn!(%error, "perft thread pool failed to start, using scoped threads");
+            split_root_scoped(moves, threads, count)
+        }
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn split_root(moves: &[Move], threads: usize, count: impl Fn(Move) -> u64 + Sync) -> Vec<(Move, u64)> {
+    split_root_scoped(moves, threads, count)
+}
+
+/// Count each root move's subtree on `threads` scoped threads.
+///
+/// A panic in a worker is re-raised on the calling thread.
+fn split_root_scoped(moves: &[Move], threads: usize, count: impl Fn(Move) -> u64 + Sync) -> Vec<(Move, u64)> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|s| {
+        let workers: Vec<_> = (0..threads.clamp(1, moves.len().max(1)))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some(&mv) = moves.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        done.push((mv, count(mv)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_else(|payload| std::panic::resume_unwind(payload)))
+            .collect()
+    })
+}
+
+/// One table slot: Zobrist key, remaining depth, and node count.
+type PerftEntry = (u64, u8, u64);
+
+/// Replace-always cache of subtree node counts for [`perft_cached`], keyed
+/// by Zobrist hash and remaining depth.
+pub struct PerftTable {
+    entries: Vec<PerftEntry>,
+}
+
+impl PerftTable {
+    /// Allocate a table of `hash_mb` megabytes (at least one entry).
+    pub fn new(hash_mb: usize) -> PerftTable {
+        let len = hash_mb * 1024 * 1024 / std::mem::size_of::<PerftEntry>();
+        PerftTable { entries: vec![(0, 0, 0); len.max(1)] }
+    }
+
+    /// Forget every cached count.
+    pub fn clear(&mut self) {
+        self.entries.fill((0, 0, 0));
+    }
+
+    #[inline]
+    fn index(&self, key: u64) -> usize {
+        ((key as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+
+    /// Return the cached count for `key` at `depth`, if present.
+    ///
+    /// Empty slots hold depth 0, which is never stored, so they never match.
+    #[inline]
+    fn probe(&self, key: u64, depth: u8) -> Option<u64> {
+        let (k, d, nodes) = self.entries[self.index(key)];
+        (k == key && d == depth).then_some(nodes)
+    }
+
+    #[inline]
+    fn store(&mut self, key: u64, depth: u8, nodes: u64) {
+        let idx = self.index(key);
+        self.entries[idx] = (key, depth, nodes);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,6 +426,154 @@ mod tests {
         assert_eq!(perft(&position5(), 5), 89_941_194);
     }
 
+    // --- Hashed perft ---
+
+    const HASHED_FENS: [&str; 5] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ];
+
+    fn check_hashed(max_depth: usize) {
+        for fen in HASHED_FENS {
+            let board: Board = fen.parse().unwrap();
+            for depth in 0..=max_depth {
+                assert_eq!(perft_hashed(&board, depth, 1), perft(&board, depth), "{fen} depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn perft_hashed_matches_perft() {
+        check_hashed(4);
+    }
+
+    #[test]
+    #[ignore] // slow
+    fn perft_hashed_matches_perft_depth_5() {
+        check_hashed(5);
+    }
+
+    #[test]
+    fn perft_cached_reuses_table_across_calls() {
+        let mut table = PerftTable::new(1);
+        for fen in HASHED_FENS {
+            let board: Board = fen.parse().unwrap();
+            for depth in (0..=4).rev() {
+                assert_eq!(perft_cached(&board, depth, &mut table), perft(&board, depth), "{fen} depth {depth}");
+            }
+        }
+        table.clear();
+        let board = Board::starting_position();
+        assert_eq!(perft_cached(&board, 4, &mut table), 197_281);
+    }
+
+    #[test]
+    fn perft_hashed_without_table_is_plain_perft() {
+        assert_eq!(perft_hashed(&kiwipete(), 3, 0), 97_862);
+    }
+
+    // --- Bulk counting ---
+
+    fn check_slow(max_depth: usize) {
+        for fen in HASHED_FENS {
+            let board: Board = fen.parse().unwrap();
+            for depth in 0..=max_depth {
+                assert_eq!(perft_slow(&board, depth), perft(&board, depth), "{fen} depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn bulk_counting_matches_slow_perft() {
+        check_slow(3);
+    }
+
+    #[test]
+    #[ignore] // slow
+    fn bulk_counting_matches_slow_perft_depth_5() {
+        check_slow(5);
+    }
+
+    // --- Parallel perft ---
+
+    #[test]
+    fn perft_parallel_matches_perft() {
+        for fen in HASHED_FENS {
+            let board: Board = fen.parse().unwrap();
+            let expected = perft(&board, 3);
+            // One thread, a few, and more threads than root moves
+            for threads in [0, 1, 3, 64] {
+                assert_eq!(perft_parallel(&board, 3, threads), expected, "{fen} threads {threads}");
+            }
+            assert_eq!(perft_parallel(&board, 0, 4), 1);
+        }
+    }
+
+    #[test]
+    #[ignore] // slow
+    fn perft_parallel_matches_perft_depth_6() {
+        for fen in HASHED_FENS {
+            let board: Board = fen.parse().unwrap();
+            for depth in 1..=6 {
+                assert_eq!(perft_parallel(&board, depth, 4), perft(&board, depth), "{fen} depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn divide_parallel_matches_divide() {
+        let board = kiwipete();
+        assert_eq!(divide_parallel(&board, 3, 4), divide(&board, 3));
+        assert_eq!(divide_parallel(&board, 1, 100), divide(&board, 1));
+    }
+
+    #[test]
+    fn perft_parallel_with_no_legal_moves() {
+        let mate: Board = "3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1".parse().unwrap();
+        assert_eq!(perft_parallel(&mate, 2, 4), 0);
+        assert!(divide_parallel(&mate, 2, 4).is_empty());
+    }
+
+    // --- Chess960 ---
+
+    /// The first positions of the published Chess960 perft suite, with node
+    /// counts for depths 1 to 6.
+    const CHESS960: [(&str, [u64; 6]); 10] = [
+        ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", [21, 528, 12_189, 326_672, 8_146_062, 227_689_589]),
+        ("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", [21, 807, 18_002, 667_366, 16_253_601, 590_751_109]),
+        ("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", [20, 479, 10_471, 273_318, 6_417_013, 177_654_692]),
+        ("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9", [22, 593, 13_440, 382_958, 9_183_776, 274_103_539]),
+        ("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9", [28, 1_120, 31_058, 1_171_749, 34_030_312, 1_250_970_898]),
+        ("qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9", [29, 899, 26_578, 824_055, 24_851_983, 775_718_317]),
+        ("q1bnrkr1/ppppp2p/2n2p2/4b1p1/2NP4/8/PPP1PPPP/QNB1RRKB w ge - 1 9", [30, 860, 24_566, 732_757, 21_093_346, 649_209_803]),
+        ("qbn1brkr/ppp1p1p1/2n4p/3p1p2/P7/6PP/QPPPPP2/1BNNBRKR w HFhf - 0 9", [25, 635, 17_054, 465_806, 13_203_304, 377_184_252]),
+        ("qnnbbrkr/1p2ppp1/2pp3p/p7/1P5P/2NP4/P1P1PPP1/Q1NBBRKR w HFhf - 0 9", [24, 572, 15_243, 384_260, 11_110_203, 293_989_890]),
+        ("qn1rbbkr/ppp2p1p/1n1pp1p1/8/3P4/P6P/1PP1PPPK/QNNRBB1R w hd - 2 9", [28, 811, 23_175, 679_699, 19_836_606, 594_527_992]),
+    ];
+
+    fn check_chess960(max_depth: usize) {
+        for (fen, counts) in CHESS960 {
+            let board: Board = fen.parse().unwrap();
+            for depth in 1..=max_depth {
+                assert_eq!(perft(&board, depth), counts[depth - 1], "{fen} depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn perft_chess960_depth_3() {
+        check_chess960(3);
+    }
+
+    #[test]
+    #[ignore] // slow
+    fn perft_chess960_depth_5() {
+        check_chess960(5);
+    }
+
     // --- divide test ---
 
     #[test]
@@ -238,9 +589,64 @@ mod tests {
 
     // --- depth 0 ---
 
+    #[test]
+    fn divide_depth_0_is_empty() {
+        let board = Board::starting_position();
+        assert!(divide(&board, 0).is_empty());
+        assert!(divide_hashed(&board, 0, 1).is_empty());
+        assert!(divide_parallel(&board, 0, 4).is_empty());
+    }
+
     #[test]
     fn perft_depth_0() {
         let board = Board::starting_position();
         assert_eq!(perft(&board, 0), 1);
     }
+
+    // --- in-place make/unmake agrees with copy-make ---
+
+    /// Perft via copy-make, also folding every leaf hash into a checksum.
+    fn perft_copy_hashes(board: &Board, depth: usize) -> (u64, u64) {
+        if depth == 0 {
+            return (1, board.hash());
+        }
+        let mut totals = (0u64, 0u64);
+        for mv in generate_legal_moves(board).as_slice() {
+            let (nodes, hashes) = perft_copy_hashes(&board.make_move(*mv), depth - 1);
+            totals = (totals.0 + nodes, totals.1.wrapping_add(hashes));
+        }
+        totals
+    }
+
+    /// Perft via make/unmake on a single board, with the same leaf checksum.
+    fn perft_in_place_hashes(board: &mut Board, depth: usize) -> (u64, u64) {
+        if depth == 0 {
+            return (1, board.hash());
+        }
+        let mut totals = (0u64, 0u64);
+        for mv in generate_legal_moves(board).as_slice() {
+            let undo = board.make_move_in_place(*mv);
+            let (nodes, hashes) = perft_in_place_hashes(board, depth - 1);
+            board.unmake_move(*mv, undo);
+            totals = (totals.0 + nodes, totals.1.wrapping_add(hashes));
+        }
+        totals
+    }
+
+    #[test]
+    fn in_place_perft_matches_copy_make() {
+        for (board, depth) in [
+            (Board::starting_position(), 4),
+            (kiwipete(), 3),
+            (position3(), 4),
+            (position4(), 3),
+            (position5(), 3),
+        ] {
+            let mut in_place = board;
+            let expected = perft_copy_hashes(&board, depth);
+            assert_eq!(perft_in_place_hashes(&mut in_place, depth), expected, "{board:?}");
+            assert_eq!(expected.0, perft(&board, depth));
+            assert_eq!(in_place, board, "board not restored after in-place perft");
+        }
+    }
 }
@@ -0,0 +1,259 @@
+//! PGN (Portable Game Notation) game import.
+
+use crate::board::Board;
+use crate::chess_move::Move;
+pub use crate::error::PgnError;
+
+/// Result tokens that end the movetext of a game.
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// Tag pairs as `(name, value)`, in file order.
+type TagPairs = Vec<(String, String)>;
+
+/// A game read from PGN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedGame {
+    /// Tag pairs in the order they appear, e.g. `("White", "Morphy")`.
+    pub tags: TagPairs,
+    /// The starting position: the `FEN` tag, or the standard start.
+    pub start: Board,
+    /// The moves of the main line.
+    pub moves: Vec<Move>,
+    /// The position after the last move.
+    pub board: Board,
+    /// The game result: `1-0`, `0-1`, `1/2-1/2` or `*`.
+    pub result: String,
+}
+
+impl ParsedGame {
+    /// The value of the first tag named `name`, if present.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parse the first game in `pgn`.
+///
+/// The tag section is followed by the movetext, whose moves are resolved
+/// with [`Move::from_san`] and played from the `FEN` tag's position when
+/// present (the `SetUp` tag is not required), or from the standard start.
+/// Move numbers (`12.` and `12...`), comments (`{...}` and `;` to end of
+/// line), NAGs (`$1`) and recursive variations `(...)` are skipped. The
+/// movetext ends at the result token; without one, the `Result` tag is used,
+/// or `*` if that is missing too.
+///
+/// # Errors
+///
+/// Returns a [`PgnError`] for a malformed tag, an invalid `FEN` tag, an
+/// unclosed comment or variation, or a move that is not legal, the last
+/// naming the move number and SAN text.
+pub fn parse_game(pgn: &str) -> Result<ParsedGame, PgnError> {
+    let (tags, movetext) = parse_tags(pgn)?;
+    let start = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => fen.parse()?,
+        None => Board::starting_position(),
+    };
+
+    let mut board = start;
+    let mut moves = Vec::new();
+    let mut result = None;
+    for token in tokenize(movetext)? {
+        if RESULTS.contains(&token) {
+            result = Some(token.to_string());
+            break;
+        }
+        let mv = Move::from_san(token, &board).map_err(|source| PgnError::IllegalMove {
+            move_number: board.fullmove_number(),
+            color: board.side_to_move(),
+            san: token.to_string(),
+            source,
+        })?;
+        board = board.make_move(mv);
+        moves.push(mv);
+    }
+
+    let result = result
+        .or_else(|| tags.iter().find(|(name, _)| name == "Result").map(|(_, value)| value.clone()))
+        .unwrap_or_else(|| "*".to_string());
+    Ok(ParsedGame { tags, start, moves, board, result })
+}
+
+/// Split off the tag pairs at the start of `pgn`, returning them and the
+/// remaining movetext.
+fn parse_tags(pgn: &str) -> Result<(TagPairs, &str), PgnError> {
+    let mut tags = Vec::new();
+    let mut rest = pgn.trim_start();
+    while rest.starts_with('[') {
+        let malformed = |end: usize| PgnError::MalformedTag { tag: rest[..end].trim_end().to_string() };
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+
+        let name_end = rest[1..].find(|c: char| c.is_whitespace() || c == '"').map_or(line_end, |i| i + 1);
+        let name = &rest[1..name_end];
+        let after_name = rest[name_end..].trim_start();
+        let Some(quoted) = after_name.strip_prefix('"') else {
+            return Err(malformed(line_end));
+        };
+
+        // The value runs to the next unescaped quote
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        let close = loop {
+            match chars.next() {
+                Some((i, '"')) => break i,
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, c)) => value.push(c),
+                    None => return Err(malformed(line_end)),
+                },
+                Some((_, '\n')) | None => return Err(malformed(line_end)),
+                Some((_, c)) => value.push(c),
+            }
+        };
+        let Some(after) = quoted[close + 1..].trim_start().strip_prefix(']') else {
+            return Err(malformed(line_end));
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(malformed(line_end));
+        }
+
+        tags.push((name.to_string(), value));
+        rest = after.trim_start();
+    }
+    Ok((tags, rest))
+}
+
+/// Split movetext into SAN moves and result tokens, dropping move numbers,
+/// comments, NAGs and variations.
+fn tokenize(movetext: &str) -> Result<Vec<&str>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut rest = movetext;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::UnterminatedComment)?;
+                rest = &rest[end + 1..];
+                continue;
+            }
+            ';' => {
+                rest = rest.find('\n').map_or("", |end| &rest[end..]);
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or(PgnError::UnbalancedVariation)?,
+            _ if c.is_whitespace() => {}
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{};()".contains(c))
+                    .unwrap_or(rest.len());
+                let token = &rest[..end];
+                rest = &rest[end..];
+                if depth == 0 {
+                    tokens.extend(movetext_token(token));
+                }
+                continue;
+            }
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    if depth != 0 {
+        return Err(PgnError::UnbalancedVariation);
+    }
+    Ok(tokens)
+}
+
+/// The move or result in a movetext word, without any move number glued to
+/// its front (`12.e4`); `None` for bare move numbers and NAGs.
+fn movetext_token(word: &str) -> Option<&str> {
+    if word.starts_with('$') {
+        return None;
+    }
+    if RESULTS.contains(&word) {
+        return Some(word);
+    }
+    match word.trim_start_matches(|c: char| c.is_ascii_digit()).strip_prefix('.') {
+        Some(san) => Some(san.trim_start_matches('.')).filter(|san| !san.is_empty()),
+        // Not a move number; SAN parsing reports anything malformed
+        None => Some(word),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::error::SanError;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+[Annotator "A \"quoted\" name"]
+
+1. e4 e5 2. Nf3 d6 {Philidor Defence} 3. d4 Bg4?! $6 4. dxe5 Bxf3 5. Qxf3 dxe5
+6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 (8. Qxb7 Qb4+ (8... Qd7)) 8... c6 9. Bg5 b5
+10. Nxb5! cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6
+; the finish
+15. Bxd7+ Nxd7 16. Qb8+!! Nxb8 17. Rd8# 1-0
+"#;
+
+    #[test]
+    fn parses_an_annotated_game() {
+        let game = parse_game(OPERA_GAME).unwrap();
+        assert_eq!(game.tags.len(), 7);
+        assert_eq!(game.tag("White"), Some("Paul Morphy"));
+        assert_eq!(game.tag("Annotator"), Some("A \"quoted\" name"));
+        assert_eq!(game.start, Board::starting_position());
+        assert_eq!(game.moves.len(), 33);
+        assert_eq!(game.moves[22].to_uci(), "e1c1");
+        assert_eq!(game.result, "1-0");
+        assert_eq!(game.board.to_string(), "1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17");
+        assert!(game.board.is_checkmate());
+    }
+
+    #[test]
+    fn parses_a_game_from_a_fen_tag() {
+        let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 10\"]\n\n10... Kd7 11.e4 Ke6 12. Kf2 $1 *";
+        let game = parse_game(pgn).unwrap();
+        assert_eq!(game.start.to_string(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 10");
+        let uci: Vec<String> = game.moves.iter().map(|mv| mv.to_uci()).collect();
+        assert_eq!(uci, ["e8d7", "e2e4", "d7e6", "e1f2"]);
+        assert_eq!(game.result, "*");
+        assert_eq!(game.board.to_string(), "8/8/4k3/8/4P3/8/5K2/8 b - - 2 12");
+    }
+
+    #[test]
+    fn result_falls_back_to_the_tag() {
+        let game = parse_game("[Result \"1/2-1/2\"]\n1. e4 e5").unwrap();
+        assert_eq!(game.result, "1/2-1/2");
+        assert_eq!(game.moves.len(), 2);
+        assert_eq!(parse_game("1. d4").unwrap().result, "*");
+    }
+
+    #[test]
+    fn reports_the_failing_move() {
+        let err = parse_game("1. e4 e5 2. Nf3 Nc6 3. Bb5 Nf4 *").unwrap_err();
+        assert_eq!(
+            err,
+            PgnError::IllegalMove {
+                move_number: 3,
+                color: Color::Black,
+                san: "Nf4".to_string(),
+                source: SanError::NoSuchMove { san: "Nf4".to_string() },
+            }
+        );
+        assert_eq!(err.to_string(), "move 3... Nf4: no legal move matches \"Nf4\"");
+        assert!(parse_game("1. e4 e5 2. Ke3").unwrap_err().to_string().starts_with("move 2. Ke3"));
+    }
+
+    #[test]
+    fn rejects_broken_structure() {
+        assert!(matches!(parse_game("[White Morphy]\n1. e4"), Err(PgnError::MalformedTag { .. })));
+        assert!(matches!(parse_game("[White \"Morphy\n1. e4"), Err(PgnError::MalformedTag { .. })));
+        assert_eq!(parse_game("1. e4 {unclosed e5"), Err(PgnError::UnterminatedComment));
+        assert_eq!(parse_game("1. e4 (1. d4 e5"), Err(PgnError::UnbalancedVariation));
+        assert_eq!(parse_game("1. e4 e5) 2. Nf3"), Err(PgnError::UnbalancedVariation));
+        assert!(matches!(parse_game("[FEN \"8/8/8/8 w - - 0 1\"]\n*"), Err(PgnError::InvalidFen { .. })));
+    }
+}
@@ -121,6 +121,13 @@ impl Piece {
         self.0
     }
 
+    /// Rebuild a piece from [`Piece::raw`]. `raw` must be a valid raw value.
+    #[inline]
+    pub(crate) const fn from_raw(raw: u8) -> Piece {
+        debug_assert!(raw & 0x07 < 6 && raw < 14);
+        Piece(raw)
+    }
+
     /// Return the FEN character for this piece.
     ///
     /// Uppercase for White pieces, lowercase for Black pieces.
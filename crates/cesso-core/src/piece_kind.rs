@@ -4,6 +4,7 @@ use std::fmt;
 
 /// The kind of a chess piece, without color information.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
 #[repr(u8)]
 pub enum PieceKind {
     Pawn = 0,
@@ -47,6 +48,34 @@ impl PieceKind {
         }
     }
 
+    /// Middlegame material value in centipawns; the king is worth `0`.
+    ///
+    /// These are the values the evaluation and exchange evaluation share.
+    #[inline]
+    pub const fn material_value_mg(self) -> i32 {
+        match self {
+            PieceKind::Pawn => 100,
+            PieceKind::Knight => 320,
+            PieceKind::Bishop => 330,
+            PieceKind::Rook => 500,
+            PieceKind::Queen => 900,
+            PieceKind::King => 0,
+        }
+    }
+
+    /// Endgame material value in centipawns; the king is worth `0`.
+    #[inline]
+    pub const fn material_value_eg(self) -> i32 {
+        match self {
+            PieceKind::Pawn => 120,
+            PieceKind::Knight => 310,
+            PieceKind::Bishop => 320,
+            PieceKind::Rook => 520,
+            PieceKind::Queen => 950,
+            PieceKind::King => 0,
+        }
+    }
+
     /// Parse a FEN character (case-insensitive) into a piece kind.
     #[inline]
     pub fn from_fen_char(c: char) -> Option<PieceKind> {
@@ -108,4 +137,17 @@ mod tests {
         assert_eq!(PieceKind::COUNT, 6);
         assert_eq!(PieceKind::ALL.len(), PieceKind::COUNT);
     }
+
+    #[test]
+    fn material_values_are_ordered() {
+        let phases: [fn(PieceKind) -> i32; 2] = [PieceKind::material_value_mg, PieceKind::material_value_eg];
+        for value in phases {
+            assert!(value(PieceKind::Queen) > value(PieceKind::Rook));
+            assert!(value(PieceKind::Rook) > value(PieceKind::Bishop));
+            assert!(value(PieceKind::Bishop) >= value(PieceKind::Knight));
+            assert!(value(PieceKind::Knight) > value(PieceKind::Pawn));
+            assert!(value(PieceKind::Pawn) > value(PieceKind::King));
+            assert_eq!(value(PieceKind::King), 0);
+        }
+    }
 }
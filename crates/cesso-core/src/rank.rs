@@ -1,6 +1,9 @@
 //! Chess board ranks (rows 1–8).
 
 use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseRankError;
 
 /// A rank (row) on the chess board, from Rank1 (White's back rank) to Rank8.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -53,11 +56,40 @@ impl Rank {
             _ => None,
         }
     }
+
+    /// Parse a rank digit ('1' to '8').
+    #[inline]
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c {
+            '1'..='8' => Rank::from_index(c as u8 - b'1'),
+            _ => None,
+        }
+    }
+
+    /// Return the rank digit ('1' to '8').
+    #[inline]
+    pub const fn to_char(self) -> char {
+        (b'1' + self as u8) as char
+    }
 }
 
 impl fmt::Display for Rank {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.index() + 1)
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseRankError;
+
+    /// Parse a rank digit (`"4"`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Rank::from_char(c),
+            _ => None,
+        }
+        .ok_or_else(|| ParseRankError { input: s.to_string() })
     }
 }
 
@@ -84,6 +116,17 @@ mod tests {
         assert_eq!(Rank::from_index(255), None);
     }
 
+    #[test]
+    fn char_roundtrip() {
+        for rank in Rank::ALL {
+            assert_eq!(Rank::from_char(rank.to_char()), Some(rank));
+        }
+        assert_eq!(Rank::Rank4.to_char(), '4');
+        assert_eq!(Rank::from_char('0'), None);
+        assert_eq!(Rank::from_char('9'), None);
+        assert_eq!(Rank::from_char('a'), None);
+    }
+
     #[test]
     fn display() {
         assert_eq!(format!("{}", Rank::Rank1), "1");
@@ -101,4 +144,14 @@ mod tests {
         assert_eq!(Rank::COUNT, 8);
         assert_eq!(Rank::ALL.len(), Rank::COUNT);
     }
+
+    #[test]
+    fn from_str_roundtrip() {
+        for rank in Rank::ALL {
+            assert_eq!(rank.to_string().parse::<Rank>(), Ok(rank));
+        }
+        for bad in ["", "0", "9", "12", "a"] {
+            assert_eq!(bad.parse::<Rank>().unwrap_err().input, bad);
+        }
+    }
 }
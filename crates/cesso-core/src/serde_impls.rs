@@ -0,0 +1,90 @@
+//! Serde support for types with a standard text form (`serde` feature).
+//!
+//! A [`Board`] serializes as its FEN string and a [`Square`] as its name
+//! (`"e4"`). Deserializing parses the string, so invalid data is rejected
+//! rather than producing a corrupt value. `Move`, `Color` and `PieceKind`
+//! derive their implementations next to their definitions.
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::board::Board;
+use crate::square::Square;
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_fen())
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fen = String::deserialize(deserializer)?;
+        fen.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Square {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+
+    use crate::{Board, Color, Move, PieceKind, Square};
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: T, json: &str) {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+    }
+
+    #[test]
+    fn board_round_trips_as_fen() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        round_trip(fen.parse::<Board>().unwrap(), &format!("\"{fen}\""));
+    }
+
+    #[test]
+    fn corrupted_fen_fails_to_deserialize() {
+        let err = serde_json::from_str::<Board>("\"rnbqkbnr/pppppppp/8/8 w KQkq - 0 1\"").unwrap_err();
+        assert!(err.to_string().contains("expected 8 ranks"), "{err}");
+        assert!(serde_json::from_str::<Board>("42").is_err());
+    }
+
+    #[test]
+    fn square_round_trips_as_name() {
+        for sq in Square::all() {
+            round_trip(sq, &format!("\"{sq}\""));
+        }
+        assert!(serde_json::from_str::<Square>("\"i9\"").is_err());
+    }
+
+    #[test]
+    fn move_round_trips_as_raw_bits() {
+        let board = Board::starting_position();
+        let mv = Move::from_uci_legal("g1f3", &board).unwrap();
+        round_trip(mv, &mv.raw().to_string());
+        let castle: Board = "4k3/8/8/8/8/8/8/4K2R w K - 0 1".parse().unwrap();
+        let mv = Move::from_uci_legal("e1g1", &castle).unwrap();
+        round_trip(mv, &mv.raw().to_string());
+    }
+
+    #[test]
+    fn color_and_piece_kind_round_trip() {
+        round_trip(Color::White, "\"white\"");
+        round_trip(Color::Black, "\"black\"");
+        round_trip(PieceKind::Knight, "\"knight\"");
+        round_trip(PieceKind::Queen, "\"queen\"");
+    }
+}
@@ -1,8 +1,10 @@
 //! Chess board squares using Little-Endian Rank-File (LERF) encoding.
 
 use std::fmt;
+use std::str::FromStr;
 
 use crate::bitboard::Bitboard;
+use crate::error::{ParseSquareError, SquareIndexError};
 use crate::file::File;
 use crate::rank::Rank;
 
@@ -45,20 +47,12 @@ impl Square {
 
     /// Parse an algebraic notation string (e.g. "e4") into a square.
     pub fn from_algebraic(s: &str) -> Option<Square> {
-        let bytes = s.as_bytes();
-        if bytes.len() != 2 {
+        let mut chars = s.chars();
+        let file = File::from_char(chars.next()?)?;
+        let rank = Rank::from_char(chars.next()?)?;
+        if chars.next().is_some() {
             return None;
         }
-
-        let file_byte = bytes[0];
-        let rank_byte = bytes[1];
-
-        if !(b'a'..=b'h').contains(&file_byte) || !(b'1'..=b'8').contains(&rank_byte) {
-            return None;
-        }
-
-        let file = File::from_index(file_byte - b'a')?;
-        let rank = Rank::from_index(rank_byte - b'1')?;
         Some(Square::new(rank, file))
     }
 
@@ -105,6 +99,38 @@ impl Square {
         Bitboard::new(1u64 << self.0)
     }
 
+    /// Distance in king moves: the larger of the rank and file differences.
+    #[inline]
+    pub const fn chebyshev_distance(self, other: Square) -> u8 {
+        let (ranks, files) = self.rank_file_diff(other);
+        if ranks > files { ranks } else { files }
+    }
+
+    /// Distance in rook steps of one square: the sum of the rank and file differences.
+    #[inline]
+    pub const fn manhattan_distance(self, other: Square) -> u8 {
+        let (ranks, files) = self.rank_file_diff(other);
+        ranks + files
+    }
+
+    /// Absolute rank and file differences between two squares.
+    #[inline]
+    const fn rank_file_diff(self, other: Square) -> (u8, u8) {
+        ((self.0 / 8).abs_diff(other.0 / 8), (self.0 % 8).abs_diff(other.0 % 8))
+    }
+
+    /// Return `true` for light squares (h1, a8, ...).
+    #[inline]
+    pub const fn is_light_square(self) -> bool {
+        (self.0 / 8 + self.0 % 8) % 2 == 1
+    }
+
+    /// Return `true` for dark squares (a1, h8, ...).
+    #[inline]
+    pub const fn is_dark_square(self) -> bool {
+        !self.is_light_square()
+    }
+
     /// Iterate over all 64 squares in index order (A1, B1, ..., H8).
     pub fn all() -> impl Iterator<Item = Square> {
         (0u8..64).map(Square)
@@ -189,6 +215,29 @@ impl fmt::Debug for Square {
     }
 }
 
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    /// Parse a square name, either case (`"e4"` or `"E4"`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Square::from_algebraic(&s.to_ascii_lowercase()).ok_or_else(|| ParseSquareError { input: s.to_string() })
+    }
+}
+
+impl TryFrom<u8> for Square {
+    type Error = SquareIndexError;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        Square::from_index(index).ok_or(SquareIndexError { index })
+    }
+}
+
+impl From<Square> for u8 {
+    fn from(sq: Square) -> u8 {
+        sq.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Square;
@@ -244,6 +293,29 @@ mod tests {
         assert!(Square::from_algebraic("a1b").is_none());
     }
 
+    #[test]
+    fn from_str_roundtrip() {
+        for sq in Square::all() {
+            let name = sq.to_string();
+            assert_eq!(name.parse::<Square>(), Ok(sq));
+            assert_eq!(name.to_uppercase().parse::<Square>(), Ok(sq));
+        }
+        for bad in ["", "e", "i1", "a9", "e4 ", "e44"] {
+            assert_eq!(bad.parse::<Square>().unwrap_err().input, bad);
+        }
+    }
+
+    #[test]
+    fn u8_conversions_roundtrip() {
+        for sq in Square::all() {
+            let index = u8::from(sq);
+            assert_eq!(index as usize, sq.index());
+            assert_eq!(Square::try_from(index), Ok(sq));
+        }
+        assert_eq!(Square::try_from(64).unwrap_err().index, 64);
+        assert!(Square::try_from(u8::MAX).is_err());
+    }
+
     #[test]
     fn named_constants() {
         assert_eq!(Square::A1.index(), 0);
@@ -271,4 +343,37 @@ mod tests {
     fn debug_shows_algebraic() {
         assert_eq!(format!("{:?}", Square::E4), "Square(e4)");
     }
+
+    #[test]
+    fn corner_distances_and_colors() {
+        assert_eq!(Square::A1.chebyshev_distance(Square::H8), 7);
+        assert_eq!(Square::A1.manhattan_distance(Square::H8), 14);
+        assert_eq!(Square::H1.chebyshev_distance(Square::A8), 7);
+        assert_eq!(Square::H1.manhattan_distance(Square::A8), 14);
+        assert_eq!(Square::A1.chebyshev_distance(Square::H1), 7);
+        assert_eq!(Square::A1.manhattan_distance(Square::H1), 7);
+        assert_eq!(Square::A1.chebyshev_distance(Square::A1), 0);
+
+        assert!(Square::A1.is_dark_square());
+        assert!(Square::H8.is_dark_square());
+        assert!(Square::H1.is_light_square());
+        assert!(Square::A8.is_light_square());
+    }
+
+    #[test]
+    fn diagonal_pairs() {
+        for a in Square::all() {
+            for b in Square::all() {
+                let ranks = (a.index() / 8).abs_diff(b.index() / 8) as u8;
+                let files = (a.index() % 8).abs_diff(b.index() % 8) as u8;
+                assert_eq!(a.chebyshev_distance(b), b.chebyshev_distance(a));
+                assert_eq!(a.manhattan_distance(b), ranks + files);
+                if ranks == files {
+                    assert_eq!(a.chebyshev_distance(b), ranks, "{a} {b}");
+                    assert_eq!(a.manhattan_distance(b), 2 * ranks, "{a} {b}");
+                    assert_eq!(a.is_light_square(), b.is_light_square(), "{a} {b}");
+                }
+            }
+        }
+    }
 }
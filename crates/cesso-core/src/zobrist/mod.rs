@@ -5,6 +5,10 @@ use crate::color::Color;
 use crate::piece::Piece;
 use crate::piece_kind::PieceKind;
 
+mod polyglot;
+
+pub use polyglot::polyglot_hash;
+
 /// Zobrist key for each (piece, square) pair. Indexed by `[Piece::index()][Square::index()]`.
 /// Piece::index() returns 0-11: White P,N,B,R,Q,K then Black P,N,B,R,Q,K.
 pub(crate) static PIECE_SQUARE: [[u64; 64]; 12] = {
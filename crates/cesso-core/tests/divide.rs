@@ -0,0 +1,70 @@
+//! `divide` breakdowns must agree with `perft` totals.
+
+use cesso_core::{Board, divide, divide_hashed, perft, print_divide};
+
+#[test]
+fn divide_startpos_depth_3_sums_to_perft() {
+    let board = Board::starting_position();
+    let breakdown = divide(&board, 3);
+    assert_eq!(breakdown.len(), 20);
+    assert_eq!(breakdown.iter().map(|(_, n)| n).sum::<u64>(), perft(&board, 3));
+
+    let (_, e2e4) = breakdown
+        .iter()
+        .find(|(mv, _)| mv.to_uci() == "e2e4")
+        .expect("e2e4 should be listed");
+    assert_eq!(*e2e4, 600);
+}
+
+#[test]
+fn divide_startpos_depth_3_matches_known_counts() {
+    // Per-move counts as printed by Stockfish's `go perft 3`
+    let expected = [
+        ("a2a3", 380), ("a2a4", 420), ("b1a3", 400), ("b1c3", 440), ("b2b3", 420),
+        ("b2b4", 421), ("c2c3", 420), ("c2c4", 441), ("d2d3", 539), ("d2d4", 560),
+        ("e2e3", 599), ("e2e4", 600), ("f2f3", 380), ("f2f4", 401), ("g1f3", 440),
+        ("g1h3", 400), ("g2g3", 420), ("g2g4", 421), ("h2h3", 380), ("h2h4", 420),
+    ];
+    let breakdown: Vec<(String, u64)> = divide(&Board::starting_position(), 3)
+        .into_iter()
+        .map(|(mv, nodes)| (mv.to_uci(), nodes))
+        .collect();
+    let expected: Vec<(String, u64)> = expected.iter().map(|&(mv, n)| (mv.to_string(), n)).collect();
+    assert_eq!(breakdown, expected);
+}
+
+#[test]
+fn print_divide_matches_stockfish_layout() {
+    let board: Board = "4k3/8/8/8/8/8/8/4K2R w K - 0 1".parse().unwrap();
+    let mut text = String::new();
+    print_divide(&mut text, &divide(&board, 1)).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 15 + 2);
+    assert!(lines.contains(&"e1g1: 1"));
+    assert_eq!(lines[15], "");
+    assert_eq!(lines[16], "Nodes searched: 15");
+    assert!(text.ends_with('\n'));
+}
+
+#[test]
+fn divide_is_sorted_by_uci() {
+    let board = Board::starting_position();
+    let names: Vec<String> = divide(&board, 1).iter().map(|(mv, _)| mv.to_uci()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+}
+
+#[test]
+fn hashed_divide_matches_divide() {
+    let board: Board = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".parse().unwrap();
+    assert_eq!(divide_hashed(&board, 3, 1), divide(&board, 3));
+}
+
+#[test]
+#[ignore] // slow
+fn divide_startpos_depth_6() {
+    let board = Board::starting_position();
+    let total: u64 = divide(&board, 6).iter().map(|(_, n)| n).sum();
+    assert_eq!(total, 119_060_324);
+}
@@ -0,0 +1,47 @@
+//! `has_legal_moves` must beat building the full move list.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cesso_core::{Board, generate_legal_moves};
+
+/// Positions with legal moves, where the fast path can stop early, followed
+/// by mate and stalemate, where both paths have to try every piece.
+const POSITIONS: [&str; 6] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+    "2K5/p7/7P/5pR1/8/5k2/r7/8 w - - 0 1",
+    "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+    "3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1",
+];
+
+fn time(rounds: u32, boards: &[Board], f: impl Fn(&Board) -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for board in boards {
+            black_box(f(black_box(board)));
+        }
+    }
+    start.elapsed()
+}
+
+#[test]
+fn has_legal_moves_matches_generator() {
+    for fen in POSITIONS {
+        let board: Board = fen.parse().unwrap();
+        assert_eq!(board.has_legal_moves(), !generate_legal_moves(&board).is_empty(), "{fen}");
+    }
+}
+
+#[test]
+#[ignore] // timing; run with --release
+fn has_legal_moves_is_three_times_faster() {
+    // Only the non-terminal positions: a terminal one has no move to stop at.
+    let boards: Vec<Board> = POSITIONS[..4].iter().map(|fen| fen.parse().unwrap()).collect();
+    let rounds = 200_000;
+    let full = time(rounds, &boards, |b| !generate_legal_moves(b).is_empty());
+    let fast = time(rounds, &boards, Board::has_legal_moves);
+    println!("generate_legal_moves: {full:?}, has_legal_moves: {fast:?}");
+    assert!(fast * 3 <= full, "has_legal_moves took {fast:?}, full generation {full:?}");
+}
@@ -0,0 +1,40 @@
+//! `Board::make_null_move` through the public API.
+
+use cesso_core::{Board, Color, PieceKind};
+
+fn kiwipete() -> Board {
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".parse().unwrap()
+}
+
+#[test]
+fn null_move_changes_hash_and_side() {
+    for board in [Board::starting_position(), kiwipete()] {
+        let null = board.make_null_move();
+        assert_ne!(null.hash(), board.hash());
+        assert_eq!(null.side_to_move(), !board.side_to_move());
+        for kind in PieceKind::ALL {
+            assert_eq!(null.pieces(kind), board.pieces(kind));
+        }
+        for color in Color::ALL {
+            assert_eq!(null.side(color), board.side(color));
+        }
+    }
+}
+
+#[test]
+fn double_null_move_restores_hash() {
+    for board in [Board::starting_position(), kiwipete()] {
+        assert_eq!(board.make_null_move().make_null_move().hash(), board.hash());
+    }
+}
+
+#[test]
+fn null_move_clears_en_passant_and_matches_parsed_hash() {
+    let board: Board = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3".parse().unwrap();
+    let null = board.make_null_move();
+    assert_eq!(null.en_passant(), None);
+
+    // Parsing computes the hash from scratch.
+    let expected: Board = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR b KQkq - 1 3".parse().unwrap();
+    assert_eq!(null.hash(), expected.hash());
+}
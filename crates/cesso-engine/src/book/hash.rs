@@ -1 +0,0 @@
-//! Polyglot opening book hash computation (work in progress).
@@ -1,3 +1,407 @@
-//! Opening book support (work in progress).
+//! Polyglot opening books.
+//!
+//! A `.bin` book is a flat array of 16-byte big-endian records sorted by
+//! position key:
+//!
+//! ```text
+//! key (u64) | move (u16) | weight (u16) | learn (u32)
+//! ```
+//!
+//! Keys are [`polyglot_hash`] values. Moves pack the destination square in
+//! bits 0-5, the source square in bits 6-11 and the promotion piece
+//! (1 = knight .. 4 = queen) in bits 12-14; castling is written as the king
+//! capturing its own rook.
+//!
+//! Several books can be layered with [`MultiBook`], for example a narrow,
+//! sharp repertoire over a broad reference book: the repertoire's weights
+//! add to the reference book's where both know a position, and the reference
+//! book still answers where the repertoire runs out.
 
-pub mod hash;
+use std::path::{Path, PathBuf};
+
+use cesso_core::{Board, Move, Square, polyglot_hash};
+
+/// Size of one book record in bytes.
+const ENTRY_SIZE: usize = 16;
+
+/// Errors from loading a Polyglot book.
+#[derive(Debug, thiserror::Error)]
+pub enum BookError {
+    /// The file could not be read.
+    #[error("cannot read book {}: {source}", path.display())]
+    Io {
+        /// The book file.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+    /// The data is not a whole number of 16-byte records.
+    #[error("book size {len} is not a multiple of {ENTRY_SIZE} bytes")]
+    Truncated {
+        /// Length of the data in bytes.
+        len: usize,
+    },
+}
+
+/// One book record; the learn field is not used.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    key: u64,
+    mv: u16,
+    weight: u16,
+}
+
+/// An opening book in Polyglot format, held in memory.
+#[derive(Debug, Clone, Default)]
+pub struct PolyglotBook {
+    /// Records sorted by key; records for one key keep their file order.
+    entries: Vec<Entry>,
+}
+
+impl PolyglotBook {
+    /// Read a book from a `.bin` file.
+    pub fn open(path: impl AsRef<Path>) -> Result<PolyglotBook, BookError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| BookError::Io { path: path.to_path_buf(), source })?;
+        PolyglotBook::from_bytes(&bytes)
+    }
+
+    /// Parse a book from the raw contents of a `.bin` file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PolyglotBook, BookError> {
+        let (records, rest) = bytes.as_chunks::<ENTRY_SIZE>();
+        if !rest.is_empty() {
+            return Err(BookError::Truncated { len: bytes.len() });
+        }
+        let mut entries: Vec<Entry> = records
+            .iter()
+            .map(|&record| {
+                let [key @ .., m0, m1, w0, w1, _, _, _, _] = record;
+                Entry {
+                    key: u64::from_be_bytes(key),
+                    mv: u16::from_be_bytes([m0, m1]),
+                    weight: u16::from_be_bytes([w0, w1]),
+                }
+            })
+            .collect();
+        // Books are written sorted, but lookups must not depend on it
+        entries.sort_by_key(|entry| entry.key);
+        Ok(PolyglotBook { entries })
+    }
+
+    /// Number of records in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the book has no records.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The legal book moves for `board` with their weights, in file order.
+    ///
+    /// Records whose move is not legal here (a key collision or a corrupt
+    /// book) are skipped.
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u16)> {
+        let key = polyglot_hash(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| Some((decode_move(entry.mv, board)?, entry.weight)))
+            .collect()
+    }
+
+    /// The highest-weighted book move for `board`, earliest in the file on ties.
+    pub fn probe(&self, board: &Board) -> Option<Move> {
+        best_move(&widen(self.moves(board)))
+    }
+
+    /// Pick a book move for `board` at random, favouring heavier moves.
+    ///
+    /// Each move is chosen with probability proportional to
+    /// `weight^(1 / temperature)`: at `1.0` that is the weight itself, higher
+    /// temperatures flatten the choice towards uniform, and `0.0` (or below)
+    /// always plays the [`probe`](Self::probe) move.
+    pub fn probe_weighted(&self, board: &Board, rng: &mut BookRng, temperature: f32) -> Option<Move> {
+        weighted_choice(&widen(self.moves(board)), rng, temperature)
+    }
+}
+
+/// Several Polyglot books probed as one.
+///
+/// The books' entries for a position are merged, summing the weights of a
+/// move listed in more than one book, before a move is picked. Moves keep
+/// the order of their first appearance, so on equal weights the earlier book
+/// wins.
+#[derive(Debug, Clone, Default)]
+pub struct MultiBook {
+    books: Vec<PolyglotBook>,
+}
+
+impl MultiBook {
+    /// Combine already loaded books, highest priority first.
+    pub fn new(books: Vec<PolyglotBook>) -> MultiBook {
+        MultiBook { books }
+    }
+
+    /// Read every book in a path list such as `repertoire.bin:main.bin`.
+    ///
+    /// The list uses the platform's separator for path lists, as in `PATH`:
+    /// `:` on Unix, `;` on Windows. Empty entries are skipped.
+    pub fn open(paths: &str) -> Result<MultiBook, BookError> {
+        let books = std::env::split_paths(paths)
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(PolyglotBook::open)
+            .collect::<Result<_, _>>()?;
+        Ok(MultiBook { books })
+    }
+
+    /// Total number of records across all books.
+    pub fn len(&self) -> usize {
+        self.books.iter().map(PolyglotBook::len).sum()
+    }
+
+    /// Whether no book has any records.
+    pub fn is_empty(&self) -> bool {
+        self.books.iter().all(PolyglotBook::is_empty)
+    }
+
+    /// The legal book moves for `board` from all books with their summed
+    /// weights, in order of first appearance.
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u32)> {
+        let mut merged: Vec<(Move, u32)> = Vec::new();
+        for (mv, weight) in self.books.iter().flat_map(|book| book.moves(board)) {
+            match merged.iter_mut().find(|(seen, _)| *seen == mv) {
+                Some((_, total)) => *total += u32::from(weight),
+                None => merged.push((mv, u32::from(weight))),
+            }
+        }
+        merged
+    }
+
+    /// The move with the highest summed weight for `board`.
+    pub fn probe(&self, board: &Board) -> Option<Move> {
+        best_move(&self.moves(board))
+    }
+
+    /// Pick a move from the merged entries as
+    /// [`PolyglotBook::probe_weighted`] does from one book.
+    pub fn probe_weighted(&self, board: &Board, rng: &mut BookRng, temperature: f32) -> Option<Move> {
+        weighted_choice(&self.moves(board), rng, temperature)
+    }
+}
+
+/// Widen single-book weights to the merged weight type.
+fn widen(moves: Vec<(Move, u16)>) -> Vec<(Move, u32)> {
+    moves.into_iter().map(|(mv, weight)| (mv, u32::from(weight))).collect()
+}
+
+/// The first move with the largest weight.
+fn best_move(moves: &[(Move, u32)]) -> Option<Move> {
+    // `max_by_key` keeps the last maximum; reversing keeps the first
+    moves.iter().rev().max_by_key(|&&(_, weight)| weight).map(|&(mv, _)| mv)
+}
+
+/// Sample from `moves` with probabilities proportional to `weight^(1 / temperature)`.
+fn weighted_choice(moves: &[(Move, u32)], rng: &mut BookRng, temperature: f32) -> Option<Move> {
+    let max_weight = moves.iter().map(|&(_, weight)| weight).max()?;
+    if temperature <= 0.0 || max_weight == 0 {
+        return best_move(moves);
+    }
+
+    // Relative to the heaviest move, so the powers stay in [0, 1]
+    let exponent = 1.0 / f64::from(temperature);
+    let scaled: Vec<f64> = moves
+        .iter()
+        .map(|&(_, weight)| (f64::from(weight) / f64::from(max_weight)).powf(exponent))
+        .collect();
+    let total: f64 = scaled.iter().sum();
+
+    let mut target = rng.next_f64() * total;
+    for (&(mv, _), &p) in moves.iter().zip(&scaled) {
+        if target < p {
+            return Some(mv);
+        }
+        target -= p;
+    }
+    // Rounding left the target just past the end
+    best_move(moves)
+}
+
+/// Decode a Polyglot move into a legal [`Move`] for `board`.
+fn decode_move(raw: u16, board: &Board) -> Option<Move> {
+    let dest = Square::from_index((raw & 0x3F) as u8)?;
+    let source = Square::from_index(((raw >> 6) & 0x3F) as u8)?;
+    let promotion = match (raw >> 12) & 0x7 {
+        0 => "",
+        1 => "n",
+        2 => "b",
+        3 => "r",
+        4 => "q",
+        _ => return None,
+    };
+    // UCI parsing already reads king-takes-rook as castling
+    Move::from_uci_legal(&format!("{source}{dest}{promotion}"), board)
+}
+
+/// Small xorshift generator for book move selection.
+#[derive(Debug, Clone)]
+pub struct BookRng(u64);
+
+impl BookRng {
+    /// Create a generator from a seed; a zero seed is replaced by a fixed one.
+    pub fn new(seed: u64) -> BookRng {
+        BookRng(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    /// Next 64 random bits (xorshift64*).
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode `(board, uci move, weight)` records as a `.bin` image.
+    fn book_bytes(records: &[(&Board, &str, u16)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for &(board, uci, weight) in records {
+            let square = |s: &str| u16::from(Square::from_algebraic(s).unwrap().index() as u8);
+            let promotion = match uci.as_bytes().get(4) {
+                Some(b'n') => 1,
+                Some(b'b') => 2,
+                Some(b'r') => 3,
+                Some(b'q') => 4,
+                _ => 0,
+            };
+            let mv = square(&uci[2..4]) | square(&uci[0..2]) << 6 | promotion << 12;
+            bytes.extend_from_slice(&polyglot_hash(board).to_be_bytes());
+            bytes.extend_from_slice(&mv.to_be_bytes());
+            bytes.extend_from_slice(&weight.to_be_bytes());
+            bytes.extend_from_slice(&0u32.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn start_book() -> (Board, PolyglotBook) {
+        let board = Board::starting_position();
+        let bytes = book_bytes(&[(&board, "d2d4", 30), (&board, "e2e4", 50), (&board, "g1f3", 20)]);
+        (board, PolyglotBook::from_bytes(&bytes).unwrap())
+    }
+
+    #[test]
+    fn zero_temperature_always_plays_the_heaviest_move() {
+        let (board, book) = start_book();
+        let e4 = Move::from_uci("e2e4", &board).unwrap();
+        assert_eq!(book.probe(&board), Some(e4));
+        for seed in 1..200 {
+            let mut rng = BookRng::new(seed);
+            assert_eq!(book.probe_weighted(&board, &mut rng, 0.0), Some(e4));
+        }
+    }
+
+    #[test]
+    fn unit_temperature_follows_the_weights() {
+        let (board, book) = start_book();
+        let mut rng = BookRng::new(7);
+        let mut counts = [0u32; 3];
+        for _ in 0..10_000 {
+            let mv = book.probe_weighted(&board, &mut rng, 1.0).unwrap().to_uci();
+            counts[["d2d4", "e2e4", "g1f3"].iter().position(|&m| m == mv).unwrap()] += 1;
+        }
+        // Expected 3000 / 5000 / 2000
+        for (count, expected) in counts.into_iter().zip([3000, 5000, 2000]) {
+            assert!(count.abs_diff(expected) < 300, "{counts:?}");
+        }
+    }
+
+    #[test]
+    fn high_temperature_flattens_the_choice() {
+        let (board, book) = start_book();
+        let mut rng = BookRng::new(11);
+        let knight = (0..10_000)
+            .filter(|_| book.probe_weighted(&board, &mut rng, 100.0).unwrap().to_uci() == "g1f3")
+            .count();
+        assert!(knight > 3000, "{knight}");
+    }
+
+    #[test]
+    fn castling_and_illegal_records() {
+        let board: Board = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".parse().unwrap();
+        let bytes = book_bytes(&[(&board, "e1h1", 10), (&board, "e1e3", 90)]);
+        let book = PolyglotBook::from_bytes(&bytes).unwrap();
+        let castle = book.probe(&board).unwrap();
+        assert!(castle.is_castle());
+        assert_eq!(castle.dest(), Square::G1);
+        assert_eq!(book.moves(&board).len(), 1);
+    }
+
+    #[test]
+    fn unknown_position_is_a_miss() {
+        let (_, book) = start_book();
+        let board: Board = "4k3/8/8/8/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        let mut rng = BookRng::new(1);
+        assert_eq!(book.probe(&board), None);
+        assert_eq!(book.probe_weighted(&board, &mut rng, 1.0), None);
+    }
+
+    #[test]
+    fn rejects_partial_records() {
+        let (board, _) = start_book();
+        let mut bytes = book_bytes(&[(&board, "e2e4", 1)]);
+        bytes.pop();
+        assert!(matches!(PolyglotBook::from_bytes(&bytes), Err(BookError::Truncated { len: 15 })));
+    }
+
+    #[test]
+    fn multi_book_sums_overlapping_moves() {
+        let (board, first) = start_book();
+        let second = PolyglotBook::from_bytes(&book_bytes(&[(&board, "g1f3", 40), (&board, "c2c4", 45)])).unwrap();
+        let book = MultiBook::new(vec![first, second]);
+        let moves: Vec<(String, u32)> = book.moves(&board).into_iter().map(|(mv, w)| (mv.to_uci(), w)).collect();
+        assert_eq!(
+            moves,
+            [("d2d4".to_string(), 30), ("e2e4".to_string(), 50), ("g1f3".to_string(), 60), ("c2c4".to_string(), 45)]
+        );
+        assert_eq!(book.len(), 5);
+        assert_eq!(book.probe(&board).unwrap().to_uci(), "g1f3");
+        let mut rng = BookRng::new(3);
+        assert_eq!(book.probe_weighted(&board, &mut rng, 0.0).unwrap().to_uci(), "g1f3");
+    }
+
+    #[test]
+    fn multi_book_falls_through_to_later_books() {
+        let (start, first) = start_book();
+        let after_e4 = start.make_move(Move::from_uci("e2e4", &start).unwrap());
+        let second = PolyglotBook::from_bytes(&book_bytes(&[(&after_e4, "c7c5", 7)])).unwrap();
+        let book = MultiBook::new(vec![first, second]);
+        let mut rng = BookRng::new(5);
+        assert_eq!(book.probe_weighted(&after_e4, &mut rng, 1.0).unwrap().to_uci(), "c7c5");
+        assert_eq!(book.probe(&start).unwrap().to_uci(), "e2e4");
+
+        let empty: Board = "4k3/8/8/8/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(book.probe_weighted(&empty, &mut rng, 1.0), None);
+        assert_eq!(MultiBook::default().probe(&start), None);
+    }
+
+    #[test]
+    fn multi_book_reports_the_failing_file() {
+        let missing = std::env::temp_dir().join(format!("cesso-missing-{}.bin", std::process::id()));
+        let paths = std::env::join_paths([missing.clone()]).unwrap();
+        match MultiBook::open(paths.to_str().unwrap()) {
+            Err(BookError::Io { path, .. }) => assert_eq!(path, missing),
+            other => panic!("expected an I/O error, got {other:?}"),
+        }
+    }
+}
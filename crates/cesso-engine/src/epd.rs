@@ -0,0 +1,79 @@
+//! EPD test-suite runner.
+//!
+//! Each record is searched for a fixed time and counts as solved when the
+//! best move is one of its `bm` moves and none of its `am` moves.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use cesso_core::EpdRecord;
+use tracing::warn;
+
+use crate::search::Searcher;
+use crate::search::control::SearchControl;
+use crate::search::negamax::MAX_SEARCH_DEPTH;
+
+impl Searcher {
+    /// Search every record for `movetime` and return how many were solved.
+    ///
+    /// Records with neither `bm` nor `am`, or whose moves are not legal in
+    /// the position, are skipped with a warning. The transposition table is
+    /// cleared before each record so results do not depend on order.
+    pub fn run_epd_suite(&self, records: &[EpdRecord], movetime: Duration) -> usize {
+        let mut solved = 0;
+        for record in records {
+            let name = record.id().unwrap_or("?");
+            let (best, avoid) = match (record.best_moves(), record.avoid_moves()) {
+                (Ok(best), Ok(avoid)) => (best, avoid),
+                (Err(err), _) | (_, Err(err)) => {
+                    warn!(id = name, "skipping EPD record: {err}");
+                    continue;
+                }
+            };
+            if best.is_empty() && avoid.is_empty() {
+                warn!(id = name, "skipping EPD record without bm or am");
+                continue;
+            }
+
+            self.clear_tt();
+            let control = SearchControl::new_movetime(movetime, Arc::new(AtomicBool::new(false)));
+            let board = &record.board;
+            let result = self.search(
+                board,
+                MAX_SEARCH_DEPTH,
+                &control,
+                &[],
+                None,
+                board.side_to_move(),
+                |_, _, _, _, _, _| {},
+            );
+            let found = result.best_move;
+            if (best.is_empty() || best.contains(&found)) && !avoid.contains(&found) {
+                solved += 1;
+            }
+        }
+        solved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cesso_core::parse_epd_line;
+
+    use super::*;
+
+    #[test]
+    fn counts_solved_records() {
+        let records: Vec<EpdRecord> = [
+            r#"6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; id "back rank";"#,
+            r#"6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - am Ra8; id "avoid mate";"#,
+            r#"6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - c0 "no target";"#,
+        ]
+        .iter()
+        .map(|line| parse_epd_line(line).unwrap())
+        .collect();
+        let solved = Searcher::new().run_epd_suite(&records, Duration::from_millis(100));
+        assert_eq!(solved, 1);
+    }
+}
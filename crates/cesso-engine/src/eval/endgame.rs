@@ -0,0 +1,116 @@
+//! Mop-up evaluation for winning endgames against a bare king.
+//!
+//! Material alone gives the search no reason to make progress in KQvK or
+//! KRvK: every quiet move scores the same. The mop-up term rewards pushing
+//! the lone king away from the center and bringing the attacking king
+//! closer, which is how these endings are won.
+
+use cesso_core::{Board, Color, PieceKind, Square};
+
+use crate::eval::score::{Score, S};
+
+/// Endgame bonus per step the lone king is from the center.
+const CENTER_DISTANCE_WEIGHT: i32 = 5;
+
+/// Endgame penalty per step between the two kings.
+const KING_DISTANCE_WEIGHT: i32 = 4;
+
+/// The four center squares the lone king is measured against.
+const CENTER: [Square; 4] = [Square::D4, Square::E4, Square::D5, Square::E5];
+
+/// Return the side with mating material facing a bare king, if any.
+///
+/// The stronger side needs a piece other than pawns; with pawns alone the
+/// job is promotion, which the pawn terms already reward.
+fn mopup_side(board: &Board) -> Option<Color> {
+    let king_only = |color: Color| board.side(color) == board.pieces(PieceKind::King) & board.side(color);
+    let has_piece = |color: Color| {
+        let pawns_and_king = board.pieces(PieceKind::Pawn) | board.pieces(PieceKind::King);
+        (board.side(color) & !pawns_and_king).is_nonempty()
+    };
+    [Color::White, Color::Black]
+        .into_iter()
+        .find(|&color| king_only(!color) && has_piece(color))
+}
+
+/// Return `true` if one side has only a king and the other has a piece.
+pub fn is_mopup(board: &Board) -> bool {
+    mopup_side(board).is_some()
+}
+
+/// Mop-up bonus from White's perspective, endgame-weighted only.
+///
+/// Zero unless [`is_mopup`] holds. The lone king's distance from the center
+/// is Manhattan distance to the nearest of d4/e4/d5/e5 (0 in the center, 6
+/// in a corner), so corners score above the middle of an edge; the kings'
+/// distance is Chebyshev, the number of king moves between them.
+pub fn evaluate_mopup(board: &Board) -> Score {
+    let Some(strong) = mopup_side(board) else {
+        return Score::ZERO;
+    };
+    let strong_king = board.king_square(strong);
+    let lone_king = board.king_square(!strong);
+
+    let center_distance = CENTER
+        .iter()
+        .map(|&sq| lone_king.manhattan_distance(sq))
+        .min()
+        .map_or(0, i32::from);
+    let king_distance = i32::from(strong_king.chebyshev_distance(lone_king));
+
+    let score = S(0, CENTER_DISTANCE_WEIGHT * center_distance - KING_DISTANCE_WEIGHT * king_distance);
+    match strong {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cesso_core::Board;
+
+    use super::{evaluate_mopup, is_mopup};
+    use crate::eval::evaluate;
+
+    fn board(fen: &str) -> Board {
+        fen.parse().unwrap()
+    }
+
+    #[test]
+    fn mopup_needs_a_bare_king_and_a_piece() {
+        assert!(is_mopup(&board("8/8/8/4k3/8/8/8/Q3K3 w - - 0 1")));
+        assert!(is_mopup(&board("8/8/8/4k3/8/8/8/R3K3 b - - 0 1")));
+        assert!(is_mopup(&board("4k3/8/8/8/8/8/8/4K2q w - - 0 1")));
+        // Pawns alone, or material on both sides, are not mop-up endings.
+        assert!(!is_mopup(&board("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1")));
+        assert!(!is_mopup(&board("8/8/8/4k3/8/8/8/Q3K2r w - - 0 1")));
+        assert!(!is_mopup(&board(cesso_core::STARTING_FEN)));
+        assert_eq!(evaluate_mopup(&board(cesso_core::STARTING_FEN)).eg(), 0);
+    }
+
+    #[test]
+    fn mopup_is_endgame_only_and_signed_for_the_strong_side() {
+        let white = evaluate_mopup(&board("k7/8/8/8/8/8/8/Q3K3 w - - 0 1"));
+        assert_eq!(white.mg(), 0);
+        assert!(white.eg() > 0);
+
+        let black = evaluate_mopup(&board("q3k3/8/8/8/8/8/8/K7 w - - 0 1"));
+        assert_eq!(black.mg(), 0);
+        assert_eq!(black.eg(), -white.eg());
+    }
+
+    /// In KQvK the engine should prefer the lone king cornered and its own
+    /// king close, so the search has a gradient towards mate.
+    #[test]
+    fn kqk_prefers_cornered_king_and_close_kings() {
+        let center = evaluate(&board("8/8/8/4k3/8/8/8/Q3K3 w - - 0 1"));
+        let edge = evaluate(&board("4k3/8/8/8/8/8/8/Q3K3 w - - 0 1"));
+        let corner = evaluate(&board("7k/8/8/8/8/8/8/Q3K3 w - - 0 1"));
+        assert!(edge > center, "edge {edge} should beat center {center}");
+        assert!(corner > edge, "corner {corner} should beat edge {edge}");
+
+        let far = evaluate(&board("7k/8/8/8/8/8/8/QK6 w - - 0 1"));
+        let near = evaluate(&board("7k/8/5K2/8/8/8/8/Q7 w - - 0 1"));
+        assert!(near > far, "near {near} should beat far {far}");
+    }
+}
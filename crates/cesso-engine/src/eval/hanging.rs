@@ -0,0 +1,106 @@
+//! Hanging piece evaluation.
+//!
+//! A piece hangs when an enemy piece attacks it and no friendly piece of
+//! lesser or equal value defends it. Pawns are left to the pawn structure
+//! terms and kings cannot be left en prise, so only knights, bishops, rooks,
+//! and queens are considered.
+
+use cesso_core::{Bitboard, Board, Color, PieceKind};
+
+use crate::eval::score::{Score, S};
+
+/// Penalty for each hanging piece.
+const HANGING_PIECE: Score = S(-50, -30);
+
+/// Coarse piece value used to compare defenders with the piece they defend.
+///
+/// Knights and bishops share a rank so either can cover the other.
+fn value_rank(kind: PieceKind) -> u8 {
+    match kind {
+        PieceKind::Pawn => 0,
+        PieceKind::Knight | PieceKind::Bishop => 1,
+        PieceKind::Rook => 2,
+        PieceKind::Queen => 3,
+        PieceKind::King => 4,
+    }
+}
+
+/// Least valuable piece kind in `attackers`, if any.
+fn least_valuable(board: &Board, attackers: Bitboard) -> Option<PieceKind> {
+    PieceKind::ALL
+        .into_iter()
+        .find(|&kind| (attackers & board.pieces(kind)).is_nonempty())
+}
+
+/// Evaluate hanging pieces for one side, returning the (non-positive) penalty.
+fn evaluate_hanging_for_side(board: &Board, color: Color) -> Score {
+    let friendly = board.side(color);
+    let occupied = board.occupied();
+    let candidates = friendly & !board.pieces(PieceKind::Pawn) & !board.pieces(PieceKind::King);
+
+    let mut score = Score::ZERO;
+
+    for sq in candidates {
+        let attackers = board.attackers_to(sq, occupied);
+        if (attackers & board.side(!color)).is_empty() {
+            continue;
+        }
+        let Some(kind) = board.piece_on(sq) else { continue };
+        let defended = least_valuable(board, attackers & friendly)
+            .is_some_and(|defender| value_rank(defender) <= value_rank(kind));
+        if !defended {
+            score += HANGING_PIECE;
+        }
+    }
+
+    score
+}
+
+/// Evaluate hanging pieces from White's perspective.
+///
+/// Returns `white_penalty - black_penalty`, so White's hanging pieces lower
+/// the score and Black's raise it.
+pub fn evaluate_hanging(board: &Board) -> Score {
+    evaluate_hanging_for_side(board, Color::White) - evaluate_hanging_for_side(board, Color::Black)
+}
+
+#[cfg(test)]
+mod tests {
+    use cesso_core::Board;
+
+    use super::{evaluate_hanging, HANGING_PIECE};
+    use crate::eval::evaluate;
+    use crate::eval::score::Score;
+
+    #[test]
+    fn starting_position_has_no_hanging_pieces() {
+        assert_eq!(evaluate_hanging(&Board::starting_position()), Score::ZERO);
+    }
+
+    /// The white queen on d2 is hit by the bishop on b4 and only the king
+    /// guards it; with the bishop on b5 instead, nothing hangs.
+    #[test]
+    fn hanging_queen_lowers_the_evaluation() {
+        let hanging: Board = "4k3/8/8/p7/1b6/8/3Q4/4K3 w - - 0 1".parse().unwrap();
+        let safe: Board = "4k3/8/p7/1b6/8/8/3Q4/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(evaluate_hanging(&hanging), HANGING_PIECE);
+        assert_eq!(evaluate_hanging(&safe), Score::ZERO);
+
+        let (hanging, safe) = (evaluate(&hanging), evaluate(&safe));
+        assert!(hanging < safe - 25, "hanging queen {hanging} vs safe queen {safe}");
+    }
+
+    /// A rook attacked by a knight but defended by a pawn does not hang.
+    #[test]
+    fn cheaper_defender_saves_the_piece() {
+        let board: Board = "4k3/8/8/8/3n4/5R2/6P1/4K3 w - - 0 1".parse().unwrap();
+        assert_eq!(evaluate_hanging(&board), Score::ZERO);
+    }
+
+    /// A rook defended only by the queen still hangs.
+    #[test]
+    fn dearer_defender_does_not_save_the_piece() {
+        let board: Board = "4k3/8/8/8/3n4/5R2/8/4KQ2 w - - 0 1".parse().unwrap();
+        assert_eq!(evaluate_hanging(&board), HANGING_PIECE);
+    }
+}
@@ -19,6 +19,10 @@ const MISSING_SHIELD_PAWN_PENALTY: Score = S(-30, 0);
 /// Attack weights by piece kind index: [Pawn, Knight, Bishop, Rook, Queen, King]
 const ATTACK_WEIGHTS: [i32; 6] = [0, 2, 2, 3, 5, 0];
 
+/// Danger points per safe checking square, counted once per piece type that
+/// can reach it.
+const SAFE_CHECK_DANGER: i32 = 10;
+
 /// Penalty for an open file adjacent to or on the king's file.
 const OPEN_FILE_PENALTY: Score = S(-25, 0);
 
@@ -39,41 +43,19 @@ const STORM_FAR_PENALTY: Score = S(-10, 0);
 /// extended one rank forward.
 fn king_zone(king_sq: Square, color: Color) -> Bitboard {
     let base = king_attacks(king_sq) | king_sq.bitboard();
-    let forward = match color {
-        Color::White => (base & !Bitboard::RANK_8) << 8u8,
-        Color::Black => (base & !Bitboard::RANK_1) >> 8u8,
-    };
-    base | forward
+    base | base.forward(color)
 }
 
 /// Return the file cluster around the king: the king's file plus adjacent files.
 fn king_file_cluster(king_sq: Square) -> Bitboard {
-    let file = king_sq.file();
-    let mut mask = Bitboard::file_mask(file);
-    if file.index() > 0 {
-        if let Some(f) = File::from_index(file.index() as u8 - 1) {
-            mask = mask | Bitboard::file_mask(f);
-        }
-    }
-    if file.index() < 7 {
-        if let Some(f) = File::from_index(file.index() as u8 + 1) {
-            mask = mask | Bitboard::file_mask(f);
-        }
-    }
-    mask
+    let file = Bitboard::file_mask(king_sq.file());
+    file | file.east() | file.west()
 }
 
 /// Compute the pawn shield mask for a king on the given square.
 fn shield_mask(king_sq: Square, color: Color) -> Bitboard {
-    let king_bb = king_sq.bitboard();
-    let shifted = match color {
-        Color::White => king_bb << 8u8,
-        Color::Black => king_bb >> 8u8,
-    };
-    if shifted.is_empty() {
-        return Bitboard::EMPTY;
-    }
-    shifted | ((shifted << 1u8) & !Bitboard::FILE_A) | ((shifted >> 1u8) & !Bitboard::FILE_H)
+    let front = king_sq.bitboard().forward(color);
+    front | front.east() | front.west()
 }
 
 // ---------------------------------------------------------------------------
@@ -144,11 +126,47 @@ fn attacker_zone_danger(board: &Board, king_color: Color) -> i32 {
     }
 
     // Scale danger by number of attackers
-    if attacker_count < 2 {
-        0
-    } else {
-        danger * danger / 4
-    }
+    let zone_danger = if attacker_count < 2 { 0 } else { danger * danger / 4 };
+
+    zone_danger + safe_check_danger(board, king_color)
+}
+
+/// Danger from safe checks against `king_color`'s king.
+///
+/// A checking square is one from which a piece of a given type would attack
+/// the king. It is safe if an enemy piece of that type can move there and no
+/// friendly piece defends it. Each safe square counts once per piece type.
+fn safe_check_danger(board: &Board, king_color: Color) -> i32 {
+    let attacker_color = !king_color;
+    let king_sq = board.king_square(king_color);
+    let occupied = board.occupied();
+    let enemy = board.side(attacker_color);
+
+    let reach = |kind: PieceKind, attacks: &dyn Fn(Square) -> Bitboard| {
+        (board.pieces(kind) & enemy).into_iter().fold(Bitboard::EMPTY, |acc, sq| acc | attacks(sq))
+    };
+    let knight_reach = reach(PieceKind::Knight, &knight_attacks);
+    let bishop_reach = reach(PieceKind::Bishop, &|sq| bishop_attacks(sq, occupied));
+    let rook_reach = reach(PieceKind::Rook, &|sq| rook_attacks(sq, occupied));
+    let queen_reach = reach(PieceKind::Queen, &|sq| queen_attacks(sq, occupied));
+
+    let diagonal_checks = bishop_attacks(king_sq, occupied);
+    let orthogonal_checks = rook_attacks(king_sq, occupied);
+
+    let checks = [
+        knight_attacks(king_sq) & knight_reach,
+        diagonal_checks & bishop_reach,
+        orthogonal_checks & rook_reach,
+        (diagonal_checks | orthogonal_checks) & queen_reach,
+    ];
+
+    checks
+        .into_iter()
+        .map(|squares| {
+            let safe = (squares & !enemy).into_iter().filter(|&sq| !board.is_square_attacked(sq, king_color));
+            safe.count() as i32 * SAFE_CHECK_DANGER
+        })
+        .sum()
 }
 
 /// Evaluate pawn storm for one side's king.
@@ -168,13 +186,13 @@ fn pawn_storm_penalty(board: &Board, king_color: Color) -> Score {
         let dist = if king_color == Color::White {
             // Enemy (black) pawns advance downward (decreasing rank index).
             // Distance is how close the pawn is to the king.
-            if king_rank >= pawn_rank { king_rank - pawn_rank } else { pawn_rank - king_rank }
+            king_rank.abs_diff(pawn_rank)
         } else {
             // Enemy (white) pawns advance upward (increasing rank index).
-            if pawn_rank >= king_rank { pawn_rank - king_rank } else { king_rank - pawn_rank }
+            pawn_rank.abs_diff(king_rank)
         };
 
-        if dist >= 2 && dist <= 3 {
+        if (2..=3).contains(&dist) {
             penalty += STORM_CLOSE_PENALTY;
         } else if dist == 4 {
             penalty += STORM_FAR_PENALTY;
@@ -231,7 +249,7 @@ pub fn evaluate_king_safety(board: &Board) -> Score {
     // Attacker zone danger (quadratic, converted to middlegame-only penalty)
     let white_danger = attacker_zone_danger(board, Color::White);
     let black_danger = attacker_zone_danger(board, Color::Black);
-    let danger_score = S(-(white_danger as i16), 0) - S(-(black_danger as i16), 0);
+    let danger_score = S(black_danger - white_danger, 0);
 
     // Pawn storm
     let white_storm = pawn_storm_penalty(board, Color::White);
@@ -252,9 +270,9 @@ pub fn evaluate_king_safety(board: &Board) -> Score {
 
 #[cfg(test)]
 mod tests {
-    use cesso_core::Board;
+    use cesso_core::{Board, Color};
 
-    use super::evaluate_king_safety;
+    use super::{attacker_zone_danger, evaluate_king_safety, safe_check_danger, SAFE_CHECK_DANGER};
     use crate::eval::score::Score;
 
     #[test]
@@ -301,4 +319,25 @@ mod tests {
         // This test just checks it doesn't crash
         let _ = score;
     }
+
+    #[test]
+    fn safe_checks_add_danger() {
+        // The knight on g5 can check from f3 and h3 and the queen from d1;
+        // nothing guards those squares once the g-pawn is gone.
+        let exposed: Board = "3qk3/8/8/6n1/8/8/5P1P/6K1 b - - 0 1".parse().unwrap();
+        assert_eq!(safe_check_danger(&exposed, Color::White), 3 * SAFE_CHECK_DANGER);
+        assert_eq!(attacker_zone_danger(&exposed, Color::White), 3 * SAFE_CHECK_DANGER);
+
+        // With the g-pawn home, only the queen check on d1 is safe.
+        let covered: Board = "3qk3/8/8/6n1/8/8/5PPP/6K1 b - - 0 1".parse().unwrap();
+        assert_eq!(safe_check_danger(&covered, Color::White), SAFE_CHECK_DANGER);
+        assert!(evaluate_king_safety(&exposed).mg() < evaluate_king_safety(&covered).mg());
+    }
+
+    #[test]
+    fn defended_checking_squares_are_not_safe() {
+        // The rook on f1 guards d1 and the pawns cover f3 and h3.
+        let board: Board = "3qk3/8/8/6n1/8/8/5PPP/5RK1 b - - 0 1".parse().unwrap();
+        assert_eq!(safe_check_danger(&board, Color::White), 0);
+    }
 }
@@ -1,29 +1,28 @@
 //! Material balance evaluation.
 //!
-//! Counts weighted piece material for each side and adds a bishop-pair bonus.
+//! Counts weighted piece material for each side, adds a bishop-pair bonus,
+//! and corrects for common piece imbalances.
 //! All scores are returned from White's perspective (positive = White ahead).
 
 use cesso_core::{Board, Color, PieceKind};
 
 use crate::eval::score::{Score, S};
 
-/// Base material values indexed by [`PieceKind::index()`].
-///
-/// | Piece  | mg  | eg  |
-/// |--------|-----|-----|
-/// | Pawn   | 100 | 120 |
-/// | Knight | 320 | 310 |
-/// | Bishop | 330 | 320 |
-/// | Rook   | 500 | 520 |
-/// | Queen  | 900 | 950 |
-/// | King   |   0 |   0 |
+/// The material value of `kind` as a packed score, from
+/// [`PieceKind::material_value_mg`] and [`PieceKind::material_value_eg`].
+pub const fn material_score(kind: PieceKind) -> Score {
+    S(kind.material_value_mg(), kind.material_value_eg())
+}
+
+/// Base material values indexed by [`PieceKind::index()`]; see
+/// [`material_score`].
 pub const MATERIAL_VALUE: [Score; PieceKind::COUNT] = [
-    S(100, 120), // Pawn
-    S(320, 310), // Knight
-    S(330, 320), // Bishop
-    S(500, 520), // Rook
-    S(900, 950), // Queen
-    S(0, 0),     // King
+    material_score(PieceKind::Pawn),
+    material_score(PieceKind::Knight),
+    material_score(PieceKind::Bishop),
+    material_score(PieceKind::Rook),
+    material_score(PieceKind::Queen),
+    material_score(PieceKind::King),
 ];
 
 /// Bonus awarded to a side that has two or more bishops.
@@ -92,6 +91,69 @@ pub fn bishop_knight_balance(board: &Board) -> Score {
     score
 }
 
+/// Number of rows and columns in [`IMBALANCE`]: the bishop pair plus the five
+/// non-king piece kinds.
+const IMBALANCE_KINDS: usize = 6;
+
+/// Imbalance slot for owning the bishop pair (count is 0 or 1).
+const PAIR: usize = 0;
+/// Imbalance slots for each non-king piece kind.
+const PAWN: usize = 1;
+const KNIGHT: usize = 2;
+const BISHOP: usize = 3;
+const ROOK: usize = 4;
+
+/// Hand-tuned imbalance corrections, `IMBALANCE[own][opp]`.
+///
+/// Each entry is added once per own piece of kind `own` for every opposing
+/// piece of kind `opp`. Rows and columns are ordered bishop pair, pawn,
+/// knight, bishop, rook, queen.
+///
+/// - Minor pieces gain against rooks, so two minors edge out rook and pawn.
+/// - The bishop pair gains against knights.
+/// - A rook loses a little against a bishop.
+const IMBALANCE: [[Score; IMBALANCE_KINDS]; IMBALANCE_KINDS] = {
+    let mut table = [[Score::ZERO; IMBALANCE_KINDS]; IMBALANCE_KINDS];
+    table[PAIR][KNIGHT] = S(8, 12);
+    table[KNIGHT][ROOK] = S(12, 8);
+    table[BISHOP][ROOK] = S(12, 8);
+    table[ROOK][BISHOP] = S(-6, -4);
+    table[ROOK][PAWN] = S(-2, -2);
+    table
+};
+
+/// Count one side's material by imbalance slot.
+fn imbalance_counts(board: &Board, color: Color) -> [i16; IMBALANCE_KINDS] {
+    let ours = board.side(color);
+    let mut counts = [0; IMBALANCE_KINDS];
+    for kind in [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen] {
+        counts[kind.index() + 1] = (board.pieces(kind) & ours).count() as i16;
+    }
+    counts[PAIR] = i16::from(counts[BISHOP] >= 2);
+    counts
+}
+
+/// Sum the [`IMBALANCE`] entries for `own` facing `opp`.
+fn imbalance_for(own: &[i16; IMBALANCE_KINDS], opp: &[i16; IMBALANCE_KINDS]) -> Score {
+    let mut score = Score::ZERO;
+    for (row, &own_count) in IMBALANCE.iter().zip(own) {
+        for (&entry, &opp_count) in row.iter().zip(opp) {
+            score += entry * (own_count * opp_count);
+        }
+    }
+    score
+}
+
+/// Evaluate material imbalances from White's perspective.
+///
+/// Positions where both sides have the same pieces always score zero, since
+/// each side's correction is computed the same way against the other.
+pub fn material_imbalance(board: &Board) -> Score {
+    let white = imbalance_counts(board, Color::White);
+    let black = imbalance_counts(board, Color::Black);
+    imbalance_for(&white, &black) - imbalance_for(&black, &white)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -100,7 +162,10 @@ pub fn bishop_knight_balance(board: &Board) -> Score {
 mod tests {
     use cesso_core::Board;
 
-    use super::{material, BISHOP_PAIR_BONUS, MATERIAL_VALUE};
+    use super::{
+        material, material_imbalance, BISHOP, BISHOP_PAIR_BONUS, IMBALANCE, KNIGHT,
+        MATERIAL_VALUE, PAIR, ROOK,
+    };
     use crate::eval::score::{Score, S};
     use cesso_core::PieceKind;
 
@@ -167,7 +232,7 @@ mod tests {
     fn extra_white_rook() {
         // White has an extra rook compared to Black.
         // FEN: remove one Black rook (a8).
-        let board = "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        let board = "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1"
             .parse::<Board>()
             .unwrap();
         let score = material(&board);
@@ -191,4 +256,47 @@ mod tests {
     fn material_value_table_king_is_zero() {
         assert_eq!(MATERIAL_VALUE[PieceKind::King.index()], S(0, 0));
     }
+
+    #[test]
+    fn symmetric_material_has_no_imbalance() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "4k3/pp3n2/2b5/8/8/2B5/PP3N2/4K3 w - - 0 1",
+            "r3k3/8/8/8/8/8/8/R3K3 b - - 0 1",
+        ] {
+            let board: Board = fen.parse().unwrap();
+            assert_eq!(material_imbalance(&board), Score::ZERO, "{fen}");
+        }
+    }
+
+    #[test]
+    fn two_minors_beat_rook_and_pawn() {
+        // White: knight and bishop. Black: rook and pawn.
+        let board: Board = "r3k3/p7/8/8/8/8/8/2B1KN2 w - - 0 1".parse().unwrap();
+        let expected = IMBALANCE[KNIGHT][ROOK] + IMBALANCE[BISHOP][ROOK]
+            - IMBALANCE[ROOK][BISHOP];
+        assert_eq!(material_imbalance(&board), expected);
+        assert!(expected.mg() > 0 && expected.eg() > 0);
+    }
+
+    #[test]
+    fn bishop_pair_beats_two_knights() {
+        let board: Board = "1n2kn2/8/8/8/8/8/8/2B1KB2 w - - 0 1".parse().unwrap();
+        let expected = IMBALANCE[PAIR][KNIGHT] * 2;
+        assert_eq!(material_imbalance(&board), expected);
+        assert!(expected.mg() > 0 && expected.eg() > 0);
+    }
+
+    #[test]
+    fn rook_against_bishop_is_marked_down() {
+        // White has the exchange: rook against bishop.
+        let board: Board = "2b1k3/8/8/8/8/8/8/R3K3 w - - 0 1".parse().unwrap();
+        let expected = IMBALANCE[ROOK][BISHOP] - IMBALANCE[BISHOP][ROOK];
+        assert_eq!(material_imbalance(&board), expected);
+        assert!(expected.mg() < 0 && expected.eg() < 0);
+
+        // Mirrored, the correction flips sign.
+        let board: Board = "r3k3/8/8/8/8/8/8/2B1K3 w - - 0 1".parse().unwrap();
+        assert_eq!(material_imbalance(&board), -expected);
+    }
 }
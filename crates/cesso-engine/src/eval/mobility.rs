@@ -1,12 +1,13 @@
 //! Piece mobility evaluation for HCE (Handcrafted Evaluation).
 //!
-//! Mobility measures how many safe squares each piece can reach. Pieces with
-//! greater freedom of movement receive a bonus proportional to their mobility.
-//! Safe squares exclude friendly-occupied squares and squares controlled by
-//! enemy pawns.
+//! Mobility measures how many squares each piece can reach. Each piece type
+//! has its own bonus table indexed by that count, so a trapped piece is
+//! penalised and extra squares are worth less once a piece is already active.
+//! Friendly-occupied squares never count; for knights and bishops, neither do
+//! squares controlled by enemy pawns.
 
-use cesso_core::{bishop_attacks, knight_attacks, queen_attacks, rook_attacks};
-use cesso_core::{Bitboard, Board, Color, PieceKind};
+use cesso_core::{bishop_attacks, knight_attacks, pawn_attacks_all, queen_attacks, rook_attacks};
+use cesso_core::{Board, Color, PieceKind};
 
 use crate::eval::score::{Score, S};
 
@@ -14,43 +15,31 @@ use crate::eval::score::{Score, S};
 // Mobility bonus tables
 // ---------------------------------------------------------------------------
 
-/// Per-square mobility bonus for knights.
-const KNIGHT_MOBILITY: Score = S(4, 4);
-
-/// Per-square mobility bonus for bishops.
-const BISHOP_MOBILITY: Score = S(3, 5);
-
-/// Per-square mobility bonus for rooks.
-const ROOK_MOBILITY: Score = S(2, 3);
-
-/// Per-square mobility bonus for queens.
-const QUEEN_MOBILITY: Score = S(1, 2);
-
-// ---------------------------------------------------------------------------
-// Helper: bulk pawn attack span
-// ---------------------------------------------------------------------------
-
-/// Compute all squares attacked by pawns of the given color.
-///
-/// Uses bitboard shifts for O(1) bulk computation instead of per-pawn
-/// iteration. Masks out wraparound across the A and H files.
-///
-/// - White pawns attack NE (`<< 9`, not FILE_A) and NW (`<< 7`, not FILE_H).
-/// - Black pawns attack SE (`>> 7`, not FILE_A) and SW (`>> 9`, not FILE_H).
-fn pawn_attack_span(pawns: Bitboard, color: Color) -> Bitboard {
-    match color {
-        Color::White => {
-            let ne = (pawns << 9u8) & !Bitboard::FILE_A;
-            let nw = (pawns << 7u8) & !Bitboard::FILE_H;
-            ne | nw
-        }
-        Color::Black => {
-            let se = (pawns >> 7u8) & !Bitboard::FILE_A;
-            let sw = (pawns >> 9u8) & !Bitboard::FILE_H;
-            se | sw
-        }
-    }
-}
+/// Knight mobility bonus indexed by the number of safe squares (0..=8).
+const KNIGHT_MOBILITY_BONUS: [Score; 9] = [
+    S(-20, -25), S(-12, -15), S(-5, -7), S(0, -2), S(4, 4),
+    S(8, 8), S(11, 11), S(13, 13), S(15, 15),
+];
+
+/// Bishop mobility bonus indexed by the number of safe squares (0..=13).
+const BISHOP_MOBILITY_BONUS: [Score; 14] = [
+    S(-20, -30), S(-10, -15), S(-2, -5), S(3, 2), S(7, 8), S(11, 13), S(14, 18),
+    S(17, 22), S(19, 25), S(21, 28), S(23, 31), S(25, 33), S(27, 35), S(28, 36),
+];
+
+/// Rook mobility bonus indexed by the number of reachable squares (0..=14).
+const ROOK_MOBILITY_BONUS: [Score; 15] = [
+    S(-15, -25), S(-10, -15), S(-6, -8), S(-3, -3), S(0, 2), S(2, 6), S(4, 10), S(6, 14),
+    S(8, 18), S(10, 21), S(11, 24), S(12, 27), S(13, 29), S(14, 31), S(15, 32),
+];
+
+/// Queen mobility bonus indexed by the number of reachable squares (0..=27).
+const QUEEN_MOBILITY_BONUS: [Score; 28] = [
+    S(-10, -20), S(-7, -14), S(-5, -10), S(-3, -6), S(-2, -3), S(-1, 0), S(0, 3),
+    S(1, 6), S(2, 9), S(3, 11), S(4, 13), S(5, 15), S(6, 17), S(7, 19),
+    S(8, 21), S(9, 23), S(10, 25), S(11, 26), S(12, 27), S(13, 28), S(14, 29),
+    S(14, 30), S(15, 31), S(15, 32), S(16, 33), S(16, 34), S(17, 35), S(17, 35),
+];
 
 // ---------------------------------------------------------------------------
 // Per-side evaluation
@@ -58,40 +47,40 @@ fn pawn_attack_span(pawns: Bitboard, color: Color) -> Bitboard {
 
 /// Evaluate piece mobility for one side, returning the raw mobility score.
 ///
-/// Counts safe squares reachable by each knight, bishop, rook, and queen.
-/// Safe squares exclude squares occupied by friendly pieces and squares
-/// attacked by enemy pawns.
+/// Each piece's reachable squares exclude those occupied by friendly pieces;
+/// knights and bishops also skip squares attacked by enemy pawns. The count
+/// indexes the piece type's bonus table.
 fn evaluate_mobility_for_side(board: &Board, color: Color) -> Score {
     let occupied = board.occupied();
     let friendly = board.side(color);
-    let enemy_pawns = board.pieces(PieceKind::Pawn) & board.side(!color);
-    let enemy_pawn_attacks = pawn_attack_span(enemy_pawns, !color);
-    let safe = !friendly & !enemy_pawn_attacks;
+    let enemy_pawn_attacks = pawn_attacks_all(board, !color);
+    let minor_targets = !friendly & !enemy_pawn_attacks;
+    let major_targets = !friendly;
 
     let mut score = Score::ZERO;
 
     let knights = board.pieces(PieceKind::Knight) & friendly;
     for sq in knights {
-        let attacks = knight_attacks(sq) & safe;
-        score += KNIGHT_MOBILITY * attacks.count() as i16;
+        let attacks = knight_attacks(sq) & minor_targets;
+        score += KNIGHT_MOBILITY_BONUS[attacks.count() as usize];
     }
 
     let bishops = board.pieces(PieceKind::Bishop) & friendly;
     for sq in bishops {
-        let attacks = bishop_attacks(sq, occupied) & safe;
-        score += BISHOP_MOBILITY * attacks.count() as i16;
+        let attacks = bishop_attacks(sq, occupied) & minor_targets;
+        score += BISHOP_MOBILITY_BONUS[attacks.count() as usize];
     }
 
     let rooks = board.pieces(PieceKind::Rook) & friendly;
     for sq in rooks {
-        let attacks = rook_attacks(sq, occupied) & safe;
-        score += ROOK_MOBILITY * attacks.count() as i16;
+        let attacks = rook_attacks(sq, occupied) & major_targets;
+        score += ROOK_MOBILITY_BONUS[attacks.count() as usize];
     }
 
     let queens = board.pieces(PieceKind::Queen) & friendly;
     for sq in queens {
-        let attacks = queen_attacks(sq, occupied) & safe;
-        score += QUEEN_MOBILITY * attacks.count() as i16;
+        let attacks = queen_attacks(sq, occupied) & major_targets;
+        score += QUEEN_MOBILITY_BONUS[attacks.count() as usize];
     }
 
     score
@@ -103,9 +92,8 @@ fn evaluate_mobility_for_side(board: &Board, color: Color) -> Score {
 
 /// Evaluate piece mobility from White's perspective.
 ///
-/// For each side, counts the number of safe squares each piece (knight,
-/// bishop, rook, queen) can access. Safe squares exclude squares occupied by
-/// friendly pieces and squares attacked by enemy pawns. Returns the difference
+/// For each side, looks up every knight, bishop, rook, and queen's reachable
+/// square count in its piece type's bonus table. Returns the difference
 /// `white_mobility - black_mobility`.
 pub fn evaluate_mobility(board: &Board) -> Score {
     evaluate_mobility_for_side(board, Color::White)
@@ -120,7 +108,8 @@ pub fn evaluate_mobility(board: &Board) -> Score {
 mod tests {
     use cesso_core::Board;
 
-    use super::evaluate_mobility;
+    use super::{evaluate_mobility, KNIGHT_MOBILITY_BONUS};
+    use crate::eval::score::Score;
 
     /// At the starting position both sides have identical piece placement and
     /// mobility constraints. All pieces except the two knights are completely
@@ -170,4 +159,26 @@ mod tests {
             score.mg()
         );
     }
+
+    /// Colour-mirrored positions have exactly opposite mobility, so each side
+    /// with the same pieces on mirrored squares nets to zero.
+    #[test]
+    fn symmetric_positions_are_zero() {
+        for fen in [
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 4 4",
+            "r3k2r/1b3pp1/p1n1p3/2q5/2Q5/P1N1P3/1B3PP1/R3K2R w KQkq - 0 1",
+            "4k3/3n4/8/2b5/2B5/8/3N4/4K3 b - - 0 1",
+        ] {
+            let board: Board = fen.parse().unwrap();
+            assert_eq!(evaluate_mobility(&board), Score::ZERO, "{fen}");
+        }
+    }
+
+    /// A knight in the corner is worth less than one in the centre.
+    #[test]
+    fn cornered_knight_scores_below_central_knight() {
+        let board: Board = "n3k3/8/8/8/4N3/8/8/4K3 w - - 0 1".parse().unwrap();
+        let score = evaluate_mobility(&board);
+        assert_eq!(score, KNIGHT_MOBILITY_BONUS[8] - KNIGHT_MOBILITY_BONUS[2]);
+    }
 }
@@ -1,7 +1,8 @@
 //! Hand-crafted evaluation (HCE) with tapered eval.
 //!
 //! Evaluation terms: material, piece-square tables, pawn structure,
-//! piece mobility, and king safety (pawn shield).
+//! piece mobility, king safety (pawn shield), hanging pieces, and mop-up
+//! against a bare king.
 //!
 //! All individual terms return [`score::Score`] from White's perspective.
 //! The orchestrator tapers the combined mg/eg values based on game phase
@@ -12,6 +13,10 @@ compile_error!("Enable exactly one of `hce` or `nnue`");
 #[cfg(not(any(feature = "hce", feature = "nnue")))]
 compile_error!("Enable exactly one of `hce` or `nnue`");
 
+#[cfg(feature = "hce")]
+pub mod endgame;
+#[cfg(feature = "hce")]
+pub mod hanging;
 #[cfg(feature = "hce")]
 pub mod king_safety;
 #[cfg(feature = "hce")]
@@ -33,13 +38,19 @@ pub mod score;
 #[cfg(feature = "nnue")]
 mod nnue;
 
+use cesso_core::MaterialDraw;
+
 #[cfg(feature = "hce")]
 use cesso_core::{Board, Color, PieceKind};
 
+#[cfg(feature = "hce")]
+use self::endgame::{evaluate_mopup, is_mopup};
+#[cfg(feature = "hce")]
+use self::hanging::evaluate_hanging;
 #[cfg(feature = "hce")]
 use self::king_safety::evaluate_king_safety;
 #[cfg(feature = "hce")]
-use self::material::{bishop_knight_balance, material};
+use self::material::{bishop_knight_balance, material, material_imbalance};
 #[cfg(feature = "hce")]
 use self::mobility::evaluate_mobility;
 #[cfg(feature = "hce")]
@@ -55,9 +66,45 @@ use self::rooks::evaluate_rooks;
 #[cfg(feature = "hce")]
 use self::score::{Score, S};
 
+/// Which evaluation the search uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvalMode {
+    /// The evaluation the engine was built with (HCE or NNUE).
+    #[default]
+    Full,
+    /// Material balance only, from the midgame piece values.
+    Material,
+}
+
+impl EvalMode {
+    /// Every mode, in the order the `EvalMode` UCI option lists them.
+    pub const ALL: [EvalMode; 2] = [EvalMode::Full, EvalMode::Material];
+
+    /// Name of the mode as a UCI option value.
+    pub const fn name(self) -> &'static str {
+        match self {
+            EvalMode::Full => "Full",
+            EvalMode::Material => "Material",
+        }
+    }
+}
+
 /// Evaluate the board position and return a centipawn score from the
 /// side-to-move's perspective (positive = good for the side to move).
+///
+/// Positions where neither side can force mate are scored as dead draws.
 pub fn evaluate(board: &cesso_core::Board) -> i32 {
+    evaluate_with(board, EvalMode::Full)
+}
+
+/// [`evaluate`] with the given [`EvalMode`].
+pub fn evaluate_with(board: &cesso_core::Board, mode: EvalMode) -> i32 {
+    if matches!(board.material_draw_class(), Some(MaterialDraw::Dead | MaterialDraw::Unforceable)) {
+        return 0;
+    }
+    if mode == EvalMode::Material {
+        return material_only(board);
+    }
     #[cfg(feature = "hce")]
     {
         hce_evaluate(board)
@@ -68,11 +115,25 @@ pub fn evaluate(board: &cesso_core::Board) -> i32 {
     }
 }
 
+/// Material balance from the side to move's perspective.
+fn material_only(board: &cesso_core::Board) -> i32 {
+    let us = board.side(board.side_to_move());
+    cesso_core::PieceKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let pieces = board.pieces(kind);
+            let diff = (pieces & us).count() as i32 - (pieces & !us).count() as i32;
+            kind.material_value_mg() * diff
+        })
+        .sum()
+}
+
 // ── HCE implementation ─────────────────────────────────────────────
 
-/// Small tempo bonus for the side to move.
+/// Small bonus for the side to move, so that otherwise equal positions are
+/// not scored the same whoever is on move.
 #[cfg(feature = "hce")]
-const TEMPO: Score = S(15, 5);
+const TEMPO_BONUS: Score = S(15, 8);
 
 #[cfg(feature = "hce")]
 fn hce_evaluate(board: &Board) -> i32 {
@@ -80,11 +141,9 @@ fn hce_evaluate(board: &Board) -> i32 {
     let phase = game_phase(board);
     let tapered = taper(white_score, phase);
 
-    // Add tempo bonus for the side to move
-    let tempo = taper(TEMPO, phase);
     match board.side_to_move() {
-        Color::White => tapered + tempo,
-        Color::Black => -tapered + tempo,
+        Color::White => tapered,
+        Color::Black => -tapered,
     }
 }
 
@@ -93,27 +152,39 @@ fn hce_evaluate(board: &Board) -> i32 {
 /// Formula: `(mg * phase + eg * (MAX_PHASE - phase)) / MAX_PHASE`
 #[cfg(feature = "hce")]
 fn taper(score: Score, phase: i32) -> i32 {
-    let mg = score.mg() as i32;
-    let eg = score.eg() as i32;
+    let mg = score.mg();
+    let eg = score.eg();
     (mg * phase + eg * (MAX_PHASE - phase)) / MAX_PHASE
 }
 
 /// Compute the total evaluation from White's perspective as a packed Score.
 ///
-/// Sums material, piece-square tables, pawn structure, mobility, king safety,
-/// rook placement, and outpost bonuses.
+/// Sums material, imbalance corrections, piece-square tables, pawn structure,
+/// mobility, king safety, rook placement, outpost bonuses, and hanging-piece
+/// penalties, plus the mop-up term when one side has only a king and the
+/// tempo bonus for whichever side is to move.
 #[cfg(feature = "hce")]
 fn evaluate_white(board: &Board) -> Score {
     let mut score = Score::ZERO;
 
     score += material(board);
     score += bishop_knight_balance(board);
+    score += material_imbalance(board);
     score += pst_total(board);
     score += evaluate_pawns(board);
     score += evaluate_mobility(board);
     score += evaluate_king_safety(board);
     score += evaluate_rooks(board);
     score += evaluate_outposts(board);
+    score += evaluate_hanging(board);
+    if is_mopup(board) {
+        score += evaluate_mopup(board);
+    }
+
+    match board.side_to_move() {
+        Color::White => score += TEMPO_BONUS,
+        Color::Black => score -= TEMPO_BONUS,
+    }
 
     score
 }
@@ -148,7 +219,17 @@ fn pst_total(board: &Board) -> Score {
 #[cfg(feature = "hce")]
 mod tests {
     use cesso_core::Board;
-    use super::evaluate;
+    use super::{EvalMode, evaluate, evaluate_with};
+
+    #[test]
+    fn material_mode_counts_material_only() {
+        let board = Board::starting_position();
+        assert_eq!(evaluate_with(&board, EvalMode::Material), 0);
+
+        // Black is missing its queen; with Black to move the score flips
+        let board: Board = "rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1".parse().unwrap();
+        assert_eq!(evaluate_with(&board, EvalMode::Material), -900);
+    }
 
     /// The starting position is symmetric, so evaluate should return
     /// approximately 0 from White's perspective. Due to PST differences
@@ -209,6 +290,96 @@ mod tests {
         );
     }
 
+    /// The starting position is balanced apart from the tempo bonus, which
+    /// belongs to whoever is to move.
+    #[test]
+    fn tempo_bonus_follows_side_to_move() {
+        use super::{evaluate_white, TEMPO_BONUS};
+
+        let white_to_move = Board::starting_position();
+        let black_to_move: Board = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+            .parse()
+            .unwrap();
+        assert_eq!(evaluate_white(&white_to_move), TEMPO_BONUS);
+        assert_eq!(evaluate_white(&black_to_move), -TEMPO_BONUS);
+
+        // From the mover's side both are the same small edge.
+        let score = evaluate(&white_to_move);
+        assert!(score > 0 && score < 20, "tempo should be a small edge, got {score}");
+        assert_eq!(evaluate(&black_to_move), score);
+    }
+
+    /// Two minors against rook and pawn: the final score carries exactly the
+    /// imbalance correction on top of every other term.
+    #[test]
+    fn imbalance_is_part_of_the_white_score() {
+        use super::material::{bishop_knight_balance, material, material_imbalance};
+        use super::score::Score;
+        use super::{
+            evaluate_hanging, evaluate_king_safety, evaluate_mobility, evaluate_outposts,
+            evaluate_pawns, evaluate_rooks, evaluate_white, pst_total, TEMPO_BONUS,
+        };
+
+        let board: Board = "r3k3/p7/8/8/8/8/8/2B1KN2 w - - 0 1".parse().unwrap();
+        let imbalance = material_imbalance(&board);
+        assert_ne!(imbalance, Score::ZERO);
+
+        let without = material(&board)
+            + bishop_knight_balance(&board)
+            + pst_total(&board)
+            + evaluate_pawns(&board)
+            + evaluate_mobility(&board)
+            + evaluate_king_safety(&board)
+            + evaluate_rooks(&board)
+            + evaluate_outposts(&board)
+            + evaluate_hanging(&board)
+            + TEMPO_BONUS;
+        assert_eq!(evaluate_white(&board), without + imbalance);
+    }
+
+    /// Evaluation is from the side to move, so a position and its colour
+    /// mirror must score the same (and exactly opposite from White's view),
+    /// as must a position and its file mirror. Walks pseudo-random games from
+    /// a few roots and checks 100 of the positions met along the way.
+    #[test]
+    fn evaluation_is_colour_symmetric() {
+        use super::evaluate_white;
+        use cesso_core::generate_legal_moves;
+
+        let roots = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        ];
+        let mut seed = 0x9E37_79B9_7F4A_7C15_u64;
+        let mut checked = 0;
+        for fen in roots.iter().cycle() {
+            let mut board: Board = fen.parse().unwrap();
+            for _ in 0..40 {
+                let moves = generate_legal_moves(&board);
+                if moves.is_empty() {
+                    break;
+                }
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                board = board.make_move(moves[(seed % moves.len() as u64) as usize]);
+                if seed.is_multiple_of(8) {
+                    let flipped = board.flip_color();
+                    assert_eq!(evaluate(&board), evaluate(&flipped), "{board} vs {flipped}");
+                    assert_eq!(evaluate_white(&board), -evaluate_white(&flipped), "{board} vs {flipped}");
+                    let mirrored = board.mirror_horizontal();
+                    assert_eq!(evaluate(&board), evaluate(&mirrored), "{board} vs {mirrored}");
+                    checked += 1;
+                    if checked == 100 {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
     /// Test tapering: middlegame position should use mg values more.
     #[test]
     fn taper_function_works() {
@@ -10,14 +10,13 @@ use self::accumulator::Accumulator;
 use self::network::Network;
 use self::network::NUM_BUCKETS;
 
-/// Compute the output bucket index from material count.
+/// Compute the output bucket index from the material key's piece count.
 ///
 /// Must match Bullet's `MaterialCount<8>`:
 /// `bucket = (occupied_count - 2) / (32.div_ceil(8))` = `(occ - 2) / 4`.
 #[inline]
 fn output_bucket(board: &Board) -> usize {
-    let piece_count = board.occupied().count() as usize;
-    (piece_count.saturating_sub(2)) / 4
+    (board.piece_total() as usize).saturating_sub(2) / 4
 }
 
 /// Evaluate the board using NNUE.
@@ -58,6 +57,23 @@ mod tests {
         );
     }
 
+    /// The bucket from the material key matches the occupancy count Bullet trains with.
+    #[test]
+    fn output_bucket_matches_occupancy() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        ];
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            let bucket = super::output_bucket(&board);
+            assert_eq!(bucket, (board.occupied().count() as usize - 2) / 4, "{fen}");
+            assert!(bucket < NUM_BUCKETS);
+        }
+    }
+
     /// Starting position is symmetric -- NNUE eval should be near zero.
     #[test]
     fn starting_position_near_zero() {
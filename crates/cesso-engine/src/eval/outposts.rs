@@ -42,15 +42,15 @@ fn is_outpost(sq: Square, color: Color, enemy_pawns: Bitboard) -> bool {
     // Only care about adjacent files (not same file) since pawns attack diagonally
     let file_idx = sq.file().index();
     let mut adj_files = Bitboard::EMPTY;
-    if file_idx > 0 {
-        if let Some(f) = File::from_index(file_idx as u8 - 1) {
-            adj_files = adj_files | Bitboard::file_mask(f);
-        }
+    if file_idx > 0
+        && let Some(f) = File::from_index(file_idx as u8 - 1)
+    {
+        adj_files |= Bitboard::file_mask(f);
     }
-    if file_idx < 7 {
-        if let Some(f) = File::from_index(file_idx as u8 + 1) {
-            adj_files = adj_files | Bitboard::file_mask(f);
-        }
+    if file_idx < 7
+        && let Some(f) = File::from_index(file_idx as u8 + 1)
+    {
+        adj_files |= Bitboard::file_mask(f);
     }
 
     let relevant_mask = mask & adj_files;
@@ -1,6 +1,6 @@
 //! Game phase calculation based on remaining non-pawn material.
 
-use cesso_core::{Board, PieceKind};
+use cesso_core::Board;
 
 /// Maximum game phase value, corresponding to a full starting-position complement
 /// of non-pawn material.
@@ -25,13 +25,7 @@ pub const MAX_PHASE: i32 = 24;
 /// | Rook   | 2      |
 /// | Queen  | 4      |
 pub fn game_phase(board: &Board) -> i32 {
-    let knights = board.pieces(PieceKind::Knight).count() as i32;
-    let bishops = board.pieces(PieceKind::Bishop).count() as i32;
-    let rooks = board.pieces(PieceKind::Rook).count() as i32;
-    let queens = board.pieces(PieceKind::Queen).count() as i32;
-
-    let phase = knights + bishops + rooks * 2 + queens * 4;
-    phase.min(MAX_PHASE)
+    board.phase().min(MAX_PHASE)
 }
 
 #[cfg(test)]
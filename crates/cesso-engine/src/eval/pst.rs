@@ -220,4 +220,43 @@ mod tests {
         let white_a8 = pst_value(PieceKind::King, Color::White, Square::A8);
         assert_eq!(black_a1, white_a8);
     }
+
+    /// Random boards filled on every square: summing the PST terms one at a
+    /// time, as evaluation does, never leaves the `i16` range. The debug
+    /// overflow checks in `+=` and `-=` would panic if it did.
+    #[test]
+    fn full_board_pst_sums_do_not_overflow() {
+        use crate::eval::score::Score;
+
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..1_000 {
+            let mut score = Score::ZERO;
+            for sq in Square::all() {
+                let bits = next();
+                let kind = PieceKind::ALL[(bits % 6) as usize];
+                match (bits >> 8) & 1 {
+                    0 => score += pst_value(kind, Color::White, sq),
+                    _ => score -= pst_value(kind, Color::Black, sq),
+                }
+            }
+        }
+
+        // Worst case: on every square the entry that pushes the middlegame
+        // or endgame sum furthest in either direction
+        let components: [fn(Score) -> i32; 2] = [Score::mg, Score::eg];
+        for component in components {
+            let (mut high, mut low) = (Score::ZERO, Score::ZERO);
+            for sq in Square::all() {
+                let values = PieceKind::ALL.map(|kind| pst_value(kind, Color::White, sq));
+                high += *values.iter().max_by_key(|&&v| component(v)).unwrap();
+                low += *values.iter().min_by_key(|&&v| component(v)).unwrap();
+            }
+        }
+    }
 }
@@ -28,9 +28,27 @@ impl Score {
     /// Zero score (mg=0, eg=0).
     pub const ZERO: Score = Score(0);
 
+    /// A balanced evaluation in both phases; the same as [`Score::ZERO`].
+    pub const DRAW: Score = Score(0);
+
     /// Construct a `Score` from separate middlegame and endgame values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either value does not fit in an `i16`; in a `const` (such
+    /// as the evaluation tables) that is a compile error.
+    #[inline]
+    pub const fn new(mg: i32, eg: i32) -> Score {
+        assert!(
+            mg >= i16::MIN as i32 && mg <= i16::MAX as i32 && eg >= i16::MIN as i32 && eg <= i16::MAX as i32,
+            "score component out of i16 range"
+        );
+        Score::pack(mg as i16, eg as i16)
+    }
+
+    /// Pack two components without a range check.
     #[inline]
-    pub const fn new(mg: i16, eg: i16) -> Score {
+    const fn pack(mg: i16, eg: i16) -> Score {
         // wrapping_add is required to handle the full i16 range: when mg is
         // i16::MIN the shift produces i32::MIN, and adding a negative eg
         // value would overflow with ordinary addition.
@@ -39,16 +57,30 @@ impl Score {
 
     /// Extract the middlegame component.
     #[inline]
-    pub fn mg(self) -> i16 {
+    pub fn mg(self) -> i32 {
         // wrapping_add avoids overflow when self.0 is i32::MIN (eg = i16::MIN,
         // mg = i16::MIN): the +0x8000 compensates for eg sign contamination.
-        (self.0.wrapping_add(0x8000) >> 16) as i16
+        i32::from((self.0.wrapping_add(0x8000) >> 16) as i16)
     }
 
     /// Extract the endgame component.
     #[inline]
-    pub fn eg(self) -> i16 {
-        self.0 as i16
+    pub fn eg(self) -> i32 {
+        i32::from(self.0 as i16)
+    }
+
+    /// In debug builds, panic if `self + sign * rhs` leaves the `i16` range
+    /// in either component, which would silently corrupt the packed sum.
+    #[inline]
+    fn debug_check_sum(self, rhs: Score, sign: i32) {
+        #[cfg(debug_assertions)]
+        {
+            let fits = |v: i32| i32::from(i16::MIN) <= v && v <= i32::from(i16::MAX);
+            let (mg, eg) = (self.mg() + sign * rhs.mg(), self.eg() + sign * rhs.eg());
+            assert!(fits(mg) && fits(eg), "score overflow: {self} {} {rhs}", if sign > 0 { '+' } else { '-' });
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = (rhs, sign);
     }
 }
 
@@ -58,7 +90,7 @@ impl Score {
 /// deliberate domain convention shared across HCE chess engine literature.
 #[allow(non_snake_case)]
 #[inline]
-pub const fn S(mg: i16, eg: i16) -> Score {
+pub const fn S(mg: i32, eg: i32) -> Score {
     Score::new(mg, eg)
 }
 
@@ -67,6 +99,7 @@ impl Add for Score {
 
     #[inline]
     fn add(self, rhs: Score) -> Score {
+        self.debug_check_sum(rhs, 1);
         Score(self.0 + rhs.0)
     }
 }
@@ -74,6 +107,7 @@ impl Add for Score {
 impl AddAssign for Score {
     #[inline]
     fn add_assign(&mut self, rhs: Score) {
+        self.debug_check_sum(rhs, 1);
         self.0 += rhs.0;
     }
 }
@@ -83,6 +117,7 @@ impl Sub for Score {
 
     #[inline]
     fn sub(self, rhs: Score) -> Score {
+        self.debug_check_sum(rhs, -1);
         Score(self.0 - rhs.0)
     }
 }
@@ -90,6 +125,7 @@ impl Sub for Score {
 impl SubAssign for Score {
     #[inline]
     fn sub_assign(&mut self, rhs: Score) {
+        self.debug_check_sum(rhs, -1);
         self.0 -= rhs.0;
     }
 }
@@ -116,7 +152,7 @@ impl Mul<i16> for Score {
     /// corrupt both components.
     #[inline]
     fn mul(self, rhs: i16) -> Score {
-        Score::new(self.mg() * rhs, self.eg() * rhs)
+        Score::new(self.mg() * i32::from(rhs), self.eg() * i32::from(rhs))
     }
 }
 
@@ -197,23 +233,23 @@ mod tests {
 
     #[test]
     fn boundary_max() {
-        let s = S(i16::MAX, i16::MAX);
-        assert_eq!(s.mg(), i16::MAX);
-        assert_eq!(s.eg(), i16::MAX);
+        let s = S(i32::from(i16::MAX), i32::from(i16::MAX));
+        assert_eq!(s.mg(), i32::from(i16::MAX));
+        assert_eq!(s.eg(), i32::from(i16::MAX));
     }
 
     #[test]
     fn boundary_min() {
-        let s = S(i16::MIN, i16::MIN);
-        assert_eq!(s.mg(), i16::MIN);
-        assert_eq!(s.eg(), i16::MIN);
+        let s = S(i32::from(i16::MIN), i32::from(i16::MIN));
+        assert_eq!(s.mg(), i32::from(i16::MIN));
+        assert_eq!(s.eg(), i32::from(i16::MIN));
     }
 
     #[test]
     fn boundary_mixed() {
-        let s = S(i16::MAX, i16::MIN);
-        assert_eq!(s.mg(), i16::MAX);
-        assert_eq!(s.eg(), i16::MIN);
+        let s = S(i32::from(i16::MAX), i32::from(i16::MIN));
+        assert_eq!(s.mg(), i32::from(i16::MAX));
+        assert_eq!(s.eg(), i32::from(i16::MIN));
     }
 
     #[test]
@@ -222,4 +258,30 @@ mod tests {
         s += S(3, 4);
         assert_eq!(s, S(4, 6));
     }
+
+    #[test]
+    fn draw_constant() {
+        assert_eq!(Score::DRAW, S(0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of i16 range")]
+    fn new_rejects_out_of_range() {
+        let _ = Score::new(i32::from(i16::MAX) + 1, 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "score overflow")]
+    fn add_assign_detects_overflow() {
+        let mut s = S(30_000, 0);
+        s += S(5_000, 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "score overflow")]
+    fn sub_detects_overflow() {
+        let _ = S(0, -30_000) - S(0, 5_000);
+    }
 }
@@ -1,13 +1,20 @@
 //! Search and evaluation for cesso.
 
+pub mod bench;
+pub mod book;
+pub mod epd;
 pub mod eval;
+pub mod pgn;
 pub mod search;
+pub mod tb;
 pub mod time;
-pub(crate) mod book;
 
-pub use eval::evaluate;
-pub use search::control::SearchControl;
+pub use bench::{BENCH_DEPTH, BenchResult, bench};
+pub use book::{BookError, BookRng, MultiBook, PolyglotBook};
+pub use eval::{EvalMode, evaluate};
+pub use search::control::{AnalysisInfo, AnalysisSink, SearchControl};
 pub use search::pool::ThreadPool;
-pub use search::{SearchResult, Searcher};
-pub use time::limits_from_go;
+pub use search::{SearchResult, Searcher, SearcherBuilder, mate_in};
+pub use tb::Tablebases;
+pub use time::{BARE_GO_DEPTH, is_bare_go, limits_from_go};
 pub use search::draw::{DrawDecision, decide_draw};
@@ -0,0 +1,163 @@
+//! PGN export of analysed lines.
+
+use cesso_core::{Board, Color};
+
+use crate::search::{SearchResult, mate_in};
+
+/// Longest movetext line written, as conventional for PGN export.
+const LINE_WIDTH: usize = 80;
+
+/// Write the principal variation of `result`, searched from `board`, as a
+/// PGN game.
+///
+/// The moves are in SAN, and the first carries a `{+0.85/17 1.2s}` comment
+/// with the score in pawns (or `#5` / `#-5` for a mate), the depth and the
+/// search time. Like the search score, it is from the point of view of the
+/// side to move in `board`. The tags are the seven-tag roster with unknown
+/// values as `?`, an `Annotator` naming the engine, and `SetUp`/`FEN` when
+/// `board` is not the standard starting position. The result is `*`, since
+/// the line is analysis rather than a finished game.
+///
+/// PV moves that are not legal (which would mean a corrupt result) end the
+/// line early rather than producing unreadable SAN.
+pub fn export(board: &Board, result: &SearchResult) -> String {
+    let mut pgn = String::new();
+    let mut tag = |name: &str, value: &str| {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+    };
+    tag("Event", "Analysis");
+    tag("Site", "?");
+    tag("Date", "????.??.??");
+    tag("Round", "-");
+    tag("White", "?");
+    tag("Black", "?");
+    tag("Result", "*");
+    tag("Annotator", concat!("cesso ", env!("CARGO_PKG_VERSION")));
+    if *board != Board::starting_position() {
+        tag("SetUp", "1");
+        tag("FEN", &board.to_fen());
+    }
+    pgn.push('\n');
+
+    let mut words = Vec::new();
+    let mut position = *board;
+    for (ply, &mv) in result.pv.iter().enumerate() {
+        if mv.is_null() || !position.is_legal(mv) {
+            break;
+        }
+        let number = position.fullmove_number();
+        match position.side_to_move() {
+            Color::White => words.push(format!("{number}.")),
+            Color::Black if ply == 0 => words.push(format!("{number}...")),
+            Color::Black => {}
+        }
+        words.push(mv.to_san(&position));
+        if ply == 0 {
+            words.push(format!(
+                "{{{}/{} {:.1}s}}",
+                format_score(result.score),
+                result.depth,
+                result.elapsed.as_secs_f64()
+            ));
+        }
+        position = position.make_move(mv);
+    }
+    words.push("*".to_string());
+
+    let mut line_len = 0;
+    for word in words {
+        if line_len > 0 && line_len + 1 + word.len() > LINE_WIDTH {
+            pgn.push('\n');
+            line_len = 0;
+        } else if line_len > 0 {
+            pgn.push(' ');
+            line_len += 1;
+        }
+        pgn.push_str(&word);
+        line_len += word.len();
+    }
+    pgn.push('\n');
+    pgn
+}
+
+/// Format a search score as `+0.85` pawns, or `#5` / `#-5` for mates.
+fn format_score(score: i32) -> String {
+    match mate_in(score) {
+        Some(moves) => format!("#{moves}"),
+        None => format!("{:+.2}", f64::from(score) / 100.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cesso_core::{Move, pgn::parse_game};
+
+    use super::*;
+    use crate::search::negamax::MATE_SCORE;
+
+    /// A result for the UCI moves `line` played from `board`.
+    fn result(board: &Board, line: &[&str], score: i32, depth: u8) -> SearchResult {
+        let mut position = *board;
+        let pv: Vec<Move> = line
+            .iter()
+            .map(|uci| {
+                let mv = Move::from_uci_legal(uci, &position).unwrap();
+                position = position.make_move(mv);
+                mv
+            })
+            .collect();
+        SearchResult {
+            best_move: pv[0],
+            ponder_move: pv.get(1).copied(),
+            pv,
+            score,
+            nodes: 0,
+            depth,
+            elapsed: Duration::from_millis(1234),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_importer() {
+        let board = Board::starting_position();
+        let line = ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "b5a4", "g8f6", "e1g1"];
+        let searched = result(&board, &line, 85, 17);
+        let pgn = export(&board, &searched);
+        assert!(pgn.contains("1. e4 {+0.85/17 1.2s} e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O *"), "{pgn}");
+        assert!(!pgn.contains("[FEN"), "{pgn}");
+
+        let game = parse_game(&pgn).unwrap();
+        assert_eq!(game.moves, searched.pv);
+        assert_eq!(game.start, board);
+        assert_eq!(game.result, "*");
+        assert!(game.tag("Annotator").unwrap().starts_with("cesso "));
+    }
+
+    #[test]
+    fn custom_positions_get_fen_tags_and_mate_scores() {
+        let board: Board = "6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 3 30".parse().unwrap();
+        let searched = result(&board, &["g8h8", "a1a8"], -(MATE_SCORE - 2), 5);
+        let pgn = export(&board, &searched);
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 3 30\"]"), "{pgn}");
+        assert!(pgn.contains("30... Kh8 {#-1/5 1.2s} 31. Ra8# *"), "{pgn}");
+
+        let game = parse_game(&pgn).unwrap();
+        assert_eq!(game.start, board);
+        assert_eq!(game.moves, searched.pv);
+        assert!(game.board.is_checkmate());
+    }
+
+    #[test]
+    fn long_lines_are_wrapped() {
+        let board = Board::starting_position();
+        let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+        let line: Vec<&str> = shuffle.iter().copied().cycle().take(40).collect();
+        let pgn = export(&board, &result(&board, &line, 0, 10));
+        assert!(pgn.lines().all(|l| l.len() <= LINE_WIDTH), "{pgn}");
+        assert_eq!(parse_game(&pgn).unwrap().moves.len(), 40);
+        assert!(pgn.contains("{+0.00/10 1.2s}"), "{pgn}");
+    }
+}
@@ -1,16 +1,45 @@
 //! Search control — stop flag and time management.
 
-use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use cesso_core::Move;
+
+use super::negamax::MAX_SEARCH_DEPTH;
+
+/// Minimum time between two `currline` reports (and before the first one).
+const CURRLINE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Extra search output for analysis GUIs (`UCI_AnalyseMode`).
+#[derive(Debug, Clone, Copy)]
+pub enum AnalysisInfo<'a> {
+    /// A root move that failed low, followed by the line that refutes it.
+    Refutation(&'a [Move]),
+    /// The line the main thread is searching right now.
+    CurrLine(&'a [Move]),
+}
+
+/// Receives [`AnalysisInfo`] while an analysis search runs.
+pub type AnalysisSink = Box<dyn Fn(AnalysisInfo<'_>) + Send + Sync>;
+
+/// Analysis-mode output hook and its `currline` throttle.
+struct Analysis {
+    sink: AnalysisSink,
+    show_currline: bool,
+    started: Instant,
+    /// Milliseconds after `started` of the last `currline` report.
+    last_currline_ms: AtomicU64,
+}
+
 /// Controls when a search should stop.
 ///
 /// Checked periodically by the search (every 2048 nodes) to decide
-/// whether to abort. Supports three modes:
+/// whether to abort. Supports these modes:
 /// - **Infinite**: no time pressure, only responds to external stop flag
-/// - **Timed**: clock starts immediately (normal `go wtime/btime`)
+/// - **Timed**: clock starts immediately (normal `go wtime/btime`, `go movetime`)
 /// - **Ponder**: clock inactive until [`activate()`](SearchControl::activate) is called (`go ponder` -> `ponderhit`)
+/// - **Depth / nodes**: no clock; stops once a depth is completed or a node count is reached
 pub struct SearchControl {
     stopped: Arc<AtomicBool>,
     clock_active: AtomicBool,
@@ -24,6 +53,14 @@ pub struct SearchControl {
     /// `ponderhit` the engine plays more quickly to compensate for time spent
     /// pondering. Set to `100` (neutral) for timed and infinite modes.
     ponder_scale: AtomicI32,
+    /// Mate-search target in moves (`go mate N`); `None` for a normal search.
+    mate: Option<u8>,
+    /// Deepest iteration to run (`go depth N`).
+    max_depth: Option<u8>,
+    /// Node budget (`go nodes N`).
+    max_nodes: Option<u64>,
+    /// Analysis-mode output; `None` in match play.
+    analysis: Option<Analysis>,
 }
 
 impl SearchControl {
@@ -37,6 +74,10 @@ impl SearchControl {
             hard_limit: None,
             soft_scale: AtomicI32::new(100),
             ponder_scale: AtomicI32::new(100),
+            mate: None,
+            max_depth: None,
+            max_nodes: None,
+            analysis: None,
         }
     }
 
@@ -50,6 +91,33 @@ impl SearchControl {
             hard_limit: Some(hard),
             soft_scale: AtomicI32::new(100),
             ponder_scale: AtomicI32::new(100),
+            mate: None,
+            max_depth: None,
+            max_nodes: None,
+            analysis: None,
+        }
+    }
+
+    /// Create control for `go movetime`: soft and hard limit are both `duration`.
+    pub fn new_movetime(duration: Duration, stopped: Arc<AtomicBool>) -> Self {
+        Self::new_timed(stopped, duration, duration)
+    }
+
+    /// Create control for `go depth`: no time limit; the stop flag is set once
+    /// iterative deepening completes `max_depth`.
+    pub fn new_depth(max_depth: u8, stopped: Arc<AtomicBool>) -> Self {
+        Self {
+            max_depth: Some(max_depth.min(MAX_SEARCH_DEPTH)),
+            ..Self::new_infinite(stopped)
+        }
+    }
+
+    /// Create control for `go nodes`: no time limit; the search stops once a
+    /// thread has visited `max_nodes` nodes.
+    pub fn new_nodes(max_nodes: u64, stopped: Arc<AtomicBool>) -> Self {
+        Self {
+            max_nodes: Some(max_nodes),
+            ..Self::new_infinite(stopped)
         }
     }
 
@@ -69,6 +137,88 @@ impl SearchControl {
             hard_limit: Some(hard),
             soft_scale: AtomicI32::new(100),
             ponder_scale: AtomicI32::new(50),
+            mate: None,
+            max_depth: None,
+            max_nodes: None,
+            analysis: None,
+        }
+    }
+
+    /// Restrict the search to forced mates in at most `moves` moves (`go mate N`).
+    ///
+    /// Iterative deepening stops at depth `2 * moves`, and the root window
+    /// starts at the mate threshold so non-mating lines fail low immediately.
+    pub fn with_mate(mut self, moves: u8) -> Self {
+        self.mate = Some(moves);
+        self
+    }
+
+    /// Report refutations (and, if `show_currline`, the current line) to `sink`.
+    ///
+    /// Without this the search produces no analysis output and does no work
+    /// to collect it.
+    pub fn with_analysis(mut self, sink: AnalysisSink, show_currline: bool) -> Self {
+        self.analysis = Some(Analysis {
+            sink,
+            show_currline,
+            started: Instant::now(),
+            last_currline_ms: AtomicU64::new(0),
+        });
+        self
+    }
+
+    /// Whether analysis output was requested.
+    pub fn analysing(&self) -> bool {
+        self.analysis.is_some()
+    }
+
+    /// Pass `info` to the analysis sink, if any.
+    pub fn report(&self, info: AnalysisInfo<'_>) {
+        if let Some(analysis) = &self.analysis {
+            (analysis.sink)(info);
+        }
+    }
+
+    /// Whether a `currline` report is due; if so, restarts the throttle.
+    pub fn currline_due(&self) -> bool {
+        let Some(analysis) = self.analysis.as_ref().filter(|a| a.show_currline) else {
+            return false;
+        };
+        let now = analysis.started.elapsed().as_millis() as u64;
+        let last = analysis.last_currline_ms.load(Ordering::Relaxed);
+        if now < last + CURRLINE_INTERVAL.as_millis() as u64 {
+            return false;
+        }
+        analysis.last_currline_ms.store(now, Ordering::Relaxed);
+        true
+    }
+
+    /// Whether only `stop` or `ponderhit` can end this search: no clock, depth,
+    /// node or mate limit applies (`go infinite`, or `go ponder` before `ponderhit`).
+    pub fn is_open_ended(&self) -> bool {
+        !self.clock_active.load(Ordering::Acquire)
+            && self.max_depth.is_none()
+            && self.max_nodes.is_none()
+            && self.mate.is_none()
+    }
+
+    /// Mate-search target in moves, if this is a `go mate` search.
+    pub fn mate_moves(&self) -> Option<u8> {
+        self.mate
+    }
+
+    /// Cap `max_depth` at this control's depth limit, at [`MAX_SEARCH_DEPTH`],
+    /// and, for a mate search, at the horizon of `2 * moves` plies.
+    pub fn depth_limit(&self, max_depth: u8) -> u8 {
+        let max_depth = max_depth.min(MAX_SEARCH_DEPTH);
+        let max_depth = self.max_depth.map_or(max_depth, |limit| max_depth.min(limit));
+        self.mate.map_or(max_depth, |moves| max_depth.min(moves.saturating_mul(2)))
+    }
+
+    /// Record a completed iteration; sets the stop flag once the depth limit is reached.
+    pub fn depth_completed(&self, depth: u8) {
+        if self.max_depth.is_some_and(|limit| depth >= limit) {
+            self.stopped.store(true, Ordering::Release);
         }
     }
 
@@ -84,16 +234,22 @@ impl SearchControl {
     ///
     /// Returns `true` if:
     /// - The external stop flag was set, OR
+    /// - `nodes` has reached the node budget, OR
     /// - The clock is active and the hard limit has been exceeded
     ///   (checked only every 2048 nodes for performance)
     ///
-    /// When the hard limit fires, the stop flag is set so subsequent
-    /// calls return immediately without re-checking the clock.
+    /// When a limit fires, the stop flag is set so subsequent
+    /// calls return immediately without re-checking.
     pub fn should_stop(&self, nodes: u64) -> bool {
         if self.stopped.load(Ordering::Relaxed) {
             return true;
         }
 
+        if self.max_nodes.is_some_and(|limit| nodes >= limit) {
+            self.stopped.store(true, Ordering::Release);
+            return true;
+        }
+
         // Only check the clock every 2048 nodes
         if nodes & 2047 != 0 {
             return false;
@@ -170,6 +326,11 @@ impl SearchControl {
             .map_or(Duration::ZERO, |s| s.elapsed())
     }
 
+    /// Hard time limit, if any.
+    pub fn hard_limit(&self) -> Option<Duration> {
+        self.hard_limit
+    }
+
     /// Reference to the shared stop flag.
     pub fn stop_flag(&self) -> &Arc<AtomicBool> {
         &self.stopped
@@ -300,4 +461,39 @@ mod tests {
         assert!(!control.should_stop_iterating());
         assert!(!control.should_stop(2048));
     }
+
+    #[test]
+    fn movetime_sets_equal_soft_and_hard_limits() {
+        let control = SearchControl::new_movetime(Duration::from_millis(250), Arc::new(AtomicBool::new(false)));
+        assert_eq!(control.hard_limit(), Some(Duration::from_millis(250)));
+        assert!(!control.should_stop_iterating());
+    }
+
+    #[test]
+    fn depth_control_stops_after_limit() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let control = SearchControl::new_depth(4, Arc::clone(&stopped));
+        assert_eq!(control.depth_limit(128), 4);
+        control.depth_completed(3);
+        assert!(!stopped.load(Ordering::Relaxed));
+        control.depth_completed(4);
+        assert!(stopped.load(Ordering::Relaxed));
+        assert!(control.should_stop_iterating());
+    }
+
+    #[test]
+    fn depth_limit_never_exceeds_max_search_depth() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        assert_eq!(SearchControl::new_infinite(Arc::clone(&stopped)).depth_limit(255), MAX_SEARCH_DEPTH);
+        assert_eq!(SearchControl::new_depth(200, stopped).depth_limit(255), MAX_SEARCH_DEPTH);
+    }
+
+    #[test]
+    fn node_control_stops_at_budget() {
+        let control = SearchControl::new_nodes(1000, Arc::new(AtomicBool::new(false)));
+        assert_eq!(control.hard_limit(), None);
+        assert!(!control.should_stop(999));
+        assert!(control.should_stop(1000));
+        assert!(control.should_stop(0), "stop flag should latch");
+    }
 }
@@ -1,4 +1,5 @@
-//! Killer move table, history heuristic, continuation history, and correction history.
+//! Killer move table, history heuristic, capture history, continuation history,
+//! and correction history.
 
 use cesso_core::{Color, Move, PieceKind, Square};
 
@@ -30,6 +31,14 @@ impl KillerTable {
         }
     }
 
+    /// Both killer slots at the given ply (null where empty).
+    pub fn get(&self, ply: usize) -> [Move; 2] {
+        if ply >= MAX_PLY {
+            return [Move::NULL; 2];
+        }
+        self.slots[ply]
+    }
+
     /// Check if a move is a killer at the given ply.
     pub fn is_killer(&self, ply: usize, mv: Move) -> bool {
         if ply >= MAX_PLY {
@@ -37,6 +46,18 @@ impl KillerTable {
         }
         self.slots[ply][0] == mv || self.slots[ply][1] == mv
     }
+
+    /// Shift killers toward the root by `plies`, for reuse after moves were played.
+    ///
+    /// Killers found at ply `p` of the previous search belong to ply `p - plies`
+    /// of a search started `plies` moves later in the same game.
+    pub fn shift(&mut self, plies: usize) {
+        let plies = plies.min(MAX_PLY);
+        self.slots.copy_within(plies.., 0);
+        for slot in &mut self.slots[MAX_PLY - plies..] {
+            *slot = [Move::NULL; 2];
+        }
+    }
 }
 
 impl Default for KillerTable {
@@ -48,6 +69,13 @@ impl Default for KillerTable {
 /// Maximum absolute value for history scores (prevents overflow).
 pub const HISTORY_MAX: i32 = 16_384;
 
+/// Halve every value in `values`, rounding toward zero.
+fn halve_all<'a>(values: impl IntoIterator<Item = &'a mut i32>) {
+    for v in values {
+        *v /= 2;
+    }
+}
+
 /// Apply gravity update: `entry += bonus - entry * |bonus| / HISTORY_MAX`.
 ///
 /// Keeps history scores bounded without a hard clamp by pulling values
@@ -92,6 +120,11 @@ impl HistoryTable {
     pub fn score(&self, piece: PieceKind, to: usize) -> i32 {
         self.table[piece.index()][to]
     }
+
+    /// Halve all scores so a new search is not dominated by old ones.
+    pub fn age_down(&mut self) {
+        halve_all(self.table.iter_mut().flatten());
+    }
 }
 
 impl Default for HistoryTable {
@@ -100,6 +133,44 @@ impl Default for HistoryTable {
     }
 }
 
+/// Capture history table — indexed by `[attacker][victim][to_square]`.
+///
+/// Rewards captures that cause beta cutoffs, penalises those searched
+/// without one.
+pub struct CaptureHistoryTable {
+    table: [[[i32; 64]; 6]; 6],
+}
+
+impl CaptureHistoryTable {
+    /// Create a zeroed capture history table.
+    pub fn new() -> Self {
+        Self {
+            table: [[[0; 64]; 6]; 6],
+        }
+    }
+
+    /// Update capture history score using gravity formula.
+    pub fn update(&mut self, attacker: PieceKind, victim: PieceKind, to: usize, bonus: i32) {
+        apply_gravity(&mut self.table[attacker.index()][victim.index()][to], bonus);
+    }
+
+    /// Get the history score for a capture.
+    pub fn score(&self, attacker: PieceKind, victim: PieceKind, to: usize) -> i32 {
+        self.table[attacker.index()][victim.index()][to]
+    }
+
+    /// Halve all scores so a new search is not dominated by old ones.
+    pub fn age_down(&mut self) {
+        halve_all(self.table.iter_mut().flatten().flatten());
+    }
+}
+
+impl Default for CaptureHistoryTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Continuation history
 // ---------------------------------------------------------------------------
@@ -163,6 +234,17 @@ impl ContinuationHistory {
     pub fn entry_mut(&mut self, idx: &ContHistIndex) -> &mut ContHistEntry {
         &mut self.table[idx.side.index()][idx.piece.index()][idx.to.index()]
     }
+
+    /// Halve all scores so a new search is not dominated by old ones.
+    pub fn age_down(&mut self) {
+        halve_all(
+            self.table
+                .iter_mut()
+                .flatten()
+                .flatten()
+                .flat_map(|entry| entry.table.iter_mut().flatten()),
+        );
+    }
 }
 
 impl Default for ContinuationHistory {
@@ -171,6 +253,59 @@ impl Default for ContinuationHistory {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Carried-over heuristics
+// ---------------------------------------------------------------------------
+
+/// Move-ordering and eval-correction tables that can outlive a single search.
+///
+/// When the next search continues the same game, these are handed back to the
+/// searcher instead of being rebuilt from scratch.
+pub struct SearchHeuristics {
+    /// Killer moves per ply.
+    pub killers: KillerTable,
+    /// Butterfly history.
+    pub history_table: HistoryTable,
+    /// Capture history.
+    pub capture_history: CaptureHistoryTable,
+    /// Continuation history.
+    pub cont_history: Box<ContinuationHistory>,
+    /// Static-eval correction history.
+    pub correction_history: Box<CorrectionHistory>,
+}
+
+impl SearchHeuristics {
+    /// Create empty tables.
+    pub fn new() -> Self {
+        Self {
+            killers: KillerTable::new(),
+            history_table: HistoryTable::new(),
+            capture_history: CaptureHistoryTable::new(),
+            cont_history: Box::new(ContinuationHistory::new()),
+            correction_history: Box::new(CorrectionHistory::new()),
+        }
+    }
+
+    /// Adjust for `plies` moves having been played since the tables were filled.
+    pub fn advance(&mut self, plies: usize) {
+        self.killers.shift(plies);
+    }
+
+    /// Halve the history and correction tables ahead of a new search.
+    pub fn age_down(&mut self) {
+        self.history_table.age_down();
+        self.capture_history.age_down();
+        self.cont_history.age_down();
+        self.correction_history.age_down();
+    }
+}
+
+impl Default for SearchHeuristics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Per-ply search stack entry
 // ---------------------------------------------------------------------------
@@ -252,6 +387,7 @@ impl CorrectionHistory {
     }
 
     /// Apply correction to a raw static eval.
+    #[allow(clippy::too_many_arguments)]
     pub fn correct_eval(
         &self,
         side: Color,
@@ -286,6 +422,7 @@ impl CorrectionHistory {
     }
 
     /// Update correction history tables after a search.
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &mut self,
         side: Color,
@@ -317,6 +454,15 @@ impl CorrectionHistory {
         }
     }
 
+    /// Halve all corrections so a new search is not dominated by old ones.
+    pub fn age_down(&mut self) {
+        halve_all(self.pawn.iter_mut().flatten());
+        halve_all(self.non_pawn.iter_mut().flatten().flatten());
+        halve_all(self.major.iter_mut().flatten());
+        halve_all(self.minor.iter_mut().flatten());
+        halve_all(self.cont.iter_mut().flatten().flatten());
+    }
+
     fn apply_corr_gravity(entry: &mut i32, bonus: i32) {
         *entry += bonus - *entry * bonus.abs() / MAX_CORRHIST;
         *entry = (*entry).clamp(-MAX_CORRHIST, MAX_CORRHIST);
@@ -344,10 +490,10 @@ pub fn cont_hist_score(
     let offsets: [usize; 5] = [1, 2, 3, 4, 6];
     let mut score = 0i32;
     for &offset in &offsets {
-        if ply >= offset {
-            if let Some(idx) = &stack[ply - offset].cont_hist_index {
-                score += cont_history.entry(idx).score(piece, to);
-            }
+        if ply >= offset
+            && let Some(idx) = &stack[ply - offset].cont_hist_index
+        {
+            score += cont_history.entry(idx).score(piece, to);
         }
     }
     score
@@ -364,12 +510,12 @@ pub fn update_cont_history(
 ) {
     let offsets: [usize; 5] = [1, 2, 3, 4, 6];
     for &offset in &offsets {
-        if ply >= offset {
-            if let Some(idx) = &stack[ply - offset].cont_hist_index {
-                let idx_owned = *idx;
-                let entry = cont_history.entry_mut(&idx_owned);
-                apply_gravity(entry.entry_mut(piece, to), bonus);
-            }
+        if ply >= offset
+            && let Some(idx) = &stack[ply - offset].cont_hist_index
+        {
+            let idx_owned = *idx;
+            let entry = cont_history.entry_mut(&idx_owned);
+            apply_gravity(entry.entry_mut(piece, to), bonus);
         }
     }
 }
@@ -379,6 +525,20 @@ mod tests {
     use super::*;
     use cesso_core::{Move, PieceKind, Square};
 
+    #[test]
+    fn killer_shift_moves_toward_root() {
+        let mut kt = KillerTable::new();
+        let mv = Move::new(Square::E2, Square::E4);
+        kt.store(5, mv);
+        kt.store(MAX_PLY - 1, mv);
+
+        kt.shift(2);
+        assert!(kt.is_killer(3, mv));
+        assert!(!kt.is_killer(5, mv));
+        assert!(kt.is_killer(MAX_PLY - 3, mv));
+        assert!(!kt.is_killer(MAX_PLY - 1, mv));
+    }
+
     #[test]
     fn killer_store_and_check() {
         let mut kt = KillerTable::new();
@@ -462,6 +622,57 @@ mod tests {
         assert!(entry <= HISTORY_MAX);
     }
 
+    #[test]
+    fn history_age_down_halves_toward_zero() {
+        let mut ht = HistoryTable::new();
+        ht.table[PieceKind::Knight.index()][20] = 301;
+        ht.table[PieceKind::Bishop.index()][5] = -301;
+        ht.table[PieceKind::Rook.index()][0] = -1;
+
+        ht.age_down();
+        assert_eq!(ht.score(PieceKind::Knight, 20), 150);
+        assert_eq!(ht.score(PieceKind::Bishop, 5), -150);
+        assert_eq!(ht.score(PieceKind::Rook, 0), 0);
+
+        // A later update builds on the halved value
+        let mut expected = 150;
+        apply_gravity(&mut expected, 64);
+        ht.update(PieceKind::Knight, 20, 64);
+        assert_eq!(ht.score(PieceKind::Knight, 20), expected);
+    }
+
+    #[test]
+    fn cont_history_age_down_halves_toward_zero() {
+        let mut ch = ContinuationHistory::new();
+        let idx = ContHistIndex {
+            side: Color::Black,
+            piece: PieceKind::Queen,
+            to: Square::from_index(63).unwrap(),
+        };
+        *ch.entry_mut(&idx).entry_mut(PieceKind::Pawn, 8) = 99;
+        *ch.entry_mut(&idx).entry_mut(PieceKind::King, 60) = -99;
+
+        ch.age_down();
+        assert_eq!(ch.entry(&idx).score(PieceKind::Pawn, 8), 49);
+        assert_eq!(ch.entry(&idx).score(PieceKind::King, 60), -49);
+
+        *ch.entry_mut(&idx).entry_mut(PieceKind::Pawn, 8) += 10;
+        assert_eq!(ch.entry(&idx).score(PieceKind::Pawn, 8), 59);
+    }
+
+    #[test]
+    fn correction_history_age_down_halves_toward_zero() {
+        let mut ch = CorrectionHistory::new();
+        ch.pawn[0][3] = 255;
+        ch.non_pawn[1][0][7] = -255;
+        ch.cont[0][2][9] = 7;
+
+        ch.age_down();
+        assert_eq!(ch.pawn[0][3], 127);
+        assert_eq!(ch.non_pawn[1][0][7], -127);
+        assert_eq!(ch.cont[0][2][9], 3);
+    }
+
     #[test]
     fn stack_entry_empty_is_zeroed() {
         let entry = StackEntry::EMPTY;
@@ -9,13 +9,20 @@ pub mod pool;
 pub mod see;
 pub mod tt;
 
-use cesso_core::{Board, Color, Move, generate_legal_moves};
+use std::sync::Arc;
+use std::time::Duration;
+
+use cesso_core::{Board, Color, Move, MoveList, generate_legal_moves};
 
 use control::SearchControl;
-use heuristics::{ContinuationHistory, CorrectionHistory, HistoryTable, KillerTable, StackEntry};
+use heuristics::{CaptureHistoryTable, ContinuationHistory, CorrectionHistory, HistoryTable, KillerTable, StackEntry};
 use negamax::{INF, MAX_PLY, PvTable, SearchContext, aspiration_search};
 use tt::TranspositionTable;
 
+use crate::eval::EvalMode;
+use crate::tb::Tablebases;
+use pool::ThreadPool;
+
 /// Result of a completed search.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -31,6 +38,41 @@ pub struct SearchResult {
     pub nodes: u64,
     /// Depth reached.
     pub depth: u8,
+    /// Wall-clock time the search took.
+    pub elapsed: Duration,
+}
+
+/// With the `serde` feature the moves serialize as UCI strings and
+/// `elapsed` as whole milliseconds (`elapsed_ms`). There is no
+/// `Deserialize`: reading a UCI move back needs the searched position.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SearchResult {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let pv: Vec<String> = self.pv.iter().map(|mv| mv.to_uci()).collect();
+        let mut state = serializer.serialize_struct("SearchResult", 7)?;
+        state.serialize_field("best_move", &self.best_move.to_uci())?;
+        state.serialize_field("ponder_move", &self.ponder_move.map(Move::to_uci))?;
+        state.serialize_field("pv", &pv)?;
+        state.serialize_field("score", &self.score)?;
+        state.serialize_field("nodes", &self.nodes)?;
+        state.serialize_field("depth", &self.depth)?;
+        state.serialize_field("elapsed_ms", &(self.elapsed.as_millis() as u64))?;
+        state.end()
+    }
+}
+
+/// Convert a mate score to a UCI `score mate` distance in full moves.
+///
+/// Returns `Some(n)` when the side to move mates in `n`, `Some(-n)` when it
+/// is mated in `n`, and `None` for ordinary centipawn scores.
+pub fn mate_in(score: i32) -> Option<i32> {
+    if score.abs() <= negamax::MATE_THRESHOLD {
+        return None;
+    }
+    let moves = (negamax::MATE_SCORE - score.abs() + 1) / 2;
+    Some(if score > 0 { moves } else { -moves })
 }
 
 /// Tracks best-move stability across ID iterations for time management.
@@ -38,17 +80,23 @@ pub struct SearchResult {
 /// When the best move changes or the score drops significantly, the engine
 /// should think longer. When the best move is stable, it can play faster.
 pub(super) struct StabilityTracker {
-    last_move: Move,
-    last_score: i32,
+    /// Best move and score of the last [`STABILITY_WINDOW`] iterations,
+    /// oldest first; unfilled slots hold [`Move::NULL`].
+    recent: [(Move, i32); STABILITY_WINDOW],
     stable_streak: u32,
     peak_score: i32,
 }
 
+/// Iterations that must agree before the stable streak grows.
+const STABILITY_WINDOW: usize = 3;
+
+/// Largest score spread (cp) across the window that still counts as stable.
+const STABLE_SCORE_SPREAD: i32 = 10;
+
 impl StabilityTracker {
     fn new() -> Self {
         Self {
-            last_move: Move::NULL,
-            last_score: 0,
+            recent: [(Move::NULL, 0); STABILITY_WINDOW],
             stable_streak: 0,
             peak_score: -30_000,
         }
@@ -56,69 +104,137 @@ impl StabilityTracker {
 
     /// Update with the latest iteration results and return a scale factor (in hundredths).
     ///
-    /// `depth` is the current iterative-deepening depth (u8). Priority order:
+    /// `depth` is the current iterative-deepening depth (u8). The stable
+    /// streak grows only while the last [`STABILITY_WINDOW`] iterations share
+    /// one best move and their scores lie within [`STABLE_SCORE_SPREAD`]; any
+    /// other iteration resets it. Priority order:
     ///
     /// - Score drop > 100cp: 250 (think much longer), resets streak and peak
     /// - Score drop > 50cp: 180 (think longer), resets streak and peak
-    /// - Same move, easy-move condition (`stable_streak >= 5 && depth >= 8 && peak_score - score <= 10`): 30 (very fast)
-    /// - Same move, streak >= 3: 60 (play faster)
-    /// - Same move, streak < 3: 100 (neutral)
-    /// - Move changed: 100 (neutral), resets streak and peak
+    /// - Move flip-flops back (A → B → A): 150 (think longer), resets streak and peak
+    /// - Easy-move condition (`stable_streak >= 5 && depth >= 8 && peak_score - score <= 10`): 30 (very fast)
+    /// - Streak >= 3: 60 (play faster)
+    /// - Otherwise: 100 (neutral); a changed move also resets the peak
     fn update(&mut self, best_move: Move, score: i32, depth: u8) -> i32 {
-        let scale;
+        let (last_move, last_score) = self.recent[STABILITY_WINDOW - 1];
+        self.recent.rotate_left(1);
+        self.recent[STABILITY_WINDOW - 1] = (best_move, score);
 
-        if self.last_move.is_null() {
+        if last_move.is_null() {
             // First iteration — neutral
-            scale = 100;
-        } else {
-            let score_drop = self.last_score - score;
+            return 100;
+        }
 
-            if score_drop > 100 {
-                // Score dropped significantly — think much longer
-                self.stable_streak = 0;
-                self.peak_score = -30_000;
-                scale = 250;
+        let score_drop = last_score - score;
+        let flip_flop = best_move != last_move && best_move == self.recent[0].0;
+        if score_drop > 50 || flip_flop {
+            // Score dropped or the search keeps changing its mind — think longer
+            self.stable_streak = 0;
+            self.peak_score = -30_000;
+            return if score_drop > 100 {
+                250
             } else if score_drop > 50 {
-                // Moderate score drop — think longer
-                self.stable_streak = 0;
-                self.peak_score = -30_000;
-                scale = 180;
-            } else if best_move == self.last_move {
-                self.stable_streak += 1;
-                self.peak_score = self.peak_score.max(score);
-                if self.stable_streak >= 5 && depth >= 8 && (self.peak_score - score) <= 10 {
-                    // Easy move — very stable, high depth, score hasn't eroded
-                    scale = 30;
-                } else if self.stable_streak >= 3 {
-                    scale = 60;
-                } else {
-                    scale = 100;
-                }
+                180
             } else {
-                // Move changed — reset stability
-                self.stable_streak = 0;
-                self.peak_score = -30_000;
-                scale = 100;
-            }
+                150
+            };
+        }
+
+        if best_move != last_move {
+            // Move changed — reset stability
+            self.stable_streak = 0;
+            self.peak_score = -30_000;
+            return 100;
+        }
+        self.peak_score = self.peak_score.max(score);
+
+        let settled = self.recent.iter().all(|&(mv, _)| mv == best_move) && {
+            let (low, high) = self
+                .recent
+                .iter()
+                .fold((i32::MAX, i32::MIN), |(low, high), &(_, s)| (low.min(s), high.max(s)));
+            high - low < STABLE_SCORE_SPREAD
+        };
+        if !settled {
+            self.stable_streak = 0;
+            return 100;
         }
 
-        self.last_move = best_move;
-        self.last_score = score;
-        scale
+        self.stable_streak += 1;
+        if self.stable_streak >= 5 && depth >= 8 && (self.peak_score - score) <= 10 {
+            // Easy move — very stable, high depth, score hasn't eroded
+            30
+        } else if self.stable_streak >= 3 {
+            60
+        } else {
+            100
+        }
     }
 }
 
+/// Root moves allowed by the tablebases, or empty if the root cannot be probed.
+///
+/// Also resets the tablebase hit counter for the new search.
+pub(crate) fn tb_root_moves(tb: Option<&Tablebases>, board: &Board, history: &[u64]) -> MoveList {
+    let Some(tb) = tb else {
+        return MoveList::new();
+    };
+    tb.reset_hits();
+    tb.filter_root_moves(board, history).unwrap_or_default().into_iter().collect()
+}
+
+/// Root moves the search may play, or empty if every legal move is allowed.
+///
+/// `searchmoves` (the UCI `go searchmoves` list) is narrowed to the legal
+/// moves and then to those the tablebases keep; if no listed move survives
+/// the tablebase filter the listed moves are searched anyway. A list with no
+/// legal move in it is ignored.
+pub(crate) fn allowed_root_moves(
+    tb: Option<&Tablebases>,
+    board: &Board,
+    history: &[u64],
+    searchmoves: Option<Vec<Move>>,
+) -> MoveList {
+    let tb_moves = tb_root_moves(tb, board, history);
+    let Some(searchmoves) = searchmoves else {
+        return tb_moves;
+    };
+    let mut allowed = generate_legal_moves(board);
+    allowed.retain(|mv| searchmoves.contains(&mv));
+    if allowed.is_empty() {
+        return tb_moves;
+    }
+    if !tb_moves.is_empty() {
+        let mut preserving = allowed.clone();
+        preserving.retain(|mv| tb_moves.contains(mv));
+        if !preserving.is_empty() {
+            return preserving;
+        }
+    }
+    allowed
+}
+
+/// Default transposition table size in megabytes.
+const DEFAULT_TT_MB: usize = 16;
+
 /// Iterative-deepening searcher with transposition table.
 pub struct Searcher {
     tt: TranspositionTable,
+    tt_mb: usize,
+    contempt: i32,
+    eval_mode: EvalMode,
+    tb: Option<Arc<Tablebases>>,
 }
 
 impl Searcher {
-    /// Create a fresh searcher with a 16 MB transposition table.
+    /// Create a fresh searcher with a 16 MB transposition table and no contempt.
     pub fn new() -> Self {
-        Self {
-            tt: TranspositionTable::new(16),
-        }
+        SearcherBuilder::default().build()
+    }
+
+    /// Set (or clear) the Syzygy tablebases probed during search.
+    pub fn set_tablebases(&mut self, tb: Option<Arc<Tablebases>>) {
+        self.tb = tb;
     }
 
     /// Clear the transposition table (preserving the allocation).
@@ -129,24 +245,44 @@ impl Searcher {
     /// Resize the transposition table to the given size in megabytes.
     pub fn resize_tt(&mut self, mb: usize) {
         self.tt = TranspositionTable::new(mb);
+        self.tt_mb = mb;
+    }
+
+    /// Transposition table size in megabytes, as last configured.
+    pub fn tt_size_mb(&self) -> usize {
+        self.tt_mb
+    }
+
+    /// Contempt factor in centipawns applied to draw scores.
+    pub fn contempt(&self) -> i32 {
+        self.contempt
+    }
+
+    /// Evaluation used by the search.
+    pub fn eval_mode(&self) -> EvalMode {
+        self.eval_mode
     }
 
     /// Run iterative-deepening search up to `max_depth`.
     ///
-    /// Calls `on_iter(depth, score, nodes, pv)` after each completed
+    /// Calls `on_iter(depth, score, nodes, qnodes, hashfull, pv)` after each completed
     /// iteration, allowing the caller to emit UCI `info` lines.
+    ///
+    /// If `root_moves` is set, only those moves (that are legal) are searched
+    /// at the root, so the best move and PV come from that set.
+    #[allow(clippy::too_many_arguments)]
     pub fn search<F>(
         &self,
         board: &Board,
         max_depth: u8,
         control: &SearchControl,
         history: &[u64],
-        contempt: i32,
+        root_moves: Option<Vec<Move>>,
         engine_color: Color,
         mut on_iter: F,
     ) -> SearchResult
     where
-        F: FnMut(u8, i32, u64, &[Move]),
+        F: FnMut(u8, i32, u64, u64, u32, &[Move]),
     {
         self.tt.new_generation();
 
@@ -167,22 +303,31 @@ impl Searcher {
                 score: 0,
                 nodes: 0,
                 depth: 0,
+                elapsed: control.elapsed(),
             };
         }
 
         let mut ctx = SearchContext {
             nodes: 0,
+            qnodes: 0,
             tt: &self.tt,
             pv: PvTable::new(),
             control,
             killers: KillerTable::new(),
             history_table: HistoryTable::new(),
+            capture_history: CaptureHistoryTable::new(),
             cont_history: Box::new(ContinuationHistory::new()),
             correction_history: Box::new(CorrectionHistory::new()),
             stack: [StackEntry::EMPTY; MAX_PLY],
             history: history.to_vec(),
-            contempt,
+            contempt: self.contempt,
             engine_color,
+            eval_mode: self.eval_mode,
+            tb: self.tb.as_deref(),
+            root_moves: allowed_root_moves(self.tb.as_deref(), board, history, root_moves),
+            qsearch_check_extensions: 0,
+            refutations: Vec::new(),
+            main_thread: true,
         };
 
         // Track completed iteration results (for abort-safety)
@@ -193,7 +338,7 @@ impl Searcher {
         let mut prev_score: i32 = 0;
         let mut stability = StabilityTracker::new();
 
-        for depth in 1..=max_depth {
+        for depth in 1..=control.depth_limit(max_depth) {
             // Check soft limit before starting a new iteration
             if control.should_stop_iterating() {
                 break;
@@ -218,11 +363,18 @@ impl Searcher {
             completed_pv = pv.iter().copied().filter(|m| !m.is_null()).collect();
 
             debug_assert!(
-                !completed_move.is_null() || generate_legal_moves(board).is_empty(),
+                !completed_move.is_null() || board.is_terminal(),
                 "negamax returned without setting root_best_move at depth {depth}"
             );
 
-            on_iter(depth, score, ctx.nodes, &completed_pv);
+            on_iter(depth, score, ctx.nodes, ctx.qnodes, self.tt.utilization_permille(), &completed_pv);
+            ctx.report_refutations();
+            control.depth_completed(depth);
+
+            // `go mate N`: stop as soon as a forced mate is confirmed
+            if control.mate_moves().is_some() && score > negamax::MATE_THRESHOLD {
+                break;
+            }
 
             // Update time management based on best-move stability
             let scale = stability.update(completed_move, score, depth);
@@ -242,6 +394,7 @@ impl Searcher {
             score: completed_score,
             nodes: ctx.nodes,
             depth: completed_depth,
+            elapsed: control.elapsed(),
         }
     }
 }
@@ -250,6 +403,8 @@ impl std::fmt::Debug for Searcher {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Searcher")
             .field("tt", &self.tt)
+            .field("contempt", &self.contempt)
+            .field("eval_mode", &self.eval_mode)
             .finish()
     }
 }
@@ -260,6 +415,80 @@ impl Default for Searcher {
     }
 }
 
+/// Builder for a [`Searcher`] or [`ThreadPool`] with non-default configuration.
+///
+/// ```
+/// use cesso_engine::SearcherBuilder;
+/// use cesso_engine::EvalMode;
+///
+/// let searcher = SearcherBuilder::default().tt_size_mb(64).contempt(20).build();
+/// assert_eq!(searcher.contempt(), 20);
+///
+/// let pool = SearcherBuilder::default().threads(4).eval_mode(EvalMode::Material).build_pool();
+/// assert_eq!(pool.num_threads(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearcherBuilder {
+    pub(crate) tt_mb: usize,
+    pub(crate) threads: usize,
+    pub(crate) contempt: i32,
+    pub(crate) eval_mode: EvalMode,
+}
+
+impl Default for SearcherBuilder {
+    fn default() -> Self {
+        Self {
+            tt_mb: DEFAULT_TT_MB,
+            threads: 1,
+            contempt: 0,
+            eval_mode: EvalMode::Full,
+        }
+    }
+}
+
+impl SearcherBuilder {
+    /// Transposition table size in megabytes (default 16).
+    pub fn tt_size_mb(mut self, mb: usize) -> Self {
+        self.tt_mb = mb;
+        self
+    }
+
+    /// Search threads of a [`ThreadPool`] (default 1); a [`Searcher`] is
+    /// always single-threaded.
+    pub fn threads(mut self, n: usize) -> Self {
+        self.threads = n.max(1);
+        self
+    }
+
+    /// Contempt factor in centipawns (default 0).
+    pub fn contempt(mut self, cp: i32) -> Self {
+        self.contempt = cp;
+        self
+    }
+
+    /// Evaluation used by the search (default [`EvalMode::Full`]).
+    pub fn eval_mode(mut self, mode: EvalMode) -> Self {
+        self.eval_mode = mode;
+        self
+    }
+
+    /// Allocate the transposition table and build the searcher.
+    pub fn build(self) -> Searcher {
+        Searcher {
+            tt: TranspositionTable::new(self.tt_mb),
+            tt_mb: self.tt_mb,
+            contempt: self.contempt,
+            eval_mode: self.eval_mode,
+            tb: None,
+        }
+    }
+
+    /// Allocate the shared transposition table and build a Lazy SMP pool.
+    pub fn build_pool(self) -> ThreadPool {
+        ThreadPool::from_builder(&self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,9 +499,79 @@ mod tests {
     use cesso_core::Board;
 
     fn search_depth(searcher: &Searcher, board: &Board, depth: u8) -> SearchResult {
-        let stopped = Arc::new(AtomicBool::new(false));
-        let control = SearchControl::new_infinite(stopped);
-        searcher.search(board, depth, &control, &[], 0, Color::White, |_, _, _, _| {})
+        let control = SearchControl::new_depth(depth, Arc::new(AtomicBool::new(false)));
+        searcher.search(board, MAX_PLY as u8, &control, &[], None, Color::White, |_, _, _, _, _, _| {})
+    }
+
+    #[test]
+    fn builder_sets_tt_size_and_contempt() {
+        let searcher = SearcherBuilder::default().tt_size_mb(4).contempt(20).build();
+        assert_eq!(searcher.tt_size_mb(), 4);
+        assert_eq!(searcher.contempt(), 20);
+
+        let default = Searcher::new();
+        assert_eq!(default.tt_size_mb(), 16);
+        assert_eq!(default.contempt(), 0);
+        assert_eq!(default.eval_mode(), EvalMode::Full);
+
+        let builder = SearcherBuilder::default().tt_size_mb(4).threads(3).contempt(20).eval_mode(EvalMode::Material);
+        let mut pool = builder.clone().build_pool();
+        assert_eq!(pool.tt_size_mb(), 4);
+        assert_eq!(pool.num_threads(), 3);
+        assert_eq!(pool.contempt(), 20);
+        assert_eq!(pool.eval_mode(), EvalMode::Material);
+        assert!(!pool.reconfigure(&builder.clone().contempt(-5)), "same size keeps the table");
+        assert_eq!(pool.contempt(), -5);
+        assert!(pool.reconfigure(&builder.tt_size_mb(8)));
+        assert_eq!(pool.tt_size_mb(), 8);
+    }
+
+    #[test]
+    fn analysis_reports_refutations_of_root_moves() {
+        use std::sync::Mutex;
+
+        use control::AnalysisInfo;
+
+        let board = Board::starting_position();
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink_lines = Arc::clone(&lines);
+        let control = SearchControl::new_depth(5, Arc::new(AtomicBool::new(false))).with_analysis(
+            Box::new(move |info| {
+                if let AnalysisInfo::Refutation(line) = info {
+                    sink_lines.lock().unwrap().push(line.to_vec());
+                }
+            }),
+            false,
+        );
+        Searcher::new().search(&board, 5, &control, &[], None, Color::White, |_, _, _, _, _, _| {});
+
+        let lines = lines.lock().unwrap();
+        assert!(!lines.is_empty(), "no refutations reported");
+        let legal = generate_legal_moves(&board);
+        for line in lines.iter() {
+            assert!(line.len() >= 2);
+            assert!(legal.as_slice().contains(&line[0]));
+            let replies = generate_legal_moves(&board.make_move(line[0]));
+            assert!(replies.as_slice().contains(&line[1]), "{} is not a reply", line[1]);
+        }
+    }
+
+    #[test]
+    fn searchmoves_restricts_root_to_listed_move() {
+        let board = Board::starting_position();
+        let a2a3 = Move::from_uci("a2a3", &board).unwrap();
+        let control = SearchControl::new_depth(10, Arc::new(AtomicBool::new(false)));
+        let result = Searcher::new().search(&board, 10, &control, &[], Some(vec![a2a3]), Color::White, |_, _, _, _, _, _| {});
+        assert_eq!(result.best_move, a2a3);
+        assert_eq!(result.pv.first(), Some(&a2a3));
+    }
+
+    #[test]
+    fn searchmoves_without_legal_entries_is_ignored() {
+        let board = Board::starting_position();
+        let illegal = Move::from_uci("e2e5", &board).unwrap();
+        assert!(allowed_root_moves(None, &board, &[], Some(vec![illegal])).is_empty());
+        assert!(allowed_root_moves(None, &board, &[], None).is_empty());
     }
 
     #[test]
@@ -302,6 +601,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mate_in_one_reports_mate_1() {
+        let board: Board = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4"
+            .parse()
+            .unwrap();
+        let result = search_depth(&Searcher::new(), &board, 2);
+        assert_eq!(result.score, negamax::MATE_SCORE - 1);
+        assert_eq!(mate_in(result.score), Some(1));
+    }
+
+    #[test]
+    fn mate_in_two_reports_mate_2() {
+        // KR vs K: Kc7 boxes the king in, then Rh8#
+        let board: Board = "k7/8/2K5/8/8/8/8/7R w - - 0 1".parse().unwrap();
+        let result = search_depth(&Searcher::new(), &board, 5);
+        assert_eq!(result.score, negamax::MATE_SCORE - 3);
+        assert_eq!(mate_in(result.score), Some(2));
+    }
+
+    #[test]
+    fn mated_in_three_reports_mate_minus_3() {
+        // KQ vs K with the defender to move
+        let board: Board = "k7/8/3K4/8/8/8/8/7Q b - - 0 1".parse().unwrap();
+        let result = search_depth(&Searcher::new(), &board, 8);
+        assert_eq!(result.score, -(negamax::MATE_SCORE - 6));
+        assert_eq!(mate_in(result.score), Some(-3));
+    }
+
+    #[test]
+    fn qsearch_pruning_keeps_exact_results() {
+        // Mates, stalemate and a mated position whose scores are exact, so
+        // pruning in quiescence must not move them
+        let cases = [
+            ("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", 2, negamax::MATE_SCORE - 1),
+            ("k7/8/2K5/8/8/8/8/7R w - - 0 1", 5, negamax::MATE_SCORE - 3),
+            ("k7/8/3K4/8/8/8/8/7Q b - - 0 1", 8, -(negamax::MATE_SCORE - 6)),
+            ("k7/8/8/3K4/8/8/8/7Q w - - 0 1", 8, negamax::MATE_SCORE - 5),
+            ("k7/2K5/1Q6/8/8/8/8/8 b - - 0 1", 1, 0),
+            ("7k/6Q1/5K2/8/8/8/8/8 b - - 0 1", 1, -negamax::MATE_SCORE),
+        ];
+        for (fen, depth, score) in cases {
+            let board: Board = fen.parse().unwrap();
+            let result = search_depth(&Searcher::new(), &board, depth);
+            assert_eq!(result.score, score, "{fen}");
+        }
+    }
+
+    fn mate_search(fen: &str, moves: u8) -> SearchResult {
+        let board: Board = fen.parse().unwrap();
+        let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false))).with_mate(moves);
+        Searcher::new().search(&board, 128, &control, &[], None, Color::White, |_, _, _, _, _, _| {})
+    }
+
+    #[test]
+    fn go_mate_finds_mate_in_one() {
+        let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+        let result = mate_search(fen, 1);
+        assert_eq!(result.best_move.to_uci(), "h5f7");
+        assert_eq!(mate_in(result.score), Some(1));
+    }
+
+    #[test]
+    fn go_mate_finds_mate_in_two() {
+        let result = mate_search("k7/8/2K5/8/8/8/8/7R w - - 0 1", 2);
+        assert_eq!(mate_in(result.score), Some(2));
+        assert!(result.depth <= 4);
+    }
+
+    #[test]
+    fn go_mate_finds_mate_in_three() {
+        let fen = "k7/8/8/3K4/8/8/8/7Q w - - 0 1";
+        let result = mate_search(fen, 3);
+        assert_eq!(mate_in(result.score), Some(3));
+        assert!(!result.best_move.is_null());
+    }
+
+    #[test]
+    fn go_mate_reports_zero_without_mate_in_n() {
+        // The queen mates in three, not two
+        let result = mate_search("k7/8/8/3K4/8/8/8/7Q w - - 0 1", 2);
+        assert_eq!(result.score, 0);
+        assert_eq!(result.depth, 4);
+        assert!(!result.best_move.is_null());
+    }
+
+    #[test]
+    fn mate_in_ignores_centipawn_scores() {
+        assert_eq!(mate_in(0), None);
+        assert_eq!(mate_in(negamax::MATE_THRESHOLD), None);
+        assert_eq!(mate_in(-negamax::MATE_THRESHOLD), None);
+    }
+
     #[test]
     fn stalemate_returns_zero() {
         // Black king on a8, white king on c7, white queen on b6 — black to move, stalemate
@@ -330,21 +721,40 @@ mod tests {
         let board = Board::starting_position();
         let searcher = Searcher::new();
         let stopped = Arc::new(AtomicBool::new(false));
-        let control = SearchControl::new_infinite(stopped);
+        let control = SearchControl::new_depth(3, stopped);
         let mut depths_seen = Vec::new();
-        searcher.search(&board, 3, &control, &[], 0, Color::White, |depth, _, _, _| {
+        searcher.search(&board, 3, &control, &[], None, Color::White, |depth, _, _, _, _, _| {
             depths_seen.push(depth);
         });
         assert_eq!(depths_seen, vec![1, 2, 3]);
     }
 
+    #[test]
+    fn qnodes_are_a_subset_of_nodes() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ];
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            let control = SearchControl::new_depth(5, Arc::new(AtomicBool::new(false)));
+            let mut iterations = 0;
+            Searcher::new().search(&board, 5, &control, &[], None, Color::White, |_, _, nodes, qnodes, _, _| {
+                assert!(qnodes <= nodes, "{fen}: qnodes {qnodes} > nodes {nodes}");
+                iterations += 1;
+            });
+            assert!(iterations > 0);
+        }
+    }
+
     #[test]
     fn on_iter_never_emits_null_move() {
         let board = Board::starting_position();
         let searcher = Searcher::new();
         let stopped = Arc::new(AtomicBool::new(false));
-        let control = SearchControl::new_infinite(stopped);
-        searcher.search(&board, 4, &control, &[], 0, Color::White, |_d, _score, _nodes, pv| {
+        let control = SearchControl::new_depth(4, stopped);
+        searcher.search(&board, 4, &control, &[], None, Color::White, |_d, _score, _nodes, _qnodes, _hashfull, pv| {
             assert!(
                 !pv.is_empty() && !pv[0].is_null(),
                 "on_iter callback received empty PV or Move::NULL"
@@ -358,8 +768,8 @@ mod tests {
         let searcher = Searcher::new();
         // First search warms the TT
         let stopped1 = Arc::new(AtomicBool::new(false));
-        let control1 = SearchControl::new_infinite(stopped1);
-        searcher.search(&board, 3, &control1, &[], 0, Color::White, |_d, _score, _nodes, pv| {
+        let control1 = SearchControl::new_depth(3, stopped1);
+        searcher.search(&board, 3, &control1, &[], None, Color::White, |_d, _score, _nodes, _qnodes, _hashfull, pv| {
             assert!(
                 !pv.is_empty() && !pv[0].is_null(),
                 "null move in first search callback"
@@ -367,8 +777,8 @@ mod tests {
         });
         // Second search probes the warm TT
         let stopped2 = Arc::new(AtomicBool::new(false));
-        let control2 = SearchControl::new_infinite(stopped2);
-        searcher.search(&board, 3, &control2, &[], 0, Color::White, |_d, _score, _nodes, pv| {
+        let control2 = SearchControl::new_depth(3, stopped2);
+        searcher.search(&board, 3, &control2, &[], None, Color::White, |_d, _score, _nodes, _qnodes, _hashfull, pv| {
             assert!(
                 !pv.is_empty() && !pv[0].is_null(),
                 "null move in second search callback (warm TT)"
@@ -450,7 +860,7 @@ mod tests {
 
         // Stop after depth 1 callback fires
         let stop_clone = Arc::clone(&stopped);
-        let result = searcher.search(&board, 128, &control, &[], 0, Color::White, |depth, _, _, _| {
+        let result = searcher.search(&board, 128, &control, &[], None, Color::White, |depth, _, _, _, _, _| {
             if depth >= 1 {
                 stop_clone.store(true, Ordering::Release);
             }
@@ -507,9 +917,9 @@ mod tests {
         let board = Board::starting_position();
         let searcher = Searcher::new();
         let stopped = Arc::new(AtomicBool::new(false));
-        let control = SearchControl::new_infinite(stopped);
+        let control = SearchControl::new_depth(6, stopped);
         let mut depths_seen = Vec::new();
-        searcher.search(&board, 6, &control, &[], 0, Color::White, |depth, _, _, _| {
+        searcher.search(&board, 6, &control, &[], None, Color::White, |depth, _, _, _, _, _| {
             depths_seen.push(depth);
         });
         assert_eq!(depths_seen, vec![1, 2, 3, 4, 5, 6], "aspiration should not skip depths");
@@ -537,14 +947,14 @@ mod tests {
 
         // First do a normal depth-2 search to get a baseline
         let stopped2 = Arc::new(AtomicBool::new(false));
-        let control2 = SearchControl::new_infinite(stopped2);
-        let baseline = searcher.search(&board, 2, &control2, &[], 0, Color::White, |_, _, _, _| {});
+        let control2 = SearchControl::new_depth(2, stopped2);
+        let baseline = searcher.search(&board, 2, &control2, &[], None, Color::White, |_, _, _, _, _, _| {});
         assert!(!baseline.best_move.is_null());
 
         // Now set stop immediately and search to depth 100
         stopped.store(true, Ordering::Release);
         let searcher2 = Searcher::new();
-        let result = searcher2.search(&board, 100, &control, &[], 0, Color::White, |_, _, _, _| {});
+        let result = searcher2.search(&board, 100, &control, &[], None, Color::White, |_, _, _, _, _, _| {});
 
         // With stop set immediately, depth 0 means no iteration completed
         // The best_move should be NULL (no completed iterations)
@@ -569,12 +979,34 @@ mod tests {
         let mut tracker = StabilityTracker::new();
         let mv = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
         tracker.update(mv, 25, 1); // first
-        tracker.update(mv, 25, 2); // streak 1
-        tracker.update(mv, 25, 3); // streak 2
-        let scale = tracker.update(mv, 25, 4); // streak 3
+        tracker.update(mv, 25, 2); // window not yet full
+        tracker.update(mv, 25, 3); // streak 1
+        tracker.update(mv, 25, 4); // streak 2
+        let scale = tracker.update(mv, 25, 5); // streak 3
         assert_eq!(scale, 60, "stable streak >= 3 should return 60");
     }
 
+    #[test]
+    fn stability_flip_flop_thinks_longer() {
+        let mut tracker = StabilityTracker::new();
+        let a = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
+        let b = cesso_core::Move::new(cesso_core::Square::D2, cesso_core::Square::D4);
+        tracker.update(a, 25, 1);
+        assert_eq!(tracker.update(b, 25, 2), 100, "a single change is neutral");
+        assert_eq!(tracker.update(a, 25, 3), 150, "A -> B -> A is strong instability");
+        assert_eq!(tracker.update(b, 25, 4), 150);
+    }
+
+    #[test]
+    fn stability_improving_score_stays_neutral() {
+        let mut tracker = StabilityTracker::new();
+        let mv = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
+        for (depth, score) in (1..=8).zip((0..).step_by(20)) {
+            let scale = tracker.update(mv, score, depth);
+            assert_eq!(scale, 100, "same move but scores spread >= 10cp at depth {depth}");
+        }
+    }
+
     #[test]
     fn stability_score_drop_overrides() {
         let mut tracker = StabilityTracker::new();
@@ -591,13 +1023,12 @@ mod tests {
     fn easy_move_triggers_at_depth_8_with_stable_streak_5() {
         let mut tracker = StabilityTracker::new();
         let mv = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
-        // Build up streak: first call + 5 more = stable_streak reaches 5
-        tracker.update(mv, 50, 1);  // first (streak stays 0, neutral)
-        tracker.update(mv, 50, 2);  // streak 1
-        tracker.update(mv, 50, 3);  // streak 2
-        tracker.update(mv, 50, 4);  // streak 3
-        tracker.update(mv, 50, 5);  // streak 4
-        let scale = tracker.update(mv, 50, 8);  // streak 5, depth 8 -> easy move
+        tracker.update(mv, 50, 1); // first
+        tracker.update(mv, 50, 2); // window not yet full
+        for depth in 3..=6 {
+            tracker.update(mv, 50, depth); // streak 1..=4
+        }
+        let scale = tracker.update(mv, 50, 8); // streak 5, depth 8 -> easy move
         assert_eq!(scale, 30, "easy move should trigger at streak 5, depth 8");
     }
 
@@ -605,12 +1036,10 @@ mod tests {
     fn easy_move_does_not_trigger_at_low_depth() {
         let mut tracker = StabilityTracker::new();
         let mv = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
-        tracker.update(mv, 50, 1);  // first
-        tracker.update(mv, 50, 2);  // streak 1
-        tracker.update(mv, 50, 3);  // streak 2
-        tracker.update(mv, 50, 4);  // streak 3
-        tracker.update(mv, 50, 5);  // streak 4
-        let scale = tracker.update(mv, 50, 7);  // streak 5 but depth only 7
+        for depth in 1..=6 {
+            tracker.update(mv, 50, depth); // streak 4 after depth 6
+        }
+        let scale = tracker.update(mv, 50, 7); // streak 5 but depth only 7
         assert_eq!(scale, 60, "easy move should NOT trigger at depth < 8, should be 60 (stable)");
     }
 
@@ -618,13 +1047,11 @@ mod tests {
     fn easy_move_not_triggered_when_score_erodes() {
         let mut tracker = StabilityTracker::new();
         let mv = cesso_core::Move::new(cesso_core::Square::E2, cesso_core::Square::E4);
-        tracker.update(mv, 50, 1);  // first, peak_score set to 50
-        tracker.update(mv, 55, 2);  // streak 1, peak_score -> 55
-        tracker.update(mv, 55, 3);  // streak 2, peak_score stays 55
-        tracker.update(mv, 55, 4);  // streak 3
-        tracker.update(mv, 55, 5);  // streak 4
-        // Score drops by 20 from peak (55 -> 35), exceeds threshold of 10
-        let scale = tracker.update(mv, 35, 8);  // streak 5, depth 8, but score eroded
+        // Each window spreads less than 10cp, but the score slides 12cp from its peak
+        for (depth, score) in (1..=6).zip([60, 60, 60, 57, 54, 51]) {
+            tracker.update(mv, score, depth);
+        }
+        let scale = tracker.update(mv, 48, 8); // streak 5, depth 8, but score eroded
         assert_eq!(scale, 60, "easy move should NOT trigger when score erodes > 10cp from peak");
     }
 
@@ -639,24 +1066,31 @@ mod tests {
         assert!(!result.best_move.is_null(), "should return the forced move");
     }
 
+    #[test]
+    fn pawn_ending_zugzwang_is_seen() {
+        // Trébuchet: whoever moves must abandon their pawn. A null move
+        // would pass the obligation back, so the search must not try one.
+        let board: Board = "8/8/8/4pK2/3kP3/8/8/8 b - - 0 1".parse().unwrap();
+        let result = search_depth(&Searcher::new(), &board, 10);
+        assert!(result.score <= -200, "Black to move loses the e5 pawn, got {}", result.score);
+    }
+
     #[test]
     fn repetition_returns_draw() {
         use cesso_core::{Move as CessoMove, Square};
 
         // 1.Nf3 Nf6 2.Ng1 Ng8 — position returns to the starting position
-        let board = Board::starting_position();
-        let b1 = board.make_move(CessoMove::new(Square::G1, Square::F3));
-        let b2 = b1.make_move(CessoMove::new(Square::G8, Square::F6));
-        let b3 = b2.make_move(CessoMove::new(Square::F3, Square::G1));
-        let b4 = b3.make_move(CessoMove::new(Square::F6, Square::G8));
-        // b4 is the same position as `board` (starting position)
-        // history = [board.hash, b1.hash, b2.hash, b3.hash]
-        let history = vec![board.hash(), b1.hash(), b2.hash(), b3.hash()];
+        let (board, history) = Board::starting_position().apply_moves_with_history(&[
+            CessoMove::new(Square::G1, Square::F3),
+            CessoMove::new(Square::G8, Square::F6),
+            CessoMove::new(Square::F3, Square::G1),
+            CessoMove::new(Square::F6, Square::G8),
+        ]);
 
         let searcher = Searcher::new();
         let stopped = Arc::new(AtomicBool::new(false));
-        let control = SearchControl::new_infinite(stopped);
-        let result = searcher.search(&b4, 6, &control, &history, 0, Color::White, |_, _, _, _| {});
+        let control = SearchControl::new_depth(6, stopped);
+        let result = searcher.search(&board, 6, &control, &history, None, Color::White, |_, _, _, _, _, _| {});
         // With repetition detected, the score should be near zero (draw)
         assert!(
             result.score.abs() <= 100,
@@ -664,4 +1098,27 @@ mod tests {
             result.score
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn search_result_serializes_moves_as_uci() {
+        let board: Board = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4"
+            .parse()
+            .unwrap();
+        let result = search_depth(&Searcher::new(), &board, 2);
+        let json: serde_json::Value = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["best_move"], "h5f7");
+        assert_eq!(json["score"], negamax::MATE_SCORE - 1);
+        assert_eq!(json["depth"], result.depth);
+        assert_eq!(json["elapsed_ms"], result.elapsed.as_millis() as u64);
+
+        // The UCI strings read back against the searched position
+        let mut position = board;
+        for (uci, &mv) in json["pv"].as_array().unwrap().iter().zip(&result.pv) {
+            let parsed = Move::from_uci_legal(uci.as_str().unwrap(), &position).unwrap();
+            assert_eq!(parsed, mv);
+            position = position.make_move(parsed);
+        }
+        assert_eq!(json["ponder_move"], serde_json::Value::Null);
+    }
 }
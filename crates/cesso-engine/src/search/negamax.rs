@@ -1,16 +1,17 @@
 //! Negamax alpha-beta search with quiescence, PVS, LMR, and advanced pruning.
 
-use cesso_core::{Board, Color, Move, MoveKind, PieceKind, generate_legal_moves};
+use cesso_core::{Board, Color, MaterialDraw, Move, MoveKind, MoveList, PieceKind, generate_captures, generate_legal_moves};
 
-use crate::evaluate;
-use crate::search::control::SearchControl;
+use crate::eval::{EvalMode, evaluate_with};
+use crate::search::control::{AnalysisInfo, SearchControl};
 use crate::search::heuristics::{
-    ContHistIndex, ContinuationHistory, CorrectionHistory, HistoryTable, KillerTable,
+    CaptureHistoryTable, ContHistIndex, ContinuationHistory, CorrectionHistory, HistoryTable, KillerTable,
     StackEntry, update_cont_history,
 };
-use crate::search::ordering::{MovePicker, lmr_reduction};
+use crate::search::ordering::{MovePicker, OrderingTables, lmr_reduction};
 use crate::search::see::see_ge;
 use crate::search::tt::{Bound, TranspositionTable};
+use crate::tb::{Tablebases, Wdl};
 
 /// Score representing an unreachable upper/lower bound.
 pub const INF: i32 = 30_000;
@@ -24,14 +25,33 @@ pub const MATE_THRESHOLD: i32 = 28_000;
 /// Maximum search depth (in plies) for array sizing and recursion limits.
 pub const MAX_PLY: usize = 128;
 
+/// Nodes between checks whether a `currline` report is due.
+const CURRLINE_NODE_INTERVAL: u64 = 16_384;
+
+/// Deepest iteration the searcher will run; requests beyond it are clamped.
+pub const MAX_SEARCH_DEPTH: u8 = (MAX_PLY - 1) as u8;
+
+/// Base score for a tablebase win (adjusted by ply), kept below the mate range.
+pub const TB_WIN_SCORE: i32 = MATE_THRESHOLD - MAX_PLY as i32;
+
+/// Delta pruning margin: a qsearch capture is skipped when even winning the
+/// captured piece plus this much cannot lift the stand-pat score to alpha.
+const DELTA_MARGIN: i32 = 200;
+
 /// Maximum depth for futility pruning.
-const FUTILITY_DEPTH: u8 = 3;
+const FUTILITY_DEPTH: u8 = 4;
 
 /// Forward futility margins indexed by depth.
-const FUTILITY_MARGIN: [i32; 4] = [0, 200, 450, 700];
+const FUTILITY_MARGIN: [i32; 5] = [0, 200, 450, 700, 1000];
+
+/// Maximum depth for reverse futility pruning.
+const RFP_DEPTH: u8 = 5;
 
 /// Reverse futility pruning margins indexed by depth.
-const RFP_MARGIN: [i32; 4] = [0, 200, 450, 700];
+const RFP_MARGIN: [i32; 6] = [0, 200, 450, 700, 850, 1000];
+
+/// Margin reduction applied to futility and RFP margins when the eval is improving.
+const IMPROVING_MARGIN: i32 = 100;
 
 /// Maximum depth for Late Move Pruning.
 const LMP_MAX_DEPTH: u8 = 4;
@@ -54,9 +74,22 @@ const SE_DEPTH: u8 = 8;
 /// Double extension threshold (singular_score < singular_beta - SE_DOUBLE_MARGIN).
 const SE_DOUBLE_MARGIN: i32 = 23;
 
+/// Base null-move reduction before depth and evaluation adjustments.
+const NMP_BASE_REDUCTION: i32 = 3;
+
+/// Centipawns of static-eval advantage over beta worth one extra ply of
+/// null-move reduction (capped at three extra plies).
+const NMP_EVAL_BONUS: i32 = 100;
+
+/// NMP is allowed when static eval is at most this far below beta.
+const NMP_EVAL_MARGIN: i32 = 50;
+
 /// Depth threshold above which NMP verification is required.
 const NMP_VERIFY_DEPTH: u8 = 12;
 
+/// Maximum number of in-check qsearch nodes (searching all evasions) per line.
+const QS_MAX_CHECK_EXTENSIONS: u8 = 2;
+
 /// Maximum cumulative double extensions allowed per search path.
 const MAX_DOUBLE_EXTENSIONS: u8 = 16;
 
@@ -95,18 +128,34 @@ pub(super) fn negamax(
     let NodeParams { mut depth, ply, do_null, excluded, cutnode, double_extensions } = params;
     let is_pv = alpha + 1 < beta;
     let is_root = ply == 0;
+    // `go mate N`: no selective pruning, so every mate found is forced
+    let mate_search = ctx.control.mate_moves().is_some();
 
     ctx.pv.clear_ply(ply as usize);
     ctx.nodes += 1;
 
     // Ply ceiling to prevent out-of-bounds access and runaway recursion
     if ply as usize >= MAX_PLY {
-        return evaluate(board);
+        return ctx.evaluate(board);
     }
 
     // Reset cutoff count for this node
     ctx.stack[ply as usize].cutoff_count = 0;
 
+    if is_root {
+        ctx.refutations.clear();
+    }
+
+    // Analysis mode: now and then show the line leading to this node
+    if ctx.main_thread && ctx.nodes.is_multiple_of(CURRLINE_NODE_INTERVAL) && ctx.control.currline_due() {
+        let line: Vec<Move> = ctx.stack[..ply as usize]
+            .iter()
+            .map(|entry| entry.current_move)
+            .take_while(|mv| !mv.is_null())
+            .collect();
+        ctx.control.report(AnalysisInfo::CurrLine(&line));
+    }
+
     // Check stop condition (time limit, node limit, etc.)
     if ctx.control.should_stop(ctx.nodes) {
         return 0;
@@ -117,17 +166,14 @@ pub(super) fn negamax(
         return ctx.draw_score(board);
     }
 
+    // Neither side can force mate
+    if ply > 0 && matches!(board.material_draw_class(), Some(MaterialDraw::Dead | MaterialDraw::Unforceable)) {
+        return ctx.draw_score(board);
+    }
+
     // Repetition detection (twofold repetition = draw in search)
-    if ply > 0 {
-        let hash = board.hash();
-        let hmc = board.halfmove_clock() as usize;
-        let len = ctx.history.len();
-        let lookback = hmc.min(len);
-        for i in (len.saturating_sub(lookback)..len).rev() {
-            if ctx.history[i] == hash {
-                return ctx.draw_score(board);
-            }
-        }
+    if ply > 0 && board.repetitions(&ctx.history) > 0 {
+        return ctx.draw_score(board);
     }
 
     // Mate Distance Pruning
@@ -147,51 +193,86 @@ pub(super) fn negamax(
     let mut tt_is_pv = is_pv;
     let mut tt_eval: i32 = 0;
 
-    if excluded.is_null() {
-        if let Some(tt_entry) = ctx.tt.probe(board.hash(), ply) {
+    if excluded.is_null()
+        && let Some(tt_entry) = ctx.tt.probe(board.hash(), ply)
+    {
+        // A key collision can hand back a move from another position.
+        if board.is_legal(tt_entry.best_move) {
             tt_move = tt_entry.best_move;
-            tt_score = tt_entry.score;
-            tt_depth = tt_entry.depth;
-            tt_bound = tt_entry.bound;
-            tt_is_pv = tt_is_pv || tt_entry.is_pv;
-            tt_eval = tt_entry.eval;
-
-            // TT cutoff (not at root, not in PV)
-            if !is_root && tt_depth >= depth {
-                let cutoff = match tt_bound {
-                    Bound::Exact => true,
-                    Bound::LowerBound => tt_score >= beta,
-                    Bound::UpperBound => tt_score <= alpha,
-                    Bound::None => false,
-                };
-                if cutoff {
-                    return tt_score;
-                }
+        }
+        tt_score = tt_entry.score;
+        tt_depth = tt_entry.depth;
+        tt_bound = tt_entry.bound;
+        tt_is_pv = tt_is_pv || tt_entry.is_pv;
+        tt_eval = tt_entry.eval;
+
+        // TT cutoff (not at root, not in PV)
+        if !is_root && tt_depth >= depth {
+            let cutoff = match tt_bound {
+                Bound::Exact => true,
+                Bound::LowerBound => tt_score >= beta,
+                Bound::UpperBound => tt_score <= alpha,
+                Bound::None => false,
+            };
+            if cutoff {
+                return tt_score;
             }
         }
     }
 
+    // Tablebase probe — only right after a zeroing move, so the fifty-move
+    // counter matches what the WDL tables assume
+    if !is_root && excluded.is_null() && board.halfmove_clock() == 0
+        && let Some(tb) = ctx.tb
+        && let Some(wdl) = tb.probe_wdl(board)
+    {
+        let (score, bound) = match wdl {
+            Wdl::Win => (TB_WIN_SCORE - ply as i32, Bound::LowerBound),
+            Wdl::Loss => (-TB_WIN_SCORE + ply as i32, Bound::UpperBound),
+            // Cursed wins and blessed losses are draws, nudged by one centipawn
+            Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => {
+                (ctx.draw_score(board) + wdl as i32, Bound::Exact)
+            }
+        };
+        let cutoff = match bound {
+            Bound::LowerBound => score >= beta,
+            Bound::UpperBound => score <= alpha,
+            _ => true,
+        };
+        if cutoff {
+            let tb_depth = (depth as usize + 6).min(MAX_PLY - 1) as u8;
+            ctx.tt.store(board.hash(), tb_depth, score, 0, Move::NULL, bound, ply, is_pv);
+            return score;
+        }
+    }
+
     // Compute check status
-    let king_sq = board.king_square(board.side_to_move());
-    let in_check = board.is_square_attacked(king_sq, !board.side_to_move());
+    let in_check = board.checkers().is_nonempty();
 
     // IIR — Internal Iterative Reduction
-    if (is_pv || cutnode) && depth > 4 && tt_move.is_null() {
+    if (is_pv || cutnode) && depth > 4 && tt_move.is_null() && !mate_search {
         depth = depth.saturating_sub(2);
     }
 
     // Check extension
     if in_check && (ply as usize) < MAX_PLY - 1 {
-        depth += 1;
+        depth = depth.saturating_add(1);
     }
 
-    // Drop to qsearch at depth 0
+    // Extensions must not carry the line past the stack arrays
+    depth = depth.min((MAX_PLY - 1 - ply as usize) as u8);
+
+    // Drop to qsearch at depth 0. A mate search has found no mate on this
+    // line, so fail low for the attacker (and high for the defender) instead.
     if depth == 0 {
+        if mate_search {
+            return alpha.clamp(-MATE_THRESHOLD, MATE_THRESHOLD);
+        }
         return qsearch(board, ply, alpha, beta, ctx);
     }
 
     // Static eval with correction history
-    let raw_eval = if tt_eval != 0 { tt_eval } else { evaluate(board) };
+    let raw_eval = if tt_eval != 0 { tt_eval } else { ctx.evaluate(board) };
 
     // Get previous move info for correction history
     let (prev_piece, prev_dest) = if ply >= 1 {
@@ -232,7 +313,7 @@ pub(super) fn negamax(
     };
 
     // Razoring
-    if !is_pv && !in_check && depth <= 3
+    if !is_pv && !in_check && depth <= 3 && !mate_search
         && static_eval + RAZOR_MARGIN[depth as usize] < alpha
     {
         let razor_score = qsearch(board, ply, alpha, beta, ctx);
@@ -243,22 +324,25 @@ pub(super) fn negamax(
 
     // Reverse Futility Pruning
     if !is_pv && !in_check && excluded.is_null()
-        && depth >= 1 && depth <= FUTILITY_DEPTH
+        && (1..=RFP_DEPTH).contains(&depth)
         && beta.abs() < MATE_THRESHOLD
     {
-        let margin = RFP_MARGIN[depth as usize] - if improving { 0 } else { 100 };
+        let margin = RFP_MARGIN[depth as usize] - if improving { IMPROVING_MARGIN } else { 0 };
         if static_eval - margin >= beta {
             return static_eval;
         }
     }
 
-    // Null Move Pruning
+    // Null Move Pruning — never without pieces, where zugzwang makes the
+    // null move's assumption that passing is worst unsound
     if do_null && !is_pv && ply > 0 && excluded.is_null()
         && depth >= 3 && beta.abs() < MATE_THRESHOLD
         && !in_check && has_non_pawn_material(board)
-        && static_eval >= beta
+        && static_eval >= beta - NMP_EVAL_MARGIN
     {
-        let r = if depth >= 6 { 3 } else { 2 };
+        // Deeper nodes and larger eval advantages can withstand a bigger reduction
+        let eval_bonus = (static_eval - beta).min(3 * NMP_EVAL_BONUS) / NMP_EVAL_BONUS;
+        let r = (NMP_BASE_REDUCTION + depth as i32 / 5 + eval_bonus).clamp(2, 5) as u8;
         let null_board = board.make_null_move();
         ctx.history.push(board.hash());
 
@@ -312,17 +396,10 @@ pub(super) fn negamax(
     // ProbCut
     if !is_pv && !in_check && depth >= 7 && beta.abs() < MATE_THRESHOLD {
         let probcut_beta = beta + PROBCUT_MARGIN;
-        let moves = generate_legal_moves(board);
-
-        for i in 0..moves.len() {
-            let mv = moves[i];
-            let is_tactical = board.piece_on(mv.dest()).is_some()
-                || mv.kind() == MoveKind::EnPassant
-                || mv.kind() == MoveKind::Promotion;
-            if !is_tactical || !see_ge(board, mv, probcut_beta - static_eval) {
-                continue;
-            }
+        let mut moves = generate_captures(board);
+        moves.retain(|mv| see_ge(board, mv, probcut_beta - static_eval));
 
+        for &mv in &moves {
             let child = board.make_move(mv);
             ctx.history.push(board.hash());
 
@@ -365,55 +442,45 @@ pub(super) fn negamax(
         }
     }
 
-    // Move generation
-    let moves = generate_legal_moves(board);
-
-    if moves.is_empty() {
-        return if in_check {
-            -(MATE_SCORE - ply as i32)
-        } else {
-            ctx.draw_score(board)
-        };
-    }
-
     let original_alpha = alpha;
     let mut best_score = -INF;
     let mut best_move = Move::NULL;
-    let mut picker = MovePicker::new(
-        &moves,
-        board,
-        tt_move,
-        &ctx.killers,
-        &ctx.history_table,
-        &ctx.cont_history,
-        &ctx.stack,
-        ply as usize,
-    );
+    let mut picker = MovePicker::new(tt_move, &ctx.killers, ply as usize);
     let mut searched_quiets = [Move::NULL; 64];
     let mut quiet_count: usize = 0;
+    let mut searched_captures = [(Move::NULL, PieceKind::Pawn, PieceKind::Pawn); 32];
+    let mut capture_count: usize = 0;
     let mut move_count: usize = 0;
 
-    while let Some(mv) = picker.pick_next() {
+    while let Some(mv) = picker.pick_next_staged(board, ctx.ordering_tables(ply as usize)) {
         // Skip excluded move (singular extension search)
         if mv == excluded {
             continue;
         }
 
-        let is_tactical = board.piece_on(mv.dest()).is_some()
-            || mv.kind() == MoveKind::EnPassant
-            || mv.kind() == MoveKind::Promotion;
+        // Restrict the root to the allowed move set (searchmoves, tablebase filtering)
+        if is_root && !ctx.root_allows(mv) {
+            continue;
+        }
+
+        let is_tactical = mv.is_capture(board) || mv.kind() == MoveKind::Promotion;
 
         let moved_piece = board.piece_on(mv.source()).unwrap_or(PieceKind::Pawn);
 
         // ── Pruning (skip non-first moves in some conditions) ──────────────
 
-        if move_count > 0 && !is_root {
+        if move_count > 0 && !is_root && !mate_search {
             // Forward Futility Pruning
             if !in_check && depth <= FUTILITY_DEPTH && !is_tactical
                 && alpha.abs() < MATE_THRESHOLD
             {
-                let margin = FUTILITY_MARGIN[depth as usize] - if improving { 0 } else { 50 };
-                if static_eval + margin <= alpha {
+                let margin = FUTILITY_MARGIN[depth as usize] - if improving { IMPROVING_MARGIN } else { 0 };
+                // At the deepest futility depth only prune non-checking,
+                // SEE-safe moves outside the PV
+                if static_eval + margin <= alpha
+                    && (depth < FUTILITY_DEPTH
+                        || (!is_pv && see_ge(board, mv, 0) && !board.gives_check(mv)))
+                {
                     continue;
                 }
             }
@@ -456,6 +523,14 @@ pub(super) fn negamax(
             searched_quiets[quiet_count] = mv;
             quiet_count += 1;
         }
+        // Track captures likewise (for capture history)
+        let victim = if mv.is_promotion() { None } else { mv.captured_piece(board) };
+        if let Some(victim) = victim
+            && capture_count < 32
+        {
+            searched_captures[capture_count] = (mv, moved_piece, victim);
+            capture_count += 1;
+        }
 
         // Set stack entry before make_move
         ctx.stack[ply as usize].current_move = mv;
@@ -536,7 +611,7 @@ pub(super) fn negamax(
                 ctx,
             );
         } else {
-            let do_lmr = depth >= 3 && move_count >= 4 && !is_tactical && !in_check;
+            let do_lmr = depth >= 3 && move_count >= 4 && !is_tactical && !in_check && !mate_search;
 
             let mut searched_depth = new_depth;
 
@@ -621,6 +696,18 @@ pub(super) fn negamax(
 
         ctx.history.pop();
 
+        // Analysis mode: remember why each failed-low root move is worse
+        if is_root && ctx.main_thread && move_count > 1 && score <= alpha && ctx.control.analysing() {
+            let mut line = vec![mv];
+            match ctx.pv.line(1) {
+                [] => line.extend(ctx.tt.probe_tt_move(child.hash()).filter(|&m| child.is_legal(m))),
+                reply => line.extend_from_slice(reply),
+            }
+            if line.len() > 1 {
+                ctx.refutations.push(line);
+            }
+        }
+
         if score > best_score {
             best_score = score;
             best_move = mv;
@@ -650,8 +737,7 @@ pub(super) fn negamax(
                 );
 
                 // Penalise all previously searched quiets
-                for i in 0..quiet_count.saturating_sub(1) {
-                    let bad_mv = searched_quiets[i];
+                for &bad_mv in &searched_quiets[..quiet_count.saturating_sub(1)] {
                     if let Some(bad_piece) = board.piece_on(bad_mv.source()) {
                         ctx.history_table.update(bad_piece, bad_mv.dest().index(), -bonus);
                         update_cont_history(
@@ -664,11 +750,43 @@ pub(super) fn negamax(
                         );
                     }
                 }
+            } else if let Some(victim) = victim {
+                // Reward the cutoff capture, penalise the captures tried before it
+                let bonus = (depth as i32) * (depth as i32);
+                ctx.capture_history.update(moved_piece, victim, mv.dest().index(), bonus);
+                for &(bad_mv, attacker, bad_victim) in &searched_captures[..capture_count] {
+                    if bad_mv != mv {
+                        ctx.capture_history.update(attacker, bad_victim, bad_mv.dest().index(), -bonus);
+                    }
+                }
             }
             break;
         }
     }
 
+    // Mate or stalemate. The first move is never pruned, so nothing was
+    // searched only if there was no legal move (or the excluded one alone).
+    if move_count == 0 && excluded.is_null() {
+        return if in_check {
+            -(MATE_SCORE - ply as i32)
+        } else {
+            ctx.draw_score(board)
+        };
+    }
+
+    // All-node: none of the captures searched here improved alpha
+    if best_score <= original_alpha {
+        let penalty = (depth as i32) * (depth as i32);
+        for &(capture, attacker, victim) in &searched_captures[..capture_count] {
+            ctx.capture_history.update(attacker, victim, capture.dest().index(), -penalty);
+        }
+    }
+
+    // Keep a root move even when every move failed low (mate search)
+    if is_root && ctx.pv.root_len() == 0 && !best_move.is_null() {
+        ctx.pv.set_single(0, best_move);
+    }
+
     // TT store — skip during singular extension search
     if excluded.is_null() {
         let bound = if best_score <= original_alpha {
@@ -679,7 +797,10 @@ pub(super) fn negamax(
             Bound::Exact
         };
 
-        let store_move = if bound == Bound::UpperBound && best_move.is_null() {
+        // Never let a fail-low root keep a TT move outside the allowed set
+        let store_move = if bound == Bound::UpperBound && best_move.is_null()
+            && (!is_root || ctx.root_allows(tt_move))
+        {
             tt_move
         } else {
             best_move
@@ -722,6 +843,11 @@ pub(super) fn negamax(
 ///
 /// For depths 1-4 or near-mate scores, uses a full window.
 /// For deeper searches, starts with `delta = 50` centered on `prev_score`.
+/// Each fail triples `delta` and widens only the failing bound, from the
+/// returned score; a fail low also pulls beta halfway down. Three fails in a
+/// row in the same direction fall back to the full window.
+/// A mate search (`go mate N`) uses `[MATE_THRESHOLD, INF]` and reports 0
+/// until a mate in at most N moves is found.
 pub(super) fn aspiration_search(
     board: &Board,
     depth: u8,
@@ -737,6 +863,13 @@ pub(super) fn aspiration_search(
         double_extensions: 0,
     };
 
+    if let Some(moves) = ctx.control.mate_moves() {
+        let score = negamax(board, MATE_THRESHOLD, INF, base_params, ctx);
+        // Check extensions can reach mates longer than the requested N
+        let longest = MATE_SCORE - 2 * moves as i32 + 1;
+        return if score >= longest { score } else { 0 };
+    }
+
     // Full window for shallow depths or near-mate scores
     if depth <= 4 || prev_score.abs() >= MATE_THRESHOLD {
         return negamax(board, -INF, INF, base_params, ctx);
@@ -745,6 +878,8 @@ pub(super) fn aspiration_search(
     let mut delta: i32 = 50;
     let mut alpha = (prev_score - delta).max(-INF);
     let mut beta = (prev_score + delta).min(INF);
+    let mut fail_lows = 0;
+    let mut fail_highs = 0;
 
     loop {
         let score = negamax(board, alpha, beta, base_params, ctx);
@@ -755,25 +890,28 @@ pub(super) fn aspiration_search(
         }
 
         if score <= alpha {
-            // Fail low — widen alpha
-            delta *= 4;
-            alpha = (prev_score - delta).max(-INF);
-            if delta > INF {
-                alpha = -INF;
-                beta = INF;
-            }
+            // Fail low — pull beta in to the middle and push alpha well below the score
+            fail_lows += 1;
+            fail_highs = 0;
+            delta *= 3;
+            beta = (alpha + beta) / 2;
+            alpha = (score - delta).max(-INF);
         } else if score >= beta {
-            // Fail high — widen beta
-            delta *= 4;
-            beta = (prev_score + delta).min(INF);
-            if delta > INF {
-                alpha = -INF;
-                beta = INF;
-            }
+            // Fail high — keep alpha and push beta well above the score
+            fail_highs += 1;
+            fail_lows = 0;
+            delta *= 3;
+            beta = (score + delta).min(INF);
         } else {
             // Score is within the window — done
             return score;
         }
+
+        // Three fails in a row in one direction: fall back to the full window
+        if fail_lows >= 3 || fail_highs >= 3 {
+            alpha = -INF;
+            beta = INF;
+        }
     }
 }
 
@@ -789,6 +927,7 @@ fn qsearch(
     ctx: &mut SearchContext<'_>,
 ) -> i32 {
     ctx.nodes += 1;
+    ctx.qnodes += 1;
 
     // Check stop condition (time limit, node limit, etc.)
     if ctx.control.should_stop(ctx.nodes) {
@@ -797,7 +936,7 @@ fn qsearch(
 
     // Ply ceiling to prevent runaway recursion
     if ply as usize >= MAX_PLY {
-        return evaluate(board);
+        return ctx.evaluate(board);
     }
 
     // Fifty-move rule draw
@@ -805,26 +944,65 @@ fn qsearch(
         return ctx.draw_score(board);
     }
 
-    // Stand-pat: the side to move can choose not to capture
-    let stand_pat = evaluate(board);
-    if stand_pat >= beta {
-        return stand_pat;
-    }
-    if stand_pat > alpha {
-        alpha = stand_pat;
+    // In check, search every evasion (a bounded number of times per line)
+    // instead of captures only
+    let in_check = board.checkers().is_nonempty();
+    let evasions = in_check && ctx.qsearch_check_extensions < QS_MAX_CHECK_EXTENSIONS;
+
+    // Out of evasion budget: standing pat in check is unsound, so the static
+    // eval is only a guess. Keep it below beta so an unresolved check never
+    // produces a cutoff.
+    if in_check && !evasions {
+        return ctx.evaluate(board).min(beta - 1);
     }
 
-    let moves = generate_legal_moves(board);
-    let mut picker = MovePicker::new_qsearch(&moves, board);
+    let moves;
+    let mut picker;
+    // Stand-pat score used for delta pruning; `None` when searching evasions
+    let mut delta_base = None;
+    if evasions {
+        moves = generate_legal_moves(board);
+        if moves.is_empty() {
+            return -(MATE_SCORE - ply as i32);
+        }
+        picker = MovePicker::new_evasions(&moves, board);
+    } else {
+        // Stand-pat: the side to move can choose not to capture
+        let stand_pat = ctx.evaluate(board);
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        if stand_pat > alpha {
+            alpha = stand_pat;
+        }
+        if stand_pat.abs() < MATE_THRESHOLD {
+            delta_base = Some(stand_pat);
+        }
+
+        moves = generate_captures(board);
+        picker = MovePicker::new_qsearch(&moves, board);
+    }
 
     while let Some(mv) = picker.pick_next() {
         // Skip captures with negative SEE (losing exchanges), but never skip promotions.
-        if mv.kind() != MoveKind::Promotion && !see_ge(board, mv, 0) {
+        if !evasions && mv.kind() != MoveKind::Promotion && !see_ge(board, mv, 0) {
+            continue;
+        }
+
+        // Delta pruning: even winning the captured piece outright cannot
+        // reach alpha. Promotions gain far more than a pawn, so keep them.
+        if let Some(stand_pat) = delta_base
+            && mv.kind() != MoveKind::Promotion
+            && let Some(victim) = mv.captured_piece(board)
+            && stand_pat + victim.material_value_mg() + DELTA_MARGIN <= alpha
+        {
             continue;
         }
 
         let child = board.make_move(mv);
+        ctx.qsearch_check_extensions += evasions as u8;
         let score = -qsearch(&child, ply + 1, -beta, -alpha, ctx);
+        ctx.qsearch_check_extensions -= evasions as u8;
 
         if score >= beta {
             return score;
@@ -905,6 +1083,11 @@ impl PvTable {
         &self.moves[0][..self.len[0]]
     }
 
+    /// The best line found from `ply` by the node last searched there.
+    pub fn line(&self, ply: usize) -> &[Move] {
+        if ply < MAX_PLY { &self.moves[ply][..self.len[ply]] } else { &[] }
+    }
+
     /// Length of the root PV line.
     pub fn root_len(&self) -> usize {
         self.len[0]
@@ -919,8 +1102,10 @@ impl Default for PvTable {
 
 /// Search state threaded through negamax calls.
 pub(super) struct SearchContext<'a> {
-    /// Total nodes visited.
+    /// Total nodes visited (main search and quiescence).
     pub nodes: u64,
+    /// Nodes visited in quiescence search; a subset of `nodes`.
+    pub qnodes: u64,
     /// Transposition table (shared, lockless).
     pub tt: &'a TranspositionTable,
     /// Principal variation table.
@@ -931,6 +1116,8 @@ pub(super) struct SearchContext<'a> {
     pub killers: KillerTable,
     /// History heuristic table.
     pub history_table: HistoryTable,
+    /// Capture history table.
+    pub capture_history: CaptureHistoryTable,
     /// Continuation history table.
     pub cont_history: Box<ContinuationHistory>,
     /// Correction history for static eval adjustment.
@@ -943,9 +1130,53 @@ pub(super) struct SearchContext<'a> {
     pub contempt: i32,
     /// The color the engine is playing (for contempt sign).
     pub engine_color: Color,
+    /// Which evaluation leaf nodes use.
+    pub eval_mode: EvalMode,
+    /// Syzygy tablebases, if configured.
+    pub tb: Option<&'a Tablebases>,
+    /// Moves allowed at the root; empty means all legal moves.
+    pub root_moves: MoveList,
+    /// In-check qsearch nodes on the current line (capped by `QS_MAX_CHECK_EXTENSIONS`).
+    pub qsearch_check_extensions: u8,
+    /// Root moves that failed low in the last root search, each followed by its
+    /// refutation; only collected in analysis mode.
+    pub refutations: Vec<Vec<Move>>,
+    /// Whether this context belongs to the thread that reports search output.
+    pub main_thread: bool,
 }
 
 impl SearchContext<'_> {
+    /// Send the refutations of the last completed root search to the analysis sink.
+    pub(super) fn report_refutations(&self) {
+        for line in &self.refutations {
+            self.control.report(AnalysisInfo::Refutation(line));
+        }
+    }
+
+    /// Whether `mv` may be played at the root.
+    #[inline]
+    fn root_allows(&self, mv: Move) -> bool {
+        self.root_moves.is_empty() || self.root_moves.contains(mv)
+    }
+
+    /// The move-ordering tables as the picker sees them at `ply`.
+    fn ordering_tables(&self, ply: usize) -> OrderingTables<'_> {
+        OrderingTables {
+            killers: &self.killers,
+            history: &self.history_table,
+            capture_history: &self.capture_history,
+            cont_history: &self.cont_history,
+            stack: &self.stack,
+            ply,
+        }
+    }
+
+    /// Static evaluation of `board` in this search's [`EvalMode`].
+    #[inline]
+    fn evaluate(&self, board: &Board) -> i32 {
+        evaluate_with(board, self.eval_mode)
+    }
+
     /// Contempt-aware draw score for negamax.
     ///
     /// When the engine is to move, a draw scores `-contempt` (bad when
@@ -959,3 +1190,157 @@ impl SearchContext<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+    use crate::evaluate;
+
+    fn test_ctx<'a>(
+        tt: &'a TranspositionTable,
+        control: &'a SearchControl,
+        board: &Board,
+    ) -> SearchContext<'a> {
+        SearchContext {
+            nodes: 0,
+            qnodes: 0,
+            tt,
+            pv: PvTable::new(),
+            control,
+            killers: KillerTable::new(),
+            history_table: HistoryTable::new(),
+            capture_history: CaptureHistoryTable::new(),
+            cont_history: Box::new(ContinuationHistory::new()),
+            correction_history: Box::new(CorrectionHistory::new()),
+            stack: [StackEntry::EMPTY; MAX_PLY],
+            history: Vec::new(),
+            contempt: 0,
+            engine_color: board.side_to_move(),
+            eval_mode: EvalMode::Full,
+            tb: None,
+            root_moves: MoveList::new(),
+            qsearch_check_extensions: 0,
+            refutations: Vec::new(),
+            main_thread: true,
+        }
+    }
+
+    fn run_qsearch(board: &Board) -> i32 {
+        let tt = TranspositionTable::new(1);
+        let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false)));
+        let mut ctx = test_ctx(&tt, &control, board);
+        qsearch(board, 0, -INF, INF, &mut ctx)
+    }
+
+    #[test]
+    fn check_extension_near_ply_ceiling_stays_in_bounds() {
+        // Black is in check; a requested depth of 255 plus the check
+        // extension must neither overflow nor run past the stack
+        let board: Board = "7k/8/8/8/8/8/8/K6Q b - - 0 1".parse().unwrap();
+        let tt = TranspositionTable::new(1);
+        let control = SearchControl::new_nodes(50_000, Arc::new(AtomicBool::new(false)));
+        let mut ctx = test_ctx(&tt, &control, &board);
+        let params = NodeParams {
+            depth: u8::MAX,
+            ply: (MAX_PLY - 3) as u8,
+            do_null: false,
+            excluded: Move::NULL,
+            cutnode: false,
+            double_extensions: 0,
+        };
+        let score = negamax(&board, -INF, INF, params, &mut ctx);
+        assert!(score.abs() < INF);
+        assert!(ctx.nodes < 50_000, "depth should be capped to the remaining plies");
+    }
+
+    #[test]
+    fn aspiration_matches_full_window_after_fails() {
+        let root = NodeParams {
+            depth: 6,
+            ply: 0,
+            do_null: true,
+            excluded: Move::NULL,
+            cutnode: false,
+            double_extensions: 0,
+        };
+        // Forced mates and a dead draw, so the score cannot drift between
+        // re-searches
+        for fen in [
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+            "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+        ] {
+            let board: Board = fen.parse().unwrap();
+            let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false)));
+
+            let tt = TranspositionTable::new(1);
+            let full = negamax(&board, -INF, INF, root, &mut test_ctx(&tt, &control, &board));
+
+            // Guesses far from the true score fail in the same direction
+            // until the window falls back to full width
+            for guess in [-700, -120, 0, 120, 700] {
+                let tt = TranspositionTable::new(1);
+                let score = aspiration_search(&board, root.depth, guess, &mut test_ctx(&tt, &control, &board));
+                assert_eq!(score, full, "{fen} from guess {guess}");
+            }
+        }
+    }
+
+    #[test]
+    fn qsearch_in_check_searches_quiet_evasion() {
+        // Kh1 is checked by the a1 rook; Kh2 is the only legal move and
+        // leaves the a5 knight to be captured
+        let board: Board = "7k/8/8/N7/8/8/6P1/r6K w - - 0 1".parse().unwrap();
+        let moves = generate_legal_moves(&board);
+        assert_eq!(moves.len(), 1);
+
+        let score = run_qsearch(&board);
+        let after_evasion = -run_qsearch(&board.make_move(moves[0]));
+        assert_eq!(score, after_evasion);
+        assert!(
+            score < evaluate(&board) - 200,
+            "score {score} should account for the lost knight (stand pat {})",
+            evaluate(&board)
+        );
+    }
+
+    #[test]
+    fn qsearch_in_check_without_evasions_is_mate() {
+        let board: Board = "7k/8/8/8/8/8/6PP/r6K w - - 0 1".parse().unwrap();
+        assert_eq!(run_qsearch(&board), -MATE_SCORE);
+    }
+
+    #[test]
+    fn qsearch_in_check_past_extension_limit_does_not_cut_off() {
+        // White is a queen up but in check: with no evasions left to search,
+        // the score must stay below beta rather than stand pat
+        let board: Board = "4k3/8/8/8/7Q/8/8/r3K2R w - - 0 1".parse().unwrap();
+        assert!(evaluate(&board) > 0);
+        let tt = TranspositionTable::new(1);
+        let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false)));
+        let mut ctx = test_ctx(&tt, &control, &board);
+        ctx.qsearch_check_extensions = QS_MAX_CHECK_EXTENSIONS;
+        assert_eq!(qsearch(&board, 0, -INF, 0, &mut ctx), -1);
+    }
+
+    #[test]
+    fn qsearch_delta_prunes_hopeless_captures() {
+        // White is a queen down; winning the d5 pawn cannot reach alpha
+        let board: Board = "q3k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1".parse().unwrap();
+        let alpha = evaluate(&board) + 300;
+        let tt = TranspositionTable::new(1);
+        let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false)));
+        let mut ctx = test_ctx(&tt, &control, &board);
+        assert_eq!(qsearch(&board, 0, alpha, alpha + 1, &mut ctx), alpha);
+        assert_eq!(ctx.qnodes, 1, "exd5 should be pruned");
+
+        // Inside the margin the capture is still searched
+        let alpha = evaluate(&board) + 250;
+        let mut ctx = test_ctx(&tt, &control, &board);
+        qsearch(&board, 0, alpha, alpha + 1, &mut ctx);
+        assert!(ctx.qnodes > 1);
+    }
+}
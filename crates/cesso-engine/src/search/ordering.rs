@@ -3,17 +3,20 @@
 //! Score bands ensure correct ordering:
 //! - TT move:              100,000
 //! - Queen promotion:       30,000
-//! - Good captures (SEE >= 0): 10,000 + MVV_LVA (10,007..10,144)
+//! - Good captures (SEE >= 0): 10,000 + MVV_LVA + capture history / 64
+//!   (about 9,750..10,400)
 //! - En passant:            10,015
 //! - Killer moves:           9,000
-//! - Quiet moves (history): bounded by ±HISTORY_MAX plus cont_hist
+//! - Quiet moves (history):  history + cont_hist / 2, clamped to -49,999..8,999
 //! - Bad captures (SEE < 0): -50,000 + see_score (always very negative)
 
 use std::sync::OnceLock;
 
-use cesso_core::{Board, Move, MoveKind, MoveList, PieceKind, PromotionPiece};
+use cesso_core::{generate_captures, generate_quiets, Board, Move, MoveKind, MoveList, PieceKind, PromotionPiece};
 
-use crate::search::heuristics::{cont_hist_score, ContinuationHistory, HistoryTable, KillerTable, StackEntry};
+use crate::search::heuristics::{
+    cont_hist_score, CaptureHistoryTable, ContinuationHistory, HistoryTable, KillerTable, StackEntry,
+};
 use crate::search::see::{see, see_ge};
 
 /// MVV-LVA scores indexed by `[victim][attacker]`.
@@ -35,6 +38,10 @@ const MVV_LVA: [[i32; 6]; 6] = [
     [-1, -3, -3, -5, -9, 0],
 ];
 
+/// Scale applied to capture history in good-capture scores, keeping them
+/// within their band above killers.
+const CAPTURE_HISTORY_DIVISOR: i32 = 64;
+
 // ---------------------------------------------------------------------------
 // LMR reduction table
 // ---------------------------------------------------------------------------
@@ -44,10 +51,9 @@ static LMR_TABLE: OnceLock<[[i32; 64]; 64]> = OnceLock::new();
 fn lmr_table() -> &'static [[i32; 64]; 64] {
     LMR_TABLE.get_or_init(|| {
         let mut t = [[0i32; 64]; 64];
-        for i in 1..64usize {
-            for d in 1..64usize {
-                t[i][d] =
-                    ((0.76 + (i as f64).ln() * (d as f64).ln() / 2.32) * 1024.0) as i32;
+        for (i, row) in t.iter_mut().enumerate().skip(1) {
+            for (d, cell) in row.iter_mut().enumerate().skip(1) {
+                *cell = ((0.76 + (i as f64).ln() * (d as f64).ln() / 2.32) * 1024.0) as i32;
             }
         }
         t
@@ -63,12 +69,20 @@ pub fn lmr_reduction(move_index: usize, depth: usize) -> i32 {
 // Internal scoring helpers
 // ---------------------------------------------------------------------------
 
+/// Score given to killer moves; good captures always score above it.
+const KILLER_SCORE: i32 = 9_000;
+
+/// Quiet moves always score above this; bad captures always score below it.
+const BAD_CAPTURE_CEILING: i32 = -50_000;
+
 /// Score a move for the main search using staged score bands and continuation history.
+#[allow(clippy::too_many_arguments)]
 fn score_move_staged(
     board: &Board,
     mv: Move,
     killers: &KillerTable,
     history: &HistoryTable,
+    capture_history: &CaptureHistoryTable,
     cont_history: &ContinuationHistory,
     stack: &[StackEntry],
     ply: usize,
@@ -86,17 +100,20 @@ fn score_move_staged(
                 let see_score = see(board, mv);
                 if see_score >= 0 {
                     let attacker = board.piece_on(mv.source()).unwrap_or(PieceKind::Pawn);
-                    10_000 + MVV_LVA[victim.index()][attacker.index()]
+                    let capture_hist = capture_history.score(attacker, victim, mv.dest().index());
+                    10_000 + MVV_LVA[victim.index()][attacker.index()] + capture_hist / CAPTURE_HISTORY_DIVISOR
                 } else {
                     -50_000 + see_score
                 }
             } else if killers.is_killer(ply, mv) {
-                9_000
+                KILLER_SCORE
             } else {
                 let piece = board.piece_on(mv.source()).unwrap_or(PieceKind::Pawn);
                 let hist = history.score(piece, mv.dest().index());
                 let cont = cont_hist_score(cont_history, stack, ply, piece, mv.dest().index());
-                hist + cont / 2
+                // Clamped into the quiet band so the staged picker's order
+                // matches these scores
+                (hist + cont / 2).clamp(BAD_CAPTURE_CEILING + 1, KILLER_SCORE - 1)
             }
         }
     }
@@ -132,52 +149,87 @@ pub fn score_move(board: &Board, mv: Move) -> i32 {
 // MovePicker
 // ---------------------------------------------------------------------------
 
+/// Heuristic tables the staged picker reads when it scores a batch of moves.
+///
+/// Built afresh for every [`MovePicker::pick_next_staged`] call, since the
+/// search updates the tables between picks.
+#[derive(Clone, Copy)]
+pub struct OrderingTables<'a> {
+    pub killers: &'a KillerTable,
+    pub history: &'a HistoryTable,
+    pub capture_history: &'a CaptureHistoryTable,
+    pub cont_history: &'a ContinuationHistory,
+    pub stack: &'a [StackEntry],
+    pub ply: usize,
+}
+
+/// Stages of the main-search picker, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    /// Yield the TT move without generating anything.
+    TtMove,
+    /// Generate and score captures, en passant, and promotions.
+    GenerateCaptures,
+    /// Yield queen promotions and captures with non-negative SEE.
+    GoodCaptures,
+    /// Yield the killer moves that are legal here.
+    Killers,
+    /// Generate and score quiet moves.
+    GenerateQuiets,
+    /// Yield quiet moves and under-promotions by history.
+    Quiets,
+    /// Yield captures with negative SEE.
+    BadCaptures,
+    /// Yield a pre-generated list by score (quiescence search).
+    List,
+    /// Nothing left to yield.
+    Done,
+}
+
 /// Incremental move picker using selection sort.
 ///
 /// Yields moves in descending score order. Score bands ensure TT move,
 /// good captures, killers, quiets, and bad captures are searched in the
 /// correct sequence. For quiescence search, only captures and promotions
 /// (score >= 1) are yielded.
+///
+/// The main-search picker ([`MovePicker::new`]) works in stages and only
+/// generates each category of moves once the previous one is exhausted, so
+/// a cutoff on the TT move or a good capture skips generating the quiets.
 pub struct MovePicker {
     moves: [Move; 256],
     scores: [i32; 256],
     len: usize,
     cursor: usize,
     min_score: i32,
+    stage: Stage,
+    tt_move: Move,
+    killers: [Move; 2],
+    killer_cursor: usize,
+    /// Killers actually yielded, so the quiet stage can skip them.
+    yielded_killers: [Move; 2],
 }
 
 impl MovePicker {
     /// Create a staged picker that yields all legal moves ordered by priority.
     ///
-    /// Scoring uses staged bands:
-    /// TT move (100,000) > queen promotions (30,000) > good captures (10,007+) >
-    /// killers (9,000) > quiets (history-based) > bad captures (-50,000+).
-    pub fn new(
-        moves: &MoveList,
-        board: &Board,
-        tt_move: Move,
-        killers: &KillerTable,
-        history: &HistoryTable,
-        cont_history: &ContinuationHistory,
-        stack: &[StackEntry],
-        ply: usize,
-    ) -> Self {
-        let mut picker = Self {
+    /// `tt_move` must be legal in `board` or null. Moves come out in stages:
+    /// TT move, then queen promotions and good captures (10,007+), then
+    /// killers, then quiets and under-promotions by history, then bad
+    /// captures (-50,000+).
+    pub fn new(tt_move: Move, killers: &KillerTable, ply: usize) -> Self {
+        Self {
             moves: [Move::NULL; 256],
             scores: [0; 256],
-            len: moves.len(),
+            len: 0,
             cursor: 0,
             min_score: i32::MIN,
-        };
-        for i in 0..moves.len() {
-            picker.moves[i] = moves[i];
-            picker.scores[i] = if moves[i] == tt_move {
-                100_000
-            } else {
-                score_move_staged(board, moves[i], killers, history, cont_history, stack, ply)
-            };
+            stage: Stage::TtMove,
+            tt_move,
+            killers: killers.get(ply),
+            killer_cursor: 0,
+            yielded_killers: [Move::NULL; 2],
         }
-        picker
     }
 
     /// Create a picker for quiescence search (captures and promotions only).
@@ -188,6 +240,11 @@ impl MovePicker {
             len: moves.len(),
             cursor: 0,
             min_score: 1,
+            stage: Stage::List,
+            tt_move: Move::NULL,
+            killers: [Move::NULL; 2],
+            killer_cursor: 0,
+            yielded_killers: [Move::NULL; 2],
         };
         for i in 0..moves.len() {
             picker.moves[i] = moves[i];
@@ -196,6 +253,97 @@ impl MovePicker {
         picker
     }
 
+    /// Create a picker for quiescence search in check: every legal move is an
+    /// evasion candidate, captures first.
+    pub fn new_evasions(moves: &MoveList, board: &Board) -> Self {
+        let mut picker = Self::new_qsearch(moves, board);
+        picker.min_score = i32::MIN;
+        picker
+    }
+
+    /// Yield the next move of a staged picker, generating the next category
+    /// of moves when the current one runs out.
+    pub fn pick_next_staged(&mut self, board: &Board, tables: OrderingTables<'_>) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::TtMove => {
+                    self.stage = Stage::GenerateCaptures;
+                    if !self.tt_move.is_null() {
+                        debug_assert!(board.is_legal(self.tt_move));
+                        return Some(self.tt_move);
+                    }
+                }
+                Stage::GenerateCaptures => {
+                    self.append_scored(board, &generate_captures(board), &tables);
+                    self.min_score = KILLER_SCORE + 1;
+                    self.stage = Stage::GoodCaptures;
+                }
+                Stage::GoodCaptures => {
+                    if let Some(mv) = self.pick_next() {
+                        return Some(mv);
+                    }
+                    self.stage = Stage::Killers;
+                }
+                Stage::Killers => {
+                    while self.killer_cursor < self.killers.len() {
+                        let killer = self.killers[self.killer_cursor];
+                        self.killer_cursor += 1;
+                        // A killer from a sibling node may be a capture or illegal here
+                        let quiet = killer.kind() == MoveKind::Normal && !board.is_occupied(killer.dest());
+                        if !killer.is_null() && killer != self.tt_move && quiet && board.is_legal(killer) {
+                            self.yielded_killers[self.killer_cursor - 1] = killer;
+                            return Some(killer);
+                        }
+                    }
+                    self.stage = Stage::GenerateQuiets;
+                }
+                Stage::GenerateQuiets => {
+                    self.append_scored(board, &generate_quiets(board), &tables);
+                    self.min_score = BAD_CAPTURE_CEILING;
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => {
+                    if let Some(mv) = self.pick_next() {
+                        return Some(mv);
+                    }
+                    self.min_score = i32::MIN;
+                    self.stage = Stage::BadCaptures;
+                }
+                Stage::BadCaptures => {
+                    let mv = self.pick_next();
+                    if mv.is_none() {
+                        self.stage = Stage::Done;
+                    }
+                    return mv;
+                }
+                Stage::List => return self.pick_next(),
+                Stage::Done => return None,
+            }
+        }
+    }
+
+    /// Append the moves of `list` that have not been yielded yet, scored with
+    /// the eager scoring bands.
+    fn append_scored(&mut self, board: &Board, list: &MoveList, tables: &OrderingTables<'_>) {
+        for &mv in list.as_slice() {
+            if mv == self.tt_move || self.yielded_killers.contains(&mv) {
+                continue;
+            }
+            self.moves[self.len] = mv;
+            self.scores[self.len] = score_move_staged(
+                board,
+                mv,
+                tables.killers,
+                tables.history,
+                tables.capture_history,
+                tables.cont_history,
+                tables.stack,
+                tables.ply,
+            );
+            self.len += 1;
+        }
+    }
+
     /// Yield the next highest-scored move via selection sort.
     ///
     /// Returns `None` when all remaining moves score below `min_score`
@@ -254,9 +402,7 @@ impl ProbCutPicker {
         for i in 0..moves.len() {
             let mv = moves[i];
 
-            let is_tactical = board.piece_on(mv.dest()).is_some()
-                || mv.kind() == MoveKind::EnPassant
-                || mv.kind() == MoveKind::Promotion;
+            let is_tactical = mv.is_capture(board) || mv.kind() == MoveKind::Promotion;
 
             if !is_tactical {
                 continue;
@@ -314,8 +460,10 @@ impl ProbCutPicker {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cesso_core::{generate_legal_moves, Board};
-    use crate::search::heuristics::{ContinuationHistory, HistoryTable, KillerTable, StackEntry};
+    use cesso_core::{generate_legal_moves, Board, Square};
+    use crate::search::heuristics::{
+        CaptureHistoryTable, ContinuationHistory, HistoryTable, KillerTable, StackEntry, HISTORY_MAX,
+    };
 
     #[test]
     fn pawn_takes_queen_scores_higher_than_queen_takes_pawn() {
@@ -366,72 +514,126 @@ mod tests {
         assert!(picker.pick_next().is_none());
     }
 
+    /// Everything the staged picker yields for `board`, in order.
+    fn staged_order(
+        board: &Board,
+        tt_move: Move,
+        killers: &KillerTable,
+        history: &HistoryTable,
+        capture_history: &CaptureHistoryTable,
+    ) -> Vec<Move> {
+        let cont_history = ContinuationHistory::new();
+        let stack = [StackEntry::EMPTY; 128];
+        let tables = OrderingTables { killers, history, capture_history, cont_history: &cont_history, stack: &stack, ply: 0 };
+        let mut picker = MovePicker::new(tt_move, killers, 0);
+        std::iter::from_fn(|| picker.pick_next_staged(board, tables)).collect()
+    }
+
     #[test]
     fn picker_yields_all_moves_in_starting_position() {
         let board = Board::starting_position();
-        let moves = generate_legal_moves(&board);
-        let cont_hist = ContinuationHistory::new();
-        let stack = [StackEntry::EMPTY; 128];
-        let mut picker = MovePicker::new(
-            &moves,
-            &board,
-            Move::NULL,
-            &KillerTable::new(),
-            &HistoryTable::new(),
-            &cont_hist,
-            &stack,
-            0,
-        );
-        let mut count = 0;
-        while picker.pick_next().is_some() {
-            count += 1;
-        }
-        assert_eq!(count, 20);
+        let order = staged_order(&board, Move::NULL, &KillerTable::new(), &HistoryTable::new(), &CaptureHistoryTable::new());
+        assert_eq!(order.len(), 20);
     }
 
     #[test]
     fn picker_yields_captures_before_quiet() {
         // White queen on d4, black pawn on e5 — QxP is a good capture
         let board: Board = "4k3/8/8/4p3/3Q4/8/8/4K3 w - - 0 1".parse().unwrap();
-        let moves = generate_legal_moves(&board);
-        let cont_hist = ContinuationHistory::new();
-        let stack = [StackEntry::EMPTY; 128];
-        let mut picker = MovePicker::new(
-            &moves,
-            &board,
-            Move::NULL,
-            &KillerTable::new(),
-            &HistoryTable::new(),
-            &cont_hist,
-            &stack,
-            0,
-        );
-        let first = picker.pick_next().unwrap();
+        let order = staged_order(&board, Move::NULL, &KillerTable::new(), &HistoryTable::new(), &CaptureHistoryTable::new());
         assert!(
-            board.piece_on(first.dest()).is_some(),
+            board.piece_on(order[0].dest()).is_some(),
             "first move from picker should be a capture"
         );
     }
 
+    #[test]
+    fn capture_history_orders_equal_captures() {
+        // Qxc5 and Qxe5 are equal by MVV-LVA; history decides between them
+        let board: Board = "4k3/8/8/2p1p3/3Q4/8/8/4K3 w - - 0 1".parse().unwrap();
+        for (favoured, other) in [(Square::C5, Square::E5), (Square::E5, Square::C5)] {
+            let mut capture_history = CaptureHistoryTable::new();
+            capture_history.update(PieceKind::Queen, PieceKind::Pawn, favoured.index(), 400);
+            capture_history.update(PieceKind::Queen, PieceKind::Pawn, other.index(), -400);
+            let order = staged_order(&board, Move::NULL, &KillerTable::new(), &HistoryTable::new(), &capture_history);
+            assert_eq!(order[0].dest(), favoured);
+            assert_eq!(order[1].dest(), other);
+        }
+    }
+
     #[test]
     fn tt_move_yielded_first() {
         let board = Board::starting_position();
         let moves = generate_legal_moves(&board);
         let tt_move = moves[10];
-        let cont_hist = ContinuationHistory::new();
+        let order = staged_order(&board, tt_move, &KillerTable::new(), &HistoryTable::new(), &CaptureHistoryTable::new());
+        assert_eq!(order[0], tt_move, "TT move should be yielded first");
+        assert_eq!(order.iter().filter(|&&mv| mv == tt_move).count(), 1);
+    }
+
+    /// The staged picker yields the same moves as scoring the full legal move
+    /// list up front, in the same score-band order, even with history at the
+    /// ends of its range.
+    #[test]
+    fn staged_order_matches_eager_scoring() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1",
+            "r1bqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3",
+        ];
+        let cont_history = ContinuationHistory::new();
         let stack = [StackEntry::EMPTY; 128];
-        let mut picker = MovePicker::new(
-            &moves,
-            &board,
-            tt_move,
-            &KillerTable::new(),
-            &HistoryTable::new(),
-            &cont_hist,
-            &stack,
-            0,
-        );
-        let first = picker.pick_next().unwrap();
-        assert_eq!(first, tt_move, "TT move should be yielded first");
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            let legal = generate_legal_moves(&board);
+            let quiets: Vec<Move> = generate_quiets(&board).as_slice().to_vec();
+
+            // History across its whole range, so some quiets would outscore
+            // killers and good captures unclamped; captures likewise
+            let mut history = HistoryTable::new();
+            for (i, mv) in quiets.iter().enumerate() {
+                let piece = board.piece_on(mv.source()).unwrap();
+                let bonus = (i as i32 * 7919) % (2 * HISTORY_MAX) - HISTORY_MAX;
+                for _ in 0..8 {
+                    history.update(piece, mv.dest().index(), bonus);
+                }
+            }
+            let top = quiets.iter().map(|mv| history.score(board.piece_on(mv.source()).unwrap(), mv.dest().index()));
+            assert!(top.max().unwrap() > 10_000 + MVV_LVA[0][4], "{fen}");
+            let mut capture_history = CaptureHistoryTable::new();
+            for (i, mv) in legal.as_slice().iter().enumerate() {
+                if let (Some(attacker), Some(victim)) = (board.piece_on(mv.source()), board.piece_on(mv.dest())) {
+                    let bonus = if i % 2 == 0 { HISTORY_MAX } else { -HISTORY_MAX };
+                    for _ in 0..8 {
+                        capture_history.update(attacker, victim, mv.dest().index(), bonus);
+                    }
+                }
+            }
+            // A TT move, a legal quiet killer and an illegal one
+            let mut killers = KillerTable::new();
+            killers.store(0, Move::new(Square::A1, Square::H8));
+            killers.store(0, quiets[quiets.len() / 2]);
+            let tt_move = legal[legal.len() / 3];
+
+            let order = staged_order(&board, tt_move, &killers, &history, &capture_history);
+            let mut sorted = order.clone();
+            sorted.sort_by_key(|mv| mv.raw());
+            let mut expected = legal.as_slice().to_vec();
+            expected.sort_by_key(|mv| mv.raw());
+            assert_eq!(sorted, expected, "{fen}");
+
+            let eager = |mv: Move| {
+                if mv == tt_move {
+                    100_000
+                } else {
+                    score_move_staged(&board, mv, &killers, &history, &capture_history, &cont_history, &stack, 0)
+                }
+            };
+            let scores: Vec<i32> = order.iter().map(|&mv| eager(mv)).collect();
+            assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{fen}: {scores:?}");
+        }
     }
 
     #[test]
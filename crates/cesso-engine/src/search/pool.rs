@@ -1,31 +1,120 @@
 //! Lazy SMP thread pool for parallel search.
 
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-use cesso_core::{Board, Color, Move, generate_legal_moves};
+use cesso_core::{Board, Color, Move, MoveList, generate_legal_moves};
 
+use crate::eval::EvalMode;
 use crate::search::control::SearchControl;
-use crate::search::heuristics::{ContinuationHistory, CorrectionHistory, HistoryTable, KillerTable, StackEntry};
-use crate::search::negamax::{INF, MAX_PLY, PvTable, SearchContext, aspiration_search};
+use crate::search::heuristics::{SearchHeuristics, StackEntry};
+use crate::search::negamax::{INF, MATE_THRESHOLD, MAX_PLY, PvTable, SearchContext, aspiration_search};
 use crate::search::tt::TranspositionTable;
 use crate::search::SearchResult;
+use crate::search::SearcherBuilder;
 use crate::search::StabilityTracker;
+use crate::search::allowed_root_moves;
+use crate::tb::Tablebases;
 
 /// Lazy SMP thread pool — owns the shared transposition table.
 pub struct ThreadPool {
     tt: TranspositionTable,
+    tt_mb: usize,
     num_threads: usize,
+    contempt: i32,
+    eval_mode: EvalMode,
+    tb: Option<Arc<Tablebases>>,
+    /// Main-thread heuristics left over from the previous search.
+    heuristics: Mutex<Option<Box<SearchHeuristics>>>,
+    /// Whether the next search may reuse `heuristics`.
+    keep_heuristics: bool,
 }
 
 impl ThreadPool {
     /// Create a new thread pool with `hash_mb` MB transposition table.
     pub fn new(hash_mb: usize) -> Self {
+        SearcherBuilder::default().tt_size_mb(hash_mb).build_pool()
+    }
+
+    /// Build a pool from a [`SearcherBuilder`]'s configuration.
+    pub(crate) fn from_builder(builder: &SearcherBuilder) -> Self {
         Self {
-            tt: TranspositionTable::new(hash_mb),
-            num_threads: 1,
+            tt: TranspositionTable::new(builder.tt_mb),
+            tt_mb: builder.tt_mb,
+            num_threads: builder.threads,
+            contempt: builder.contempt,
+            eval_mode: builder.eval_mode,
+            tb: None,
+            heuristics: Mutex::new(None),
+            keep_heuristics: false,
+        }
+    }
+
+    /// Apply a [`SearcherBuilder`]'s configuration to this pool.
+    ///
+    /// The transposition table is only reallocated when its size changes;
+    /// returns whether it was (a fresh table is already empty).
+    pub fn reconfigure(&mut self, builder: &SearcherBuilder) -> bool {
+        self.num_threads = builder.threads;
+        self.contempt = builder.contempt;
+        self.eval_mode = builder.eval_mode;
+        if builder.tt_mb == self.tt_mb {
+            return false;
+        }
+        self.resize_tt(builder.tt_mb);
+        true
+    }
+
+    /// Let the next search reuse the previous search's heuristics.
+    ///
+    /// Call when the new root is the previous root with `plies` moves played;
+    /// killers are shifted accordingly and history tables are halved.
+    pub fn continue_game(&mut self, plies: usize) {
+        self.keep_heuristics = true;
+        if let Ok(Some(heuristics)) = self.heuristics.get_mut().map(Option::as_mut) {
+            heuristics.advance(plies);
+            heuristics.age_down();
+        }
+    }
+
+    /// Discard carried-over heuristics so the next search starts from empty tables.
+    pub fn reset_heuristics(&mut self) {
+        self.keep_heuristics = false;
+        if let Ok(heuristics) = self.heuristics.get_mut() {
+            *heuristics = None;
+        }
+    }
+
+    /// Heuristics for the main search thread: carried over if allowed, else fresh.
+    fn take_heuristics(&self) -> Box<SearchHeuristics> {
+        let carried = if self.keep_heuristics {
+            self.heuristics.lock().ok().and_then(|mut h| h.take())
+        } else {
+            None
+        };
+        carried.unwrap_or_default()
+    }
+
+    /// Keep the main thread's heuristics for a possible continuation search.
+    fn store_heuristics(&self, ctx: SearchContext<'_>) {
+        let heuristics = Box::new(SearchHeuristics {
+            killers: ctx.killers,
+            history_table: ctx.history_table,
+            capture_history: ctx.capture_history,
+            cont_history: ctx.cont_history,
+            correction_history: ctx.correction_history,
+        });
+        if let Ok(mut slot) = self.heuristics.lock() {
+            *slot = Some(heuristics);
         }
     }
 
+    /// Set (or clear) the Syzygy tablebases probed during search.
+    pub fn set_tablebases(&mut self, tb: Option<Arc<Tablebases>>) {
+        self.tb = tb;
+    }
+
     /// Set the number of search threads.
     pub fn set_num_threads(&mut self, n: usize) {
         self.num_threads = n.max(1);
@@ -34,6 +123,7 @@ impl ThreadPool {
     /// Resize the transposition table.
     pub fn resize_tt(&mut self, mb: usize) {
         self.tt = TranspositionTable::new(mb);
+        self.tt_mb = mb;
     }
 
     /// Clear the transposition table.
@@ -41,23 +131,44 @@ impl ThreadPool {
         self.tt.clear();
     }
 
+    /// Transposition table size in megabytes, as last configured.
+    pub fn tt_size_mb(&self) -> usize {
+        self.tt_mb
+    }
+
+    /// Number of search threads.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Contempt factor in centipawns applied to draw scores.
+    pub fn contempt(&self) -> i32 {
+        self.contempt
+    }
+
+    /// Evaluation used by the search.
+    pub fn eval_mode(&self) -> EvalMode {
+        self.eval_mode
+    }
+
     /// Run a Lazy SMP search.
     ///
     /// Thread 0 runs full iterative deepening with the `on_iter` callback for UCI output.
     /// Threads 1..N-1 run silent iterative deepening, contributing only to the shared TT.
     /// Uses `std::thread::scope` — no `Arc` needed on the TT.
+    #[allow(clippy::too_many_arguments)]
     pub fn search<F>(
         &self,
         board: &Board,
         max_depth: u8,
         control: &SearchControl,
         history: &[u64],
-        contempt: i32,
+        searchmoves: Option<Vec<Move>>,
         engine_color: Color,
         mut on_iter: F,
     ) -> SearchResult
     where
-        F: FnMut(u8, i32, u64, &[Move]),
+        F: FnMut(u8, i32, u64, u64, u32, &[Move]),
     {
         self.tt.new_generation();
 
@@ -78,12 +189,34 @@ impl ThreadPool {
                 score: 0,
                 nodes: 0,
                 depth: 0,
+                elapsed: control.elapsed(),
+            };
+        }
+
+        // A dead position is drawn whatever is played: answer at once, unless
+        // only `stop` may end the search.
+        if board.is_insufficient_material() && !legal_moves.is_empty() && !control.is_open_ended() {
+            let best_move = searchmoves
+                .as_ref()
+                .and_then(|moves| moves.first().copied())
+                .unwrap_or(legal_moves[0]);
+            return SearchResult {
+                best_move,
+                ponder_move: None,
+                pv: vec![best_move],
+                score: if board.side_to_move() == engine_color { -self.contempt } else { self.contempt },
+                nodes: 0,
+                depth: 0,
+                elapsed: control.elapsed(),
             };
         }
 
+        let max_depth = control.depth_limit(max_depth);
+        let root_moves = allowed_root_moves(self.tb.as_deref(), board, history, searchmoves);
+
         if self.num_threads <= 1 {
             // Single-thread fast path — no scope overhead
-            return self.search_single(board, max_depth, control, history, contempt, engine_color, on_iter);
+            return self.search_single(board, max_depth, control, history, engine_color, &root_moves, on_iter);
         }
 
         // Shared node counters — one AtomicU64 per thread to avoid contention
@@ -98,19 +231,23 @@ impl ThreadPool {
             score: -INF,
             nodes: 0,
             depth: 0,
+            elapsed: Duration::ZERO,
         };
 
         std::thread::scope(|s| {
             // Spawn N-1 helper threads (thread_id 1..num_threads)
+            let (contempt, eval_mode) = (self.contempt, self.eval_mode);
             for (thread_id, node_counter) in node_counters.iter().enumerate().skip(1) {
                 let tt = &self.tt;
+                let tb = self.tb.as_deref();
+                let root_moves = &root_moves;
                 s.spawn(move || {
-                    run_helper(thread_id, tt, board, max_depth, control, node_counter, history, contempt, engine_color);
+                    run_helper(thread_id, tt, board, max_depth, control, node_counter, history, contempt, eval_mode, engine_color, tb, root_moves);
                 });
             }
 
             // Thread 0 runs on this thread (the coordinator)
-            result = self.search_main(board, max_depth, control, history, contempt, engine_color, &mut on_iter, &node_counters[0]);
+            result = self.search_main(board, max_depth, control, history, engine_color, &root_moves, &mut on_iter, &node_counters[0]);
         });
         // scope auto-joins all helpers here
 
@@ -125,32 +262,42 @@ impl ThreadPool {
     }
 
     /// Single-thread fast path — no scope overhead.
+    #[allow(clippy::too_many_arguments)]
     fn search_single<F>(
         &self,
         board: &Board,
         max_depth: u8,
         control: &SearchControl,
         history: &[u64],
-        contempt: i32,
         engine_color: Color,
+        root_moves: &MoveList,
         mut on_iter: F,
     ) -> SearchResult
     where
-        F: FnMut(u8, i32, u64, &[Move]),
+        F: FnMut(u8, i32, u64, u64, u32, &[Move]),
     {
+        let heuristics = *self.take_heuristics();
         let mut ctx = SearchContext {
             nodes: 0,
+            qnodes: 0,
             tt: &self.tt,
             pv: PvTable::new(),
             control,
-            killers: KillerTable::new(),
-            history_table: HistoryTable::new(),
-            cont_history: Box::new(ContinuationHistory::new()),
-            correction_history: Box::new(CorrectionHistory::new()),
+            killers: heuristics.killers,
+            history_table: heuristics.history_table,
+            capture_history: heuristics.capture_history,
+            cont_history: heuristics.cont_history,
+            correction_history: heuristics.correction_history,
             stack: [StackEntry::EMPTY; MAX_PLY],
             history: history.to_vec(),
-            contempt,
+            contempt: self.contempt,
             engine_color,
+            eval_mode: self.eval_mode,
+            tb: self.tb.as_deref(),
+            root_moves: root_moves.clone(),
+            qsearch_check_extensions: 0,
+            refutations: Vec::new(),
+            main_thread: true,
         };
 
         let mut completed_move = Move::NULL;
@@ -181,12 +328,22 @@ impl ThreadPool {
             completed_depth = depth;
             completed_pv = pv.iter().copied().filter(|m| !m.is_null()).collect();
 
-            on_iter(depth, score, ctx.nodes, &completed_pv);
+            on_iter(depth, score, ctx.nodes, ctx.qnodes, self.tt.utilization_permille(), &completed_pv);
+            ctx.report_refutations();
+            control.depth_completed(depth);
+
+            // `go mate N`: stop as soon as a forced mate is confirmed
+            if control.mate_moves().is_some() && score > MATE_THRESHOLD {
+                break;
+            }
 
             let scale = stability.update(completed_move, score, depth);
             control.update_soft_scale(scale);
         }
 
+        let nodes = ctx.nodes;
+        self.store_heuristics(ctx);
+
         let ponder_move = if completed_pv.len() > 1 {
             Some(completed_pv[1])
         } else {
@@ -202,39 +359,50 @@ impl ThreadPool {
                 completed_pv
             },
             score: completed_score,
-            nodes: ctx.nodes,
+            nodes,
             depth: completed_depth,
+            elapsed: control.elapsed(),
         }
     }
 
     /// Thread 0 search — same as single, but stores final node count to an atomic counter.
+    #[allow(clippy::too_many_arguments)]
     fn search_main<F>(
         &self,
         board: &Board,
         max_depth: u8,
         control: &SearchControl,
         history: &[u64],
-        contempt: i32,
         engine_color: Color,
+        root_moves: &MoveList,
         on_iter: &mut F,
         node_counter: &AtomicU64,
     ) -> SearchResult
     where
-        F: FnMut(u8, i32, u64, &[Move]),
+        F: FnMut(u8, i32, u64, u64, u32, &[Move]),
     {
+        let heuristics = *self.take_heuristics();
         let mut ctx = SearchContext {
             nodes: 0,
+            qnodes: 0,
             tt: &self.tt,
             pv: PvTable::new(),
             control,
-            killers: KillerTable::new(),
-            history_table: HistoryTable::new(),
-            cont_history: Box::new(ContinuationHistory::new()),
-            correction_history: Box::new(CorrectionHistory::new()),
+            killers: heuristics.killers,
+            history_table: heuristics.history_table,
+            capture_history: heuristics.capture_history,
+            cont_history: heuristics.cont_history,
+            correction_history: heuristics.correction_history,
             stack: [StackEntry::EMPTY; MAX_PLY],
             history: history.to_vec(),
-            contempt,
+            contempt: self.contempt,
             engine_color,
+            eval_mode: self.eval_mode,
+            tb: self.tb.as_deref(),
+            root_moves: root_moves.clone(),
+            qsearch_check_extensions: 0,
+            refutations: Vec::new(),
+            main_thread: true,
         };
 
         let mut completed_move = Move::NULL;
@@ -265,7 +433,14 @@ impl ThreadPool {
             completed_depth = depth;
             completed_pv = pv.iter().copied().filter(|m| !m.is_null()).collect();
 
-            on_iter(depth, score, ctx.nodes, &completed_pv);
+            on_iter(depth, score, ctx.nodes, ctx.qnodes, self.tt.utilization_permille(), &completed_pv);
+            ctx.report_refutations();
+            control.depth_completed(depth);
+
+            // `go mate N`: stop as soon as a forced mate is confirmed
+            if control.mate_moves().is_some() && score > MATE_THRESHOLD {
+                break;
+            }
 
             let scale = stability.update(completed_move, score, depth);
             control.update_soft_scale(scale);
@@ -273,6 +448,9 @@ impl ThreadPool {
 
         node_counter.store(ctx.nodes, Ordering::Relaxed);
 
+        let nodes = ctx.nodes;
+        self.store_heuristics(ctx);
+
         let ponder_move = if completed_pv.len() > 1 {
             Some(completed_pv[1])
         } else {
@@ -288,13 +466,15 @@ impl ThreadPool {
                 completed_pv
             },
             score: completed_score,
-            nodes: ctx.nodes,
+            nodes,
             depth: completed_depth,
+            elapsed: control.elapsed(),
         }
     }
 }
 
 /// Silent helper thread for Lazy SMP — writes to TT only, no UCI output.
+#[allow(clippy::too_many_arguments)]
 fn run_helper(
     thread_id: usize,
     tt: &TranspositionTable,
@@ -304,21 +484,33 @@ fn run_helper(
     node_counter: &AtomicU64,
     history: &[u64],
     contempt: i32,
+    eval_mode: EvalMode,
     engine_color: Color,
+    tb: Option<&Tablebases>,
+    root_moves: &MoveList,
 ) {
+    let heuristics = SearchHeuristics::new();
     let mut ctx = SearchContext {
         nodes: 0,
+        qnodes: 0,
         tt,
         pv: PvTable::new(),
         control,
-        killers: KillerTable::new(),
-        history_table: HistoryTable::new(),
-        cont_history: Box::new(ContinuationHistory::new()),
-        correction_history: Box::new(CorrectionHistory::new()),
+        killers: heuristics.killers,
+        history_table: heuristics.history_table,
+        capture_history: heuristics.capture_history,
+        cont_history: heuristics.cont_history,
+        correction_history: heuristics.correction_history,
         stack: [StackEntry::EMPTY; MAX_PLY],
         history: history.to_vec(),
         contempt,
         engine_color,
+        eval_mode,
+        tb,
+        root_moves: root_moves.clone(),
+        qsearch_check_extensions: 0,
+        refutations: Vec::new(),
+        main_thread: false,
     };
 
     // Depth offset: helpers start at different depths to increase search divergence.
@@ -349,6 +541,7 @@ impl std::fmt::Debug for ThreadPool {
         f.debug_struct("ThreadPool")
             .field("tt", &self.tt)
             .field("num_threads", &self.num_threads)
+            .field("tb", &self.tb)
             .finish()
     }
 }
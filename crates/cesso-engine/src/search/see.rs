@@ -4,31 +4,22 @@
 //! assuming both sides use their least valuable attacker at each step.
 
 use cesso_core::{
-    bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks, Bitboard, Board,
-    Color, Move, MoveKind, PieceKind, PromotionPiece, Square,
+    bishop_attacks, rook_attacks, Bitboard, Board, Color, Move, MoveKind, PieceKind, PromotionPiece, Square,
 };
 
-/// Material values for SEE, indexed by `PieceKind::index()`.
-const SEE_VALUE: [i32; 6] = [100, 320, 330, 500, 900, 20_000];
+/// Material values for SEE, indexed by `PieceKind::index()`: the middlegame
+/// material values, with the king made too valuable to ever trade.
+const SEE_VALUE: [i32; 6] = [
+    PieceKind::Pawn.material_value_mg(),
+    PieceKind::Knight.material_value_mg(),
+    PieceKind::Bishop.material_value_mg(),
+    PieceKind::Rook.material_value_mg(),
+    PieceKind::Queen.material_value_mg(),
+    20_000,
+];
 
-/// Compute all pieces that attack a given square with the given occupancy.
-///
-/// Uses all 6 attack functions. Sliding attacks use the provided `occ`
-/// bitboard, which reveals X-ray attackers as pieces are removed.
-fn attackers_of(sq: Square, occ: Bitboard, board: &Board) -> Bitboard {
-    let knights = knight_attacks(sq) & board.pieces(PieceKind::Knight);
-    let kings = king_attacks(sq) & board.pieces(PieceKind::King);
-    let rook_like = rook_attacks(sq, occ)
-        & (board.pieces(PieceKind::Rook) | board.pieces(PieceKind::Queen));
-    let bishop_like = bishop_attacks(sq, occ)
-        & (board.pieces(PieceKind::Bishop) | board.pieces(PieceKind::Queen));
-    let white_pawns =
-        pawn_attacks(Color::Black, sq) & board.pieces(PieceKind::Pawn) & board.side(Color::White);
-    let black_pawns =
-        pawn_attacks(Color::White, sq) & board.pieces(PieceKind::Pawn) & board.side(Color::Black);
-
-    knights | kings | rook_like | bishop_like | white_pawns | black_pawns
-}
+/// Extra material gained when a pawn captures onto the last rank and promotes to a queen.
+const PROMOTION_GAIN: i32 = SEE_VALUE[4] - SEE_VALUE[0];
 
 /// Find the least valuable attacker from the given attacker set for a side.
 ///
@@ -61,7 +52,7 @@ pub fn see(board: &Board, mv: Move) -> i32 {
     let attacker_kind = board.piece_on(src).unwrap_or(PieceKind::Pawn);
 
     // Determine the initial victim value
-    let victim_value = if mv.kind() == MoveKind::EnPassant {
+    let mut victim_value = if mv.kind() == MoveKind::EnPassant {
         SEE_VALUE[PieceKind::Pawn.index()]
     } else if let Some(victim) = board.piece_on(dst) {
         SEE_VALUE[victim.index()]
@@ -78,6 +69,8 @@ pub fn see(board: &Board, mv: Move) -> i32 {
             PromotionPiece::Rook => PieceKind::Rook,
             PromotionPiece::Queen => PieceKind::Queen,
         };
+        // The promoting side also gains the difference between the new piece and the pawn.
+        victim_value += SEE_VALUE[promo_kind.index()] - SEE_VALUE[PieceKind::Pawn.index()];
         SEE_VALUE[promo_kind.index()]
     } else {
         SEE_VALUE[attacker_kind.index()]
@@ -113,8 +106,13 @@ pub fn see(board: &Board, mv: Move) -> i32 {
     // Side making the next recapture (opponent of the initial mover)
     let mut side_to_move = !board.side_to_move();
 
+    // Recapturing pawns on the last rank promote (always to a queen in SEE).
+    let promotes_on_dst = (Bitboard::RANK_1 | Bitboard::RANK_8).contains(dst);
+    let orthogonal_sliders = board.pieces(PieceKind::Rook) | board.pieces(PieceKind::Queen);
+    let diagonal_sliders = board.pieces(PieceKind::Bishop) | board.pieces(PieceKind::Queen);
+
     // Compute all attackers to dst with the initial attacker removed from occ.
-    let mut all_attackers = attackers_of(dst, occ, board);
+    let mut all_attackers = board.attackers_to(dst, occ);
     all_attackers &= occ; // only include pieces still on the board
 
     loop {
@@ -136,22 +134,20 @@ pub fn see(board: &Board, mv: Move) -> i32 {
         gain[depth] = next_victim_value - gain[depth - 1];
 
         // Update: the recapturer now sits on dst and becomes the next victim.
-        next_victim_value = SEE_VALUE[kind.index()];
+        if kind == PieceKind::Pawn && promotes_on_dst {
+            gain[depth] += PROMOTION_GAIN;
+            next_victim_value = SEE_VALUE[PieceKind::Queen.index()];
+        } else {
+            next_victim_value = SEE_VALUE[kind.index()];
+        }
 
         // Remove this attacker from occupancy.
         occ = occ.without(sq);
 
-        // After removing a piece, refresh sliding attackers for X-ray discovery.
-        // Pawns, bishops, and queens can unblock diagonal sliders.
-        // Rooks and queens can unblock orthogonal sliders.
-        if kind == PieceKind::Pawn || kind == PieceKind::Bishop || kind == PieceKind::Queen {
-            all_attackers |= bishop_attacks(dst, occ)
-                & (board.pieces(PieceKind::Bishop) | board.pieces(PieceKind::Queen));
-        }
-        if kind == PieceKind::Rook || kind == PieceKind::Queen {
-            all_attackers |= rook_attacks(dst, occ)
-                & (board.pieces(PieceKind::Rook) | board.pieces(PieceKind::Queen));
-        }
+        // Removing the capturer may reveal X-ray sliders behind it on either
+        // line through dst, so recompute both slider sets from the new occupancy.
+        all_attackers |= rook_attacks(dst, occ) & orthogonal_sliders;
+        all_attackers |= bishop_attacks(dst, occ) & diagonal_sliders;
         all_attackers &= occ;
 
         side_to_move = !side_to_move;
@@ -242,6 +238,61 @@ mod tests {
         assert_eq!(see(&board, mv), 320);
     }
 
+    #[test]
+    fn rook_takes_pawn_shielded_by_queen_xray() {
+        // Doubled white rooks on d1/d2 take a pawn on d5 defended by a black rook
+        // on d7 with the queen behind it on d8. The queen only joins via X-ray.
+        let board: Board = "3q2k1/3r4/8/3p4/8/8/3R4/3RK3 w - - 0 1".parse().unwrap();
+        let mv = find_move(&board, "d2", "d5");
+        // RxP (+100), rxR (-500), RxR (+500), QxR (-500) => white loses the exchange
+        assert!(see(&board, mv) < 0);
+        assert_eq!(see(&board, mv), -400);
+    }
+
+    #[test]
+    fn pawn_takes_rook_with_rook_behind() {
+        // Pawn on e4 takes rook on d5; the black rook on d8 recaptures and
+        // the white rook on d1 wins it back.
+        let board: Board = "3r2k1/8/8/3r4/4P3/8/8/3RK3 w - - 0 1".parse().unwrap();
+        let mv = find_move(&board, "e4", "d5");
+        assert!(see(&board, mv) > 0);
+        assert_eq!(see(&board, mv), 500);
+    }
+
+    #[test]
+    fn bishop_xray_behind_pawn_recaptures() {
+        // Pawn on e4 takes a knight on d5 defended by a pawn on c6; the bishop
+        // on f3 stands behind the e4 pawn and recaptures after it is gone.
+        let board: Board = "6k1/8/2p5/3n4/4P3/5B2/8/4K3 w - - 0 1".parse().unwrap();
+        let mv = find_move(&board, "e4", "d5");
+        // PxN (+320), pxP (-100), BxP (+100) => 320
+        assert_eq!(see(&board, mv), 320);
+    }
+
+    #[test]
+    fn capture_promotion_counts_promotion_gain() {
+        // Undefended rook on a8 captured by a promoting pawn on b7.
+        let board: Board = "r5k1/1P6/8/8/8/8/8/4K3 w - - 0 1".parse().unwrap();
+        let mv = generate_legal_moves(&board)
+            .as_slice()
+            .iter()
+            .copied()
+            .find(|m| {
+                m.dest() == Square::A8 && m.is_promotion() && m.promotion_piece() == PromotionPiece::Queen
+            })
+            .expect("queen promotion capture must be legal");
+        assert_eq!(see(&board, mv), 500 + 900 - 100);
+    }
+
+    #[test]
+    fn pawn_recapture_on_last_rank_promotes() {
+        // Black rook takes a knight on c8; the white pawn on b7 recaptures and promotes.
+        let board: Board = "2N4r/1P5k/8/8/8/8/8/4K3 b - - 0 1".parse().unwrap();
+        let mv = find_move(&board, "h8", "c8");
+        // rxN (+320), PxR=Q (-500 - 800) => -980
+        assert_eq!(see(&board, mv), -980);
+    }
+
     #[test]
     fn see_ge_threshold() {
         let board: Board = "4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1".parse().unwrap();
@@ -26,6 +26,14 @@
 //! mid-write and we return `None` rather than using garbage data.
 //!
 //! All atomic accesses use `Relaxed` ordering — the standard Stockfish technique.
+//!
+//! ## Two tiers
+//!
+//! The table is split into a primary tier with a depth/generation
+//! replacement policy and a secondary tier of the same size that every store
+//! overwrites. Probes try the primary slot first and fall back to the
+//! secondary one, so a deep entry no longer hides the recent shallow entries
+//! that collide with it.
 
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 
@@ -65,6 +73,10 @@ impl Bound {
     }
 }
 
+/// Depth subtracted from secondary-tier hits: the always-replace slot keeps
+/// no record of whether a deeper search of the position was overwritten.
+const SECONDARY_DEPTH_PENALTY: u8 = 1;
+
 /// Scores above this threshold indicate a forced mate.
 const MATE_THRESHOLD: i32 = 28_000;
 
@@ -214,8 +226,11 @@ impl AtomicEntry {
 ///
 /// All method receivers are `&self` — the table is safe to share across threads.
 pub struct TranspositionTable {
+    /// Primary tier: depth- and generation-preferred replacement.
     entries: Box<[AtomicEntry]>,
-    /// Index mask — `num_entries - 1` (power-of-two allocation).
+    /// Secondary tier: always replaced, same size and indexing as `entries`.
+    entries_secondary: Box<[AtomicEntry]>,
+    /// Index mask — `num_entries - 1` (power-of-two allocation per tier).
     mask: u64,
     /// Current search generation (wraps every 64 searches).
     generation: AtomicU8,
@@ -224,19 +239,19 @@ pub struct TranspositionTable {
 impl TranspositionTable {
     /// Create a new transposition table with the given size in megabytes.
     ///
-    /// The actual number of entries is rounded down to the nearest power of two.
+    /// The two tiers split the budget equally; the number of entries per
+    /// tier is a power of two.
     pub fn new(mb: usize) -> Self {
         let bytes = mb * 1024 * 1024;
         let entry_size = std::mem::size_of::<AtomicEntry>();
-        let num_entries = (bytes / entry_size).next_power_of_two() >> 1;
+        let num_entries = (bytes / entry_size).next_power_of_two() >> 2;
         let num_entries = num_entries.max(1);
 
-        let entries: Box<[AtomicEntry]> = (0..num_entries)
-            .map(|_| AtomicEntry::new())
-            .collect();
+        let tier = || -> Box<[AtomicEntry]> { (0..num_entries).map(|_| AtomicEntry::new()).collect() };
 
         Self {
-            entries,
+            entries: tier(),
+            entries_secondary: tier(),
             mask: (num_entries - 1) as u64,
             generation: AtomicU8::new(0),
         }
@@ -244,7 +259,7 @@ impl TranspositionTable {
 
     /// Clear all entries and reset the generation counter.
     pub fn clear(&self) {
-        for entry in self.entries.iter() {
+        for entry in self.entries.iter().chain(self.entries_secondary.iter()) {
             entry.word0.store(0, Ordering::Relaxed);
             entry.word1.store(0, Ordering::Relaxed);
         }
@@ -260,17 +275,21 @@ impl TranspositionTable {
 
     /// Probe the table for a position.
     ///
-    /// Returns `Some(TtProbeResult)` if a matching, intact entry is found.
+    /// Returns `Some(TtProbeResult)` if a matching, intact entry is found in
+    /// either tier, trying the primary tier first. A secondary-tier hit
+    /// reports its depth lowered by [`SECONDARY_DEPTH_PENALTY`].
     /// Returns `None` on a miss, key mismatch, or torn-write detection.
     pub fn probe(&self, hash: u64, ply: u8) -> Option<TtProbeResult> {
         let index = (hash & self.mask) as usize;
-        let entry = &self.entries[index];
+        let load = |entry: &AtomicEntry| entry.load(hash).filter(|&(_, _, bound, ..)| bound != Bound::None);
 
-        let (_, is_pv, bound, depth, mv, _w0, w1) = entry.load(hash)?;
-
-        if bound == Bound::None {
-            return None;
-        }
+        let (is_pv, bound, depth, mv, w1) = match load(&self.entries[index]) {
+            Some((_, is_pv, bound, depth, mv, _w0, w1)) => (is_pv, bound, depth, mv, w1),
+            None => {
+                let (_, is_pv, bound, depth, mv, _w0, w1) = load(&self.entries_secondary[index])?;
+                (is_pv, bound, depth.saturating_sub(SECONDARY_DEPTH_PENALTY), mv, w1)
+            }
+        };
 
         let score_raw = ((w1 >> 16) & 0xFFFF) as u16 as i16;
         let eval_raw = (w1 & 0xFFFF) as u16 as i16;
@@ -285,13 +304,34 @@ impl TranspositionTable {
         })
     }
 
+    /// Look up only the best move stored for `hash`.
+    ///
+    /// A cheaper [`probe`](Self::probe) for callers that just want a move to
+    /// try first: it reads `word0` alone and checks the key, skipping the XOR
+    /// integrity check and the score, eval and depth decoding. A torn entry can
+    /// therefore hand back a stray move, so callers must check legality.
+    pub fn probe_tt_move(&self, hash: u64) -> Option<Move> {
+        let index = (hash & self.mask) as usize;
+        let key32 = (hash >> 32) as u32;
+        let matching = |entry: &AtomicEntry| {
+            let w0 = entry.peek_w0();
+            let bound_bits = (w0 >> 24) & 0x03;
+            ((w0 >> 32) as u32 == key32 && bound_bits != 0).then(|| Move::from_raw((w0 & 0xFFFF) as u16))
+        };
+        matching(&self.entries[index])
+            .or_else(|| matching(&self.entries_secondary[index]))
+            .filter(|mv| !mv.is_null())
+    }
+
     /// Store a position in the table.
     ///
-    /// Replacement policy: replace if any of:
+    /// The secondary tier is always overwritten. The primary tier's
+    /// replacement policy: replace if any of:
     /// - The slot is empty (bound is None)
     /// - The stored entry is from a different generation
     /// - The new depth >= stored depth
     /// - The new bound is Exact
+    #[allow(clippy::too_many_arguments)]
     pub fn store(
         &self,
         hash: u64,
@@ -307,6 +347,11 @@ impl TranspositionTable {
         let entry = &self.entries[index];
         let generation = self.generation.load(Ordering::Relaxed);
 
+        let key32 = (hash >> 32) as u32;
+        let w0 = AtomicEntry::pack_word0(key32, generation, is_pv, bound, depth, best_move);
+        let w1 = AtomicEntry::pack_word1(w0, score_to_tt(score, ply), eval as i16);
+        self.entries_secondary[index].store(w0, w1);
+
         // Replacement policy — inspect existing entry without key check
         let existing_w0 = entry.peek_w0();
         let (_, existing_generation, _existing_is_pv, existing_bound, existing_depth, _) =
@@ -317,21 +362,30 @@ impl TranspositionTable {
             || depth >= existing_depth
             || bound == Bound::Exact;
 
-        if !dominated {
-            return;
+        if dominated {
+            entry.store(w0, w1);
         }
+    }
 
-        let key32 = (hash >> 32) as u32;
-        let w0 = AtomicEntry::pack_word0(key32, generation, is_pv, bound, depth, best_move);
-        let w1 = AtomicEntry::pack_word1(w0, score_to_tt(score, ply), eval as i16);
-        entry.store(w0, w1);
+    /// Estimate table occupancy in permille (0-1000), as reported by `hashfull`.
+    ///
+    /// Samples the first `min(500, len)` entries of each tier and counts
+    /// those with a non-empty bound.
+    pub fn utilization_permille(&self) -> u32 {
+        let sample = self.entries.len().min(500);
+        let used = self.entries[..sample]
+            .iter()
+            .chain(&self.entries_secondary[..sample])
+            .filter(|entry| (entry.peek_w0() >> 24) & 0x03 != 0)
+            .count();
+        (used * 1000 / (2 * sample)) as u32
     }
 }
 
 impl std::fmt::Debug for TranspositionTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TranspositionTable")
-            .field("entries", &self.entries.len())
+            .field("entries", &(self.entries.len() + self.entries_secondary.len()))
             .field("generation", &self.generation.load(Ordering::Relaxed))
             .finish()
     }
@@ -363,7 +417,22 @@ mod tests {
         assert_eq!(result.bound, Bound::Exact);
         assert_eq!(result.score, 100);
         assert_eq!(result.eval, 50);
-        assert_eq!(result.is_pv, false);
+        assert!(!result.is_pv);
+    }
+
+    #[test]
+    fn probe_tt_move_matches_probe() {
+        let tt = TranspositionTable::new(1);
+        let hash: u64 = 0xDEAD_BEEF_1234_5678;
+        let mv = Move::new(Square::E2, Square::E4);
+        assert_eq!(tt.probe_tt_move(hash), None);
+
+        tt.store(hash, 5, 100, 50, mv, Bound::LowerBound, 0, false);
+        assert_eq!(tt.probe_tt_move(hash), Some(mv));
+        assert_eq!(tt.probe_tt_move(hash ^ (1 << 40)), None);
+
+        tt.store(hash, 6, 100, 50, Move::NULL, Bound::UpperBound, 0, false);
+        assert_eq!(tt.probe_tt_move(hash), None);
     }
 
     #[test]
@@ -442,6 +511,28 @@ mod tests {
         assert_eq!(result.best_move, mv1); // original entry preserved
     }
 
+    #[test]
+    fn secondary_tier_keeps_entries_the_primary_rejects() {
+        let tt = TranspositionTable::new(1);
+        let deep: u64 = 0x1111_2222_0000_0007;
+        let shallow: u64 = 0x3333_4444_0000_0007;
+        assert_eq!(deep & tt.mask, shallow & tt.mask);
+        let mv1 = Move::new(Square::E2, Square::E4);
+        let mv2 = Move::new(Square::D2, Square::D4);
+
+        tt.store(deep, 8, 100, 50, mv1, Bound::LowerBound, 0, false);
+        tt.store(shallow, 3, 200, 60, mv2, Bound::LowerBound, 0, false);
+
+        // The deep entry keeps the primary slot...
+        let result = tt.probe(deep, 0).unwrap();
+        assert_eq!((result.best_move, result.depth), (mv1, 8));
+        // ...and the shallow one is still found, with less depth confidence
+        let result = tt.probe(shallow, 0).unwrap();
+        assert_eq!(result.best_move, mv2);
+        assert_eq!(result.score, 200);
+        assert_eq!(result.depth, 3 - SECONDARY_DEPTH_PENALTY);
+    }
+
     #[test]
     fn clear_removes_all_entries() {
         let tt = TranspositionTable::new(1);
@@ -466,12 +557,18 @@ mod tests {
 
         // Corrupt the check bits in word1 to simulate a torn write
         let index = (hash & tt.mask) as usize;
-        let entry = &tt.entries[index];
-        let w1 = entry.word1.load(Ordering::Relaxed);
-        // Flip all bits in the check field (upper 32 bits of word1)
-        let corrupted_w1 = w1 ^ 0xFFFF_FFFF_0000_0000;
-        entry.word1.store(corrupted_w1, Ordering::Relaxed);
-
+        let corrupt = |entry: &AtomicEntry| {
+            let w1 = entry.word1.load(Ordering::Relaxed);
+            // Flip all bits in the check field (upper 32 bits of word1)
+            entry.word1.store(w1 ^ 0xFFFF_FFFF_0000_0000, Ordering::Relaxed);
+        };
+
+        // A torn primary slot falls back to the intact secondary copy
+        corrupt(&tt.entries[index]);
+        let result = tt.probe(hash, 0).expect("secondary copy should survive");
+        assert_eq!(result.depth, 5 - SECONDARY_DEPTH_PENALTY);
+
+        corrupt(&tt.entries_secondary[index]);
         assert!(
             tt.probe(hash, 0).is_none(),
             "probe should return None after XOR corruption"
@@ -530,4 +627,21 @@ mod tests {
         let result = tt.probe(hash, 0).expect("should find stored entry");
         assert!(!result.is_pv, "is_pv should be false");
     }
+
+    #[test]
+    fn utilization_empty_table_is_zero() {
+        let tt = TranspositionTable::new(1);
+        assert_eq!(tt.utilization_permille(), 0);
+    }
+
+    #[test]
+    fn utilization_full_table_is_near_thousand() {
+        let tt = TranspositionTable::new(1);
+        let mv = Move::new(Square::E2, Square::E4);
+        for index in 0..=tt.mask {
+            let hash = (index << 32) | index;
+            tt.store(hash, 1, 0, 0, mv, Bound::LowerBound, 0, false);
+        }
+        assert!(tt.utilization_permille() >= 990);
+    }
 }
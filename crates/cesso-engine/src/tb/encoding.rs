@@ -0,0 +1,173 @@
+//! Square-mapping and binomial tables used to turn a position into a Syzygy index.
+//!
+//! ## Glossary
+//!
+//! | Term | Meaning |
+//! |---|---|
+//! | `off_a1h8` | `rank - file`: negative below the a1-h8 diagonal, zero on it |
+//! | lead group | The first encoded group: kings (+ one piece) or the leading pawns |
+//! | `map_kk` | Index of the 462 legal, non-mirrored two-king placements |
+//! | `map_pawns` | Pawn squares a2-h7 ordered so the leading pawn has the highest value |
+
+use std::sync::OnceLock;
+
+use cesso_core::{Square, king_attacks};
+
+/// Maximum number of pieces in one encoding group (`KPPPPPK` has 5 lead pawns).
+pub(super) const MAX_GROUP: usize = 6;
+
+/// Precomputed encoding tables, built once on first use.
+pub(super) struct EncodingTables {
+    /// Maps a square below the a1-h8 diagonal to `0..28`.
+    pub map_b1h1h7: [u64; 64],
+    /// Maps a square in the a1-d1-d4 triangle to `0..10` (diagonal squares last).
+    pub map_a1d1d4: [u64; 64],
+    /// Maps `(map_a1d1d4[first king], second king)` to `0..462`.
+    pub map_kk: [[u64; 64]; 10],
+    /// `binomial[k][n]` = number of ways to choose `k` of `n` squares.
+    pub binomial: [[u64; 64]; MAX_GROUP + 1],
+    /// Maps pawn squares to `0..48`; higher values are closer to the edge and lower rank.
+    pub map_pawns: [u64; 64],
+    /// Index offset for the leading pawn square, per leading-pawn count.
+    pub lead_pawn_idx: [[u64; 64]; MAX_GROUP],
+    /// Number of leading-pawn placements per leading-pawn count and file (a-d).
+    pub lead_pawns_size: [[u64; 4]; MAX_GROUP],
+}
+
+static TABLES: OnceLock<EncodingTables> = OnceLock::new();
+
+/// Return the shared encoding tables.
+pub(super) fn tables() -> &'static EncodingTables {
+    TABLES.get_or_init(EncodingTables::build)
+}
+
+/// Signed distance of `sq` from the a1-h8 diagonal (`rank - file`).
+#[inline]
+pub(super) fn off_a1h8(sq: u8) -> i32 {
+    (sq >> 3) as i32 - (sq & 7) as i32
+}
+
+/// Mirror a square across the d/e file boundary.
+#[inline]
+pub(super) fn flip_file(sq: u8) -> u8 {
+    sq ^ 7
+}
+
+/// Mirror a square across the 4th/5th rank boundary.
+#[inline]
+pub(super) fn flip_rank(sq: u8) -> u8 {
+    sq ^ 56
+}
+
+/// Mirror a square across the a1-h8 diagonal.
+#[inline]
+pub(super) fn flip_diagonal(sq: u8) -> u8 {
+    ((sq >> 3) | (sq << 3)) & 63
+}
+
+impl EncodingTables {
+    fn build() -> Self {
+        let mut t = EncodingTables {
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; MAX_GROUP + 1],
+            map_pawns: [0; 64],
+            lead_pawn_idx: [[0; 64]; MAX_GROUP],
+            lead_pawns_size: [[0; 4]; MAX_GROUP],
+        };
+
+        let mut code = 0;
+        for sq in 0..64u8 {
+            if off_a1h8(sq) < 0 {
+                t.map_b1h1h7[sq as usize] = code;
+                code += 1;
+            }
+        }
+
+        // Triangle squares first, then the four diagonal squares a1, b2, c3, d4.
+        let mut diagonal = Vec::with_capacity(4);
+        code = 0;
+        for sq in 0..=Square::D4.index() as u8 {
+            if sq & 7 > 3 {
+                continue;
+            }
+            if off_a1h8(sq) < 0 {
+                t.map_a1d1d4[sq as usize] = code;
+                code += 1;
+            } else if off_a1h8(sq) == 0 {
+                diagonal.push(sq);
+            }
+        }
+        for sq in diagonal {
+            t.map_a1d1d4[sq as usize] = code;
+            code += 1;
+        }
+
+        // Two-king placements: with the first king on the diagonal the second
+        // may not be above it; placements with both on the diagonal come last.
+        let mut both_on_diagonal = Vec::new();
+        code = 0;
+        for idx in 0..10u64 {
+            for s1 in 0..=Square::D4.index() as u8 {
+                if t.map_a1d1d4[s1 as usize] != idx || (idx == 0 && s1 != Square::B1.index() as u8) {
+                    continue;
+                }
+                let Some(s1_sq) = Square::from_index(s1) else { continue };
+                let forbidden = king_attacks(s1_sq).with(s1_sq);
+                for s2 in 0..64u8 {
+                    let Some(s2_sq) = Square::from_index(s2) else { continue };
+                    if forbidden.contains(s2_sq) {
+                        continue;
+                    }
+                    if off_a1h8(s1) == 0 && off_a1h8(s2) > 0 {
+                        continue;
+                    }
+                    if off_a1h8(s1) == 0 && off_a1h8(s2) == 0 {
+                        both_on_diagonal.push((idx, s2));
+                    } else {
+                        t.map_kk[idx as usize][s2 as usize] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, s2) in both_on_diagonal {
+            t.map_kk[idx as usize][s2 as usize] = code;
+            code += 1;
+        }
+
+        // Pascal's rule.
+        t.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..=MAX_GROUP.min(n) {
+                let left = if k > 0 { t.binomial[k - 1][n - 1] } else { 0 };
+                let right = if k < n { t.binomial[k][n - 1] } else { 0 };
+                t.binomial[k][n] = left + right;
+            }
+        }
+
+        // Pawn squares: 47 available when the leading pawn is on a2, two fewer
+        // for each rank it advances (the mirrored square is excluded too).
+        let mut available = 47u64;
+        for lead_count in 1..MAX_GROUP {
+            for file in 0..4u8 {
+                let mut idx = 0;
+                for rank in 1..7u8 {
+                    let sq = rank * 8 + file;
+                    if lead_count == 1 {
+                        t.map_pawns[sq as usize] = available;
+                        available -= 1;
+                        t.map_pawns[flip_file(sq) as usize] = available;
+                        available = available.saturating_sub(1);
+                    }
+                    t.lead_pawn_idx[lead_count][sq as usize] = idx;
+                    idx += t.binomial[lead_count - 1][t.map_pawns[sq as usize] as usize];
+                }
+                t.lead_pawns_size[lead_count][file as usize] = idx;
+            }
+        }
+
+        t
+    }
+}
@@ -0,0 +1,373 @@
+//! Test fixture: a KRvK table pair written in the Syzygy file format.
+//!
+//! The values come from a small retrograde solver that knows only king and
+//! rook geometry, so it shares nothing with the engine's move generator. The
+//! writer uses the simplest encoding the format allows (fixed-length codes,
+//! leaf symbols only), which exercises the same header parsing, sparse index,
+//! block walk and Huffman decoding as the published tables.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::tb::encoding::{flip_diagonal, flip_file, flip_rank, off_a1h8, tables};
+use crate::tb::table::TableKind;
+
+const WHITE: usize = 0;
+const BLACK: usize = 1;
+
+/// Piece codes of the table's encoding order: white rook, white king, black king.
+const PIECES: [u8; 3] = [4, 6, 14];
+
+/// Number of indices in a pawnless table with three unique leading pieces.
+const TB_SIZE: usize = 31_332;
+
+/// Block size (log2, bytes) and sparse-index span (log2, indices).
+const BLOCK_BITS: u8 = 6;
+const SPAN_BITS: u8 = 10;
+
+/// First [`state`] with black to move.
+const BLACK_BASE: usize = 64 * 64 * 64;
+
+/// A KRvK position as `(side to move, white king, white rook, black king)`.
+pub(super) type Krvk = (usize, u8, u8, u8);
+
+/// Retrograde solution of KRvK.
+pub(super) struct Solution {
+    /// Plies to mate with best play, indexed by [`state`]; `None` for draws and
+    /// illegal placements.
+    plies: Vec<Option<u8>>,
+}
+
+impl Solution {
+    /// Plies to mate from `pos`, or `None` if it is drawn.
+    pub(super) fn plies(&self, pos: Krvk) -> Option<u8> {
+        self.plies[state(pos)]
+    }
+}
+
+fn state((stm, wk, wr, bk): Krvk) -> usize {
+    ((stm * 64 + wk as usize) * 64 + wr as usize) * 64 + bk as usize
+}
+
+fn adjacent(a: u8, b: u8) -> bool {
+    let (fa, ra, fb, rb) = ((a & 7) as i8, (a >> 3) as i8, (b & 7) as i8, (b >> 3) as i8);
+    a != b && (fa - fb).abs() <= 1 && (ra - rb).abs() <= 1
+}
+
+fn king_steps(sq: u8) -> impl Iterator<Item = u8> {
+    let (f, r) = ((sq & 7) as i8, (sq >> 3) as i8);
+    (-1..=1i8)
+        .flat_map(move |dr| (-1..=1i8).map(move |df| (f + df, r + dr)))
+        .filter(move |&(tf, tr)| (0..8).contains(&tf) && (0..8).contains(&tr) && (tf, tr) != (f, r))
+        .map(|(tf, tr)| (tr * 8 + tf) as u8)
+}
+
+/// Squares the rook reaches from `wr`, stopping before `wk` and `bk`.
+fn rook_steps(wr: u8, wk: u8, bk: u8) -> Vec<u8> {
+    let mut steps = Vec::new();
+    for (df, dr) in [(1i8, 0i8), (-1, 0), (0, 1), (0, -1)] {
+        let (mut f, mut r) = ((wr & 7) as i8 + df, (wr >> 3) as i8 + dr);
+        while (0..8).contains(&f) && (0..8).contains(&r) {
+            let sq = (r * 8 + f) as u8;
+            if sq == wk || sq == bk {
+                break;
+            }
+            steps.push(sq);
+            f += df;
+            r += dr;
+        }
+    }
+    steps
+}
+
+/// Whether the rook on `wr` attacks `target`, with only the white king blocking.
+fn rook_attacks(wr: u8, wk: u8, target: u8) -> bool {
+    let (f, r) = ((wr & 7) as i8, (wr >> 3) as i8);
+    let (tf, tr) = ((target & 7) as i8, (target >> 3) as i8);
+    if wr == target || (f != tf && r != tr) {
+        return false;
+    }
+    let (kf, kr) = ((wk & 7) as i8, (wk >> 3) as i8);
+    let between = |a: i8, b: i8, x: i8| a.min(b) < x && x < a.max(b);
+    let blocked = if f == tf { kf == f && between(r, tr, kr) } else { kr == r && between(f, tf, kf) };
+    !blocked
+}
+
+/// Whether `pos` is a legal position.
+pub(super) fn is_legal((stm, wk, wr, bk): Krvk) -> bool {
+    wk != wr && wk != bk && wr != bk && !adjacent(wk, bk) && (stm == BLACK || !rook_attacks(wr, wk, bk))
+}
+
+/// Legal black king moves in `pos`, a rook capture included.
+fn black_moves((_, wk, wr, bk): Krvk) -> impl Iterator<Item = u8> {
+    king_steps(bk).filter(move |&t| !adjacent(t, wk) && (t == wr || !rook_attacks(wr, wk, t)))
+}
+
+/// Solve KRvK by retrograde analysis outwards from the mates.
+///
+/// Each black position counts its moves not yet known to lose; it is lost
+/// once the count reaches zero, which a rook capture prevents.
+pub(super) fn solve() -> Solution {
+    let mut plies = vec![None; 2 * BLACK_BASE];
+    let mut remaining = vec![0u8; BLACK_BASE];
+    let mut queue = std::collections::VecDeque::new();
+    for wk in 0..64 {
+        for wr in 0..64 {
+            for bk in 0..64 {
+                let pos = (BLACK, wk, wr, bk);
+                if !is_legal(pos) {
+                    continue;
+                }
+                remaining[state(pos) - BLACK_BASE] = black_moves(pos).count() as u8;
+                if remaining[state(pos) - BLACK_BASE] == 0 && rook_attacks(wr, wk, bk) {
+                    plies[state(pos)] = Some(0);
+                    queue.push_back(pos);
+                }
+            }
+        }
+    }
+
+    while let Some(pos @ (stm, wk, wr, bk)) = queue.pop_front() {
+        let Some(n) = plies[state(pos)] else { continue };
+        if stm == BLACK {
+            // White wins in n + 1 from every position that moves here
+            let king = king_steps(wk).filter(|&t| t != wr && t != bk && !adjacent(t, bk)).map(|t| (WHITE, t, wr, bk));
+            let rook = rook_steps(wr, wk, bk).into_iter().map(|t| (WHITE, wk, t, bk));
+            for prev in king.chain(rook) {
+                if is_legal(prev) && plies[state(prev)].is_none() {
+                    plies[state(prev)] = Some(n + 1);
+                    queue.push_back(prev);
+                }
+            }
+        } else {
+            for t in king_steps(bk).filter(|&t| t != wr && t != wk && !adjacent(t, wk)) {
+                let prev = (BLACK, wk, wr, t);
+                let left = &mut remaining[state(prev) - BLACK_BASE];
+                *left -= 1;
+                if *left == 0 {
+                    plies[state(prev)] = Some(n + 1);
+                    queue.push_back(prev);
+                }
+            }
+        }
+    }
+    Solution { plies }
+}
+
+/// Table index of `squares`, given in [`PIECES`] order.
+///
+/// Follows the reference encoding for pawnless tables with a unique piece.
+fn index(mut s: [u8; 3]) -> usize {
+    let t = tables();
+    if s[0] & 7 > 3 {
+        s = s.map(flip_file);
+    }
+    if s[0] >> 3 > 3 {
+        s = s.map(flip_rank);
+    }
+    // Below the diagonal: flip from the first piece off it, if that one is above
+    if let Some(i) = (0..3).find(|&i| off_a1h8(s[i]) != 0)
+        && off_a1h8(s[i]) > 0
+    {
+        for sq in &mut s[i..] {
+            *sq = flip_diagonal(*sq);
+        }
+    }
+    let adjust1 = (s[1] > s[0]) as u64;
+    let adjust2 = (s[2] > s[0]) as u64 + (s[2] > s[1]) as u64;
+    let rank = |sq: u8| (sq >> 3) as u64;
+    let idx = if off_a1h8(s[0]) != 0 {
+        (t.map_a1d1d4[s[0] as usize] * 63 + (s[1] as u64 - adjust1)) * 62 + s[2] as u64 - adjust2
+    } else if off_a1h8(s[1]) != 0 {
+        (6 * 63 + rank(s[0]) * 28 + t.map_b1h1h7[s[1] as usize]) * 62 + s[2] as u64 - adjust2
+    } else if off_a1h8(s[2]) != 0 {
+        6 * 63 * 62 + 4 * 28 * 62 + rank(s[0]) * 7 * 28 + (rank(s[1]) - adjust1) * 28 + t.map_b1h1h7[s[2] as usize]
+    } else {
+        6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + rank(s[0]) * 7 * 6 + (rank(s[1]) - adjust1) * 6 + (rank(s[2]) - adjust2)
+    };
+    idx as usize
+}
+
+/// Fill a table of `TB_SIZE` values for side `stm`; `value` is `None` for
+/// positions the table may store anything for.
+fn fill(stm: usize, fallback: u8, value: impl Fn(Krvk) -> Option<u8>) -> Vec<u8> {
+    let mut table = vec![None; TB_SIZE];
+    for wk in 0..64 {
+        for wr in 0..64 {
+            for bk in 0..64 {
+                let pos = (stm, wk, wr, bk);
+                if !is_legal(pos) {
+                    continue;
+                }
+                let Some(v) = value(pos) else { continue };
+                let slot = &mut table[index([wr, wk, bk])];
+                assert!(slot.is_none_or(|old| old == v), "symmetric positions disagree at {pos:?}");
+                *slot = Some(v);
+            }
+        }
+    }
+    table.into_iter().map(|v| v.unwrap_or(fallback)).collect()
+}
+
+/// One encoded sub-table: its `PairsData` header, sparse index, block
+/// lengths and data blocks.
+struct Encoded {
+    header: Vec<u8>,
+    sparse: Vec<u8>,
+    lengths: Vec<u8>,
+    blocks: Vec<u8>,
+}
+
+/// Encode `values` with one fixed-length code per distinct value.
+fn encode(values: &[u8], flags: u8) -> Encoded {
+    let mut syms: Vec<u8> = values.to_vec();
+    syms.sort_unstable();
+    syms.dedup();
+    let bits = (usize::BITS - (syms.len() - 1).leading_zeros()).max(1) as usize;
+
+    // Leave the decoder's 64-bit lookahead inside the block.
+    let block_size = 1usize << BLOCK_BITS;
+    let per_block = (block_size * 8 - 64) / bits;
+    let chunks: Vec<&[u8]> = values.chunks(per_block).collect();
+
+    let mut header = vec![flags, BLOCK_BITS, SPAN_BITS, 0];
+    header.extend((chunks.len() as u32).to_le_bytes());
+    header.extend([bits as u8, bits as u8]);
+    header.extend(0u16.to_le_bytes());
+    header.extend((syms.len() as u16).to_le_bytes());
+    for &v in &syms {
+        header.extend([v, 0xF0, 0xFF]);
+    }
+    header.resize(header.len() + (syms.len() & 1), 0);
+
+    let mut lengths = Vec::new();
+    let mut blocks = Vec::new();
+    for chunk in &chunks {
+        lengths.extend((chunk.len() as u16 - 1).to_le_bytes());
+        let mut block = vec![0u8; block_size];
+        for (i, v) in chunk.iter().enumerate() {
+            let code = syms.binary_search(v).unwrap_or(0);
+            for b in 0..bits {
+                if code >> (bits - 1 - b) & 1 != 0 {
+                    let bit = i * bits + b;
+                    block[bit / 8] |= 0x80 >> (bit % 8);
+                }
+            }
+        }
+        blocks.extend(block);
+    }
+
+    let span = 1usize << SPAN_BITS;
+    let mut sparse = Vec::new();
+    for k in 0..values.len().div_ceil(span) {
+        let anchor = k * span + span / 2;
+        let block = (anchor / per_block).min(chunks.len() - 1);
+        sparse.extend((block as u32).to_le_bytes());
+        sparse.extend(((anchor - block * per_block) as u16).to_le_bytes());
+    }
+
+    Encoded { header, sparse, lengths, blocks }
+}
+
+/// Lay out a table file: magic, piece order, then every sub-table's header,
+/// sparse index, block lengths and 64-byte aligned data, section by section.
+fn write(path: &Path, kind: TableKind, subtables: &[Encoded]) {
+    let (magic, split) = match kind {
+        TableKind::Wdl => ([0x71, 0xE8, 0x23, 0x5D], true),
+        TableKind::Dtz => ([0xD7, 0x66, 0x0C, 0xA5], false),
+    };
+    let mut bytes = magic.to_vec();
+    bytes.push(split as u8);
+    bytes.push(0x00);
+    bytes.extend(PIECES.map(|p| p | p << 4));
+    bytes.resize(bytes.len() + (bytes.len() & 1), 0);
+    for sub in subtables {
+        bytes.extend(&sub.header);
+    }
+    if kind == TableKind::Dtz {
+        // No value map, then realign
+        bytes.resize(bytes.len() + (bytes.len() & 1), 0);
+    }
+    for sub in subtables {
+        bytes.extend(&sub.sparse);
+    }
+    for sub in subtables {
+        bytes.extend(&sub.lengths);
+    }
+    for sub in subtables {
+        bytes.resize(bytes.len().div_ceil(64) * 64, 0);
+        bytes.extend(&sub.blocks);
+    }
+    if let Err(e) = std::fs::write(path, bytes) {
+        panic!("cannot write {}: {e}", path.display());
+    }
+}
+
+/// Directory holding `KRvK.rtbw` and `KRvK.rtbz`, written once per test run,
+/// and the solution they encode.
+pub(super) fn krvk() -> &'static (PathBuf, Solution) {
+    static FIXTURE: OnceLock<(PathBuf, Solution)> = OnceLock::new();
+    FIXTURE.get_or_init(|| {
+        let solution = solve();
+        let dir = std::env::temp_dir().join(format!("cesso-tb-fixture-{}", std::process::id()));
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            panic!("cannot create {}: {e}", dir.display());
+        }
+
+        // WDL values are stored as `wdl + 2`, one sub-table per side to move
+        let wdl = |stm| {
+            fill(stm, 2, |pos| {
+                Some(match (stm, solution.plies(pos)) {
+                    (WHITE, Some(_)) => 4,
+                    (BLACK, Some(_)) => 0,
+                    _ => 2,
+                })
+            })
+        };
+        write(
+            &dir.join("KRvK.rtbw"),
+            TableKind::Wdl,
+            &[encode(&wdl(WHITE), 0), encode(&wdl(BLACK), 0)],
+        );
+
+        // DTZ stores white to move only, in moves: value = (plies - 1) / 2
+        let dtz = fill(WHITE, 0, |pos| solution.plies(pos).map(|p| (p - 1) / 2));
+        write(&dir.join("KRvK.rtbz"), TableKind::Dtz, &[encode(&dtz, 0)]);
+
+        (dir, solution)
+    })
+}
+
+/// FEN of `pos`, with the colors swapped if `mirrored` (black holds the rook).
+pub(super) fn fen((stm, wk, wr, bk): Krvk, mirrored: bool) -> String {
+    let mut board = [['.'; 8]; 8];
+    let (rook, king, other, flip) = if mirrored { ('r', 'k', 'K', 56) } else { ('R', 'K', 'k', 0) };
+    for (sq, c) in [(wk, king), (wr, rook), (bk, other)] {
+        let sq = sq ^ flip;
+        board[7 - (sq >> 3) as usize][(sq & 7) as usize] = c;
+    }
+    let placement: Vec<String> = board
+        .iter()
+        .map(|rank| {
+            let mut row = String::new();
+            let mut empty = 0;
+            for &c in rank {
+                if c == '.' {
+                    empty += 1;
+                } else {
+                    if empty > 0 {
+                        row.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    row.push(c);
+                }
+            }
+            if empty > 0 {
+                row.push_str(&empty.to_string());
+            }
+            row
+        })
+        .collect();
+    let side = if (stm == WHITE) != mirrored { 'w' } else { 'b' };
+    format!("{} {side} - - 0 1", placement.join("/"))
+}
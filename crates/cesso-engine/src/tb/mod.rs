@@ -0,0 +1,763 @@
+//! Syzygy endgame tablebase probing (WDL and DTZ).
+//!
+//! Table files are discovered by [`Tablebases::load`] and memory-mapped
+//! lazily, the first time a position with matching material is probed. The
+//! decoding follows the reference Syzygy format: WDL tables answer
+//! "win/draw/loss" for the side to move, DTZ tables give the distance to the
+//! next zeroing move (capture or pawn move) and are used to rank root moves.
+//! See the crate README for how the pieces fit together.
+
+mod encoding;
+#[cfg(test)]
+mod fixture;
+mod table;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use cesso_core::{Board, Color, Move, PieceKind, generate_legal_moves};
+use tracing::debug;
+
+use encoding::{flip_diagonal, flip_file, flip_rank, off_a1h8, tables};
+use table::{LoadedTable, Material, TB_PIECES, TableFile, TableKind, material_key_from_counts};
+
+/// Largest piece count (kings included) this prober will look up.
+pub const MAX_TB_PIECES: usize = 6;
+
+/// Separator between directories in a `SyzygyPath` value.
+#[cfg(windows)]
+const PATH_SEPARATOR: char = ';';
+#[cfg(not(windows))]
+const PATH_SEPARATOR: char = ':';
+
+/// Win/draw/loss outcome for the side to move, taking the fifty-move rule into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    /// Loss.
+    Loss = -2,
+    /// Loss that the fifty-move rule turns into a draw.
+    BlessedLoss = -1,
+    /// Draw.
+    Draw = 0,
+    /// Win that the fifty-move rule turns into a draw.
+    CursedWin = 1,
+    /// Win.
+    Win = 2,
+}
+
+impl Wdl {
+    fn from_value(v: i32) -> Wdl {
+        match v {
+            ..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    fn negate(self) -> Wdl {
+        Wdl::from_value(-(self as i32))
+    }
+
+    /// DTZ of the move that leads into a zeroing position with this outcome.
+    fn dtz_before_zeroing(self) -> i32 {
+        match self {
+            Wdl::Win => 1,
+            Wdl::CursedWin => 101,
+            Wdl::BlessedLoss => -101,
+            Wdl::Loss => -1,
+            Wdl::Draw => 0,
+        }
+    }
+}
+
+/// Internal probe outcome flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeState {
+    Ok,
+    Fail,
+    /// The best move is zeroing; the stored DTZ is not meaningful.
+    ZeroingBestMove,
+    /// The DTZ table stores the other side to move.
+    ChangeStm,
+}
+
+/// A set of Syzygy table files discovered on disk.
+pub struct Tablebases {
+    wdl: HashMap<u64, TableFile>,
+    dtz: HashMap<u64, TableFile>,
+    files: usize,
+    max_pieces: usize,
+    hits: AtomicU64,
+}
+
+impl Tablebases {
+    /// Scan every directory in `paths` (separated by `:`, or `;` on Windows) for tables.
+    ///
+    /// Missing directories and unrecognised files are ignored. Files are only
+    /// indexed here; each is mapped on its first probe.
+    pub fn load(paths: &str) -> Tablebases {
+        let mut tb = Tablebases {
+            wdl: HashMap::new(),
+            dtz: HashMap::new(),
+            files: 0,
+            max_pieces: 0,
+            hits: AtomicU64::new(0),
+        };
+
+        for dir in paths.split(PATH_SEPARATOR).map(str::trim).filter(|d| !d.is_empty()) {
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    debug!(dir, error = %e, "skipping tablebase directory");
+                    continue;
+                }
+            };
+            for entry in entries.flatten() {
+                tb.add_file(&entry.path());
+            }
+        }
+
+        debug!(files = tb.file_count(), max_pieces = tb.max_pieces, "tablebases indexed");
+        tb
+    }
+
+    fn add_file(&mut self, path: &Path) {
+        let kind = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext == TableKind::Wdl.extension() => TableKind::Wdl,
+            Some(ext) if ext == TableKind::Dtz.extension() => TableKind::Dtz,
+            _ => return,
+        };
+        let Some(material) = path.file_stem().and_then(|s| s.to_str()).and_then(Material::parse) else {
+            return;
+        };
+        let pieces = material.piece_count();
+        if pieces > MAX_TB_PIECES {
+            return;
+        }
+
+        let map = match kind {
+            TableKind::Wdl => &mut self.wdl,
+            TableKind::Dtz => &mut self.dtz,
+        };
+        // Register under both keys so either color can hold the stronger side.
+        let file = TableFile::new(path, kind, &material);
+        if map.contains_key(&file.key) {
+            return;
+        }
+        let (key, key2) = (file.key, file.key2);
+        map.insert(key, file);
+        if key2 != key {
+            map.insert(key2, TableFile::new(path, kind, &material));
+        }
+        self.files += 1;
+        self.max_pieces = self.max_pieces.max(pieces);
+    }
+
+    /// Number of distinct table files found (WDL and DTZ).
+    pub fn file_count(&self) -> usize {
+        self.files
+    }
+
+    /// Largest piece count covered by the loaded tables (0 if none).
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Number of successful probes since the last [`reset_hits`](Self::reset_hits).
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Reset the probe hit counter (called at the start of each search).
+    pub fn reset_hits(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+    }
+
+    /// Whether `board` is eligible for a probe: few enough pieces and no castling rights.
+    pub fn can_probe(&self, board: &Board) -> bool {
+        let pieces = board.occupied().count() as usize;
+        pieces <= self.max_pieces && board.castling().is_empty()
+    }
+
+    /// Probe the WDL outcome for the side to move.
+    ///
+    /// Returns `None` if the position is not covered or a table is missing.
+    pub fn probe_wdl(&self, board: &Board) -> Option<Wdl> {
+        if !self.can_probe(board) {
+            return None;
+        }
+        let mut state = ProbeState::Ok;
+        let wdl = self.search(board, false, &mut state);
+        if state == ProbeState::Fail {
+            return None;
+        }
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(wdl)
+    }
+
+    /// Probe the distance to zeroing (in plies, signed by the outcome) for the side to move.
+    ///
+    /// Returns `None` if the position is not covered or a table is missing.
+    pub fn probe_dtz(&self, board: &Board) -> Option<i32> {
+        if !self.can_probe(board) {
+            return None;
+        }
+        let mut state = ProbeState::Ok;
+        let dtz = self.dtz(board, &mut state);
+        if state == ProbeState::Fail {
+            return None;
+        }
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(dtz)
+    }
+
+    /// Restrict the root moves to those that preserve the best tablebase outcome.
+    ///
+    /// `history` holds the hashes of earlier game positions (for repetition
+    /// awareness). Moves are ranked by DTZ when DTZ tables are available and by
+    /// WDL otherwise. Returns `None` if the root position cannot be probed.
+    pub fn filter_root_moves(&self, board: &Board, history: &[u64]) -> Option<Vec<Move>> {
+        if !self.can_probe(board) {
+            return None;
+        }
+        let moves = generate_legal_moves(board);
+        if moves.is_empty() {
+            return None;
+        }
+
+        let ranks = self
+            .rank_root_moves_dtz(board, moves.as_slice(), history)
+            .or_else(|| self.rank_root_moves_wdl(board, moves.as_slice()))?;
+        let best = ranks.iter().map(|&(_, r)| r).max()?;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(ranks.into_iter().filter(|&(_, r)| r == best).map(|(m, _)| m).collect())
+    }
+
+    fn rank_root_moves_dtz(&self, board: &Board, moves: &[Move], history: &[u64]) -> Option<Vec<(Move, i32)>> {
+        const MAX_DTZ: i32 = 1 << 18;
+
+        let cnt50 = board.halfmove_clock() as i32;
+        let rep = has_repeated(board, history);
+        let mut ranked = Vec::with_capacity(moves.len());
+
+        for &mv in moves {
+            let child = board.make_move(mv);
+            let mut state = ProbeState::Ok;
+            let mut dtz = if child.halfmove_clock() == 0 {
+                self.search(&child, false, &mut state).negate().dtz_before_zeroing()
+            } else {
+                let d = -self.dtz(&child, &mut state);
+                d + d.signum()
+            };
+            if state == ProbeState::Fail {
+                return None;
+            }
+            if dtz == 2 && child.is_checkmate() {
+                dtz = 1;
+            }
+
+            // Shorter wins rank higher; wins the fifty-move rule would spoil rank below them.
+            let rank = if dtz > 0 {
+                if dtz + cnt50 <= 99 && !rep { MAX_DTZ - dtz } else { MAX_DTZ / 2 - (dtz + cnt50) }
+            } else if dtz < 0 {
+                if -dtz * 2 + cnt50 < 100 { -MAX_DTZ - dtz } else { -MAX_DTZ / 2 + (-dtz + cnt50) }
+            } else {
+                0
+            };
+            ranked.push((mv, rank));
+        }
+        Some(ranked)
+    }
+
+    fn rank_root_moves_wdl(&self, board: &Board, moves: &[Move]) -> Option<Vec<(Move, i32)>> {
+        let mut ranked = Vec::with_capacity(moves.len());
+        for &mv in moves {
+            let child = board.make_move(mv);
+            let mut state = ProbeState::Ok;
+            let wdl = self.search(&child, false, &mut state).negate();
+            if state == ProbeState::Fail {
+                return None;
+            }
+            ranked.push((mv, wdl as i32));
+        }
+        Some(ranked)
+    }
+
+    // ── Probing internals ──────────────────────────────────────────────────
+
+    /// One-ply search over captures (and pawn moves if `check_zeroing`), then a table lookup.
+    ///
+    /// Tables do not store positions with en passant rights, so the captures
+    /// have to be resolved explicitly.
+    fn search(&self, board: &Board, check_zeroing: bool, state: &mut ProbeState) -> Wdl {
+        let moves = generate_legal_moves(board);
+        let mut best = Wdl::Loss;
+        let mut move_count = 0;
+
+        for &mv in moves.as_slice() {
+            let zeroing = mv.is_capture(board)
+                || (check_zeroing && board.piece_on(mv.source()) == Some(PieceKind::Pawn));
+            if !zeroing {
+                continue;
+            }
+            move_count += 1;
+            let child = board.make_move(mv);
+            let value = self.search(&child, false, state).negate();
+            if *state == ProbeState::Fail {
+                return Wdl::Draw;
+            }
+            if value > best {
+                best = value;
+                if value == Wdl::Win {
+                    *state = ProbeState::ZeroingBestMove;
+                    return value;
+                }
+            }
+        }
+
+        let no_more_moves = move_count > 0 && move_count == moves.len();
+        let value = if no_more_moves {
+            best
+        } else {
+            let v = self.probe_wdl_table(board, state);
+            if *state == ProbeState::Fail {
+                return Wdl::Draw;
+            }
+            v
+        };
+
+        if best >= value {
+            *state = if best > Wdl::Draw || no_more_moves {
+                ProbeState::ZeroingBestMove
+            } else {
+                ProbeState::Ok
+            };
+            return best;
+        }
+        *state = ProbeState::Ok;
+        value
+    }
+
+    fn dtz(&self, board: &Board, state: &mut ProbeState) -> i32 {
+        *state = ProbeState::Ok;
+        let wdl = self.search(board, true, state);
+        if *state == ProbeState::Fail || wdl == Wdl::Draw {
+            return 0;
+        }
+        if *state == ProbeState::ZeroingBestMove {
+            return wdl.dtz_before_zeroing();
+        }
+
+        let dtz = self.probe_dtz_table(board, wdl, state);
+        if *state == ProbeState::Fail {
+            return 0;
+        }
+        if *state != ProbeState::ChangeStm {
+            let cursed = matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss);
+            return (dtz + if cursed { 100 } else { 0 }) * (wdl as i32).signum();
+        }
+
+        // The table stores the other side to move: take the best DTZ over one ply.
+        let mut min_dtz = i32::MAX;
+        for &mv in generate_legal_moves(board).as_slice() {
+            let zeroing = mv.is_capture(board) || board.piece_on(mv.source()) == Some(PieceKind::Pawn);
+            let child = board.make_move(mv);
+            let mut d = if zeroing {
+                -self.search(&child, false, state).dtz_before_zeroing()
+            } else {
+                -self.dtz(&child, state)
+            };
+            if *state == ProbeState::Fail {
+                return 0;
+            }
+            if d == 1 && child.is_checkmate() {
+                min_dtz = 1;
+            }
+            if !zeroing {
+                d += d.signum();
+            }
+            if d < min_dtz && d.signum() == (wdl as i32).signum() {
+                min_dtz = d;
+            }
+        }
+        if min_dtz == i32::MAX { -1 } else { min_dtz }
+    }
+
+    fn probe_wdl_table(&self, board: &Board, state: &mut ProbeState) -> Wdl {
+        if board.occupied().count() == 2 {
+            return Wdl::Draw;
+        }
+        let key = position_material_key(board);
+        let Some(value) = self
+            .wdl
+            .get(&key)
+            .and_then(|file| Some((file, file.get()?)))
+            .and_then(|(file, table)| probe_table(board, file, table, TableKind::Wdl, state))
+        else {
+            *state = ProbeState::Fail;
+            return Wdl::Draw;
+        };
+        Wdl::from_value(value - 2)
+    }
+
+    fn probe_dtz_table(&self, board: &Board, wdl: Wdl, state: &mut ProbeState) -> i32 {
+        let key = position_material_key(board);
+        let Some((file, table)) = self.dtz.get(&key).and_then(|file| Some((file, file.get()?))) else {
+            *state = ProbeState::Fail;
+            return 0;
+        };
+        let Some((value, tb_file)) = probe_table_raw(board, file, table, TableKind::Dtz, state) else {
+            if *state != ProbeState::ChangeStm {
+                *state = ProbeState::Fail;
+            }
+            return 0;
+        };
+        map_dtz_score(table, tb_file, value, wdl).unwrap_or_else(|| {
+            *state = ProbeState::Fail;
+            0
+        })
+    }
+}
+
+impl std::fmt::Debug for Tablebases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tablebases")
+            .field("files", &self.file_count())
+            .field("max_pieces", &self.max_pieces)
+            .finish()
+    }
+}
+
+/// Whether the current position already occurred since the last zeroing move.
+fn has_repeated(board: &Board, history: &[u64]) -> bool {
+    let lookback = (board.halfmove_clock() as usize).min(history.len());
+    history[history.len() - lookback..].contains(&board.hash())
+}
+
+/// Material key of a position with white on the left-hand side.
+fn position_material_key(board: &Board) -> u64 {
+    let mut counts = [[0u8; 6]; 2];
+    for color in Color::ALL {
+        for kind in PieceKind::ALL {
+            counts[color.index()][kind.index()] = (board.pieces(kind) & board.side(color)).count() as u8;
+        }
+    }
+    material_key_from_counts(&counts)
+}
+
+/// Syzygy piece code: `1..=6` for white pawn..king, `+8` for black.
+fn piece_code(kind: PieceKind, color: Color) -> u8 {
+    kind.index() as u8 + 1 + if color == Color::Black { 8 } else { 0 }
+}
+
+fn probe_table(board: &Board, file: &TableFile, table: &LoadedTable, kind: TableKind, state: &mut ProbeState) -> Option<i32> {
+    probe_table_raw(board, file, table, kind, state).map(|(value, _)| value)
+}
+
+/// Encode `board` into a table index and decompress the stored value.
+///
+/// Returns the raw value and the leading pawn file (0 for pawnless tables).
+fn probe_table_raw(
+    board: &Board,
+    file: &TableFile,
+    table: &LoadedTable,
+    kind: TableKind,
+    state: &mut ProbeState,
+) -> Option<(i32, usize)> {
+    let t = tables();
+    let mut squares = [0u8; TB_PIECES];
+    let mut pieces = [0u8; TB_PIECES];
+    let mut size = 0;
+    let mut lead_count = 0;
+    let mut tb_file = 0;
+    let mut lead_pawns = 0u64;
+
+    // Tables store the stronger side as white; symmetric tables only store white to move.
+    let black_to_move = board.side_to_move() == Color::Black;
+    let symmetric_black_to_move = file.key == file.key2 && black_to_move;
+    let black_stronger = position_material_key(board) != file.key;
+    let flip = symmetric_black_to_move || black_stronger;
+    let flip_color = if flip { 8 } else { 0 };
+    let flip_squares = if flip { 56 } else { 0 };
+    let stm = (flip ^ black_to_move) as usize;
+
+    let pawns_cmp = |a: &u8, b: &u8| t.map_pawns[*a as usize].cmp(&t.map_pawns[*b as usize]);
+
+    if file.has_pawns {
+        let lead_code = table.pairs(0, 0)?.pieces[0] ^ flip_color;
+        let lead_color = if lead_code & 8 != 0 { Color::Black } else { Color::White };
+        let bb = board.pieces(PieceKind::Pawn) & board.side(lead_color);
+        lead_pawns = bb.inner();
+        for sq in bb {
+            squares[size] = sq.index() as u8 ^ flip_squares;
+            size += 1;
+        }
+        lead_count = size;
+        // The leading pawn has the highest map_pawns value; first one wins ties.
+        let mut max_i = 0;
+        for i in 1..lead_count {
+            if pawns_cmp(&squares[i], &squares[max_i]) == std::cmp::Ordering::Greater {
+                max_i = i;
+            }
+        }
+        squares.swap(0, max_i);
+        let f = (squares[0] & 7) as usize;
+        tb_file = f.min(7 - f);
+    }
+
+    let d = table.pairs(stm, tb_file)?;
+
+    // DTZ tables are one-sided.
+    if kind == TableKind::Dtz
+        && (d.flags & table::flags::STM) as usize != stm
+        && (file.key != file.key2 || file.has_pawns)
+    {
+        *state = ProbeState::ChangeStm;
+        return None;
+    }
+
+    for sq in board.occupied() {
+        if lead_pawns & (1u64 << sq.index()) != 0 {
+            continue;
+        }
+        let piece_kind = board.piece_on(sq)?;
+        let color = board.color_on(sq)?;
+        squares[size] = sq.index() as u8 ^ flip_squares;
+        pieces[size] = piece_code(piece_kind, color) ^ flip_color;
+        size += 1;
+    }
+
+    // Reorder pieces to match the table's encoding sequence.
+    for i in lead_count..size.saturating_sub(1) {
+        for j in i + 1..size {
+            if d.pieces[i] == pieces[j] {
+                pieces.swap(i, j);
+                squares.swap(i, j);
+                break;
+            }
+        }
+    }
+
+    // Map the lead piece into the a-d files.
+    if squares[0] & 7 > 3 {
+        for sq in squares.iter_mut().take(size) {
+            *sq = flip_file(*sq);
+        }
+    }
+
+    let mut idx: u64;
+    if file.has_pawns {
+        idx = t.lead_pawn_idx[lead_count][squares[0] as usize];
+        squares[1..lead_count].sort_by(pawns_cmp);
+        for (i, &sq) in squares.iter().enumerate().take(lead_count).skip(1) {
+            idx += t.binomial[i][t.map_pawns[sq as usize] as usize];
+        }
+    } else {
+        // Pawnless: keep the lead piece below rank 5 and below the a1-h8 diagonal.
+        if squares[0] >> 3 > 3 {
+            for sq in squares.iter_mut().take(size) {
+                *sq = flip_rank(*sq);
+            }
+        }
+        for i in 0..d.group_len[0] {
+            if off_a1h8(squares[i]) == 0 {
+                continue;
+            }
+            if off_a1h8(squares[i]) > 0 {
+                for sq in squares.iter_mut().take(size).skip(i) {
+                    *sq = flip_diagonal(*sq);
+                }
+            }
+            break;
+        }
+
+        idx = if file.has_unique_pieces {
+            let s = &squares;
+            let adjust1 = (s[1] > s[0]) as u64;
+            let adjust2 = (s[2] > s[0]) as u64 + (s[2] > s[1]) as u64;
+            let rank = |sq: u8| (sq >> 3) as u64;
+            if off_a1h8(s[0]) != 0 {
+                (t.map_a1d1d4[s[0] as usize] * 63 + (s[1] as u64 - adjust1)) * 62 + s[2] as u64 - adjust2
+            } else if off_a1h8(s[1]) != 0 {
+                (6 * 63 + rank(s[0]) * 28 + t.map_b1h1h7[s[1] as usize]) * 62 + s[2] as u64 - adjust2
+            } else if off_a1h8(s[2]) != 0 {
+                6 * 63 * 62 + 4 * 28 * 62 + rank(s[0]) * 7 * 28 + (rank(s[1]) - adjust1) * 28
+                    + t.map_b1h1h7[s[2] as usize]
+            } else {
+                6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + rank(s[0]) * 7 * 6 + (rank(s[1]) - adjust1) * 6
+                    + (rank(s[2]) - adjust2)
+            }
+        } else {
+            t.map_kk[t.map_a1d1d4[squares[0] as usize] as usize][squares[1] as usize]
+        };
+    }
+
+    // Encode the remaining groups; each square is mapped down past earlier groups.
+    idx *= d.group_idx[0];
+    let mut group_start = d.group_len[0];
+    let mut remaining_pawns = file.has_pawns && file.pawn_count[1] > 0;
+    let mut next = 1;
+    while d.group_len[next] != 0 {
+        let len = d.group_len[next];
+        squares[group_start..group_start + len].sort_unstable();
+        let mut n = 0u64;
+        for i in 0..len {
+            let sq = squares[group_start + i];
+            let adjust = squares[..group_start].iter().filter(|&&s| sq > s).count() as u64;
+            let offset = if remaining_pawns { 8 } else { 0 };
+            n += t.binomial[i + 1][(sq as u64 - adjust - offset) as usize];
+        }
+        remaining_pawns = false;
+        idx += n * d.group_idx[next];
+        group_start += len;
+        next += 1;
+    }
+
+    table.decompress(d, idx).map(|v| (v, tb_file))
+}
+
+/// Convert a raw DTZ table value into plies, undoing the optional value remapping.
+fn map_dtz_score(table: &LoadedTable, tb_file: usize, value: i32, wdl: Wdl) -> Option<i32> {
+    const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+    let d = table.pairs(0, tb_file)?;
+    let mut value = value;
+    if d.flags & table::flags::MAPPED != 0 {
+        value = table.map_value(d, WDL_MAP[(wdl as i32 + 2) as usize], value)?;
+    }
+    let in_moves = match wdl {
+        Wdl::Win => d.flags & table::flags::WIN_PLIES == 0,
+        Wdl::Loss => d.flags & table::flags::LOSS_PLIES == 0,
+        Wdl::CursedWin | Wdl::BlessedLoss => true,
+        Wdl::Draw => false,
+    };
+    Some(if in_moves { value * 2 } else { value } + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_tables_match_reference_sizes() {
+        let t = tables();
+        let max_kk = t.map_kk.iter().flatten().copied().max().unwrap();
+        assert_eq!(max_kk, 461);
+        assert_eq!(t.binomial[2][5], 10);
+        assert_eq!(t.binomial[3][48], 17_296);
+        assert_eq!(t.map_pawns[8], 47);
+        assert_eq!(t.map_pawns[15], 46);
+        assert_eq!(t.lead_pawns_size[1][0], 6);
+    }
+
+    #[test]
+    fn material_parsing() {
+        let m = Material::parse("KRPvKR").unwrap();
+        assert_eq!(m.piece_count(), 5);
+        assert_eq!(m.counts[0][0], 1);
+        assert_eq!(m.counts[0][3], 1);
+        assert_ne!(m.key(), m.swapped_key());
+        assert!(Material::parse("KRvR").is_none());
+        assert!(Material::parse("KXvK").is_none());
+
+        let sym = Material::parse("KRvKR").unwrap();
+        assert_eq!(sym.key(), sym.swapped_key());
+    }
+
+    #[test]
+    fn position_key_matches_table_key() {
+        let board: Board = "8/8/8/4k3/8/8/3RK3/8 w - - 0 1".parse().unwrap();
+        let m = Material::parse("KRvK").unwrap();
+        assert_eq!(position_material_key(&board), m.key());
+    }
+
+    #[test]
+    fn empty_path_loads_nothing() {
+        let tb = Tablebases::load("");
+        assert_eq!(tb.file_count(), 0);
+        assert_eq!(tb.max_pieces(), 0);
+
+        let tb = Tablebases::load("/nonexistent/cesso/syzygy");
+        assert_eq!(tb.file_count(), 0);
+    }
+
+    #[test]
+    fn probes_without_tables_return_none() {
+        let tb = Tablebases::load("");
+        let board: Board = "8/8/8/4k3/8/8/3RK3/8 w - - 0 1".parse().unwrap();
+        assert_eq!(tb.probe_wdl(&board), None);
+        assert_eq!(tb.probe_dtz(&board), None);
+        assert_eq!(tb.filter_root_moves(&board, &[]), None);
+        assert_eq!(tb.hits(), 0);
+    }
+
+    /// Every `step`-th legal KRvK position, alternately with the colors swapped.
+    fn krvk_sample(step: usize) -> impl Iterator<Item = (fixture::Krvk, bool)> {
+        (0..2 * 64 * 64 * 64)
+            .map(|i| (i >> 18, (i >> 12 & 63) as u8, (i >> 6 & 63) as u8, (i & 63) as u8))
+            .filter(|&pos| fixture::is_legal(pos))
+            .step_by(step)
+            .enumerate()
+            .map(|(n, pos)| (pos, n % 2 == 1))
+    }
+
+    #[test]
+    fn krvk_fixture_probes_match_retrograde_solution() {
+        let (dir, solution) = fixture::krvk();
+        let tb = Tablebases::load(&dir.to_string_lossy());
+        assert_eq!(tb.file_count(), 2);
+        assert_eq!(tb.max_pieces(), 3);
+
+        for (pos, mirrored) in krvk_sample(89) {
+            let fen = fixture::fen(pos, mirrored);
+            let board: Board = fen.parse().unwrap();
+            let rook_to_move = pos.0 == 0;
+            let (wdl, dtz) = match (rook_to_move, solution.plies(pos)) {
+                (true, Some(p)) => (Wdl::Win, p as i32),
+                (false, Some(0)) => (Wdl::Loss, -1),
+                (false, Some(p)) => (Wdl::Loss, -(p as i32)),
+                (_, None) => (Wdl::Draw, 0),
+            };
+            assert_eq!(tb.probe_wdl(&board), Some(wdl), "{fen}");
+            assert_eq!(tb.probe_dtz(&board), Some(dtz), "{fen}");
+        }
+    }
+
+    #[test]
+    fn krvk_fixture_known_positions() {
+        let (dir, solution) = fixture::krvk();
+        // The longest KRvK win is mate in 16, 32 plies with black to move
+        let longest = krvk_sample(1).filter_map(|(pos, _)| solution.plies(pos)).max();
+        assert_eq!(longest, Some(32));
+
+        let tb = Tablebases::load(&dir.to_string_lossy());
+        let probe = |fen: &str| {
+            let board: Board = fen.parse().unwrap();
+            (tb.probe_wdl(&board), tb.probe_dtz(&board))
+        };
+
+        // Mate in one, and the mated position after it
+        assert_eq!(probe("k7/8/1K6/8/8/8/8/7R w - - 0 1"), (Some(Wdl::Win), Some(1)));
+        assert_eq!(probe("k6R/8/1K6/8/8/8/8/8 b - - 1 1"), (Some(Wdl::Loss), Some(-1)));
+        // Stalemate, and a hanging rook
+        assert_eq!(probe("k7/1R6/1K6/8/8/8/8/8 b - - 0 1"), (Some(Wdl::Draw), Some(0)));
+        assert_eq!(probe("8/8/8/8/8/8/1k6/1R5K b - - 0 1"), (Some(Wdl::Draw), Some(0)));
+        // The same mate in one with the colors swapped
+        assert_eq!(probe("7r/8/8/8/8/1k6/8/K7 b - - 0 1"), (Some(Wdl::Win), Some(1)));
+
+        let board: Board = "k7/8/1K6/8/8/8/8/7R w - - 0 1".parse().unwrap();
+        let best = tb.filter_root_moves(&board, &[]).unwrap();
+        assert_eq!(best, vec![Move::from_uci_legal("h1h8", &board).unwrap()]);
+    }
+
+    #[test]
+    fn bare_kings_are_a_draw() {
+        let tb = Tablebases::load("");
+        let board: Board = "8/8/8/4k3/8/8/4K3/8 w - - 0 1".parse().unwrap();
+        let mut state = ProbeState::Ok;
+        assert_eq!(tb.probe_wdl_table(&board, &mut state), Wdl::Draw);
+        assert_eq!(state, ProbeState::Ok);
+    }
+}
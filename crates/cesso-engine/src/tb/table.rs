@@ -0,0 +1,629 @@
+//! Syzygy table files: header parsing and pairs (Huffman + recursive pairing) decompression.
+//!
+//! A table file starts with a 4-byte magic, followed by per-file piece orders,
+//! `PairsData` headers, an optional DTZ value map, sparse indices, block lengths,
+//! and finally 64-byte aligned blocks of canonical Huffman codes.
+//!
+//! ## Glossary
+//!
+//! | Term | Meaning |
+//! |---|---|
+//! | sym | A Huffman symbol; expands (via `btree`) into a pair of symbols or a leaf value |
+//! | `sym_len[s]` | Number of values represented by `s`, minus one |
+//! | span | Distance between sparse-index entries (in table indices) |
+//! | side | `0` = white to move, `1` = black to move (WDL only; DTZ is one-sided) |
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use memmap2::Mmap;
+use tracing::{debug, warn};
+
+use crate::tb::encoding::tables;
+
+/// Maximum number of pieces a Syzygy table can describe.
+pub(super) const TB_PIECES: usize = 7;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+/// Per-file flags stored in the first byte of each `PairsData` header.
+pub(super) mod flags {
+    /// DTZ table stores black-to-move positions.
+    pub const STM: u8 = 1;
+    /// DTZ values go through a remapping table.
+    pub const MAPPED: u8 = 2;
+    /// DTZ values for wins are stored in plies rather than moves.
+    pub const WIN_PLIES: u8 = 4;
+    /// DTZ values for losses are stored in plies rather than moves.
+    pub const LOSS_PLIES: u8 = 8;
+    /// The DTZ remapping table uses 16-bit entries.
+    pub const WIDE: u8 = 16;
+    /// Every position in the table has the same value.
+    pub const SINGLE_VALUE: u8 = 128;
+}
+
+/// Which of the two Syzygy table families a file belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TableKind {
+    /// Win/draw/loss tables (`.rtbw`).
+    Wdl,
+    /// Distance-to-zeroing tables (`.rtbz`).
+    Dtz,
+}
+
+impl TableKind {
+    /// File extension (without the dot) used by this family.
+    pub(super) fn extension(self) -> &'static str {
+        match self {
+            TableKind::Wdl => "rtbw",
+            TableKind::Dtz => "rtbz",
+        }
+    }
+
+    fn magic(self) -> [u8; 4] {
+        match self {
+            TableKind::Wdl => WDL_MAGIC,
+            TableKind::Dtz => DTZ_MAGIC,
+        }
+    }
+}
+
+/// Material signature of a table, parsed from a file stem such as `KRPvKR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Material {
+    /// Piece counts indexed by `[color][PieceKind::index()]`; color 0 is the left-hand side.
+    pub counts: [[u8; 6]; 2],
+}
+
+impl Material {
+    /// Parse a table name like `KQvKR`. Returns `None` for anything malformed.
+    pub(super) fn parse(stem: &str) -> Option<Material> {
+        let (white, black) = stem.split_once('v')?;
+        let mut counts = [[0u8; 6]; 2];
+        for (side, pieces) in [white, black].into_iter().enumerate() {
+            if !pieces.starts_with('K') {
+                return None;
+            }
+            for c in pieces.chars() {
+                let kind = match c {
+                    'P' => 0,
+                    'N' => 1,
+                    'B' => 2,
+                    'R' => 3,
+                    'Q' => 4,
+                    'K' => 5,
+                    _ => return None,
+                };
+                counts[side][kind] += 1;
+            }
+            if counts[side][5] != 1 {
+                return None;
+            }
+        }
+        Some(Material { counts })
+    }
+
+    /// Material key with the left-hand side as white.
+    pub(super) fn key(&self) -> u64 {
+        material_key_from_counts(&self.counts)
+    }
+
+    /// Material key with the colors swapped.
+    pub(super) fn swapped_key(&self) -> u64 {
+        material_key_from_counts(&[self.counts[1], self.counts[0]])
+    }
+
+    /// Total number of pieces, kings included.
+    pub(super) fn piece_count(&self) -> usize {
+        self.counts.iter().flatten().map(|&c| c as usize).sum()
+    }
+}
+
+/// Pack per-color piece counts into a single comparable key (4 bits per count).
+pub(super) fn material_key_from_counts(counts: &[[u8; 6]; 2]) -> u64 {
+    counts
+        .iter()
+        .flatten()
+        .enumerate()
+        .fold(0u64, |key, (i, &c)| key | ((c as u64 & 0xF) << (4 * i)))
+}
+
+/// Decoding parameters for one (side, file) sub-table.
+#[derive(Debug, Clone, Default)]
+pub(super) struct PairsData {
+    pub flags: u8,
+    block_size: usize,
+    span: u64,
+    num_blocks: usize,
+    min_sym_len: u8,
+    /// Byte offset of the `lowest_sym` array.
+    lowest_sym: usize,
+    /// Byte offset of the symbol tree (3 bytes per symbol).
+    btree: usize,
+    /// Byte offset of the block-length array (`u16` per block).
+    block_lengths: usize,
+    block_length_count: usize,
+    /// Byte offset of the sparse index (6 bytes per entry).
+    sparse_index: usize,
+    sparse_index_size: usize,
+    /// Byte offset of the compressed data blocks.
+    data: usize,
+    base64: Vec<u64>,
+    sym_len: Vec<u8>,
+    /// Piece codes in encoding order (`1..=6` = P..K, `+8` for black).
+    pub pieces: [u8; TB_PIECES],
+    /// Multiplier for each group's index contribution; the last entry is the table size.
+    pub group_idx: [u64; TB_PIECES + 1],
+    /// Number of pieces per group, zero-terminated.
+    pub group_len: [usize; TB_PIECES + 1],
+    /// Offsets into the DTZ map for win, loss, cursed win, and blessed loss.
+    pub map_idx: [u16; 4],
+}
+
+/// Static description of a table plus its lazily loaded contents.
+pub(super) struct TableFile {
+    path: PathBuf,
+    kind: TableKind,
+    /// Material key with the stronger (left-hand) side as white.
+    pub key: u64,
+    /// Material key with colors swapped.
+    pub key2: u64,
+    pub piece_count: usize,
+    pub has_pawns: bool,
+    pub has_unique_pieces: bool,
+    /// Pawn counts of the leading color and the other color.
+    pub pawn_count: [u8; 2],
+    loaded: OnceLock<Option<LoadedTable>>,
+}
+
+/// A memory-mapped table file with all sub-table headers decoded.
+pub(super) struct LoadedTable {
+    bytes: Mmap,
+    /// Sub-tables indexed by `side * files + file`.
+    pairs: Vec<PairsData>,
+    files: usize,
+    sides: usize,
+    /// Byte offset of the DTZ value map.
+    map: usize,
+}
+
+impl TableFile {
+    /// Describe the table at `path` without reading it.
+    pub(super) fn new(path: &Path, kind: TableKind, material: &Material) -> TableFile {
+        let has_pawns = material.counts[0][0] + material.counts[1][0] > 0;
+        let has_unique_pieces = material
+            .counts
+            .iter()
+            .any(|side| side[..5].contains(&1));
+
+        // The leading color is the side with fewer (but some) pawns.
+        let (white_pawns, black_pawns) = (material.counts[0][0], material.counts[1][0]);
+        let white_leads = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+        let pawn_count = if white_leads {
+            [white_pawns, black_pawns]
+        } else {
+            [black_pawns, white_pawns]
+        };
+
+        TableFile {
+            path: path.to_path_buf(),
+            kind,
+            key: material.key(),
+            key2: material.swapped_key(),
+            piece_count: material.piece_count(),
+            has_pawns,
+            has_unique_pieces,
+            pawn_count,
+            loaded: OnceLock::new(),
+        }
+    }
+
+    /// Return the decoded table, mapping the file on first access.
+    ///
+    /// Only the pages a probe touches are read from disk, so large 6-piece
+    /// files cost address space rather than memory. Returns `None` if the
+    /// file cannot be mapped or is corrupt; the failure is cached so the file
+    /// is not re-opened on every probe.
+    pub(super) fn get(&self) -> Option<&LoadedTable> {
+        self.loaded
+            .get_or_init(|| {
+                let bytes = match File::open(&self.path).and_then(|file| map_read_only(&file)) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        warn!(path = %self.path.display(), error = %e, "failed to map tablebase file");
+                        return None;
+                    }
+                };
+                let loaded = LoadedTable::parse(bytes, self);
+                if loaded.is_none() {
+                    warn!(path = %self.path.display(), "corrupt tablebase file");
+                } else {
+                    debug!(path = %self.path.display(), "tablebase file loaded");
+                }
+                loaded
+            })
+            .as_ref()
+    }
+}
+
+/// Map `file` read-only.
+fn map_read_only(file: &File) -> std::io::Result<Mmap> {
+    // SAFETY: the map is only ever read, and every read is bounds-checked
+    // against its length. Table files are treated as immutable while the
+    // engine runs; truncating one underneath it is outside what we support.
+    unsafe { Mmap::map(file) }
+}
+
+// ── Little helpers for bounds-checked reads ─────────────────────────────────
+
+fn byte(data: &[u8], off: usize) -> Option<u8> {
+    data.get(off).copied()
+}
+
+fn u16_le(data: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn u32_le(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn u32_be(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn u64_be(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+/// Left and right child symbols packed into 3 bytes (12 bits each).
+fn btree_left(data: &[u8], btree: usize, sym: usize) -> Option<usize> {
+    let off = btree + 3 * sym;
+    Some((((byte(data, off + 1)? & 0xF) as usize) << 8) | byte(data, off)? as usize)
+}
+
+fn btree_right(data: &[u8], btree: usize, sym: usize) -> Option<usize> {
+    let off = btree + 3 * sym;
+    Some(((byte(data, off + 2)? as usize) << 4) | (byte(data, off + 1)? >> 4) as usize)
+}
+
+impl LoadedTable {
+    /// Decode all sub-table headers of a table file.
+    fn parse(bytes: Mmap, file: &TableFile) -> Option<LoadedTable> {
+        if bytes.get(..4)? != file.kind.magic() {
+            return None;
+        }
+
+        let data = &bytes;
+        let mut pos = 4;
+        let header = byte(data, pos)?;
+        pos += 1;
+
+        // Bit 0: table is split into white/black-to-move halves; bit 1: has pawns.
+        if (header & 2 != 0) != file.has_pawns {
+            return None;
+        }
+        let split = file.key != file.key2;
+        let sides = if file.kind == TableKind::Wdl && split { 2 } else { 1 };
+        let files = if file.has_pawns { 4 } else { 1 };
+        let both_pawns = file.has_pawns && file.pawn_count[1] > 0;
+
+        let mut pairs = vec![PairsData::default(); sides * files];
+
+        for f in 0..files {
+            let b0 = byte(data, pos)?;
+            let b1 = if both_pawns { byte(data, pos + 1)? } else { 0xFF };
+            let order = [
+                [(b0 & 0xF) as usize, (b1 & 0xF) as usize],
+                [(b0 >> 4) as usize, (b1 >> 4) as usize],
+            ];
+            pos += 1 + both_pawns as usize;
+
+            for k in 0..file.piece_count {
+                let b = byte(data, pos)?;
+                for side in 0..sides {
+                    pairs[side * files + f].pieces[k] = if side == 1 { b >> 4 } else { b & 0xF };
+                }
+                pos += 1;
+            }
+
+            for (side, side_order) in order.iter().enumerate().take(sides) {
+                set_groups(file, &mut pairs[side * files + f], side_order, f);
+            }
+        }
+
+        pos += pos & 1;
+
+        for f in 0..files {
+            for side in 0..sides {
+                pos = set_sizes(&mut pairs[side * files + f], data, pos)?;
+            }
+        }
+
+        let map = pos;
+        if file.kind == TableKind::Dtz {
+            for d in pairs.iter_mut().take(files) {
+                if d.flags & flags::MAPPED == 0 {
+                    continue;
+                }
+                if d.flags & flags::WIDE != 0 {
+                    pos += pos & 1;
+                    for i in 0..4 {
+                        d.map_idx[i] = ((pos - map) / 2 + 1) as u16;
+                        pos += 2 * u16_le(data, pos)? as usize + 2;
+                    }
+                } else {
+                    for i in 0..4 {
+                        d.map_idx[i] = (pos - map + 1) as u16;
+                        pos += byte(data, pos)? as usize + 1;
+                    }
+                }
+            }
+            pos += pos & 1;
+        }
+
+        for f in 0..files {
+            for side in 0..sides {
+                let d = &mut pairs[side * files + f];
+                d.sparse_index = pos;
+                pos += d.sparse_index_size * 6;
+            }
+        }
+
+        for f in 0..files {
+            for side in 0..sides {
+                let d = &mut pairs[side * files + f];
+                d.block_lengths = pos;
+                pos += d.block_length_count * 2;
+            }
+        }
+
+        for f in 0..files {
+            for side in 0..sides {
+                let d = &mut pairs[side * files + f];
+                pos = (pos + 0x3F) & !0x3F;
+                d.data = pos;
+                pos += d.num_blocks * d.block_size;
+            }
+        }
+
+        if pos > data.len() {
+            return None;
+        }
+
+        Some(LoadedTable { bytes, pairs, files, sides, map })
+    }
+
+    /// Sub-table for the given side to move and leading file.
+    pub(super) fn pairs(&self, stm: usize, file: usize) -> Option<&PairsData> {
+        let side = stm % self.sides;
+        let file = if self.files == 1 { 0 } else { file };
+        self.pairs.get(side * self.files + file)
+    }
+
+    /// Look up a remapped DTZ value.
+    pub(super) fn map_value(&self, d: &PairsData, which: usize, value: i32) -> Option<i32> {
+        let base = d.map_idx[which] as usize;
+        if d.flags & flags::WIDE != 0 {
+            let off = self.map + 2 * (base + value as usize);
+            u16_le(&self.bytes, off).map(|v| v as i32)
+        } else {
+            byte(&self.bytes, self.map + base + value as usize).map(|v| v as i32)
+        }
+    }
+
+    /// Decompress the value stored at index `idx` of sub-table `d`.
+    pub(super) fn decompress(&self, d: &PairsData, idx: u64) -> Option<i32> {
+        if d.flags & flags::SINGLE_VALUE != 0 {
+            return Some(d.min_sym_len as i32);
+        }
+
+        let data = &self.bytes;
+        let k = (idx / d.span) as usize;
+        if k >= d.sparse_index_size {
+            return None;
+        }
+        let entry = d.sparse_index + 6 * k;
+        let mut block = u32_le(data, entry)? as usize;
+        let mut offset = u16_le(data, entry + 4)? as i64;
+
+        // Walk from the sparse-index anchor to the block containing idx.
+        offset += (idx % d.span) as i64 - (d.span / 2) as i64;
+        let block_len = |b: usize| -> Option<i64> {
+            if b >= d.block_length_count {
+                return None;
+            }
+            u16_le(data, d.block_lengths + 2 * b).map(|l| l as i64)
+        };
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_len(block)? + 1;
+        }
+        while offset > block_len(block)? {
+            offset -= block_len(block)? + 1;
+            block += 1;
+        }
+
+        // Read canonical Huffman symbols until we reach the one covering offset.
+        let mut ptr = d.data + block * d.block_size;
+        let mut buf64 = u64_be(data, ptr)?;
+        ptr += 8;
+        let mut buf64_size = 64i32;
+        let min_len = d.min_sym_len as usize;
+        let mut sym;
+
+        loop {
+            let mut len = 0usize;
+            while buf64 < *d.base64.get(len)? {
+                len += 1;
+            }
+            sym = ((buf64 - d.base64[len]) >> (64 - len - min_len)) as usize;
+            sym += u16_le(data, d.lowest_sym + 2 * len)? as usize;
+
+            let sl = *d.sym_len.get(sym)? as i64;
+            if offset < sl + 1 {
+                break;
+            }
+            offset -= sl + 1;
+            let bits = len + min_len;
+            buf64 = if bits >= 64 { 0 } else { buf64 << bits };
+            buf64_size -= bits as i32;
+
+            if buf64_size <= 32 {
+                buf64_size += 32;
+                buf64 |= (u32_be(data, ptr)? as u64) << (64 - buf64_size);
+                ptr += 4;
+            }
+        }
+
+        // Descend the pairing tree to the leaf holding our value.
+        while *d.sym_len.get(sym)? != 0 {
+            let left = btree_left(data, d.btree, sym)?;
+            let left_len = *d.sym_len.get(left)? as i64;
+            if offset < left_len + 1 {
+                sym = left;
+            } else {
+                offset -= left_len + 1;
+                sym = btree_right(data, d.btree, sym)?;
+            }
+        }
+
+        btree_left(data, d.btree, sym).map(|v| v as i32)
+    }
+}
+
+/// Compute group lengths and index multipliers for one sub-table.
+///
+/// Groups are encoded as `g1 * N(g2) * N(g3) + g2 * N(g3) + g3`, in the
+/// per-table `order` (leading group at `order[0]`, remaining pawns at `order[1]`).
+fn set_groups(file: &TableFile, d: &mut PairsData, order: &[usize; 2], f: usize) {
+    let t = tables();
+    let mut n = 0;
+    let mut first_len: i32 = if file.has_pawns {
+        0
+    } else if file.has_unique_pieces {
+        3
+    } else {
+        2
+    };
+    d.group_len[0] = 1;
+
+    for i in 1..file.piece_count {
+        first_len -= 1;
+        if first_len > 0 || d.pieces[i] == d.pieces[i - 1] {
+            d.group_len[n] += 1;
+        } else {
+            n += 1;
+            d.group_len[n] = 1;
+        }
+    }
+    n += 1;
+    d.group_len[n] = 0;
+
+    let both_pawns = file.has_pawns && file.pawn_count[1] > 0;
+    let mut next = if both_pawns { 2 } else { 1 };
+    let mut free_squares = 64 - d.group_len[0] - if both_pawns { d.group_len[1] } else { 0 };
+    let mut idx: u64 = 1;
+
+    let mut k = 0;
+    while next < n || k == order[0] || k == order[1] {
+        if k == order[0] {
+            d.group_idx[0] = idx;
+            idx *= if file.has_pawns {
+                t.lead_pawns_size[d.group_len[0]][f]
+            } else if file.has_unique_pieces {
+                31_332
+            } else {
+                462
+            };
+        } else if k == order[1] {
+            d.group_idx[1] = idx;
+            idx *= t.binomial[d.group_len[1]][48 - d.group_len[0]];
+        } else {
+            d.group_idx[next] = idx;
+            idx *= t.binomial[d.group_len[next]][free_squares];
+            free_squares -= d.group_len[next];
+            next += 1;
+        }
+        k += 1;
+    }
+    d.group_idx[n] = idx;
+}
+
+/// Read the `PairsData` header starting at `pos`; returns the offset just past it.
+fn set_sizes(d: &mut PairsData, data: &[u8], mut pos: usize) -> Option<usize> {
+    d.flags = byte(data, pos)?;
+    pos += 1;
+
+    if d.flags & flags::SINGLE_VALUE != 0 {
+        d.min_sym_len = byte(data, pos)?;
+        return Some(pos + 1);
+    }
+
+    let terminator = d.group_len.iter().position(|&l| l == 0)?;
+    let tb_size = d.group_idx[terminator];
+
+    d.block_size = 1usize << byte(data, pos)?;
+    d.span = 1u64 << byte(data, pos + 1)?;
+    d.sparse_index_size = tb_size.div_ceil(d.span) as usize;
+    let padding = byte(data, pos + 2)? as usize;
+    d.num_blocks = u32_le(data, pos + 3)? as usize;
+    d.block_length_count = d.num_blocks + padding;
+    let max_sym_len = byte(data, pos + 7)?;
+    d.min_sym_len = byte(data, pos + 8)?;
+    pos += 9;
+    if max_sym_len < d.min_sym_len || max_sym_len > 32 {
+        return None;
+    }
+    d.lowest_sym = pos;
+
+    // Canonical Huffman: longer codes have lower numeric values, so base64[l]
+    // is the smallest 64-bit left-aligned code of length `l + min_sym_len`.
+    let count = (max_sym_len - d.min_sym_len) as usize + 1;
+    d.base64 = vec![0; count];
+    for i in (0..count.saturating_sub(1)).rev() {
+        let lo_i = u16_le(data, d.lowest_sym + 2 * i)? as u64;
+        let lo_next = u16_le(data, d.lowest_sym + 2 * (i + 1))? as u64;
+        d.base64[i] = (d.base64[i + 1] + lo_i).checked_sub(lo_next)? / 2;
+    }
+    for (i, base) in d.base64.iter_mut().enumerate() {
+        let shift = 64 - i - d.min_sym_len as usize;
+        *base = if shift >= 64 { 0 } else { *base << shift };
+    }
+    pos += count * 2;
+
+    let sym_count = u16_le(data, pos)? as usize;
+    pos += 2;
+    d.btree = pos;
+    d.sym_len = vec![0; sym_count];
+    let mut visited = vec![false; sym_count];
+    for sym in 0..sym_count {
+        if !visited[sym] {
+            let len = set_sym_len(d, data, sym, &mut visited)?;
+            d.sym_len[sym] = len;
+        }
+    }
+
+    Some(pos + sym_count * 3 + (sym_count & 1))
+}
+
+/// Compute how many values (minus one) symbol `sym` expands to.
+fn set_sym_len(d: &mut PairsData, data: &[u8], sym: usize, visited: &mut [bool]) -> Option<u8> {
+    *visited.get_mut(sym)? = true;
+    let right = btree_right(data, d.btree, sym)?;
+    if right == 0xFFF {
+        return Some(0);
+    }
+    let left = btree_left(data, d.btree, sym)?;
+    if !*visited.get(left)? {
+        let len = set_sym_len(d, data, left, visited)?;
+        d.sym_len[left] = len;
+    }
+    if !*visited.get(right)? {
+        let len = set_sym_len(d, data, right, visited)?;
+        d.sym_len[right] = len;
+    }
+    Some(d.sym_len[left].wrapping_add(d.sym_len[right]).wrapping_add(1))
+}
@@ -76,14 +76,34 @@ pub fn compute_limits(
     )
 }
 
+/// Time budget for a bare `go` (no clock, movetime, depth, nodes, or `infinite`).
+pub const BARE_GO_MOVETIME: Duration = Duration::from_millis(5000);
+
+/// Depth cap for a bare `go`; whichever of this and [`BARE_GO_MOVETIME`] comes first ends the search.
+pub const BARE_GO_DEPTH: u8 = 18;
+
+/// Whether a `go` command carries no limit at all and should fall back to the bare-`go` budget.
+pub fn is_bare_go(
+    wtime: Option<Duration>,
+    btime: Option<Duration>,
+    movetime: Option<Duration>,
+    depth: Option<u8>,
+    nodes: Option<u64>,
+    infinite: bool,
+) -> bool {
+    wtime.is_none() && btime.is_none() && movetime.is_none() && depth.is_none() && nodes.is_none() && !infinite
+}
+
 /// Build a [`SearchControl`] from UCI `go` parameters and the side to move.
 ///
 /// Priority order:
 /// 1. `ponder: true` with time -> `SearchControl::new_ponder`
 /// 2. `infinite: true` -> `SearchControl::new_infinite`
-/// 3. `movetime: Some(d)` -> `SearchControl::new_timed(d, d)`
+/// 3. `movetime: Some(d)` -> `SearchControl::new_movetime(d)`
 /// 4. `wtime/btime` present -> `compute_limits()` then `SearchControl::new_timed`
-/// 5. `depth` only / bare `go` -> `SearchControl::new_infinite`
+/// 5. `nodes` -> `SearchControl::new_nodes`, else `depth` -> `SearchControl::new_depth`
+/// 6. bare `go` -> `SearchControl::new_timed` with [`BARE_GO_MOVETIME`]; the caller
+///    also caps depth at [`BARE_GO_DEPTH`]
 #[allow(clippy::too_many_arguments)]
 pub fn limits_from_go(
     wtime: Option<Duration>,
@@ -92,6 +112,8 @@ pub fn limits_from_go(
     binc: Option<Duration>,
     movestogo: Option<u32>,
     movetime: Option<Duration>,
+    depth: Option<u8>,
+    nodes: Option<u64>,
     infinite: bool,
     ponder: bool,
     side: Color,
@@ -112,7 +134,7 @@ pub fn limits_from_go(
         if ponder {
             return SearchControl::new_ponder(stopped, mt, mt);
         }
-        return SearchControl::new_timed(stopped, mt, mt);
+        return SearchControl::new_movetime(mt, stopped);
     }
 
     if let Some(rem) = remaining {
@@ -126,12 +148,23 @@ pub fn limits_from_go(
         return SearchControl::new_timed(stopped, soft, hard);
     }
 
-    // depth-only or bare `go` — no time limits
     if ponder {
         // Ponder with no time info — just infinite pondering
         return SearchControl::new_infinite(stopped);
     }
-    SearchControl::new_infinite(stopped)
+
+    if is_bare_go(wtime, btime, movetime, depth, nodes, infinite) {
+        return SearchControl::new_timed(stopped, BARE_GO_MOVETIME, BARE_GO_MOVETIME);
+    }
+
+    // depth- or nodes-limited — no time limits
+    if let Some(max_nodes) = nodes {
+        return SearchControl::new_nodes(max_nodes, stopped);
+    }
+    match depth {
+        Some(max_depth) => SearchControl::new_depth(max_depth, stopped),
+        None => SearchControl::new_infinite(stopped),
+    }
 }
 
 #[cfg(test)]
@@ -142,8 +175,8 @@ mod tests {
 
     use cesso_core::{Board, Color};
 
-    use crate::time::compute_limits;
-    use crate::time::limits_from_go;
+    use crate::search::negamax::MAX_SEARCH_DEPTH;
+    use crate::time::{BARE_GO_MOVETIME, compute_limits, is_bare_go, limits_from_go};
 
     #[test]
     fn compute_limits_with_increment() {
@@ -234,7 +267,7 @@ mod tests {
         let stopped = Arc::new(AtomicBool::new(false));
         let board = Board::starting_position();
         let control = limits_from_go(
-            None, None, None, None, None, None,
+            None, None, None, None, None, None, None, None,
             true, false, Color::White, stopped, &board,
         );
         // Infinite should not stop on its own
@@ -249,6 +282,7 @@ mod tests {
         let control = limits_from_go(
             None, None, None, None, None,
             Some(Duration::from_secs(5)),
+            None, None,
             false, false, Color::White, stopped, &board,
         );
         // Should not stop immediately
@@ -264,7 +298,7 @@ mod tests {
             Some(Duration::from_secs(300)),
             Some(Duration::from_secs(2)),
             Some(Duration::from_secs(2)),
-            None, None,
+            None, None, None, None,
             false, false, Color::White, stopped, &board,
         );
         // Should not stop immediately with 5 minutes
@@ -276,11 +310,51 @@ mod tests {
         let stopped = Arc::new(AtomicBool::new(false));
         let board = Board::starting_position();
         let control = limits_from_go(
-            None, None, None, None, None, None,
+            None, None, None, None, None, None, Some(10), None,
             false, false, Color::White, stopped, &board,
         );
-        // Should behave like infinite
+        // No clock — only the depth limit applies
         assert!(!control.should_stop(10000));
+        assert_eq!(control.depth_limit(128), 10);
+    }
+
+    #[test]
+    fn limits_from_go_clamps_depth_beyond_max_ply() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let board = Board::starting_position();
+        let control = limits_from_go(
+            None, None, None, None, None, None, Some(255), None,
+            false, false, Color::White, Arc::clone(&stopped), &board,
+        );
+        assert_eq!(control.depth_limit(255), MAX_SEARCH_DEPTH);
+        // Completing the clamped depth ends the search
+        control.depth_completed(MAX_SEARCH_DEPTH);
+        assert!(stopped.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn limits_from_go_nodes_only() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let board = Board::starting_position();
+        let control = limits_from_go(
+            None, None, None, None, None, None, None, Some(5000),
+            false, false, Color::White, stopped, &board,
+        );
+        assert!(!control.should_stop(4999));
+        assert!(control.should_stop(5000));
+    }
+
+    #[test]
+    fn limits_from_go_bare_uses_default_budget() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let board = Board::starting_position();
+        let control = limits_from_go(
+            None, None, None, None, None, None, None, None,
+            false, false, Color::White, stopped, &board,
+        );
+        assert_eq!(control.hard_limit(), Some(BARE_GO_MOVETIME));
+        assert!(is_bare_go(None, None, None, None, None, false));
+        assert!(!is_bare_go(None, None, None, None, None, true));
     }
 
     // --- B5: New phase-aware tests ---
@@ -0,0 +1,26 @@
+//! Bench runs: deterministic node counts and a coarse speed check.
+
+use cesso_engine::{ThreadPool, bench};
+
+/// Single-thread release-build speed on a typical CI machine, in nodes per second.
+const BASELINE_NPS: u64 = 500_000;
+
+#[test]
+fn single_thread_bench_is_deterministic() {
+    let mut pool = ThreadPool::new(16);
+    let first = bench(&mut pool, 3);
+    let second = bench(&mut pool, 3);
+    assert!(first.nodes > 0);
+    assert_eq!(first.nodes, second.nodes);
+}
+
+#[test]
+#[ignore] // slow; run with --release
+fn bench_speed_within_ten_times_of_baseline() {
+    let mut pool = ThreadPool::new(16);
+    let nps = bench(&mut pool, 7).nps();
+    assert!(
+        (BASELINE_NPS / 10..=BASELINE_NPS * 10).contains(&nps),
+        "bench ran at {nps} nps, baseline is {BASELINE_NPS}"
+    );
+}
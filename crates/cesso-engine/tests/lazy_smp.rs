@@ -7,7 +7,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use cesso_core::{Board, Color};
-use cesso_engine::{SearchControl, SearchResult, ThreadPool};
+use cesso_engine::{SearchControl, SearchResult, SearcherBuilder, ThreadPool};
 
 const SCHOLARS_MATE_FEN: &str =
     "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
@@ -27,7 +27,7 @@ fn search_with_threads(board: &Board, depth: u8, threads: usize) -> SearchResult
     pool.set_num_threads(threads);
     let stopped = Arc::new(AtomicBool::new(false));
     let control = SearchControl::new_infinite(stopped);
-    pool.search(board, depth, &control, &[], 0, Color::White, |_, _, _, _| {})
+    pool.search(board, depth, &control, &[], None, Color::White, |_, _, _, _, _, _| {})
 }
 
 // ── Basic correctness ─────────────────────────────────────────────────────────
@@ -58,6 +58,36 @@ fn single_thread_finds_mate_in_one() {
     );
 }
 
+#[test]
+fn dead_position_returns_immediately_with_draw_score() {
+    // KBvK: no search needed, the game is drawn whatever is played.
+    let board: Board = "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1".parse().unwrap();
+    let pool = SearcherBuilder::default().threads(2).contempt(25).build_pool();
+    let control = SearchControl::new_depth(10, Arc::new(AtomicBool::new(false)));
+    let result = pool.search(&board, 64, &control, &[], None, Color::White, |_, _, _, _, _, _| {});
+    assert!(!result.best_move.is_null());
+    assert_eq!(result.nodes, 0);
+    assert_eq!(result.score, -25, "contempt should make the draw unattractive");
+
+    // `go infinite` must keep searching until stopped, even in a dead position.
+    let stop = Arc::new(AtomicBool::new(false));
+    let control = SearchControl::new_infinite(Arc::clone(&stop));
+    let result = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            stop.store(true, std::sync::atomic::Ordering::Release);
+        });
+        pool.search(&board, 64, &control, &[], None, Color::White, |_, _, _, _, _, _| {})
+    });
+    assert!(!result.best_move.is_null());
+    assert!(result.nodes > 0, "an infinite search must not shortcut");
+
+    // KNNvK is not dead, so the engine still searches it.
+    let board: Board = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1".parse().unwrap();
+    let result = search_with_threads(&board, 3, 1);
+    assert!(result.nodes > 0);
+}
+
 // ── Multi-thread correctness ──────────────────────────────────────────────────
 
 #[test]
@@ -127,7 +157,7 @@ fn stop_signal_terminates_all_threads() {
 
     // Stop after depth 1 callback fires
     let stop_clone = Arc::clone(&stopped);
-    let result = pool.search(&board, 128, &control, &[], 0, Color::White, |depth, _, _, _| {
+    let result = pool.search(&board, 128, &control, &[], None, Color::White, |depth, _, _, _, _, _| {
         if depth >= 1 {
             stop_clone.store(true, Ordering::Release);
         }
@@ -150,7 +180,7 @@ fn pre_set_stop_returns_immediately() {
     let stopped = Arc::new(AtomicBool::new(true));
     let control = SearchControl::new_infinite(Arc::clone(&stopped));
 
-    let result = pool.search(&board, 100, &control, &[], 0, Color::White, |_, _, _, _| {});
+    let result = pool.search(&board, 100, &control, &[], None, Color::White, |_, _, _, _, _, _| {});
 
     assert_eq!(
         result.depth, 0,
@@ -211,7 +241,8 @@ fn on_iter_callback_fires() {
     let control = SearchControl::new_infinite(stopped);
 
     let mut depths_seen: Vec<u8> = Vec::new();
-    pool.search(&board, 3, &control, &[], 0, Color::White, |depth, _, _, _| {
+    pool.search(&board, 3, &control, &[], None, Color::White, |depth, _, nodes, qnodes, _, _| {
+        assert!(qnodes <= nodes, "qnodes {qnodes} > nodes {nodes}");
         depths_seen.push(depth);
     });
 
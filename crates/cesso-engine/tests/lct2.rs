@@ -0,0 +1,39 @@
+//! Tactical regression positions from the LCT-II test suite.
+//!
+//! Guards the forward-pruning margins (futility, reverse futility, null move):
+//! each position has a single winning move that aggressive pruning can hide.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use cesso_core::{Board, Color};
+use cesso_engine::{SearchControl, ThreadPool};
+
+/// Search `fen` to `depth` on one thread and return the best move in UCI notation.
+fn best_move(fen: &str, depth: u8) -> String {
+    let board: Board = fen.parse().unwrap();
+    let pool = ThreadPool::new(16);
+    let control = SearchControl::new_infinite(Arc::new(AtomicBool::new(false)));
+    pool.search(&board, depth, &control, &[], None, Color::White, |_, _, _, _, _, _| {})
+        .best_move
+        .to_uci()
+}
+
+#[test]
+fn finds_queen_sacrifice_on_h7() {
+    let fen = "r1bq2rk/pp3pbp/2p1p1pQ/7P/3P4/2PB1N2/PP3PPR/2KR4 w - - 0 1";
+    assert_eq!(best_move(fen, 6), "h6h7", "Qxh7+ should be found");
+}
+
+#[test]
+#[ignore] // slow
+fn finds_deeper_lct2_solutions() {
+    let positions = [
+        ("5r1k/1p4pp/3q4/3Pp1R1/8/8/PP4PP/4Q1K1 b - - 0 1", 12, "d6c5"),
+        ("2r1k2r/2pn1pp1/1p3n1p/p3PP2/4q2B/P1P5/2Q1N1PP/R4RK1 w k - 0 1", 11, "e5f6"),
+        ("1k1r3r/pp2qpp1/3b1n1p/3pNQ2/2pP1P2/2N1P3/PP4PP/1K1RR3 b - - 0 1", 12, "d6b4"),
+    ];
+    for (fen, depth, expected) in positions {
+        assert_eq!(best_move(fen, depth), expected, "{fen}");
+    }
+}
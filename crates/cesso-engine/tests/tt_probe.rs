@@ -0,0 +1,39 @@
+//! `probe_tt_move` must be cheaper than a full `probe`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cesso_core::{Move, Square};
+use cesso_engine::search::tt::{Bound, TranspositionTable};
+
+/// Spread keys over the table with a multiplicative hash.
+fn keys(count: u64) -> Vec<u64> {
+    (1..=count).map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15)).collect()
+}
+
+fn time(rounds: u32, keys: &[u64], f: impl Fn(u64) -> Option<Move>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for &key in keys {
+            black_box(f(black_box(key)));
+        }
+    }
+    start.elapsed()
+}
+
+#[test]
+#[ignore] // timing; run with --release
+fn probe_tt_move_is_faster_than_probe() {
+    let tt = TranspositionTable::new(16);
+    let keys = keys(4096);
+    let mv = Move::new(Square::E2, Square::E4);
+    for &key in &keys {
+        tt.store(key, 8, 35, 20, mv, Bound::LowerBound, 0, false);
+    }
+
+    let rounds = 2_000;
+    let full = time(rounds, &keys, |key| tt.probe(key, 3).map(|hit| hit.best_move));
+    let fast = time(rounds, &keys, |key| tt.probe_tt_move(key));
+    println!("probe: {full:?}, probe_tt_move: {fast:?}");
+    assert!(fast < full, "probe_tt_move took {fast:?}, probe {full:?}");
+}
@@ -1,14 +1,19 @@
 //! UCI command parsing.
 
+use std::fmt;
 use std::time::Duration;
 
-use cesso_core::{Board, Move};
+use cesso_core::{Board, FenError, Game, Move};
+use cesso_engine::{BENCH_DEPTH, EvalMode};
 
 use crate::error::UciError;
+use crate::options::{OptionSpec, OptionValue};
 
 /// Parameters for the `go` command.
 ///
-/// All fields are optional; a bare `go` uses defaults.
+/// All fields are optional. A bare `go` — no `wtime`/`btime`, `movetime`,
+/// `depth`, `nodes`, or `infinite` — searches for at most 5 seconds or to
+/// depth 18, whichever comes first. `go infinite` runs until `stop`.
 #[derive(Debug, Clone, Default)]
 pub struct GoParams {
     /// White's remaining time.
@@ -27,10 +32,17 @@ pub struct GoParams {
     pub movetime: Option<Duration>,
     /// Search this many nodes only.
     pub nodes: Option<u64>,
+    /// Search for a forced mate in at most this many moves.
+    pub mate: Option<u8>,
     /// Search until `stop` (no time limit).
     pub infinite: bool,
     /// Search in pondering mode.
     pub ponder: bool,
+    /// Run a perft divide to this depth instead of searching.
+    pub perft: Option<u8>,
+    /// Restrict the root to these moves (UCI strings, resolved against the
+    /// current position when the search starts).
+    pub searchmoves: Option<Vec<String>>,
 }
 
 /// A UCI option sent via `setoption`.
@@ -44,16 +56,119 @@ pub enum UciOption {
     Ponder(bool),
     /// Contempt factor in centipawns, clamped to [-300, 300].
     Contempt(i32),
+    /// Directories holding Syzygy tablebase files; empty or `<empty>` disables probing.
+    SyzygyPath(String),
+    /// Analysis mode: no contempt, and refuted root moves are reported.
+    AnalyseMode(bool),
+    /// In analysis mode, periodically report the line being searched.
+    ShowCurrLine(bool),
+    /// Polyglot opening book files, separated as in `PATH` (`:` on Unix);
+    /// empty or `<empty>` disables the book.
+    BookPath(String),
+    /// Randomness of book move selection (set in hundredths, clamped to [0, 10]):
+    /// 0 plays the heaviest move, 1 picks in proportion to the weights.
+    BookTemperature(f32),
+    /// Evaluation used by the search: the built-in one, or material only.
+    EvalMode(EvalMode),
 }
 
-/// Board position with game history for repetition detection.
-#[derive(Debug, Clone)]
+impl UciOption {
+    /// Name of the option as advertised in the `uci` handshake.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UciOption::Hash(_) => "Hash",
+            UciOption::Threads(_) => "Threads",
+            UciOption::Ponder(_) => "Ponder",
+            UciOption::Contempt(_) => "Contempt",
+            UciOption::SyzygyPath(_) => "SyzygyPath",
+            UciOption::AnalyseMode(_) => "UCI_AnalyseMode",
+            UciOption::ShowCurrLine(_) => "UCI_ShowCurrLine",
+            UciOption::BookPath(_) => "BookPath",
+            UciOption::BookTemperature(_) => "BookTemperature",
+            UciOption::EvalMode(_) => "EvalMode",
+        }
+    }
+
+    /// The option's value in table form.
+    pub fn value(&self) -> OptionValue {
+        match self {
+            UciOption::Hash(mb) => OptionValue::Spin(*mb as i64),
+            UciOption::Threads(n) => OptionValue::Spin(*n as i64),
+            UciOption::Ponder(on) => OptionValue::Check(*on),
+            UciOption::Contempt(cp) => OptionValue::Spin(*cp as i64),
+            UciOption::SyzygyPath(path) => OptionValue::String(path.clone()),
+            UciOption::AnalyseMode(on) | UciOption::ShowCurrLine(on) => OptionValue::Check(*on),
+            UciOption::BookPath(path) => OptionValue::String(path.clone()),
+            UciOption::BookTemperature(t) => OptionValue::Spin((t * 100.0).round() as i64),
+            UciOption::EvalMode(mode) => OptionValue::String(mode.name().to_string()),
+        }
+    }
+}
+
+/// Start position and move list of a `position` command.
+///
+/// The moves are checked only when played with [`PositionInfo::game`] or
+/// [`PositionInfo::play_from`], so a command that extends the previous one
+/// can replay just its new moves.
+#[derive(Debug, Clone, PartialEq)]
 pub struct PositionInfo {
-    /// The current board position.
-    pub board: Board,
-    /// Zobrist hashes of all positions from game start, up to but NOT
-    /// including the current position.
-    pub history: Vec<u64>,
+    /// The `startpos` or `fen` position the moves are played from.
+    pub start: Board,
+    /// The `moves` list in UCI notation.
+    pub moves: Vec<String>,
+}
+
+impl PositionInfo {
+    /// Play every move from the start position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UciError::InvalidMove`] for the first move that is malformed
+    /// or not legal.
+    pub fn game(&self) -> Result<Game, UciError> {
+        self.play_from(Game::new(self.start), 0)
+    }
+
+    /// Play the moves from index `first` on, continuing `game`, the game
+    /// reached by the moves before `first`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UciError::InvalidMove`] for the first move that is malformed
+    /// or not legal.
+    pub fn play_from(&self, mut game: Game, first: usize) -> Result<Game, UciError> {
+        for (ply, uci_move) in self.moves.iter().enumerate().skip(first) {
+            let mv = Move::from_uci_legal(uci_move, game.board()).ok_or_else(|| UciError::InvalidMove {
+                uci_move: uci_move.clone(),
+                ply,
+            })?;
+            game.push(mv);
+        }
+        Ok(game)
+    }
+
+    /// Number of moves this command adds to `previous`: `Some` when both
+    /// start from the same position and this move list begins with all of
+    /// `previous`'s moves.
+    pub fn appended_to(&self, previous: &PositionInfo) -> Option<usize> {
+        let extends = self.start == previous.start && self.moves.starts_with(&previous.moves);
+        extends.then(|| self.moves.len() - previous.moves.len())
+    }
+}
+
+impl fmt::Display for PositionInfo {
+    /// Format as the `position` command it was parsed from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == Board::starting_position() {
+            f.write_str("position startpos")?;
+        } else {
+            write!(f, "position fen {}", self.start.to_fen())?;
+        }
+        if !self.moves.is_empty() {
+            write!(f, " moves {}", self.moves.join(" "))?;
+        }
+        Ok(())
+    }
 }
 
 /// A parsed UCI command.
@@ -79,6 +194,9 @@ pub enum Command {
     Quit,
     /// `draw` -- opponent offers or claims a draw.
     Draw,
+    /// `bench [depth]` -- search the bench positions and report the speed
+    /// (unofficial extension).
+    Bench(u8),
     /// Unrecognized command (silently ignored per UCI spec).
     Unknown(String),
 }
@@ -101,6 +219,7 @@ pub fn parse_command(line: &str) -> Result<Command, UciError> {
         "go" => parse_go(&tokens[1..]),
         "setoption" => parse_setoption(&tokens[1..]),
         "draw" => Ok(Command::Draw),
+        "bench" => parse_bench(&tokens[1..]),
         _ => Ok(Command::Unknown(tokens[0].to_string())),
     }
 }
@@ -110,12 +229,14 @@ pub fn parse_command(line: &str) -> Result<Command, UciError> {
 /// Supports:
 /// - `position startpos [moves e2e4 d7d5 ...]`
 /// - `position fen <fen-string> [moves e2e4 d7d5 ...]`
+///
+/// The moves are kept as sent; see [`PositionInfo::game`].
 fn parse_position(tokens: &[&str]) -> Result<Command, UciError> {
     if tokens.is_empty() {
         return Err(UciError::MalformedPosition);
     }
 
-    let (mut board, rest) = if tokens[0] == "startpos" {
+    let (board, rest) = if tokens[0] == "startpos" {
         let rest = &tokens[1..];
         (Board::starting_position(), rest)
     } else if tokens[0] == "fen" {
@@ -123,36 +244,40 @@ fn parse_position(tokens: &[&str]) -> Result<Command, UciError> {
         if tokens.len() < 7 {
             return Err(UciError::InvalidFen {
                 fen: tokens[1..].join(" "),
+                source: FenError::WrongFieldCount { found: tokens.len() - 1 },
             });
         }
         let fen = tokens[1..7].join(" ");
-        let board: Board = fen.parse().map_err(|_| UciError::InvalidFen {
+        let board = Board::from_fen_strict(&fen).map_err(|source| UciError::InvalidFen {
             fen: fen.clone(),
+            source,
         })?;
         (board, &tokens[7..])
     } else {
         return Err(UciError::MalformedPosition);
     };
 
-    // Apply moves if present: "moves e2e4 d7d5 ..."
-    let mut history = Vec::new();
-    if !rest.is_empty() && rest[0] == "moves" {
-        for uci_str in &rest[1..] {
-            history.push(board.hash());
-            let mv = Move::from_uci(uci_str, &board).ok_or_else(|| UciError::InvalidMove {
-                uci_move: uci_str.to_string(),
-            })?;
-            board = board.make_move(mv);
-        }
-    }
+    // Moves if present: "moves e2e4 d7d5 ..."
+    let moves = match rest.split_first() {
+        Some((&"moves", moves)) => moves.iter().map(|uci| uci.to_string()).collect(),
+        _ => Vec::new(),
+    };
 
-    Ok(Command::Position(PositionInfo { board, history }))
+    Ok(Command::Position(PositionInfo { start: board, moves }))
 }
 
+/// Parameter names recognised by `go`, which end a `searchmoves` list.
+const GO_PARAMS: &[&str] = &[
+    "wtime", "btime", "winc", "binc", "movestogo", "depth", "movetime", "nodes", "mate",
+    "infinite", "ponder", "perft", "searchmoves",
+];
+
 /// Parse the `go` command arguments.
 ///
 /// Supports: wtime, btime, winc, binc, movestogo, depth, movetime,
-/// nodes, infinite, ponder. Unknown tokens are silently skipped.
+/// nodes, mate, infinite, ponder, perft, searchmoves. `searchmoves` takes
+/// every following token up to the next parameter name. Unknown tokens are
+/// silently skipped.
 fn parse_go(tokens: &[&str]) -> Result<Command, UciError> {
     let mut params = GoParams::default();
 
@@ -191,6 +316,10 @@ fn parse_go(tokens: &[&str]) -> Result<Command, UciError> {
                 params.nodes = Some(parse_int(tokens.get(i + 1), "nodes")?);
                 i += 2;
             }
+            "mate" => {
+                params.mate = Some(parse_int(tokens.get(i + 1), "mate")?);
+                i += 2;
+            }
             "infinite" => {
                 params.infinite = true;
                 i += 1;
@@ -199,6 +328,19 @@ fn parse_go(tokens: &[&str]) -> Result<Command, UciError> {
                 params.ponder = true;
                 i += 1;
             }
+            "perft" => {
+                params.perft = Some(parse_int(tokens.get(i + 1), "perft")?);
+                i += 2;
+            }
+            "searchmoves" => {
+                let moves: Vec<String> = tokens[i + 1..]
+                    .iter()
+                    .take_while(|t| !GO_PARAMS.contains(t))
+                    .map(|t| t.to_string())
+                    .collect();
+                i += 1 + moves.len();
+                params.searchmoves = Some(moves);
+            }
             _ => {
                 // Unknown token -- skip per UCI convention
                 i += 1;
@@ -212,15 +354,17 @@ fn parse_go(tokens: &[&str]) -> Result<Command, UciError> {
 /// Parse the `setoption` command arguments.
 ///
 /// Supports: `setoption name <name> [value <value>]` per UCI spec.
-/// Option names are matched case-insensitively. Unknown option names
-/// produce [`Command::Unknown`] (silently ignored per UCI spec).
+/// Options are looked up in [`crate::options::OPTIONS`], case-insensitively,
+/// and spin values are clamped to the advertised bounds. Both the name and
+/// the value may contain spaces; runs of whitespace collapse to one space.
+/// Unknown option names produce [`Command::Unknown`] (silently ignored per UCI spec).
 ///
 /// # Errors
 ///
 /// | Condition | Error |
 /// |---|---|
 /// | Missing `name` keyword | [`UciError::MalformedSetOption`] |
-/// | Invalid or missing value | [`UciError::InvalidOptionValue`] |
+/// | Invalid or missing value, or extra tokens after a spin/check value | [`UciError::InvalidOptionValue`] |
 fn parse_setoption(tokens: &[&str]) -> Result<Command, UciError> {
     // Require the "name" keyword as the first token
     if tokens.is_empty() || tokens[0] != "name" {
@@ -231,69 +375,22 @@ fn parse_setoption(tokens: &[&str]) -> Result<Command, UciError> {
     let rest = &tokens[1..];
     let value_pos = rest.iter().position(|&t| t == "value");
 
-    let (name_tokens, value_token) = match value_pos {
-        Some(pos) => (&rest[..pos], rest.get(pos + 1).copied()),
-        None => (rest, None),
+    let (name_tokens, value_tokens) = match value_pos {
+        Some(pos) => (&rest[..pos], &rest[pos + 1..]),
+        None => (rest, &rest[rest.len()..]),
     };
 
-    let name = name_tokens.join(" ").to_lowercase();
+    let name = name_tokens.join(" ");
+    let Some(spec) = OptionSpec::find(&name) else {
+        return Ok(Command::Unknown(name.to_lowercase()));
+    };
 
-    match name.as_str() {
-        "hash" => {
-            let raw = value_token.ok_or_else(|| UciError::InvalidOptionValue {
-                name: "Hash".to_string(),
-                value: String::new(),
-            })?;
-            let parsed: u32 = raw.parse().map_err(|_| UciError::InvalidOptionValue {
-                name: "Hash".to_string(),
-                value: raw.to_string(),
-            })?;
-            let clamped = parsed.clamp(1, 65536);
-            Ok(Command::SetOption(UciOption::Hash(clamped)))
-        }
-        "threads" => {
-            let raw = value_token.ok_or_else(|| UciError::InvalidOptionValue {
-                name: "Threads".to_string(),
-                value: String::new(),
-            })?;
-            let parsed: u32 = raw.parse().map_err(|_| UciError::InvalidOptionValue {
-                name: "Threads".to_string(),
-                value: raw.to_string(),
-            })?;
-            let clamped = parsed.clamp(1, 256) as u16;
-            Ok(Command::SetOption(UciOption::Threads(clamped)))
-        }
-        "ponder" => {
-            let raw = value_token.ok_or_else(|| UciError::InvalidOptionValue {
-                name: "Ponder".to_string(),
-                value: String::new(),
-            })?;
-            let enabled = match raw {
-                "true" => true,
-                "false" => false,
-                _ => {
-                    return Err(UciError::InvalidOptionValue {
-                        name: "Ponder".to_string(),
-                        value: raw.to_string(),
-                    });
-                }
-            };
-            Ok(Command::SetOption(UciOption::Ponder(enabled)))
-        }
-        "contempt" => {
-            let raw = value_token.ok_or_else(|| UciError::InvalidOptionValue {
-                name: "Contempt".to_string(),
-                value: String::new(),
-            })?;
-            let parsed: i32 = raw.parse().map_err(|_| UciError::InvalidOptionValue {
-                name: "Contempt".to_string(),
-                value: raw.to_string(),
-            })?;
-            let clamped = parsed.clamp(-300, 300);
-            Ok(Command::SetOption(UciOption::Contempt(clamped)))
-        }
-        _ => Ok(Command::Unknown(name)),
-    }
+    // The value is everything after `value`: string options keep the spaces,
+    // while spin and check options reject anything beyond a single token
+    let value = value_tokens.join(" ");
+    let raw = (!value_tokens.is_empty()).then_some(value.as_str());
+
+    spec.parse(raw).map(Command::SetOption)
 }
 
 /// Parse a millisecond value from a token.
@@ -308,6 +405,15 @@ fn parse_millis(token: Option<&&str>, param: &str) -> Result<Duration, UciError>
     Ok(Duration::from_millis(ms))
 }
 
+/// Parse the `bench` arguments: an optional depth, defaulting to [`BENCH_DEPTH`].
+fn parse_bench(tokens: &[&str]) -> Result<Command, UciError> {
+    let depth = match tokens.first() {
+        Some(_) => parse_int(tokens.first(), "depth")?,
+        None => BENCH_DEPTH,
+    };
+    Ok(Command::Bench(depth))
+}
+
 /// Parse an integer value from a token.
 fn parse_int<T: std::str::FromStr>(token: Option<&&str>, param: &str) -> Result<T, UciError> {
     let value = token.ok_or_else(|| UciError::MissingGoValue {
@@ -323,6 +429,8 @@ fn parse_int<T: std::str::FromStr>(token: Option<&&str>, param: &str) -> Result<
 mod tests {
     use std::time::Duration;
 
+    use cesso_core::BoardError;
+
     use super::*;
 
     #[test]
@@ -378,6 +486,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_go_mate() {
+        let cmd = parse_command("go mate 3").unwrap();
+        match cmd {
+            Command::Go(params) => assert_eq!(params.mate, Some(3)),
+            _ => panic!("expected Go"),
+        }
+    }
+
+    #[test]
+    fn parse_go_perft() {
+        let cmd = parse_command("go perft 6").unwrap();
+        match cmd {
+            Command::Go(params) => assert_eq!(params.perft, Some(6)),
+            _ => panic!("expected Go"),
+        }
+    }
+
+    #[test]
+    fn parse_go_searchmoves_stops_at_next_param() {
+        let cmd = parse_command("go searchmoves e2e4 d2d4 depth 5").unwrap();
+        match cmd {
+            Command::Go(params) => {
+                assert_eq!(params.searchmoves, Some(vec!["e2e4".to_string(), "d2d4".to_string()]));
+                assert_eq!(params.depth, Some(5));
+            }
+            _ => panic!("expected Go"),
+        }
+    }
+
     #[test]
     fn parse_go_bare_defaults() {
         let cmd = parse_command("go").unwrap();
@@ -505,6 +643,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_position_rejects_unreachable_fen() {
+        let err = parse_command("position fen 4k3/8/8/8/8/8/8/4R1K1 w - - 0 1").unwrap_err();
+        assert!(matches!(
+            err,
+            UciError::InvalidFen { source: FenError::InvalidBoard { source: BoardError::OpponentInCheck { .. } }, .. }
+        ));
+        assert!(err.to_string().contains("black is not to move but its king is in check"), "{err}");
+    }
+
     #[test]
     fn parse_stop() {
         assert!(matches!(parse_command("stop").unwrap(), Command::Stop));
@@ -579,17 +727,63 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn assert_illegal_move(line: &str, bad: &str, at: usize) {
+        let Ok(Command::Position(info)) = parse_command(line) else {
+            panic!("expected Position for {line}");
+        };
+        match info.game() {
+            Err(UciError::InvalidMove { uci_move, ply }) => {
+                assert_eq!(uci_move, bad);
+                assert_eq!(ply, at);
+            }
+            other => panic!("expected InvalidMove for {line}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_position_rejects_teleporting_pawn() {
+        assert_illegal_move("position startpos moves e2e5", "e2e5", 0);
+    }
+
+    #[test]
+    fn parse_position_rejects_illegal_capture() {
+        // The e4 pawn cannot capture straight ahead onto e5
+        assert_illegal_move("position startpos moves e2e4 e7e5 e4e5", "e4e5", 2);
+    }
+
+    #[test]
+    fn parse_position_rejects_wrong_side_to_move() {
+        assert_illegal_move("position startpos moves e2e4 d2d4", "d2d4", 1);
+    }
+
+    #[test]
+    fn parse_position_rejects_castling_without_rights() {
+        // The king steps out and back, losing its castling rights
+        assert_illegal_move(
+            "position fen r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1 moves e1f1 e8f8 f1e1 f8e8 e1g1",
+            "e1g1",
+            4,
+        );
+    }
+
     #[test]
     fn parse_position_builds_history() {
         let cmd = parse_command("position startpos moves e2e4 e7e5").unwrap();
         match cmd {
             Command::Position(info) => {
-                assert_eq!(info.history.len(), 2, "2 moves should produce 2 history entries");
+                assert_eq!(info.game().unwrap().history().len(), 2, "2 moves should produce 2 history entries");
             }
             _ => panic!("expected Position"),
         }
     }
 
+    #[test]
+    fn parse_bench_default_and_explicit_depth() {
+        assert!(matches!(parse_command("bench").unwrap(), Command::Bench(BENCH_DEPTH)));
+        assert!(matches!(parse_command("bench 6").unwrap(), Command::Bench(6)));
+        assert!(parse_command("bench deep").is_err());
+    }
+
     #[test]
     fn parse_draw() {
         assert!(matches!(parse_command("draw").unwrap(), Command::Draw));
@@ -601,6 +795,21 @@ mod tests {
         assert!(matches!(cmd, Command::SetOption(UciOption::Contempt(50))));
     }
 
+    #[test]
+    fn parse_setoption_syzygy_path() {
+        let cmd = parse_command("setoption name SyzygyPath value /tb/3-4-5:/tb/my tables").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::SetOption(UciOption::SyzygyPath(ref p)) if p == "/tb/3-4-5:/tb/my tables"
+        ));
+    }
+
+    #[test]
+    fn parse_setoption_syzygy_path_empty() {
+        let cmd = parse_command("setoption name SyzygyPath value <empty>").unwrap();
+        assert!(matches!(cmd, Command::SetOption(UciOption::SyzygyPath(ref p)) if p.is_empty()));
+    }
+
     #[test]
     fn parse_setoption_contempt_negative() {
         let cmd = parse_command("setoption name Contempt value -100").unwrap();
@@ -618,4 +827,39 @@ mod tests {
         let cmd = parse_command("setoption name Contempt value -999").unwrap();
         assert!(matches!(cmd, Command::SetOption(UciOption::Contempt(-300))));
     }
+
+    #[test]
+    fn parse_setoption_path_with_spaces() {
+        let cmd = parse_command(r"setoption name SyzygyPath value C:\tb\3-4-5 wdl").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::SetOption(UciOption::SyzygyPath(ref p)) if p == r"C:\tb\3-4-5 wdl"
+        ));
+    }
+
+    #[test]
+    fn parse_setoption_collapses_repeated_spaces_in_value() {
+        let cmd = parse_command("setoption name SyzygyPath value /tb/my    tables").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::SetOption(UciOption::SyzygyPath(ref p)) if p == "/tb/my tables"
+        ));
+    }
+
+    #[test]
+    fn parse_setoption_name_with_spaces() {
+        let cmd = parse_command("setoption name Move Overhead value 100").unwrap();
+        assert!(matches!(cmd, Command::Unknown(ref name) if name == "move overhead"));
+    }
+
+    #[test]
+    fn parse_setoption_spin_rejects_trailing_tokens() {
+        assert!(parse_command("setoption name Hash value 64 128").is_err());
+        assert!(parse_command("setoption name Threads value 4 threads").is_err());
+    }
+
+    #[test]
+    fn parse_setoption_check_rejects_trailing_tokens() {
+        assert!(parse_command("setoption name Ponder value true please").is_err());
+    }
 }
@@ -1,17 +1,24 @@
 //! Event-driven, multi-threaded UCI engine with pondering support.
 
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, BufReader, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, mpsc};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
-use cesso_core::Board;
-use cesso_engine::{DrawDecision, SearchControl, SearchResult, ThreadPool, decide_draw, limits_from_go};
+use cesso_core::{Board, Game, Move, Outcome, divide_hashed, generate_legal_moves, print_divide};
+use cesso_engine::{
+    AnalysisInfo, AnalysisSink, BARE_GO_DEPTH, BookRng, DrawDecision, MultiBook, SearchControl,
+    SearchResult, SearcherBuilder, Tablebases, ThreadPool, EvalMode, bench, decide_draw, is_bare_go, limits_from_go, mate_in,
+};
 use cesso_engine::eval::phase::game_phase;
 
 use crate::command::{GoParams, UciOption, parse_command, Command, PositionInfo};
 use crate::error::UciError;
+use crate::options::OPTIONS;
+use crate::output::UciOutput;
 
 /// Configuration knobs adjustable via `setoption`.
 struct EngineConfig {
@@ -21,6 +28,14 @@ struct EngineConfig {
     threads: u16,
     /// Contempt factor in centipawns — positive values make the engine avoid draws.
     contempt: i32,
+    /// `UCI_AnalyseMode`: ignore contempt and report refutations.
+    analyse_mode: bool,
+    /// `UCI_ShowCurrLine`: report `currline` while analysing.
+    show_currline: bool,
+    /// `BookTemperature`: randomness of book move selection.
+    book_temperature: f32,
+    /// `EvalMode`: which evaluation the search uses.
+    eval_mode: EvalMode,
 }
 
 impl Default for EngineConfig {
@@ -29,10 +44,17 @@ impl Default for EngineConfig {
             hash_mb: 16,
             threads: 1,
             contempt: 0,
+            analyse_mode: false,
+            show_currline: false,
+            book_temperature: 1.0,
+            eval_mode: EvalMode::Full,
         }
     }
 }
 
+/// How long `quit` (or end of input) waits for an active search to wind down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// Internal engine state — tracks whether the engine is idle, searching, or pondering.
 enum EngineState {
     Idle,
@@ -42,7 +64,9 @@ enum EngineState {
 
 /// Events processed by the main engine loop.
 enum EngineEvent {
-    UciCommand(Result<Command, UciError>),
+    UciCommand(Command),
+    /// A line that failed to parse, reported to the GUI and otherwise ignored.
+    InvalidCommand { line: String, error: UciError },
     SearchDone(SearchDone),
     InputClosed,
 }
@@ -58,8 +82,7 @@ struct SearchDone {
 /// Runs an event-driven loop on the main thread, dispatching searches
 /// to a worker thread and processing UCI commands concurrently.
 pub struct UciEngine {
-    board: Board,
-    history: Vec<u64>,
+    game: Game,
     pool: Option<ThreadPool>,
     state: EngineState,
     stop_flag: Arc<AtomicBool>,
@@ -68,37 +91,84 @@ pub struct UciEngine {
     /// Whether the opponent has offered a draw (set by `Command::Draw`).
     opponent_draw_offer: bool,
     pending_clear_tt: bool,
-    /// Pending TT resize (MB) to apply when the search thread returns the pool.
-    pending_resize_tt: Option<u32>,
+    /// Whether search options changed while the search thread owned the pool.
+    pending_reconfigure: bool,
+    /// Syzygy tablebases from the `SyzygyPath` option, handed to the pool on each `go`.
+    tablebases: Option<Arc<Tablebases>>,
+    /// Opening books from the `BookPath` option, consulted before searching.
+    book: Option<MultiBook>,
+    /// Random source for weighted book move selection.
+    book_rng: BookRng,
+    /// Handle of the worker thread running the current search.
+    search_thread: Option<JoinHandle<()>>,
+    /// Plies played since the last searched position, if the current position
+    /// continues the same game; `None` forces fresh search heuristics.
+    continuation: Option<usize>,
+    /// The `position` command that set up `game`, if any; a command that
+    /// extends it only has its new moves played.
+    position: Option<PositionInfo>,
+    /// Reply to a `go` answered without searching, held back under
+    /// `go infinite` or `go ponder` until `stop` or `ponderhit`.
+    held_bestmove: Option<String>,
+    /// Destination of all protocol output, shared with the search thread.
+    output: Arc<UciOutput>,
 }
 
 impl UciEngine {
     /// Create a new engine with the starting position.
     pub fn new() -> Self {
+        let config = EngineConfig::default();
         Self {
-            board: Board::starting_position(),
-            history: Vec::new(),
-            pool: Some(ThreadPool::new(16)),
+            game: Game::default(),
+            pool: Some(pool_builder(&config).build_pool()),
             state: EngineState::Idle,
             stop_flag: Arc::new(AtomicBool::new(false)),
             control: None,
-            config: EngineConfig::default(),
+            config,
             opponent_draw_offer: false,
             pending_clear_tt: false,
-            pending_resize_tt: None,
+            pending_reconfigure: false,
+            tablebases: None,
+            book: None,
+            book_rng: BookRng::new(seed_from_clock()),
+            continuation: None,
+            position: None,
+            held_bestmove: None,
+            search_thread: None,
+            output: Arc::new(UciOutput::stdout()),
         }
     }
 
     /// Run the UCI event loop, reading from stdin until `quit` or input closes.
-    pub fn run(mut self) -> Result<(), UciError> {
+    pub fn run(self) -> Result<(), UciError> {
+        self.run_with_input(BufReader::new(io::stdin()))
+    }
+
+    /// Run the UCI event loop on an arbitrary command source, writing to stdout.
+    pub fn run_with_input<R>(self, input: R) -> Result<(), UciError>
+    where
+        R: BufRead + Send + 'static,
+    {
+        self.run_with_io(input, io::stdout())
+    }
+
+    /// Run the UCI event loop on an arbitrary command source and output sink.
+    ///
+    /// Returns after `quit`, when `input` is exhausted, or when a write to
+    /// `output` fails (e.g. the GUI closed the pipe); any active search is
+    /// stopped and joined first, without printing its `bestmove`.
+    pub fn run_with_io<R, W>(mut self, input: R, output: W) -> Result<(), UciError>
+    where
+        R: BufRead + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        self.output = Arc::new(UciOutput::new(output));
         let (tx, rx) = mpsc::channel::<EngineEvent>();
 
-        // Spawn stdin reader thread
+        // Spawn input reader thread
         let stdin_tx = tx.clone();
         std::thread::spawn(move || {
-            let stdin = io::stdin();
-            let reader = stdin.lock();
-            for line in reader.lines() {
+            for line in input.lines() {
                 match line {
                     Ok(line) => {
                         let trimmed = line.trim().to_string();
@@ -106,8 +176,11 @@ impl UciEngine {
                             continue;
                         }
                         debug!(cmd = %trimmed, "received UCI command");
-                        let cmd = parse_command(&trimmed);
-                        if stdin_tx.send(EngineEvent::UciCommand(cmd)).is_err() {
+                        let event = match parse_command(&trimmed) {
+                            Ok(cmd) => EngineEvent::UciCommand(cmd),
+                            Err(error) => EngineEvent::InvalidCommand { line: trimmed, error },
+                        };
+                        if stdin_tx.send(event).is_err() {
                             break;
                         }
                     }
@@ -122,7 +195,7 @@ impl UciEngine {
 
         for event in &rx {
             match event {
-                EngineEvent::UciCommand(Ok(cmd)) => match cmd {
+                EngineEvent::UciCommand(cmd) => match cmd {
                     Command::Uci => self.handle_uci(),
                     Command::IsReady => self.handle_isready(),
                     Command::UciNewGame => self.handle_ucinewgame(),
@@ -131,56 +204,54 @@ impl UciEngine {
                     Command::SetOption(opt) => self.handle_setoption(opt),
                     Command::PonderHit => self.handle_ponderhit(),
                     Command::Stop => self.handle_stop(),
-                    Command::Quit => {
-                        // Stop any active search and wait for it to finish
-                        if !matches!(self.state, EngineState::Idle) {
-                            self.handle_stop();
-                            // Drain events until we get SearchDone
-                            for ev in &rx {
-                                if let EngineEvent::SearchDone(done) = ev {
-                                    self.finish_search(done);
-                                    break;
-                                }
-                            }
-                        }
-                        break;
-                    }
+                    Command::Quit => break,
                     Command::Draw => {
                         self.opponent_draw_offer = true;
                     }
+                    Command::Bench(depth) => self.handle_bench(depth),
                     Command::Unknown(_) => {}
                 },
-                EngineEvent::UciCommand(Err(e)) => {
-                    warn!(error = %e, "UCI parse error");
-                }
+                EngineEvent::InvalidCommand { line, error } => self.report_invalid(&line, &error),
                 EngineEvent::SearchDone(done) => {
                     self.finish_search(done);
                 }
                 EngineEvent::InputClosed => break,
             }
+
+            if self.output.is_closed() {
+                info!("output closed, shutting down");
+                break;
+            }
         }
 
+        self.shutdown(&rx);
+
         info!("cesso shutting down");
         Ok(())
     }
 
+    /// Report a rejected command; the previous position and search state stay as they were.
+    fn report_invalid(&self, line: &dyn std::fmt::Display, error: &UciError) {
+        warn!(%error, %line, "UCI parse error");
+        self.output.line(format_args!("info string error: {error} (in \"{line}\")"));
+    }
+
     fn handle_uci(&self) {
-        println!("id name cesso");
-        println!("id author Nicolas Lazaro");
-        println!("option name Hash type spin default 16 min 1 max 65536");
-        println!("option name Threads type spin default 1 min 1 max 256");
-        println!("option name Ponder type check default false");
-        println!("option name Contempt type spin default 0 min -300 max 300");
-        println!("uciok");
+        self.output.line("id name cesso");
+        self.output.line("id author Nicolas Lazaro");
+        for option in OPTIONS {
+            self.output.line(option);
+        }
+        self.output.line("uciok");
     }
 
     fn handle_isready(&self) {
-        println!("readyok");
+        self.output.line("readyok");
     }
 
     fn handle_ucinewgame(&mut self) {
-        self.board = Board::starting_position();
-        self.history.clear();
+        self.game = Game::default();
+        self.position = None;
         if let Some(ref pool) = self.pool {
             pool.clear_tt();
         } else {
@@ -188,36 +259,120 @@ impl UciEngine {
             self.pending_clear_tt = true;
         }
         self.opponent_draw_offer = false;
+        self.continuation = None;
+    }
+
+    /// Apply the search options to the pool, or once the search thread returns it.
+    fn reconfigure_pool(&mut self) {
+        let builder = pool_builder(&self.config);
+        match self.pool {
+            Some(ref mut pool) => {
+                pool.reconfigure(&builder);
+            }
+            None => self.pending_reconfigure = true,
+        }
     }
 
     fn handle_setoption(&mut self, option: UciOption) {
         match option {
             UciOption::Hash(mb) => {
                 self.config.hash_mb = mb;
-                if let Some(ref mut pool) = self.pool {
-                    pool.resize_tt(mb as usize);
-                } else {
-                    self.pending_resize_tt = Some(mb);
-                }
+                self.reconfigure_pool();
             }
             UciOption::Threads(threads) => {
                 self.config.threads = threads;
-                if let Some(ref mut pool) = self.pool {
-                    pool.set_num_threads(threads as usize);
-                }
+                self.reconfigure_pool();
             }
             UciOption::Ponder(_) => {
                 // Ponder option acknowledged — actual pondering is handled by the go ponder protocol
             }
             UciOption::Contempt(cp) => {
                 self.config.contempt = cp;
+                self.reconfigure_pool();
+            }
+            UciOption::SyzygyPath(path) => {
+                if path.is_empty() {
+                    self.tablebases = None;
+                    return;
+                }
+                let tb = Tablebases::load(&path);
+                self.output.line(format_args!("info string Found {} tablebase files", tb.file_count()));
+                self.tablebases = (tb.file_count() > 0).then(|| Arc::new(tb));
+            }
+            UciOption::AnalyseMode(on) => {
+                self.config.analyse_mode = on;
+                self.reconfigure_pool();
+            }
+            UciOption::ShowCurrLine(on) => {
+                self.config.show_currline = on;
+            }
+            UciOption::BookPath(path) => {
+                if path.is_empty() {
+                    self.book = None;
+                    return;
+                }
+                match MultiBook::open(&path) {
+                    Ok(book) => {
+                        self.output.line(format_args!("info string Loaded {} book entries", book.len()));
+                        self.book = Some(book);
+                    }
+                    Err(error) => {
+                        warn!(%error, "book not loaded");
+                        self.output.line(format_args!("info string error: {error}"));
+                        self.book = None;
+                    }
+                }
+            }
+            UciOption::BookTemperature(temperature) => {
+                self.config.book_temperature = temperature;
+            }
+            UciOption::EvalMode(mode) => {
+                self.config.eval_mode = mode;
+                self.reconfigure_pool();
             }
         }
     }
 
     fn handle_position(&mut self, info: PositionInfo) {
-        self.board = info.board;
-        self.history = info.history;
+        // Moves already played for the previous command are not replayed
+        let appended = self.position.as_ref().and_then(|previous| info.appended_to(previous));
+        let game = match appended {
+            Some(plies) => info.play_from(self.game.clone(), info.moves.len() - plies),
+            None => info.game(),
+        };
+        match game {
+            Ok(game) => {
+                self.continuation = self.continuation.zip(appended).map(|(searched, plies)| searched + plies);
+                self.game = game;
+                self.position = Some(info);
+            }
+            Err(error) => self.report_invalid(&info, &error),
+        }
+    }
+
+    /// Handle `bench`: search the bench positions and print total nodes and speed.
+    ///
+    /// Runs on the engine loop, so other commands wait until it finishes.
+    fn handle_bench(&mut self, depth: u8) {
+        let Some(pool) = self.pool.as_mut() else {
+            warn!("bench received while searching, ignoring");
+            return;
+        };
+        let result = bench(pool, depth);
+        // Bench leaves its own positions in the TT and heuristics
+        self.continuation = None;
+        self.output.line(format_args!("info string bench: {} nodes {} nps", result.nodes, result.nps()));
+    }
+
+    /// Handle `go perft N`: print a per-move divide and the total node count.
+    ///
+    /// Subtree counts are cached in a table the size of the `Hash` option.
+    fn handle_perft(&self, depth: u8) {
+        let breakdown = divide_hashed(self.game.board(), depth as usize, self.config.hash_mb as usize);
+        let mut report = String::new();
+        // Writing to a String never fails
+        let _ = print_divide(&mut report, &breakdown);
+        self.output.line(report.trim_end_matches('\n'));
     }
 
     fn handle_go(&mut self, params: GoParams, tx: &mpsc::Sender<EngineEvent>) {
@@ -226,38 +381,113 @@ impl UciEngine {
             return;
         }
 
+        if let Some(depth) = params.perft {
+            self.handle_perft(depth);
+            return;
+        }
+
+        // Nothing to search once the game is over
+        let score = match self.game.outcome() {
+            Some(Outcome::Checkmate { .. }) => Some("mate 0"),
+            Some(Outcome::Stalemate) => Some("cp 0"),
+            _ => None,
+        };
+        if let Some(score) = score {
+            self.output.line(format_args!("info depth 0 score {score}"));
+            self.answer_without_search(&params, "bestmove 0000".into());
+            return;
+        }
+
+        // A dead position is drawn whatever is played
+        if self.game.board().is_insufficient_material() {
+            let board = *self.game.board();
+            let best_move = params
+                .searchmoves
+                .as_deref()
+                .and_then(|moves| resolve_searchmoves(&board, moves).first().copied())
+                .or_else(|| generate_legal_moves(&board).as_slice().first().copied());
+            if let Some(mv) = best_move {
+                let contempt = search_contempt(&self.config);
+                self.output.line(format_args!("info depth 0 score cp {}", -contempt));
+                self.answer_without_search(&params, format!("bestmove {}", mv.to_uci()));
+                return;
+            }
+        }
+
+        // Play from the book in normal games only; analysis and restricted
+        // searches always want the engine's own view
+        let book_move = match &self.book {
+            Some(book) if !params.ponder && !params.infinite && !self.config.analyse_mode && params.searchmoves.is_none() => {
+                book.probe_weighted(self.game.board(), &mut self.book_rng, self.config.book_temperature)
+            }
+            _ => None,
+        };
+        if let Some(mv) = book_move {
+            self.output.line(format_args!("info string book move {}", mv.to_uci()));
+            self.output.line(format_args!("bestmove {}", mv.to_uci()));
+            return;
+        }
+
         // Reset stop flag
         self.stop_flag = Arc::new(AtomicBool::new(false));
 
-        let side = self.board.side_to_move();
-        let control = Arc::new(limits_from_go(
+        let side = self.game.board().side_to_move();
+        // `go mate N` is depth-limited to 2N plies, like `go depth`
+        let depth = params.depth.or(params.mate.map(|moves| moves.saturating_mul(2)));
+        let mut control = limits_from_go(
             params.wtime,
             params.btime,
             params.winc,
             params.binc,
             params.movestogo,
             params.movetime,
+            depth,
+            params.nodes,
             params.infinite,
             params.ponder,
             side,
             Arc::clone(&self.stop_flag),
-            &self.board,
-        ));
+            self.game.board(),
+        );
+        if let Some(moves) = params.mate {
+            control = control.with_mate(moves);
+        }
+        if self.config.analyse_mode {
+            control = control.with_analysis(analysis_output(Arc::clone(&self.output)), self.config.show_currline);
+        }
+        let control = Arc::new(control);
 
-        let max_depth = params.depth.unwrap_or(128);
+        let bare = is_bare_go(
+            params.wtime,
+            params.btime,
+            params.movetime,
+            depth,
+            params.nodes,
+            params.infinite,
+        );
+        let max_depth = depth.unwrap_or(if bare { BARE_GO_DEPTH } else { 128 });
 
         // Take the pool — the search thread will own it
-        let pool = self.pool.take().unwrap_or_default();
+        let mut pool = self.pool.take().unwrap_or_else(|| pool_builder(&self.config).build_pool());
+        pool.set_tablebases(self.tablebases.clone());
+        match self.continuation {
+            Some(plies) => pool.continue_game(plies),
+            None => pool.reset_heuristics(),
+        }
+        // A later `position` that extends this one may reuse the heuristics
+        self.continuation = Some(0);
+        let tablebases = self.tablebases.clone();
 
-        let board = self.board;
-        let history = self.history.clone();
+        let board = *self.game.board();
+        let history = self.game.history().to_vec();
         let search_control = Arc::clone(&control);
         let tx = tx.clone();
-        let contempt = self.config.contempt;
-        let engine_color = self.board.side_to_move();
+        let engine_color = self.game.board().side_to_move();
+        let output = Arc::clone(&self.output);
+        let searchmoves = params.searchmoves.map(|moves| resolve_searchmoves(&board, &moves));
 
-        std::thread::spawn(move || {
-            let result = pool.search(&board, max_depth, &search_control, &history, contempt, engine_color, |d, score, nodes, pv| {
+        let handle = std::thread::spawn(move || {
+            let result = pool.search(&board, max_depth, &search_control, &history, searchmoves, engine_color, |d, score, nodes, qnodes, hashfull, pv| {
                 let elapsed = search_control.elapsed();
                 let elapsed_ms = elapsed.as_millis().max(1);
                 let nps = (nodes as u128 * 1000) / elapsed_ms;
@@ -269,10 +499,21 @@ impl UciEngine {
                     .collect::<Vec<_>>()
                     .join(" ");
 
-                println!(
-                    "info depth {} score cp {} nodes {} nps {} time {} pv {}",
-                    d, score, nodes, nps, elapsed_ms, pv_str
-                );
+                let tbhits = tablebases.as_ref().map_or(0, |tb| tb.hits());
+
+                let written = output.line(format_args!(
+                    "info depth {} score {} nodes {} nps {} hashfull {} tbhits {} time {} pv {}",
+                    d, uci_score(score), nodes, nps, hashfull, tbhits, elapsed_ms, pv_str
+                ));
+                let written = written && output.line(format_args!(
+                    "info string qnodes {} qratio {}%",
+                    qnodes,
+                    qnodes * 100 / nodes.max(1)
+                ));
+                // Nobody is listening any more — end the search early
+                if !written {
+                    search_control.stop_flag().store(true, Ordering::Release);
+                }
             });
             let _ = tx.send(EngineEvent::SearchDone(SearchDone { result, pool }));
         });
@@ -283,6 +524,30 @@ impl UciEngine {
             EngineState::Searching
         };
         self.control = Some(control);
+        self.search_thread = Some(handle);
+    }
+
+    /// Send the reply to a `go` that needed no search.
+    ///
+    /// `go infinite` and `go ponder` must not answer before `stop` or
+    /// `ponderhit`, so there the reply is held and the engine stays busy.
+    fn answer_without_search(&mut self, params: &GoParams, bestmove: String) {
+        if !params.infinite && !params.ponder {
+            self.output.line(bestmove);
+            return;
+        }
+        self.held_bestmove = Some(bestmove);
+        self.state = if params.ponder { EngineState::Pondering } else { EngineState::Searching };
+    }
+
+    /// Send a held `go` reply, if any, and return to idle.
+    fn release_held_bestmove(&mut self) -> bool {
+        let Some(bestmove) = self.held_bestmove.take() else {
+            return false;
+        };
+        self.output.line(bestmove);
+        self.state = EngineState::Idle;
+        true
     }
 
     fn handle_ponderhit(&mut self) {
@@ -290,6 +555,9 @@ impl UciEngine {
             warn!("ponderhit received while not pondering, ignoring");
             return;
         }
+        if self.release_held_bestmove() {
+            return;
+        }
         if let Some(ref control) = self.control {
             control.activate();
         }
@@ -297,15 +565,79 @@ impl UciEngine {
     }
 
     fn handle_stop(&mut self) {
+        if self.release_held_bestmove() {
+            return;
+        }
         self.stop_flag.store(true, Ordering::Release);
     }
 
+    /// Stop and join an active search before exiting, discarding its result.
+    ///
+    /// Waits at most [`SHUTDOWN_TIMEOUT`]; a search that has not returned by
+    /// then is left detached so `quit` never hangs.
+    fn shutdown(&mut self, rx: &mpsc::Receiver<EngineEvent>) {
+        if matches!(self.state, EngineState::Idle) {
+            return;
+        }
+        if self.held_bestmove.take().is_some() {
+            self.state = EngineState::Idle;
+            return;
+        }
+        self.handle_stop();
+
+        let deadline = std::time::Instant::now() + SHUTDOWN_TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            match rx.recv_timeout(remaining) {
+                Ok(EngineEvent::SearchDone(done)) => {
+                    self.pool = Some(done.pool);
+                    if let Some(handle) = self.search_thread.take()
+                        && handle.join().is_err()
+                    {
+                        warn!("search thread panicked during shutdown");
+                    }
+                    break;
+                }
+                Ok(_) => continue,
+                Err(_) => {
+                    warn!("search did not stop within the shutdown timeout");
+                    break;
+                }
+            }
+        }
+
+        self.state = EngineState::Idle;
+        self.control = None;
+    }
+
+    /// Check the searcher's move against the legal moves before it is sent.
+    ///
+    /// An illegal move means a search bug; rather than forfeit the game, it is
+    /// reported and replaced by the first legal move, without a ponder move.
+    fn legal_best_move(&self, best_move: Move, ponder_move: Option<Move>) -> (Move, Option<Move>) {
+        let legal = generate_legal_moves(self.game.board());
+        if legal.as_slice().contains(&best_move) || legal.is_empty() {
+            return (best_move, ponder_move);
+        }
+        error!(fen = %self.game.board().to_fen(), best_move = %best_move.to_uci(), "search returned an illegal bestmove");
+        self.output.line(format_args!(
+            "info string ERROR: illegal bestmove {} falling back to first legal move",
+            best_move.to_uci()
+        ));
+        (legal[0], None)
+    }
+
     fn finish_search(&mut self, done: SearchDone) {
         let mut pool = done.pool;
+        // The worker exits right after handing back the pool
+        if let Some(handle) = self.search_thread.take()
+            && handle.join().is_err()
+        {
+            warn!("search thread panicked");
+        }
 
-        if let Some(mb) = self.pending_resize_tt.take() {
-            // Resize supersedes clear — a fresh allocation is already empty
-            pool.resize_tt(mb as usize);
+        // A resize supersedes clear — a fresh allocation is already empty
+        if std::mem::take(&mut self.pending_reconfigure) && pool.reconfigure(&pool_builder(&self.config)) {
             self.pending_clear_tt = false;
         } else if self.pending_clear_tt {
             pool.clear_tt();
@@ -313,17 +645,22 @@ impl UciEngine {
         }
 
         self.pool = Some(pool);
-        self.control = None;
+        // A mate search reports 0 when it finds no mate; that is not a draw
+        let mate_search = self.control.take().is_some_and(|control| control.mate_moves().is_some());
 
         let result = &done.result;
 
         // Evaluate draw decision
-        let draw_decision = decide_draw(
-            result.score,
-            self.config.contempt,
-            game_phase(&self.board),
-            self.opponent_draw_offer,
-        );
+        let draw_decision = if mate_search {
+            DrawDecision::PlayOn
+        } else {
+            decide_draw(
+                result.score,
+                self.config.contempt,
+                game_phase(self.game.board()),
+                self.opponent_draw_offer,
+            )
+        };
         self.opponent_draw_offer = false; // consume regardless of decision
 
         let draw_suffix = if matches!(draw_decision, DrawDecision::Accept | DrawDecision::Offer) {
@@ -332,20 +669,21 @@ impl UciEngine {
             ""
         };
 
-        if result.best_move.is_null() {
-            println!("bestmove 0000");
+        let (best_move, ponder_move) = self.legal_best_move(result.best_move, result.ponder_move);
+        if best_move.is_null() {
+            self.output.line("bestmove 0000");
         } else {
-            match result.ponder_move {
+            match ponder_move {
                 Some(pm) if !pm.is_null() => {
-                    println!(
+                    self.output.line(format_args!(
                         "bestmove {} ponder {}{}",
-                        result.best_move.to_uci(),
+                        best_move.to_uci(),
                         pm.to_uci(),
                         draw_suffix,
-                    );
+                    ));
                 }
                 _ => {
-                    println!("bestmove {}{}", result.best_move.to_uci(), draw_suffix);
+                    self.output.line(format_args!("bestmove {}{}", best_move.to_uci(), draw_suffix));
                 }
             }
         }
@@ -354,8 +692,146 @@ impl UciEngine {
     }
 }
 
+/// Format a search score for an `info` line: `mate N` for forced mates, `cp N` otherwise.
+fn uci_score(score: i32) -> String {
+    match mate_in(score) {
+        Some(moves) => format!("mate {moves}"),
+        None => format!("cp {score}"),
+    }
+}
+
+/// Analysis sink that prints `info refutation` and `info currline` lines.
+fn analysis_output(output: Arc<UciOutput>) -> AnalysisSink {
+    Box::new(move |info| {
+        let (prefix, line) = match info {
+            AnalysisInfo::Refutation(line) => ("refutation", line),
+            AnalysisInfo::CurrLine(line) => ("currline 1", line),
+        };
+        let moves: Vec<String> = line.iter().map(|mv| mv.to_uci()).collect();
+        output.line(format_args!("info {prefix} {}", moves.join(" ")));
+    })
+}
+
+/// Contempt applied in search: analysis wants objective scores, so contempt
+/// only applies in play.
+fn search_contempt(config: &EngineConfig) -> i32 {
+    if config.analyse_mode { 0 } else { config.contempt }
+}
+
+/// Search pool configuration for the current option values.
+fn pool_builder(config: &EngineConfig) -> SearcherBuilder {
+    SearcherBuilder::default()
+        .tt_size_mb(config.hash_mb as usize)
+        .threads(config.threads as usize)
+        .contempt(search_contempt(config))
+        .eval_mode(config.eval_mode)
+}
+
+/// Map `go searchmoves` strings to the legal moves they name; unknown or
+/// illegal entries are dropped.
+fn resolve_searchmoves(board: &Board, moves: &[String]) -> Vec<Move> {
+    moves
+        .iter()
+        .filter_map(|uci| Move::from_uci_legal(uci, board))
+        .collect()
+}
+
+/// Seed for book move selection, from the system clock.
+fn seed_from_clock() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
 impl Default for UciEngine {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{Command, parse_command};
+
+    fn position(line: &str) -> PositionInfo {
+        match parse_command(line).unwrap() {
+            Command::Position(info) => info,
+            _ => panic!("expected Position"),
+        }
+    }
+
+    #[test]
+    fn appended_moves_are_a_continuation() {
+        let old = position("position startpos moves e2e4 e7e5");
+        let new = position("position startpos moves e2e4 e7e5 g1f3 b8c6");
+        assert_eq!(new.appended_to(&old), Some(2));
+        assert_eq!(old.appended_to(&old), Some(0));
+    }
+
+    #[test]
+    fn takeback_or_other_game_is_not_a_continuation() {
+        let old = position("position startpos moves e2e4 e7e5");
+        let takeback = position("position startpos moves e2e4");
+        let other = position("position startpos moves d2d4 d7d5 c2c4");
+        let fen = position("position fen 8/8/8/4k3/8/8/3RK3/8 w - - 0 1");
+        assert_eq!(takeback.appended_to(&old), None);
+        assert_eq!(other.appended_to(&old), None);
+        assert_eq!(fen.appended_to(&old), None);
+    }
+
+    #[test]
+    fn continuation_plays_only_the_new_moves() {
+        let mut engine = UciEngine::new();
+        engine.handle_position(position("position startpos moves e2e4 e7e5"));
+        engine.continuation = Some(0);
+
+        // The stored prefix is trusted, so only g1f3 and b8c6 are played here
+        let next = position("position startpos moves e2e4 e7e5 g1f3 b8c6");
+        let full = next.game().unwrap();
+        engine.handle_position(next);
+        assert_eq!(engine.game.board(), full.board());
+        assert_eq!(engine.game.history(), full.history());
+        assert_eq!(engine.continuation, Some(2));
+
+        // An illegal appended move is reported and leaves the game alone
+        engine.handle_position(position("position startpos moves e2e4 e7e5 g1f3 b8c6 e1e3"));
+        assert_eq!(engine.game.board(), full.board());
+
+        // A takeback replays from scratch and drops the heuristics
+        engine.handle_position(position("position startpos moves e2e4"));
+        assert_eq!(engine.game.history().len(), 1);
+        assert_eq!(engine.continuation, None);
+    }
+
+    #[test]
+    fn setoption_reconfigures_the_pool() {
+        let mut engine = UciEngine::new();
+        engine.handle_setoption(UciOption::Hash(4));
+        engine.handle_setoption(UciOption::Threads(3));
+        engine.handle_setoption(UciOption::Contempt(20));
+        engine.handle_setoption(UciOption::EvalMode(EvalMode::Material));
+        let pool = engine.pool.as_ref().unwrap();
+        assert_eq!(pool.tt_size_mb(), 4);
+        assert_eq!(pool.num_threads(), 3);
+        assert_eq!(pool.contempt(), 20);
+        assert_eq!(pool.eval_mode(), EvalMode::Material);
+
+        // Analysis ignores contempt
+        engine.handle_setoption(UciOption::AnalyseMode(true));
+        assert_eq!(engine.pool.as_ref().unwrap().contempt(), 0);
+
+        // Options sent while the search thread owns the pool wait for it
+        engine.pool = None;
+        engine.handle_setoption(UciOption::Hash(8));
+        assert!(engine.pending_reconfigure);
+    }
+
+    #[test]
+    fn mate_scores_format_as_mate() {
+        assert_eq!(uci_score(28_999), "mate 1");
+        assert_eq!(uci_score(28_997), "mate 2");
+        assert_eq!(uci_score(-28_994), "mate -3");
+        assert_eq!(uci_score(-35), "cp -35");
+    }
+}
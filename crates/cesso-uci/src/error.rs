@@ -1,6 +1,9 @@
 //! UCI protocol errors.
 
 /// Errors that can occur during UCI protocol handling.
+///
+/// The `Display` form is a single line naming the offending input, suitable
+/// for an `info string error: ...` report to the GUI.
 #[derive(Debug, thiserror::Error)]
 pub enum UciError {
     /// The `position` command is missing `startpos` or `fen` keyword.
@@ -8,17 +11,22 @@ pub enum UciError {
     MalformedPosition,
 
     /// Failed to parse a FEN string.
-    #[error("invalid FEN: {fen}")]
+    #[error("invalid FEN \"{fen}\": {source}")]
     InvalidFen {
         /// The FEN string that failed to parse.
         fen: String,
+        /// Why the FEN was rejected.
+        source: cesso_core::FenError,
     },
 
-    /// A move string in the `position` command could not be parsed.
-    #[error("invalid move: {uci_move}")]
+    /// A move in the `position` command is malformed or not legal in the
+    /// position it is played from.
+    #[error("illegal move {uci_move} at ply {ply}")]
     InvalidMove {
-        /// The UCI move string that failed to parse.
+        /// The offending UCI move string.
         uci_move: String,
+        /// Zero-based index of the move in the `moves` list.
+        ply: usize,
     },
 
     /// A `go` parameter is missing its required value.
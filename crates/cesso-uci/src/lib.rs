@@ -3,7 +3,10 @@
 pub mod command;
 pub mod engine;
 pub mod error;
+pub mod options;
+pub mod output;
 
 pub use command::GoParams;
 pub use engine::UciEngine;
 pub use error::UciError;
+pub use output::UciOutput;
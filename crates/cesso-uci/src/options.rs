@@ -0,0 +1,292 @@
+//! Declarative table of UCI options.
+//!
+//! Every option the engine supports is described once in [`OPTIONS`]. The
+//! table drives both the `option name ...` lines printed in the `uci`
+//! handshake and the parsing (with clamping) of `setoption`, so adding an
+//! entry here is enough to advertise and accept a new option.
+
+use std::fmt;
+
+use cesso_engine::EvalMode;
+
+use crate::command::UciOption;
+use crate::error::UciError;
+
+/// Type, default, and bounds of a UCI option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    /// Integer option with inclusive bounds; out-of-range values are clamped.
+    Spin {
+        /// Default value.
+        default: i64,
+        /// Minimum accepted value.
+        min: i64,
+        /// Maximum accepted value.
+        max: i64,
+    },
+    /// Boolean option.
+    Check {
+        /// Default value.
+        default: bool,
+    },
+    /// Free-form string option; `<empty>` denotes the empty string.
+    String {
+        /// Default value.
+        default: &'static str,
+    },
+    /// One of a fixed list of strings, matched case-insensitively.
+    Combo {
+        /// Default value.
+        default: &'static str,
+        /// Accepted values.
+        vars: &'static [&'static str],
+    },
+}
+
+/// A parsed option value, already validated against its [`OptionKind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionValue {
+    /// Value of a spin option (clamped to its bounds).
+    Spin(i64),
+    /// Value of a check option.
+    Check(bool),
+    /// Value of a string option (`<empty>` mapped to `""`), or the
+    /// canonical spelling of a combo value.
+    String(String),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionValue::Spin(v) => write!(f, "{v}"),
+            OptionValue::Check(v) => write!(f, "{v}"),
+            OptionValue::String(s) if s.is_empty() => f.write_str("<empty>"),
+            OptionValue::String(s) => f.write_str(s),
+        }
+    }
+}
+
+/// One entry of the option table.
+#[derive(Debug, Clone, Copy)]
+pub struct OptionSpec {
+    /// Option name as advertised (matched case-insensitively).
+    pub name: &'static str,
+    /// Type, default, and bounds.
+    pub kind: OptionKind,
+    /// Build the [`UciOption`] for a validated value.
+    pub build: fn(OptionValue) -> UciOption,
+}
+
+/// All options the engine supports, in advertisement order.
+pub const OPTIONS: &[OptionSpec] = &[
+    OptionSpec {
+        name: "Hash",
+        kind: OptionKind::Spin { default: 16, min: 1, max: 65536 },
+        build: |v| UciOption::Hash(v.as_spin() as u32),
+    },
+    OptionSpec {
+        name: "Threads",
+        kind: OptionKind::Spin { default: 1, min: 1, max: 256 },
+        build: |v| UciOption::Threads(v.as_spin() as u16),
+    },
+    OptionSpec {
+        name: "Ponder",
+        kind: OptionKind::Check { default: false },
+        build: |v| UciOption::Ponder(v.as_check()),
+    },
+    OptionSpec {
+        name: "Contempt",
+        kind: OptionKind::Spin { default: 0, min: -300, max: 300 },
+        build: |v| UciOption::Contempt(v.as_spin() as i32),
+    },
+    OptionSpec {
+        name: "SyzygyPath",
+        kind: OptionKind::String { default: "<empty>" },
+        build: |v| UciOption::SyzygyPath(v.into_string()),
+    },
+    OptionSpec {
+        name: "UCI_AnalyseMode",
+        kind: OptionKind::Check { default: false },
+        build: |v| UciOption::AnalyseMode(v.as_check()),
+    },
+    OptionSpec {
+        name: "UCI_ShowCurrLine",
+        kind: OptionKind::Check { default: false },
+        build: |v| UciOption::ShowCurrLine(v.as_check()),
+    },
+    OptionSpec {
+        name: "BookPath",
+        kind: OptionKind::String { default: "<empty>" },
+        build: |v| UciOption::BookPath(v.into_string()),
+    },
+    OptionSpec {
+        name: "BookTemperature",
+        kind: OptionKind::Spin { default: 100, min: 0, max: 1000 },
+        build: |v| UciOption::BookTemperature(v.as_spin() as f32 / 100.0),
+    },
+    OptionSpec {
+        name: "EvalMode",
+        kind: OptionKind::Combo { default: "Full", vars: &["Full", "Material"] },
+        build: |v| {
+            let name = v.into_string();
+            UciOption::EvalMode(EvalMode::ALL.into_iter().find(|mode| mode.name() == name).unwrap_or_default())
+        },
+    },
+];
+
+impl OptionValue {
+    fn as_spin(&self) -> i64 {
+        match self {
+            OptionValue::Spin(v) => *v,
+            _ => 0,
+        }
+    }
+
+    fn as_check(&self) -> bool {
+        matches!(self, OptionValue::Check(true))
+    }
+
+    fn into_string(self) -> String {
+        match self {
+            OptionValue::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+}
+
+impl OptionSpec {
+    /// Look up an option by name, case-insensitively.
+    pub fn find(name: &str) -> Option<&'static OptionSpec> {
+        OPTIONS.iter().find(|spec| spec.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The option's default value.
+    pub fn default_value(&self) -> OptionValue {
+        match self.kind {
+            OptionKind::Spin { default, .. } => OptionValue::Spin(default),
+            OptionKind::Check { default } => OptionValue::Check(default),
+            OptionKind::String { default } => OptionValue::String(normalize_string(default)),
+            OptionKind::Combo { default, .. } => OptionValue::String(default.to_string()),
+        }
+    }
+
+    /// Parse a raw `setoption` value, clamping spin values to the option's bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UciError::InvalidOptionValue`] if the value is missing or has
+    /// the wrong type.
+    pub fn parse_value(&self, raw: Option<&str>) -> Result<OptionValue, UciError> {
+        let invalid = || UciError::InvalidOptionValue {
+            name: self.name.to_string(),
+            value: raw.unwrap_or_default().to_string(),
+        };
+        match self.kind {
+            OptionKind::Spin { min, max, .. } => {
+                let parsed: i64 = raw.ok_or_else(invalid)?.parse().map_err(|_| invalid())?;
+                Ok(OptionValue::Spin(parsed.clamp(min, max)))
+            }
+            OptionKind::Check { .. } => match raw.ok_or_else(invalid)? {
+                "true" => Ok(OptionValue::Check(true)),
+                "false" => Ok(OptionValue::Check(false)),
+                _ => Err(invalid()),
+            },
+            OptionKind::String { .. } => Ok(OptionValue::String(normalize_string(raw.unwrap_or_default()))),
+            OptionKind::Combo { vars, .. } => {
+                let raw = raw.ok_or_else(invalid)?;
+                let var = vars.iter().find(|var| var.eq_ignore_ascii_case(raw)).ok_or_else(invalid)?;
+                Ok(OptionValue::String(var.to_string()))
+            }
+        }
+    }
+
+    /// Parse a raw value and build the corresponding [`UciOption`].
+    pub fn parse(&self, raw: Option<&str>) -> Result<UciOption, UciError> {
+        self.parse_value(raw).map(self.build)
+    }
+}
+
+/// Map the UCI `<empty>` placeholder to the empty string.
+fn normalize_string(s: &str) -> String {
+    if s == "<empty>" { String::new() } else { s.to_string() }
+}
+
+impl fmt::Display for OptionSpec {
+    /// Format the `option name ...` line for the `uci` handshake.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "option name {} ", self.name)?;
+        match self.kind {
+            OptionKind::Spin { default, min, max } => {
+                write!(f, "type spin default {default} min {min} max {max}")
+            }
+            OptionKind::Check { default } => write!(f, "type check default {default}"),
+            OptionKind::String { default } => write!(f, "type string default {default}"),
+            OptionKind::Combo { default, vars } => {
+                write!(f, "type combo default {default}")?;
+                vars.iter().try_for_each(|var| write!(f, " var {var}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{Command, parse_command};
+
+    #[test]
+    fn advertised_lines() {
+        let lines: Vec<String> = OPTIONS.iter().map(ToString::to_string).collect();
+        assert!(lines.contains(&"option name Hash type spin default 16 min 1 max 65536".to_string()));
+        assert!(lines.contains(&"option name Threads type spin default 1 min 1 max 256".to_string()));
+        assert!(lines.contains(&"option name Ponder type check default false".to_string()));
+        assert!(lines.contains(&"option name SyzygyPath type string default <empty>".to_string()));
+        assert!(lines.contains(&"option name UCI_AnalyseMode type check default false".to_string()));
+        assert!(lines.contains(&"option name BookTemperature type spin default 100 min 0 max 1000".to_string()));
+        assert!(lines.contains(&"option name EvalMode type combo default Full var Full var Material".to_string()));
+    }
+
+    #[test]
+    fn combo_values_match_case_insensitively() {
+        let Command::SetOption(opt) = parse_command("setoption name EvalMode value material").unwrap() else {
+            panic!("EvalMode did not parse as setoption");
+        };
+        assert_eq!(opt, UciOption::EvalMode(EvalMode::Material));
+        assert!(parse_command("setoption name EvalMode value Random").is_err());
+    }
+
+    #[test]
+    fn every_option_round_trips_through_setoption() {
+        for spec in OPTIONS {
+            let default = spec.default_value();
+            let line = format!("setoption name {} value {}", spec.name, default);
+            let Command::SetOption(opt) = parse_command(&line).unwrap() else {
+                panic!("{line} did not parse as setoption");
+            };
+            assert_eq!(opt.name(), spec.name);
+            assert_eq!(opt.value(), default, "{line}");
+        }
+    }
+
+    #[test]
+    fn spin_bounds_clamp_on_setoption() {
+        for spec in OPTIONS {
+            let OptionKind::Spin { min, max, .. } = spec.kind else { continue };
+            let low = format!("setoption name {} value {}", spec.name, min - 1);
+            let high = format!("setoption name {} value {}", spec.name, max + 1);
+            let Command::SetOption(low) = parse_command(&low).unwrap() else { panic!() };
+            let Command::SetOption(high) = parse_command(&high).unwrap() else { panic!() };
+            assert_eq!(low.value(), OptionValue::Spin(min));
+            assert_eq!(high.value(), OptionValue::Spin(max));
+        }
+    }
+
+    #[test]
+    fn names_are_unique_case_insensitively() {
+        for (i, a) in OPTIONS.iter().enumerate() {
+            for b in &OPTIONS[i + 1..] {
+                assert!(!a.name.eq_ignore_ascii_case(b.name));
+            }
+        }
+    }
+}
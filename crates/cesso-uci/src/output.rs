@@ -0,0 +1,106 @@
+//! Line-oriented UCI output that survives a closed pipe.
+//!
+//! `println!` panics when the GUI has gone away and stdout is a broken pipe.
+//! Every line the engine sends goes through [`UciOutput::line`] instead, which
+//! swallows the write error and marks the output closed so the engine loop
+//! can shut down on its own terms.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing::{debug, warn};
+
+/// Shared sink for UCI protocol lines.
+pub struct UciOutput {
+    writer: Mutex<Box<dyn Write + Send>>,
+    closed: AtomicBool,
+}
+
+impl UciOutput {
+    /// Wrap an arbitrary writer.
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Output to the process's stdout.
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    /// Write `line` followed by a newline and flush.
+    ///
+    /// Returns `false` if the output is (or has just become) closed; the line
+    /// is dropped in that case.
+    pub fn line(&self, line: impl fmt::Display) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        let Ok(mut writer) = self.writer.lock() else {
+            self.closed.store(true, Ordering::Release);
+            return false;
+        };
+        match writeln!(writer, "{line}").and_then(|()| writer.flush()) {
+            Ok(()) => true,
+            Err(e) => {
+                if e.kind() == io::ErrorKind::BrokenPipe {
+                    debug!("stdout closed by the GUI");
+                } else {
+                    warn!(error = %e, "failed to write UCI output");
+                }
+                self.closed.store(true, Ordering::Release);
+                false
+            }
+        }
+    }
+
+    /// Whether a write has failed; nothing further will be written.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for UciOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UciOutput")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BrokenPipe;
+
+    impl Write for BrokenPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn broken_pipe_closes_output_without_panicking() {
+        let output = UciOutput::new(BrokenPipe);
+        assert!(!output.is_closed());
+        assert!(!output.line("readyok"));
+        assert!(output.is_closed());
+        assert!(!output.line("readyok"));
+    }
+
+    #[test]
+    fn successful_writes_keep_output_open() {
+        let output = UciOutput::new(io::sink());
+        assert!(output.line(format_args!("info depth {}", 1)));
+        assert!(!output.is_closed());
+    }
+}
@@ -0,0 +1,132 @@
+//! Helpers shared by the engine-loop integration tests.
+
+#![allow(dead_code)]
+
+use std::io::{self, BufReader, Cursor, Read, Write};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+use cesso_uci::UciEngine;
+
+/// Run the engine loop with `input` and `output`, failing if it does not return within 5 s.
+pub fn run_io<R, W>(input: R, output: W) -> Duration
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let start = Instant::now();
+    std::thread::spawn(move || {
+        let result = UciEngine::new().run_with_io(BufReader::new(input), output);
+        let _ = tx.send(result.is_ok());
+    });
+    let ok = rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("engine loop did not return");
+    assert!(ok, "engine loop returned an error");
+    start.elapsed()
+}
+
+/// In-memory writer whose contents stay readable after the engine drops it.
+#[derive(Clone, Default)]
+pub struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Capture {
+    /// Everything written so far.
+    pub fn text(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+
+    /// Poll until `count` written lines start with `prefix`, returning them in order.
+    pub fn wait_for_lines(&self, prefix: &str, count: usize) -> Vec<String> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let lines: Vec<String> = self
+                .text()
+                .lines()
+                .filter(|l| l.starts_with(prefix))
+                .map(str::to_string)
+                .collect();
+            if lines.len() >= count {
+                return lines;
+            }
+            assert!(Instant::now() < deadline, "fewer than {count} lines starting with {prefix:?} in:\n{}", self.text());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Poll until a written line starts with `prefix`, returning that line.
+    pub fn wait_for_line(&self, prefix: &str) -> String {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(line) = self.text().lines().find(|l| l.starts_with(prefix)) {
+                return line.to_string();
+            }
+            assert!(Instant::now() < deadline, "no line starting with {prefix:?} in:\n{}", self.text());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reader that yields `script` and then blocks until the paired sender is dropped,
+/// like a GUI that keeps stdin open.
+pub struct HeldOpen {
+    script: Cursor<&'static str>,
+    hold: mpsc::Receiver<()>,
+}
+
+impl Read for HeldOpen {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.script.read(buf)?;
+        if n == 0 {
+            let _ = self.hold.recv();
+        }
+        Ok(n)
+    }
+}
+
+/// Build a [`HeldOpen`] reader; dropping the returned sender closes the input.
+pub fn held_open(script: &'static str) -> (HeldOpen, mpsc::Sender<()>) {
+    let (tx, rx) = mpsc::channel();
+    (HeldOpen { script: Cursor::new(script), hold: rx }, tx)
+}
+
+/// Reader fed one command at a time by the test, like a GUI driving a session.
+///
+/// Reads block until the next line is sent; dropping every sender closes the input.
+pub struct Feed {
+    pending: Cursor<Vec<u8>>,
+    lines: mpsc::Receiver<String>,
+}
+
+impl Read for Feed {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.pending.read(buf)?;
+            if n > 0 {
+                return Ok(n);
+            }
+            match self.lines.recv() {
+                Ok(line) => self.pending = Cursor::new(format!("{line}\n").into_bytes()),
+                Err(_) => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Build a [`Feed`] reader and the sender used to type commands into it.
+pub fn feed() -> (Feed, mpsc::Sender<String>) {
+    let (tx, rx) = mpsc::channel();
+    (Feed { pending: Cursor::new(Vec::new()), lines: rx }, tx)
+}
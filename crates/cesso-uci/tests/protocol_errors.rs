@@ -0,0 +1,81 @@
+//! Malformed commands are reported and ignored without disturbing engine state.
+
+mod common;
+
+use std::io::BufReader;
+use std::thread;
+
+use cesso_core::generate_legal_moves;
+use cesso_uci::UciEngine;
+use cesso_uci::command::{Command, parse_command};
+
+use common::{Capture, held_open};
+
+#[test]
+fn bad_fen_keeps_last_good_position() {
+    let capture = Capture::default();
+    let (input, hold) = held_open(
+        "position startpos moves e2e4\nposition fen not/a/fen w - - 0 1\ngo depth 3\n",
+    );
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    let error = capture.wait_for_line("info string error:");
+    assert!(error.contains("invalid FEN"), "{error}");
+    assert!(error.contains("position fen not/a/fen"), "{error}");
+
+    let bestmove = capture.wait_for_line("bestmove");
+    drop(hold);
+    assert!(engine.join().unwrap().is_ok());
+
+    let Command::Position(after_e4) = parse_command("position startpos moves e2e4").unwrap() else {
+        panic!("expected Position");
+    };
+    let best = bestmove.split_whitespace().nth(1).unwrap();
+    let legal = generate_legal_moves(after_e4.game().unwrap().board());
+    assert!(
+        legal.as_slice().iter().any(|mv| mv.to_uci() == best),
+        "{best} is not a black reply to 1. e4"
+    );
+}
+
+#[test]
+fn bad_go_value_is_reported_and_loop_continues() {
+    let capture = Capture::default();
+    let (input, hold) = held_open("go wtime abc\nisready\n");
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    let error = capture.wait_for_line("info string error:");
+    assert!(error.contains("wtime") && error.contains("abc"), "{error}");
+    capture.wait_for_line("readyok");
+    assert!(!capture.text().contains("bestmove"));
+
+    drop(hold);
+    assert!(engine.join().unwrap().is_ok());
+}
+
+#[test]
+fn illegal_move_keeps_last_good_position() {
+    let capture = Capture::default();
+    let (input, hold) = held_open("position startpos moves e2e4\nposition startpos moves e2e5\ngo depth 3\n");
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    let error = capture.wait_for_line("info string error:");
+    assert!(error.contains("illegal move e2e5 at ply 0"), "{error}");
+
+    let bestmove = capture.wait_for_line("bestmove");
+    drop(hold);
+    assert!(engine.join().unwrap().is_ok());
+
+    let Command::Position(after_e4) = parse_command("position startpos moves e2e4").unwrap() else {
+        panic!("expected Position");
+    };
+    let best = bestmove.split_whitespace().nth(1).unwrap();
+    let legal = generate_legal_moves(after_e4.game().unwrap().board());
+    assert!(
+        legal.as_slice().iter().any(|mv| mv.to_uci() == best),
+        "{best} is not a black reply to 1. e4"
+    );
+}
@@ -0,0 +1,81 @@
+//! Integration tests for engine-loop teardown on `quit`, end of input, and closed output.
+
+mod common;
+
+use std::io::{self, Cursor, Write};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use cesso_uci::UciEngine;
+
+use common::{Capture, held_open, run_io};
+
+/// Run the engine loop on `script` in a background thread and return how long it took.
+fn run_script(script: &'static str) -> Duration {
+    let (tx, rx) = mpsc::channel();
+    let start = Instant::now();
+    std::thread::spawn(move || {
+        let result = UciEngine::new().run_with_input(Cursor::new(script));
+        let _ = tx.send(result.is_ok());
+    });
+    let ok = rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("engine loop did not return");
+    assert!(ok, "engine loop returned an error");
+    start.elapsed()
+}
+
+/// Writer that accepts `remaining` bytes and then reports a broken pipe.
+struct FailAfter {
+    remaining: usize,
+}
+
+impl Write for FailAfter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        let n = buf.len().min(self.remaining);
+        self.remaining -= n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn quit_during_infinite_search_returns_promptly() {
+    let elapsed = run_script("position startpos\ngo infinite\nquit\n");
+    assert!(elapsed < Duration::from_secs(1), "took {elapsed:?}");
+}
+
+#[test]
+fn end_of_input_during_infinite_search_returns_promptly() {
+    let elapsed = run_script("position startpos\ngo infinite\n");
+    assert!(elapsed < Duration::from_secs(1), "took {elapsed:?}");
+}
+
+#[test]
+fn end_of_input_mid_search_prints_no_bestmove() {
+    let capture = Capture::default();
+    run_io(Cursor::new("uci\nposition startpos\ngo infinite\n"), capture.clone());
+    let text = capture.text();
+    assert!(text.contains("uciok"), "{text}");
+    assert!(!text.contains("bestmove"), "{text}");
+}
+
+#[test]
+fn broken_pipe_during_handshake_shuts_down() {
+    let (input, _hold) = held_open("uci\nisready\n");
+    let elapsed = run_io(input, FailAfter { remaining: 20 });
+    assert!(elapsed < Duration::from_secs(1), "took {elapsed:?}");
+}
+
+#[test]
+fn broken_pipe_during_search_stops_and_shuts_down() {
+    let (input, _hold) = held_open("position startpos\ngo infinite\n");
+    let elapsed = run_io(input, FailAfter { remaining: 0 });
+    assert!(elapsed < Duration::from_secs(1), "took {elapsed:?}");
+}
@@ -0,0 +1,320 @@
+//! Complete UCI sessions driven through `run_with_io`, as a GUI would.
+
+mod common;
+
+use std::io::BufReader;
+use std::thread;
+use std::time::Duration;
+
+use cesso_core::{Board, Move, generate_legal_moves, polyglot_hash};
+use cesso_uci::UciEngine;
+
+use common::{Capture, feed};
+
+/// Value following `key` in an `info` line.
+fn info_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let mut tokens = line.split_whitespace();
+    tokens.find(|&t| t == key)?;
+    tokens.next()
+}
+
+#[test]
+fn handshake_then_depth_search() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    commands.send("uci".into()).unwrap();
+    capture.wait_for_line("uciok");
+    commands.send("isready".into()).unwrap();
+    capture.wait_for_line("readyok");
+    commands.send("position startpos".into()).unwrap();
+    commands.send("go depth 4".into()).unwrap();
+    let bestmove = capture.wait_for_line("bestmove");
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "id name cesso");
+    assert!(lines[1].starts_with("id author "), "{text}");
+    assert!(lines.iter().all(|l| !l.starts_with("option") || l.starts_with("option name ")));
+
+    let uciok = lines.iter().position(|&l| l == "uciok").unwrap();
+    let readyok = lines.iter().position(|&l| l == "readyok").unwrap();
+    let first_info = lines.iter().position(|l| l.starts_with("info")).unwrap();
+    assert!(uciok < readyok && readyok < first_info, "{text}");
+
+    let depths: Vec<u8> = lines
+        .iter()
+        .filter(|l| l.starts_with("info depth"))
+        .map(|l| info_field(l, "depth").unwrap().parse().unwrap())
+        .collect();
+    assert_eq!(depths.last(), Some(&4), "{text}");
+    for line in lines.iter().filter(|l| l.starts_with("info depth")) {
+        for key in ["score", "nodes", "nps", "hashfull", "time", "pv"] {
+            assert!(info_field(line, key).is_some(), "missing {key} in {line}");
+        }
+    }
+
+    // Analysis output stays off in match play
+    assert!(!text.contains("info refutation") && !text.contains("info currline"), "{text}");
+
+    let best = bestmove.split_whitespace().nth(1).unwrap();
+    assert_ne!(best, "0000");
+    let legal = generate_legal_moves(&Board::starting_position());
+    assert!(legal.as_slice().iter().any(|mv| mv.to_uci() == best), "{best} is not legal");
+}
+
+#[test]
+fn ucinewgame_clears_transposition_table() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    // A small table fills up quickly, so hashfull tells a used table from a cleared one
+    commands.send("setoption name Hash value 1".into()).unwrap();
+    commands.send("position startpos".into()).unwrap();
+    commands.send("go depth 7".into()).unwrap();
+    capture.wait_for_lines("bestmove", 1);
+    let filled = last_hashfull(&capture);
+
+    commands.send("go depth 1".into()).unwrap();
+    capture.wait_for_lines("bestmove", 2);
+    let reused = last_hashfull(&capture);
+
+    commands.send("ucinewgame".into()).unwrap();
+    commands.send("position startpos".into()).unwrap();
+    commands.send("go depth 1".into()).unwrap();
+    capture.wait_for_lines("bestmove", 3);
+    let cleared = last_hashfull(&capture);
+
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    assert!(filled > 50, "hashfull {filled} after depth 7");
+    assert!(reused >= filled, "hashfull dropped to {reused} without ucinewgame");
+    assert!(cleared < 10, "hashfull {cleared} after ucinewgame");
+}
+
+#[test]
+fn analyse_mode_reports_refutations() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    commands.send("setoption name UCI_AnalyseMode value true".into()).unwrap();
+    commands.send("position startpos".into()).unwrap();
+    commands.send("go depth 5".into()).unwrap();
+    capture.wait_for_line("bestmove");
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    let refutations: Vec<&str> = text.lines().filter(|l| l.starts_with("info refutation ")).collect();
+    assert!(!refutations.is_empty(), "{text}");
+    let board = Board::starting_position();
+    for line in refutations {
+        let moves: Vec<&str> = line.split_whitespace().skip(2).collect();
+        assert!(moves.len() >= 2, "{line}");
+        let refuted = generate_legal_moves(&board)
+            .as_slice()
+            .iter()
+            .copied()
+            .find(|mv| mv.to_uci() == moves[0])
+            .unwrap_or_else(|| panic!("{line}: root move is not legal"));
+        let replies = generate_legal_moves(&board.make_move(refuted));
+        assert!(replies.as_slice().iter().any(|mv| mv.to_uci() == moves[1]), "{line}");
+    }
+}
+
+#[test]
+fn bench_reports_nodes_and_speed() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    commands.send("bench 2".into()).unwrap();
+    commands.send("isready".into()).unwrap();
+    capture.wait_for_line("readyok");
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    let line = text.lines().find(|l| l.starts_with("info string bench: ")).unwrap();
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    assert_eq!(tokens[4], "nodes", "{line}");
+    assert_eq!(tokens[6], "nps", "{line}");
+    assert!(tokens[3].parse::<u64>().unwrap() > 0, "{line}");
+    assert!(tokens[5].parse::<u64>().is_ok(), "{line}");
+}
+
+/// `hashfull` of the most recent `info depth` line.
+fn last_hashfull(capture: &Capture) -> u32 {
+    let text = capture.text();
+    let line = text.lines().rfind(|l| l.starts_with("info depth")).unwrap();
+    info_field(line, "hashfull").unwrap().parse().unwrap()
+}
+
+#[test]
+fn finished_games_answer_go_without_searching() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    commands.send("position fen 3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1".into()).unwrap();
+    commands.send("go movetime 10000".into()).unwrap();
+    capture.wait_for_lines("bestmove", 1);
+
+    // `go infinite` and `go ponder` hold the answer until `stop` or `ponderhit`
+    commands.send("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".into()).unwrap();
+    commands.send("go infinite".into()).unwrap();
+    capture.wait_for_lines("info depth 0", 2);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(capture.text().matches("bestmove").count(), 1, "bestmove sent before stop");
+    commands.send("stop".into()).unwrap();
+    capture.wait_for_lines("bestmove", 2);
+    commands.send("go ponder".into()).unwrap();
+    capture.wait_for_lines("info depth 0", 3);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(capture.text().matches("bestmove").count(), 2, "bestmove sent before ponderhit");
+    commands.send("ponderhit".into()).unwrap();
+    capture.wait_for_lines("bestmove", 3);
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "info depth 0 score mate 0",
+            "bestmove 0000",
+            "info depth 0 score cp 0",
+            "bestmove 0000",
+            "info depth 0 score cp 0",
+            "bestmove 0000",
+        ]
+    );
+}
+
+#[test]
+fn dead_positions_answer_go_without_searching() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    // KBvK: a timed search answers at once
+    commands.send("position fen 4k3/8/8/8/8/8/8/2B1K3 w - - 0 1".into()).unwrap();
+    commands.send("go depth 20".into()).unwrap();
+    capture.wait_for_lines("bestmove", 1);
+
+    // `go infinite` holds the answer until `stop`
+    commands.send("go infinite".into()).unwrap();
+    capture.wait_for_lines("info depth 0", 2);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(capture.text().matches("bestmove").count(), 1, "bestmove sent before stop");
+    commands.send("stop".into()).unwrap();
+    capture.wait_for_lines("bestmove", 2);
+
+    // `go ponder` holds it until `ponderhit`
+    commands.send("go ponder".into()).unwrap();
+    capture.wait_for_lines("info depth 0", 3);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(capture.text().matches("bestmove").count(), 2, "bestmove sent before ponderhit");
+    commands.send("ponderhit".into()).unwrap();
+    capture.wait_for_lines("bestmove", 3);
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    assert!(!text.contains("nodes"), "a dead position should not be searched:\n{text}");
+    for line in text.lines().filter(|line| line.starts_with("bestmove")) {
+        assert!(["bestmove c1", "bestmove e1"].iter().any(|from| line.starts_with(from)), "{line}");
+    }
+}
+
+#[test]
+fn shallow_searches_near_mate_emit_legal_moves() {
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    // Mate in one, mated in one, and a single forced reply, each at depth 1
+    let fens = [
+        "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+        "7k/8/6K1/8/8/8/8/1R6 b - - 0 1",
+        "7k/8/8/N7/8/8/6P1/r6K w - - 0 1",
+    ];
+    for (i, fen) in fens.iter().enumerate() {
+        commands.send(format!("position fen {fen}")).unwrap();
+        commands.send("go depth 1".into()).unwrap();
+        capture.wait_for_lines("bestmove", i + 1);
+    }
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+
+    let text = capture.text();
+    assert!(!text.contains("ERROR"), "{text}");
+    let moves: Vec<&str> = text
+        .lines()
+        .filter(|l| l.starts_with("bestmove"))
+        .map(|l| l.split_whitespace().nth(1).unwrap())
+        .collect();
+    for (fen, best) in fens.iter().zip(moves) {
+        let board: Board = fen.parse().unwrap();
+        let legal = generate_legal_moves(&board);
+        assert!(legal.as_slice().iter().any(|mv| mv.to_uci() == best), "{best} is not legal in {fen}");
+    }
+}
+
+#[test]
+fn book_moves_are_played_without_searching() {
+    // One record for the start position: 1.b3 (b2 = 9, b3 = 17), weight 1
+    let mut record = polyglot_hash(&Board::starting_position()).to_be_bytes().to_vec();
+    record.extend_from_slice(&(17u16 | 9 << 6).to_be_bytes());
+    record.extend_from_slice(&[0, 1, 0, 0, 0, 0]);
+    let path = std::env::temp_dir().join(format!("cesso-book-{}.bin", std::process::id()));
+    std::fs::write(&path, record).unwrap();
+    // A second book that only knows 1.e4 e5 (e7 = 52, e5 = 36), layered under the first
+    let after_e4 = Board::starting_position().make_move(Move::from_uci("e2e4", &Board::starting_position()).unwrap());
+    let mut record = polyglot_hash(&after_e4).to_be_bytes().to_vec();
+    record.extend_from_slice(&(36u16 | 52 << 6).to_be_bytes());
+    record.extend_from_slice(&[0, 1, 0, 0, 0, 0]);
+    let second = std::env::temp_dir().join(format!("cesso-book-{}-2.bin", std::process::id()));
+    std::fs::write(&second, record).unwrap();
+    let paths = std::env::join_paths([&path, &second]).unwrap();
+
+    let capture = Capture::default();
+    let (input, commands) = feed();
+    let output = capture.clone();
+    let engine = thread::spawn(move || UciEngine::new().run_with_io(BufReader::new(input), output));
+
+    commands.send(format!("setoption name BookPath value {}", paths.to_str().unwrap())).unwrap();
+    commands.send("position startpos".into()).unwrap();
+    commands.send("go depth 4".into()).unwrap();
+    capture.wait_for_lines("bestmove", 1);
+    // Out of book: a normal search
+    commands.send("position startpos moves b2b3".into()).unwrap();
+    commands.send("go depth 2".into()).unwrap();
+    capture.wait_for_lines("bestmove", 2);
+    commands.send("quit".into()).unwrap();
+    assert!(engine.join().unwrap().is_ok());
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&second).unwrap();
+
+    let text = capture.text();
+    assert!(text.contains("info string Loaded 2 book entries"), "{text}");
+    let lines: Vec<&str> = text.lines().collect();
+    let first = lines.iter().position(|l| l.starts_with("bestmove")).unwrap();
+    assert_eq!(lines[first], "bestmove b2b3", "{text}");
+    assert_eq!(lines[first - 1], "info string book move b2b3", "{text}");
+    assert!(lines[first + 1..].iter().any(|l| l.starts_with("info depth")), "{text}");
+}
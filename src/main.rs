@@ -1,15 +1,88 @@
-use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
 use tracing::info;
 
+use cesso_core::{Board, divide_parallel, parse_epd_line, print_divide};
+use cesso_engine::Searcher;
 use cesso_uci::UciEngine;
 
+/// Search time per position for `epdtest` when none is given.
+const EPD_MOVETIME_MS: u64 = 1000;
+
 fn main() -> Result<()> {
-    // UCI protocol uses stdout; tracing defaults to stderr
-    tracing_subscriber::fmt::init();
+    // UCI protocol uses stdout, so diagnostics must go to stderr
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
     info!("cesso starting");
 
-    let engine = UciEngine::new();
-    engine.run()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("epdtest") => epdtest(&args[1..]),
+        Some("perft") => perft(&args[1..]),
+        Some(other) => bail!("unknown command {other:?}; usage: cesso [epdtest ... | perft ...]"),
+        None => {
+            let engine = UciEngine::new();
+            engine.run()?;
+            Ok(())
+        }
+    }
+}
+
+/// Run `cesso epdtest <file> [movetime_ms]`: search every record in an EPD
+/// file and print how many best moves were found.
+fn epdtest(args: &[String]) -> Result<()> {
+    let Some(path) = args.first() else {
+        bail!("usage: cesso epdtest <file> [movetime_ms]");
+    };
+    let movetime = match args.get(1) {
+        Some(ms) => ms.parse().with_context(|| format!("invalid movetime {ms:?}"))?,
+        None => EPD_MOVETIME_MS,
+    };
+
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+    let records = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_epd_line(line).with_context(|| format!("{path}:{}", i + 1)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let solved = Searcher::new().run_epd_suite(&records, Duration::from_millis(movetime));
+    println!("solved {solved}/{}", records.len());
+    Ok(())
+}
+
+/// Run `cesso perft <depth> [--threads N] [fen]`: print a divide of the
+/// position (the start position by default) counted on N threads.
+fn perft(args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: cesso perft <depth> [--threads N] [fen]";
+    let mut depth = None;
+    let mut threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let mut fen = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--threads" {
+            let n = args.next().context(USAGE)?;
+            threads = n.parse().with_context(|| format!("invalid thread count {n:?}"))?;
+        } else if depth.is_none() {
+            depth = Some(arg.parse::<usize>().with_context(|| format!("invalid depth {arg:?}"))?);
+        } else {
+            fen.push(arg.as_str());
+        }
+    }
+    let depth = depth.context(USAGE)?;
+    let board = if fen.is_empty() {
+        Board::starting_position()
+    } else {
+        fen.join(" ").parse().context("invalid FEN")?
+    };
 
+    let start = Instant::now();
+    let breakdown = divide_parallel(&board, depth, threads);
+    let elapsed = start.elapsed();
+    let mut report = String::new();
+    print_divide(&mut report, &breakdown)?;
+    print!("{report}");
+    println!("Time: {} ms on {threads} threads", elapsed.as_millis());
     Ok(())
 }